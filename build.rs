@@ -6,11 +6,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     println!("cargo:rerun-if-changed=proto/");
     
+    // Statistics are exported as JSON, so their messages and the common ones
+    // they embed can be serialized. Every service regenerates common.proto.
+    let configure = || {
+        let mut builder = tonic_build::configure()
+            .build_server(true)
+            .build_client(false);
+        for path in [".seula.common", ".seula.system"] {
+            builder = builder.type_attribute(path, "#[derive(serde::Serialize)]");
+        }
+        builder
+    };
+
     // Compile common.proto first
-    tonic_build::configure()
-        .build_server(true)
-        .build_client(false)
-        .compile(&["proto/common.proto"], &["proto/"])?;
+    configure().compile(&["proto/common.proto"], &["proto/"])?;
 
     // Compile each service separately
     let services = [
@@ -32,10 +41,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let proto_file = format!("proto/services/{}.proto", service);
         println!("cargo:rerun-if-changed={}", proto_file);
         
-        tonic_build::configure()
-            .build_server(true)
-            .build_client(false)
-            .compile(&[&proto_file], &["proto/"])?;
+        configure().compile(&[&proto_file], &["proto/"])?;
     }
 
     Ok(())
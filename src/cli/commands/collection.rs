@@ -295,6 +295,22 @@ impl TableDisplay for CollectionDetails {
         if let Some(time_sig) = &self.stats.most_common_time_signature {
             table_row!(table, "Most Common Time Signature", time_sig);
         }
+        if let (Some(min), Some(max)) = (self.stats.min_lufs, self.stats.max_lufs) {
+            table_row!(
+                table,
+                "Loudness",
+                format!(
+                    "{:.1} to {:.1} LUFS ({:.1} LU spread, {} analyzed)",
+                    min,
+                    max,
+                    max - min,
+                    self.stats.analyzed_project_count
+                )
+            );
+        }
+        if let Some(true_peak) = self.stats.max_true_peak_dbtp {
+            table_row!(table, "Max True Peak", format!("{:.1} dBTP", true_peak));
+        }
 
         // Add separator
         table.add_row(vec!["", ""]);
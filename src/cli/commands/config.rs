@@ -1,7 +1,7 @@
 use crate::cli::commands::{CliCommand, CliContext};
 use crate::cli::output::{MessageType, OutputFormatter, TableDisplay};
 use crate::cli::{CliError, ConfigCommands};
-use crate::config::{Config, CONFIG};
+use crate::config::{Config, RootSettings, CONFIG};
 use crate::{colored_cell, table_row};
 use colored::Colorize;
 use comfy_table::Table;
@@ -61,6 +61,12 @@ impl ConfigCommands {
             media_storage_dir: config.media_storage_dir.clone(),
             max_cover_art_size_mb: config.max_cover_art_size_mb,
             max_audio_file_size_mb: config.max_audio_file_size_mb,
+            poll_paths: config.poll_paths.clone(),
+            poll_interval_secs: config.poll_interval_secs,
+            scan_workers: config.scan_workers,
+            scan_file_timeout_secs: config.scan_file_timeout_secs,
+            roots: config.roots.clone(),
+            strict_media_validation: config.strict_media_validation,
             status_message: config.get_status_message(),
             is_ready: config.is_ready_for_operation(),
         })
@@ -155,6 +161,12 @@ pub struct ConfigDisplay {
     pub media_storage_dir: String,
     pub max_cover_art_size_mb: Option<u32>,
     pub max_audio_file_size_mb: Option<u32>,
+    pub poll_paths: Vec<String>,
+    pub poll_interval_secs: u64,
+    pub scan_workers: usize,
+    pub scan_file_timeout_secs: u64,
+    pub roots: Vec<RootSettings>,
+    pub strict_media_validation: bool,
     pub status_message: String,
     pub is_ready: bool,
 }
@@ -188,7 +200,18 @@ impl TableDisplay for ConfigDisplay {
         } else {
             table_row!(table, "Project Paths", format!("{} configured", self.paths.len()));
             for (i, path) in self.paths.iter().enumerate() {
-                table_row!(table, format!("  Path {}", i + 1), path);
+                if self.poll_paths.contains(path) {
+                    table_row!(
+                        table,
+                        format!("  Path {}", i + 1),
+                        format!("{} (polled every {}s)", path, self.poll_interval_secs)
+                    );
+                } else {
+                    table_row!(table, format!("  Path {}", i + 1), path);
+                }
+                if let Some(root) = self.roots.iter().find(|root| &root.path == path) {
+                    table_row!(table, "    Scan Settings", describe_root_settings(root));
+                }
             }
         }
 
@@ -202,6 +225,17 @@ impl TableDisplay for ConfigDisplay {
             .map(|size| if size == 0 { "No limit".to_string() } else { format!("{} MB", size) })
             .unwrap_or_else(|| "Default".to_string());
         table_row!(table, "Max Audio File Size", audio_limit);
+        let validation = if self.strict_media_validation { "Strict" } else { "Permissive" };
+        table_row!(table, "Media Validation", validation);
+
+        // Scanning
+        table_row!(table, "Scan Workers", self.scan_workers);
+        let file_timeout = if self.scan_file_timeout_secs == 0 {
+            "No limit".to_string()
+        } else {
+            format!("{}s", self.scan_file_timeout_secs)
+        };
+        table_row!(table, "Scan File Timeout", file_timeout);
 
         table
     }
@@ -221,6 +255,14 @@ impl TableDisplay for ConfigDisplay {
         for (i, path) in self.paths.iter().enumerate() {
             writer.write_record([&format!("project_path_{}", i + 1), path]).map_err(|e| -> CliError { e.into() })?;
         }
+        for (i, path) in self.poll_paths.iter().enumerate() {
+            writer.write_record([&format!("poll_path_{}", i + 1), path]).map_err(|e| -> CliError { e.into() })?;
+        }
+        writer.write_record(["poll_interval_secs", &self.poll_interval_secs.to_string()]).map_err(|e| -> CliError { e.into() })?;
+        for (i, root) in self.roots.iter().enumerate() {
+            writer.write_record([&format!("root_{}_path", i + 1), &root.path]).map_err(|e| -> CliError { e.into() })?;
+            writer.write_record([&format!("root_{}_settings", i + 1), &describe_root_settings(root)]).map_err(|e| -> CliError { e.into() })?;
+        }
 
         // Media limits
         let cover_art_limit = self.max_cover_art_size_mb
@@ -232,11 +274,40 @@ impl TableDisplay for ConfigDisplay {
             .map(|size| if size == 0 { "No limit".to_string() } else { format!("{} MB", size) })
             .unwrap_or_else(|| "Default".to_string());
         writer.write_record(["max_audio_file_size", &audio_limit]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["strict_media_validation", &self.strict_media_validation.to_string()]).map_err(|e| -> CliError { e.into() })?;
+
+        writer.write_record(["scan_workers", &self.scan_workers.to_string()]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["scan_file_timeout_secs", &self.scan_file_timeout_secs.to_string()]).map_err(|e| -> CliError { e.into() })?;
 
         Ok(())
     }
 }
 
+/// One-line summary of the settings that differ from the defaults
+fn describe_root_settings(root: &RootSettings) -> String {
+    let mut parts = Vec::new();
+    if !root.exclude.is_empty() {
+        parts.push(format!("exclude {}", root.exclude.join(", ")));
+    }
+    if let Some(depth) = root.max_depth {
+        parts.push(format!("max depth {}", depth));
+    }
+    if !root.follow_symlinks {
+        parts.push("symlinks not followed".to_string());
+    }
+    if root.include_backups {
+        parts.push("backups included".to_string());
+    }
+    if let Some(tag) = &root.default_tag {
+        parts.push(format!("tagged '{}'", tag));
+    }
+    if parts.is_empty() {
+        "Defaults".to_string()
+    } else {
+        parts.join("; ")
+    }
+}
+
 #[derive(Serialize)]
 pub struct ConfigValidationResult {
     pub is_valid: bool,
@@ -22,6 +22,14 @@ impl CliCommand for ProjectCommands {
             ProjectCommands::Restore { id } => self.restore_project(ctx, id).await,
             ProjectCommands::Rescan { id } => self.rescan_project(ctx, id).await,
             ProjectCommands::Stats => self.show_project_stats(ctx).await,
+            ProjectCommands::Status { id, status, clear } => {
+                self.project_status(ctx, id, status.as_deref(), *clear).await
+            }
+            ProjectCommands::Rate { id, stars } => self.rate_project(ctx, id, *stars).await,
+            ProjectCommands::Favorite { id, remove } => self.favorite_project(ctx, id, !*remove).await,
+            ProjectCommands::Gems { min_rating, months, limit } => {
+                self.forgotten_gems(ctx, *min_rating, *months, *limit).await
+            }
         }
     }
 }
@@ -39,21 +47,7 @@ impl ProjectCommands {
         let end = (start + limit).min(projects.len());
         let slice = &projects[start..end];
 
-        let rows: Vec<ProjectRow> = slice
-            .iter()
-            .map(|p| ProjectRow {
-                id: p.id.to_string(),
-                name: p.name.clone(),
-                path: p.file_path.display().to_string(),
-                tempo: p.tempo,
-                key: p
-                    .key_signature
-                    .as_ref()
-                    .map(|k| k.to_string())
-                    .unwrap_or_else(|| "".to_string()),
-                time_signature: format!("{}/{}", p.time_signature.numerator, p.time_signature.denominator),
-            })
-            .collect();
+        let rows: Vec<ProjectRow> = slice.iter().map(ProjectRow::from_live_set).collect();
 
         let data = ProjectsList { total: projects.len(), displayed: rows };
         formatter.print(&data)
@@ -64,7 +58,20 @@ impl ProjectCommands {
         let mut db = ctx.db.lock().await;
         match db.get_project_by_id(id)? {
             Some(p) => {
-                let details = ProjectDetails::from_live_set(&p);
+                let mut details = ProjectDetails::from_live_set(&p);
+                if let Some(engagement) = db.get_project_engagement(id)? {
+                    details.rating = engagement.rating;
+                    details.favorite = engagement.is_favorite;
+                    details.last_opened_at = engagement
+                        .last_opened_at
+                        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                        .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string());
+                    details.open_count = engagement.open_count;
+                }
+                if let Some((kind, folder)) = db.get_project_location(id)? {
+                    details.kind = kind.to_string();
+                    details.project_folder = folder;
+                }
                 formatter.print(&details)
             }
             None => {
@@ -160,6 +167,75 @@ impl ProjectCommands {
         let formatter = OutputFormatter::new(ctx.output_format.clone(), ctx.no_color);
         formatter.print(&display)
     }
+
+    async fn project_status(&self, ctx: &CliContext, id: &str, status: Option<&str>, clear: bool) -> Result<(), CliError> {
+        let mut db = ctx.db.lock().await;
+        let formatter = OutputFormatter::new(ctx.output_format.clone(), ctx.no_color);
+
+        if status.is_some() || clear {
+            db.set_project_status(id, status)?;
+            let message = match status {
+                Some(status) => format!("Project {} moved to {}", id, status),
+                None => format!("Status of project {} cleared", id),
+            };
+            formatter.print_message(&message, crate::cli::output::MessageType::Success);
+        }
+
+        let history = db.get_project_status_history(id)?;
+        let display = StatusHistoryList {
+            current: db.get_project_status(id)?.map(|(status, _)| status),
+            pipeline: db.get_status_pipeline()?,
+            transitions: history
+                .into_iter()
+                .map(|t| StatusTransitionRow {
+                    from: t.from_status.unwrap_or_default(),
+                    to: t.to_status.unwrap_or_default(),
+                    changed_at: chrono::DateTime::from_timestamp(t.changed_at, 0)
+                        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_default(),
+                })
+                .collect(),
+        };
+        formatter.print(&display)
+    }
+
+    async fn rate_project(&self, ctx: &CliContext, id: &str, stars: u8) -> Result<(), CliError> {
+        let mut db = ctx.db.lock().await;
+        let rating = if stars == 0 { None } else { Some(stars as i32) };
+        db.set_project_rating(id, rating)?;
+
+        let formatter = OutputFormatter::new(ctx.output_format.clone(), ctx.no_color);
+        let message = match rating {
+            Some(_) => format!("Project {} rated {}", id, "★".repeat(stars as usize)),
+            None => format!("Rating of project {} cleared", id),
+        };
+        formatter.print_message(&message, crate::cli::output::MessageType::Success);
+        Ok(())
+    }
+
+    async fn favorite_project(&self, ctx: &CliContext, id: &str, is_favorite: bool) -> Result<(), CliError> {
+        let mut db = ctx.db.lock().await;
+        db.set_project_favorite(id, is_favorite)?;
+
+        let formatter = OutputFormatter::new(ctx.output_format.clone(), ctx.no_color);
+        let message = if is_favorite {
+            format!("Project {} added to favorites", id)
+        } else {
+            format!("Project {} removed from favorites", id)
+        };
+        formatter.print_message(&message, crate::cli::output::MessageType::Success);
+        Ok(())
+    }
+
+    async fn forgotten_gems(&self, ctx: &CliContext, min_rating: u8, months: u32, limit: usize) -> Result<(), CliError> {
+        let mut db = ctx.db.lock().await;
+        let gems = db.get_forgotten_gems(min_rating as i32, months, Some(limit as i32))?;
+
+        let rows: Vec<ProjectRow> = gems.iter().map(ProjectRow::from_live_set).collect();
+        let data = ProjectsList { total: rows.len(), displayed: rows };
+        let formatter = OutputFormatter::new(ctx.output_format.clone(), ctx.no_color);
+        formatter.print(&data)
+    }
 }
 
 // Display types
@@ -174,6 +250,23 @@ struct ProjectRow {
     time_signature: String,
 }
 
+impl ProjectRow {
+    fn from_live_set(p: &crate::LiveSet) -> Self {
+        Self {
+            id: p.id.to_string(),
+            name: p.name.clone(),
+            path: p.file_path.display().to_string(),
+            tempo: p.tempo,
+            key: p
+                .key_signature
+                .as_ref()
+                .map(|k| k.to_string())
+                .unwrap_or_default(),
+            time_signature: format!("{}/{}", p.time_signature.numerator, p.time_signature.denominator),
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct ProjectsList {
     total: usize,
@@ -220,6 +313,8 @@ struct ProjectDetails {
     id: String,
     name: String,
     path: String,
+    kind: String,
+    project_folder: Option<String>,
     tempo: f64,
     time_signature: String,
     key: String,
@@ -228,6 +323,10 @@ struct ProjectDetails {
     modified_at: String,
     plugins: usize,
     samples: usize,
+    rating: Option<i32>,
+    favorite: bool,
+    last_opened_at: Option<String>,
+    open_count: i32,
 }
 
 impl ProjectDetails {
@@ -236,6 +335,8 @@ impl ProjectDetails {
             id: p.id.to_string(),
             name: p.name.clone(),
             path: p.file_path.display().to_string(),
+            kind: p.kind().to_string(),
+            project_folder: None,
             tempo: p.tempo,
             time_signature: format!("{}/{}", p.time_signature.numerator, p.time_signature.denominator),
             key: p.key_signature.as_ref().map(|k| k.to_string()).unwrap_or_else(|| "".to_string()),
@@ -244,6 +345,10 @@ impl ProjectDetails {
             modified_at: p.modified_time.format("%Y-%m-%d %H:%M:%S").to_string(),
             plugins: p.plugins.len(),
             samples: p.samples.len(),
+            rating: None,
+            favorite: false,
+            last_opened_at: None,
+            open_count: 0,
         }
     }
 }
@@ -255,6 +360,8 @@ impl TableDisplay for ProjectDetails {
         table.add_row(vec!["ID".to_string(), self.id.clone()]);
         table.add_row(vec!["Name".to_string(), self.name.clone()]);
         table.add_row(vec!["Path".to_string(), self.path.clone()]);
+        table.add_row(vec!["Kind".to_string(), self.kind.clone()]);
+        table.add_row(vec!["Project Folder".to_string(), self.project_folder.clone().unwrap_or_default()]);
         table.add_row(vec!["Tempo".to_string(), format!("{:.1}", self.tempo)]);
         table.add_row(vec!["Time Signature".to_string(), self.time_signature.clone()]);
         table.add_row(vec!["Key".to_string(), self.key.clone()]);
@@ -263,6 +370,13 @@ impl TableDisplay for ProjectDetails {
         table.add_row(vec!["Modified".to_string(), self.modified_at.clone()]);
         table.add_row(vec!["Plugins".to_string(), self.plugins.to_string()]);
         table.add_row(vec!["Samples".to_string(), self.samples.to_string()]);
+        table.add_row(vec![
+            "Rating".to_string(),
+            self.rating.map(|r| "★".repeat(r as usize)).unwrap_or_default(),
+        ]);
+        table.add_row(vec!["Favorite".to_string(), if self.favorite { "Yes" } else { "No" }.to_string()]);
+        table.add_row(vec!["Last Opened".to_string(), self.last_opened_at.clone().unwrap_or_default()]);
+        table.add_row(vec!["Times Opened".to_string(), self.open_count.to_string()]);
         table
     }
 
@@ -271,6 +385,8 @@ impl TableDisplay for ProjectDetails {
         writer.write_record(["id", &self.id]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["name", &self.name]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["path", &self.path]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["kind", &self.kind]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["project_folder", &self.project_folder.clone().unwrap_or_default()]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["tempo", &format!("{:.1}", self.tempo)]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["time_signature", &self.time_signature]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["key", &self.key]).map_err(|e| -> CliError { e.into() })?;
@@ -279,6 +395,10 @@ impl TableDisplay for ProjectDetails {
         writer.write_record(["modified_at", &self.modified_at]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["plugins", &self.plugins.to_string()]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["samples", &self.samples.to_string()]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["rating", &self.rating.map(|r| r.to_string()).unwrap_or_default()]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["favorite", &self.favorite.to_string()]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["last_opened_at", &self.last_opened_at.clone().unwrap_or_default()]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["open_count", &self.open_count.to_string()]).map_err(|e| -> CliError { e.into() })?;
         Ok(())
     }
 }
@@ -354,3 +474,43 @@ impl TableDisplay for ProjectStatisticsDisplay {
         Ok(())
     }
 }
+
+#[derive(Serialize)]
+struct StatusTransitionRow {
+    from: String,
+    to: String,
+    changed_at: String,
+}
+
+#[derive(Serialize)]
+struct StatusHistoryList {
+    current: Option<String>,
+    pipeline: Vec<String>,
+    transitions: Vec<StatusTransitionRow>,
+}
+
+impl TableDisplay for StatusHistoryList {
+    fn to_table(&self) -> Table {
+        let mut table = Table::new();
+        table.set_header(vec!["From", "To", "Changed"]);
+        for row in &self.transitions {
+            table.add_row(vec![row.from.clone(), row.to.clone(), row.changed_at.clone()]);
+        }
+        table.add_row(vec![
+            "Current".to_string(),
+            self.current.clone().unwrap_or_else(|| "-".to_string()),
+            format!("Pipeline: {}", self.pipeline.join(" → ")),
+        ]);
+        table
+    }
+
+    fn to_csv<W: std::io::Write>(&self, writer: &mut csv::Writer<W>) -> Result<(), CliError> {
+        writer.write_record(["from", "to", "changed_at"]).map_err(|e| -> CliError { e.into() })?;
+        for row in &self.transitions {
+            writer
+                .write_record([row.from.as_str(), row.to.as_str(), row.changed_at.as_str()])
+                .map_err(|e| -> CliError { e.into() })?;
+        }
+        Ok(())
+    }
+}
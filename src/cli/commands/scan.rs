@@ -1,13 +1,17 @@
 use crate::cli::commands::CliContext;
-use crate::cli::output::{OutputFormatter, MessageType};
-use crate::cli::CliError;
+use crate::cli::output::{OutputFormatter, MessageType, TableDisplay};
+use crate::cli::{CliError, ScanCommands};
+use crate::colored_cell;
+use crate::database::scan_errors::ScanErrorRecord;
 use crate::database::LiveSetDatabase;
 use crate::error::LiveSetError;
 use crate::live_set::LiveSet;
-use crate::process_projects_with_progress;
-use crate::scan::parallel::ParallelParser;
+use crate::process_projects_with_options;
 use crate::scan::project_scanner::ProjectPathScanner;
+use crate::scan::quarantine::{record_failure, retry_failed_sets};
+use crate::scan::{ScanCounts, ScanOptions, ScanRoots, SCAN_JOBS};
 use comfy_table::Table;
+use serde::Serialize;
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
@@ -29,7 +33,12 @@ impl crate::cli::commands::CliCommand for ScanCommand {
                 MessageType::Info,
             );
 
-            let progress_callback = move |completed: u32, total: u32, progress: f32, message: String, phase: &str| {
+            let progress_callback = move |completed: u32,
+                                          total: u32,
+                                          progress: f32,
+                                          message: String,
+                                          phase: &str,
+                                          _counts: &ScanCounts| {
                 let phase_label = match phase {
                     "starting" => "Starting",
                     "discovering" => "Discovering",
@@ -55,10 +64,11 @@ impl crate::cli::commands::CliCommand for ScanCommand {
                 }
             };
 
-            process_projects_with_progress(Some(progress_callback))
+            let options = ScanOptions { force: self.force };
+            let counts = process_projects_with_options(&options, Some(progress_callback))
                 .map_err(|e| -> CliError { Box::new(e) })?;
 
-            formatter.print_message("\nScan Complete", MessageType::Success);
+            self.display_scan_counts(&formatter, &counts);
             return Ok(());
         }
 
@@ -68,8 +78,11 @@ impl crate::cli::commands::CliCommand for ScanCommand {
             MessageType::Info,
         );
 
+        // Folders under a configured root are scanned with that root's settings
+        let roots = ScanRoots::from_config(ctx.config).map_err(CliError::from)?;
+
         // Discover project files
-        let project_paths = self.discover_project_files().await?;
+        let project_paths = self.discover_project_files(&roots).await?;
         if project_paths.is_empty() {
             formatter.print_message("No .als files found in specified paths", MessageType::Warning);
             return Ok(());
@@ -104,7 +117,7 @@ impl crate::cli::commands::CliCommand for ScanCommand {
         let results = self.process_projects_parallel(paths_to_process).await?;
 
         // Store results in database
-        let (success_count, error_count) = self.store_results(&ctx.db, results).await?;
+        let (success_count, error_count) = self.store_results(&ctx.db, results, &roots).await?;
 
         // Display results
         self.display_scan_results(&formatter, success_count, error_count);
@@ -114,7 +127,7 @@ impl crate::cli::commands::CliCommand for ScanCommand {
 }
 
 impl ScanCommand {
-    async fn discover_project_files(&self) -> Result<Vec<PathBuf>, CliError> {
+    async fn discover_project_files(&self, roots: &ScanRoots) -> Result<Vec<PathBuf>, CliError> {
         let scanner = ProjectPathScanner::new().map_err(|e| CliError::from(e))?;
         let mut all_paths = HashSet::new();
 
@@ -128,7 +141,7 @@ impl ScanCommand {
                 }
             } else if path.is_dir() {
                 // If it's a directory, scan for .als files
-                let paths = scanner.scan_directory(path)?;
+                let paths = scanner.scan_root(&roots.rules_for_dir(path), path)?;
                 all_paths.extend(paths);
             }
         }
@@ -164,14 +177,10 @@ impl ScanCommand {
         paths: Vec<PathBuf>,
     ) -> Result<Vec<Result<(PathBuf, LiveSet), (PathBuf, LiveSetError)>>, CliError> {
         let total = paths.len();
-        let num_threads = (total / 2).max(1).min(4);
-        let parser = ParallelParser::new(num_threads);
-
-        // Submit all paths for processing before receiving
-        parser.submit_paths(paths)?;
+        let job = SCAN_JOBS.start_scan(paths);
 
         // Collect exactly `total` results to avoid blocking forever
-        let results_rx = parser.get_results_receiver();
+        let results_rx = job.results();
         let mut results = Vec::with_capacity(total);
         while results.len() < total {
             let result = results_rx
@@ -190,6 +199,7 @@ impl ScanCommand {
         &self,
         db: &Arc<TokioMutex<LiveSetDatabase>>,
         results: Vec<Result<(PathBuf, LiveSet), (PathBuf, LiveSetError)>>,
+        roots: &ScanRoots,
     ) -> Result<(usize, usize), CliError> {
         let mut db_guard = db.lock().await;
         let mut success_count = 0;
@@ -202,6 +212,9 @@ impl ScanCommand {
                         Ok(_) => {
                             success_count += 1;
                             println!("✓ Stored: {}", path.display());
+                            roots
+                                .apply_default_tags(&mut db_guard, &[(live_set.id.to_string(), path.clone())])
+                                .map_err(|e| -> CliError { Box::new(e) })?;
                         }
                         Err(e) => {
                             error_count += 1;
@@ -212,6 +225,7 @@ impl ScanCommand {
                 Err((path, err)) => {
                     error_count += 1;
                     eprintln!("✗ Failed to parse {}: {}", path.display(), err);
+                    record_failure(&mut db_guard, &path, &err).map_err(|e| -> CliError { Box::new(e) })?;
                 }
             }
         }
@@ -219,6 +233,38 @@ impl ScanCommand {
         Ok((success_count, error_count))
     }
 
+    fn display_scan_counts(&self, formatter: &OutputFormatter, counts: &ScanCounts) {
+        let mut table = Table::new();
+        table
+            .set_header(vec!["Scan Results", "Count"])
+            .load_preset(comfy_table::presets::UTF8_FULL);
+
+        table.add_row(vec!["New".to_string(), counts.new.to_string()]);
+        table.add_row(vec!["Updated".to_string(), counts.updated.to_string()]);
+        table.add_row(vec!["Unchanged (skipped)".to_string(), counts.skipped.to_string()]);
+        table.add_row(vec!["Removed".to_string(), counts.removed.to_string()]);
+
+        if counts.failed > 0 {
+            table.add_row(vec!["Errors".to_string(), counts.failed.to_string()]);
+        }
+        if counts.quarantined > 0 {
+            table.add_row(vec!["Quarantined (skipped)".to_string(), counts.quarantined.to_string()]);
+        }
+
+        formatter.print_message("\nScan Complete", MessageType::Success);
+        println!("{}", table);
+
+        if counts.failed > 0 {
+            formatter.print_message(
+                &format!(
+                    "Encountered {} error(s) during scanning, see `seula scan errors`",
+                    counts.failed
+                ),
+                MessageType::Warning,
+            );
+        }
+    }
+
     fn display_scan_results(&self, formatter: &OutputFormatter, success_count: usize, error_count: usize) {
         let total_processed = success_count + error_count;
 
@@ -262,3 +308,140 @@ impl ScanCommand {
         }
     }
 }
+
+#[async_trait::async_trait]
+impl crate::cli::commands::CliCommand for ScanCommands {
+    async fn execute(&self, ctx: &CliContext) -> Result<(), CliError> {
+        let formatter = OutputFormatter::new(ctx.output_format.clone(), ctx.no_color);
+        match self {
+            ScanCommands::Errors {
+                quarantined,
+                retry,
+                paths,
+            } => {
+                if *retry {
+                    let db = Arc::clone(&ctx.db);
+                    let paths = paths.clone();
+                    let counts =
+                        tokio::task::spawn_blocking(move || retry_failed_sets(&db, &paths))
+                            .await?
+                            .map_err(|e| -> CliError { Box::new(e) })?;
+                    formatter.print_message(
+                        &format!(
+                            "Retried failed sets: {} fixed, {} still failing, {} missing",
+                            counts.fixed, counts.failed, counts.missing
+                        ),
+                        if counts.failed > 0 { MessageType::Warning } else { MessageType::Success },
+                    );
+                }
+
+                let errors = ctx.db.lock().await.get_scan_errors(*quarantined)?;
+                if errors.is_empty() {
+                    formatter.print_message("No scan errors recorded", MessageType::Info);
+                    return Ok(());
+                }
+                formatter.print(&ScanErrorsList::from(errors))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+pub struct ScanErrorRow {
+    pub path: String,
+    pub error_kind: String,
+    pub message: String,
+    pub line: Option<u32>,
+    pub ableton_version: Option<String>,
+    pub failure_count: u32,
+    pub last_failed_at: String,
+    pub quarantined: bool,
+}
+
+#[derive(Serialize)]
+pub struct ScanErrorsList {
+    pub errors: Vec<ScanErrorRow>,
+    pub total_count: usize,
+}
+
+impl From<Vec<ScanErrorRecord>> for ScanErrorsList {
+    fn from(records: Vec<ScanErrorRecord>) -> Self {
+        let errors: Vec<ScanErrorRow> = records
+            .into_iter()
+            .map(|record| ScanErrorRow {
+                quarantined: record.is_quarantined(),
+                path: record.path,
+                error_kind: record.error_kind,
+                message: record.message,
+                line: record.line,
+                ableton_version: record.ableton_version,
+                failure_count: record.failure_count,
+                last_failed_at: chrono::DateTime::from_timestamp(record.last_failed_at, 0)
+                    .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default(),
+            })
+            .collect();
+        Self {
+            total_count: errors.len(),
+            errors,
+        }
+    }
+}
+
+impl TableDisplay for ScanErrorsList {
+    fn to_table(&self) -> Table {
+        let mut table = Table::new();
+        table.set_header(vec!["Path", "Error", "Line", "Live Version", "Failures", "Last Failed", "Status"]);
+
+        for row in &self.errors {
+            let status = if row.quarantined {
+                colored_cell!("Skipped until changed", yellow)
+            } else {
+                "Will retry on next scan".to_string()
+            };
+            table.add_row(vec![
+                row.path.clone(),
+                format!("{}: {}", row.error_kind, row.message),
+                row.line.map(|line| line.to_string()).unwrap_or_default(),
+                row.ableton_version.clone().unwrap_or_default(),
+                row.failure_count.to_string(),
+                row.last_failed_at.clone(),
+                status,
+            ]);
+        }
+
+        table.add_row(vec![
+            format!("Total: {} failed sets", self.total_count),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ]);
+
+        table
+    }
+
+    fn to_csv<W: std::io::Write>(&self, writer: &mut csv::Writer<W>) -> Result<(), CliError> {
+        writer
+            .write_record(["path", "error_kind", "message", "line", "ableton_version", "failure_count", "last_failed_at", "quarantined"])
+            .map_err(|e| -> CliError { e.into() })?;
+        for row in &self.errors {
+            writer
+                .write_record([
+                    row.path.as_str(),
+                    row.error_kind.as_str(),
+                    row.message.as_str(),
+                    &row.line.map(|line| line.to_string()).unwrap_or_default(),
+                    row.ableton_version.as_deref().unwrap_or(""),
+                    &row.failure_count.to_string(),
+                    row.last_failed_at.as_str(),
+                    &row.quarantined.to_string(),
+                ])
+                .map_err(|e| -> CliError { e.into() })?;
+        }
+        Ok(())
+    }
+}
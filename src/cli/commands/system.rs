@@ -1,9 +1,17 @@
 use crate::cli::commands::{CliCommand, CliContext};
-use crate::cli::{SystemCommands, WatchAction};
+use crate::cli::{StatsExportFormat, SystemCommands, WatchAction};
 use crate::cli::CliError;
+use crate::cli::output::sparkline;
+use crate::grpc::common::{ExportFormat, ScanStatus};
+use crate::grpc::handlers::SystemHandler;
+use crate::grpc::system::ExportStatisticsRequest;
+use crate::watcher::events::WatcherEventBus;
 use colored::Colorize;
 use comfy_table::Table;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Mutex;
 
 #[async_trait::async_trait]
 impl CliCommand for SystemCommands {
@@ -11,7 +19,12 @@ impl CliCommand for SystemCommands {
         match self {
             SystemCommands::Info => self.show_info(ctx).await,
             SystemCommands::Stats => self.show_stats(ctx).await,
-            SystemCommands::Export { format, output } => self.export_data(ctx, format, output).await,
+            SystemCommands::Export { format, output } => {
+                self.export_data(ctx, *format, output.as_ref()).await
+            }
+            SystemCommands::Activity { days, session_gap } => {
+                self.show_activity(ctx, *days, *session_gap).await
+            }
             SystemCommands::Watch { action } => self.handle_watch(ctx, action).await,
             SystemCommands::ScanStatus => self.show_scan_status(ctx).await,
         }
@@ -80,19 +93,91 @@ impl SystemCommands {
         Ok(())
     }
 
-    async fn export_data(&self, ctx: &CliContext, format: &str, output: &PathBuf) -> Result<(), CliError> {
-        println!("{}", format!("Exporting system data to {} format...", format).bold());
-
-        // For now, just export basic statistics
-        let db = ctx.db.lock().await;
-        let (projects, _plugins, samples, _collections, _tags, _tasks) =
-            db.get_basic_counts().unwrap_or((0, 0, 0, 0, 0, 0));
+    async fn export_data(
+        &self,
+        ctx: &CliContext,
+        format: StatsExportFormat,
+        output: Option<&PathBuf>,
+    ) -> Result<(), CliError> {
+        println!("{}", format!("Exporting statistics as {:?}...", format).bold());
+
+        // Exports are rendered by the gRPC handler so they match what clients get
+        let handler = SystemHandler::new(
+            Arc::clone(&ctx.db),
+            Arc::new(Mutex::new(ScanStatus::ScanUnknown)),
+            Arc::new(Mutex::new(None)),
+            Arc::new(Mutex::new(None)),
+            Arc::new(WatcherEventBus::default()),
+            Instant::now(),
+        );
+        let format = match format {
+            StatsExportFormat::Json => ExportFormat::ExportJson,
+            StatsExportFormat::Csv => ExportFormat::ExportCsv,
+            StatsExportFormat::Markdown => ExportFormat::ExportMarkdown,
+            StatsExportFormat::Html => ExportFormat::ExportHtml,
+        };
+        let export = handler
+            .export_statistics(tonic::Request::new(ExportStatisticsRequest {
+                format: format as i32,
+                filters: None,
+            }))
+            .await
+            .map_err(|status| status.message().to_string())?
+            .into_inner();
+
+        let output = output.cloned().unwrap_or_else(|| PathBuf::from(&export.filename));
+        std::fs::write(&output, &export.data)?;
+
+        println!("{}", format!("Statistics exported to: {}", output.display()).green());
+        Ok(())
+    }
 
-        let data = format!("Projects: {}, Samples: {}", projects, samples);
+    async fn show_activity(&self, ctx: &CliContext, days: u32, session_gap: u32) -> Result<(), CliError> {
+        if days == 0 || session_gap == 0 {
+            return Err("--days and --session-gap must be at least 1".into());
+        }
+        println!("{}", format!("Activity over the last {} days", days).bold().underline());
 
-        std::fs::write(output, &data)?;
+        let db = ctx.db.lock().await;
+        let analytics = db.get_activity_analytics(days, session_gap as i64)?;
+
+        let saves: Vec<f64> = analytics.days.iter().map(|day| day.saves as f64).collect();
+        let sessions: Vec<f64> = analytics.days.iter().map(|day| day.sessions as f64).collect();
+        let hours: Vec<f64> = analytics.days.iter().map(|day| day.seconds as f64 / 3600.0).collect();
+        let total_hours = analytics.total_seconds() as f64 / 3600.0;
+        println!("Saves     {} {}", sparkline(&saves), analytics.total_saves());
+        println!("Sessions  {} {}", sparkline(&sessions), analytics.sessions.len());
+        println!("Hours     {} {:.1}", sparkline(&hours), total_hours);
+        if let (Some(first), Some(last)) = (analytics.days.first(), analytics.days.last()) {
+            println!("          {} to {}", first.date, last.date);
+        }
+        if !analytics.sessions.is_empty() {
+            println!(
+                "Average session: {:.0} minutes",
+                total_hours * 60.0 / analytics.sessions.len() as f64
+            );
+        }
 
-        println!("{}", format!("Data exported to: {}", output.display()).green());
+        if analytics.projects.is_empty() {
+            return Ok(());
+        }
+        if let Some(median) = analytics.median_project_seconds() {
+            println!("Median project: {:.1} days from first save to last", median as f64 / 86400.0);
+        }
+        let mut table = Table::new();
+        table
+            .set_header(vec!["Project", "First Save", "Last Save", "Days", "Saves"])
+            .load_preset(comfy_table::presets::UTF8_FULL);
+        for project in analytics.projects.iter().take(10) {
+            table.add_row(vec![
+                project.name.clone(),
+                format_date(project.first_save_at),
+                format_date(project.last_save_at),
+                format!("{:.1}", project.seconds() as f64 / 86400.0),
+                project.saves.to_string(),
+            ]);
+        }
+        println!("{}", table);
         Ok(())
     }
 
@@ -116,3 +201,9 @@ impl SystemCommands {
         Ok(())
     }
 }
+
+fn format_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
@@ -1,7 +1,7 @@
 use crate::cli::commands::{CliCommand, CliContext};
 use crate::cli::output::{MessageType, OutputFormatter, TableDisplay};
 use crate::cli::{CliError, TagCommands};
-use crate::database::tags::{TagStatistics, TagUsageInfo};
+use crate::database::tags::{TagStatistics, TagUsageInfo, TAG_PATH_SEPARATOR};
 use crate::database::LiveSetDatabase;
 use crate::live_set::LiveSet;
 use crate::{colored_cell, table_row};
@@ -28,12 +28,14 @@ impl CliCommand for TagCommands {
         let formatter = OutputFormatter::new(ctx.output_format.clone(), ctx.no_color);
 
         match self {
-            TagCommands::List => {
-                let tags_list = self.get_tags_list(&ctx.db).await?;
+            TagCommands::List { group } => {
+                let tags_list = self.get_tags_list(&ctx.db, group.as_deref()).await?;
                 formatter.print(&tags_list)?;
             }
-            TagCommands::Create { name, color } => {
-                let create_result = self.create_tag(&ctx.db, name, color.as_deref()).await?;
+            TagCommands::Create { name, color, parent } => {
+                let create_result = self
+                    .create_tag(&ctx.db, name, color.as_deref(), parent.as_deref())
+                    .await?;
                 formatter.print(&create_result)?;
             }
             TagCommands::Assign { project_id, tag_id } => {
@@ -58,16 +60,19 @@ impl TagCommands {
     async fn get_tags_list(
         &self,
         db: &Arc<TokioMutex<LiveSetDatabase>>,
+        group: Option<&str>,
     ) -> Result<TagsList, CliError> {
         let mut db_guard = db.lock().await;
-        let (tags, total_count) = db_guard.get_all_tags_with_usage(None, None, None, None, None)?;
+        let (tags, total_count) = db_guard.get_all_tags_with_usage(None, None, None, None, None, group)?;
 
         let displayed = tags
             .into_iter()
             .map(|tag| TagRow {
                 id: tag.tag_id[..8].to_string(), // Show only first 8 chars of UUID
                 name: tag.name,
+                color: tag.color,
                 project_count: tag.project_count,
+                total_project_count: tag.total_project_count,
                 usage_percentage: format!("{:.1}%", tag.usage_percentage),
             })
             .collect();
@@ -82,14 +87,30 @@ impl TagCommands {
         &self,
         db: &Arc<TokioMutex<LiveSetDatabase>>,
         name: &str,
-        _color: Option<&str>, // Color is not stored in current database schema
+        color: Option<&str>,
+        parent_id: Option<&str>,
     ) -> Result<TagCreateResult, CliError> {
         let mut db_guard = db.lock().await;
-        let tag_id = db_guard.add_tag(name)?;
+
+        let name = match parent_id {
+            Some(parent_id) => match db_guard.get_tag_by_id(parent_id)? {
+                Some(parent) => format!("{}{}{}", parent.name, TAG_PATH_SEPARATOR, name),
+                None => {
+                    return Err(Box::new(std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("Tag {} not found", parent_id)
+                    )) as CliError);
+                }
+            },
+            None => name.to_string(),
+        };
+
+        let tag_id = db_guard.add_tag_with_color(&name, color)?;
 
         Ok(TagCreateResult {
             id: tag_id[..8].to_string(),
-            name: name.to_string(),
+            name,
+            color: color.map(str::to_string),
             success: true,
         })
     }
@@ -105,7 +126,7 @@ impl TagCommands {
         // Verify tag exists
         let tag_data = db_guard.get_tag_by_id(tag_id)?;
         let tag_name = match tag_data {
-            Some((_, name, _)) => name,
+            Some(tag) => tag.name,
             None => {
                 return Err(Box::new(std::io::Error::new(
                     std::io::ErrorKind::NotFound,
@@ -136,7 +157,7 @@ impl TagCommands {
         // Verify tag exists
         let tag_data = db_guard.get_tag_by_id(tag_id)?;
         let tag_name = match tag_data {
-            Some((_, name, _)) => name,
+            Some(tag) => tag.name,
             None => {
                 return Err(Box::new(std::io::Error::new(
                     std::io::ErrorKind::NotFound,
@@ -178,14 +199,14 @@ impl TagCommands {
                     total_count: 0,
                 });
             }
-            tags[0].0.clone() // Use the first matching tag's ID
+            tags[0].id.clone() // Use the first matching tag's ID
         };
 
         // Get the tag info for display
         let tag_data = db_guard.get_tag_by_id(&tag_id)?;
-        let tag_name = tag_data.as_ref().map(|(_, name, _)| name.clone());
+        let tag_name = tag_data.map(|tag| tag.name);
 
-        // Get projects with this tag
+        // Get projects with this tag or any of its child tags
         let projects = db_guard.get_projects_by_tag(&tag_id)?;
         let project_rows = projects
             .into_iter()
@@ -213,7 +234,9 @@ impl TagCommands {
 pub struct TagRow {
     pub id: String,
     pub name: String,
+    pub color: Option<String>,
     pub project_count: i32,
+    pub total_project_count: i32,
     pub usage_percentage: String,
 }
 
@@ -226,7 +249,7 @@ pub struct TagsList {
 impl TableDisplay for TagsList {
     fn to_table(&self) -> Table {
         let mut table = Table::new();
-        table.set_header(vec!["ID", "Name", "Projects", "Usage %"]);
+        table.set_header(vec!["ID", "Name", "Color", "Projects", "Incl. Children", "Usage %"]);
 
         for row in &self.displayed {
             let usage_cell = if row.project_count > 0 {
@@ -238,7 +261,9 @@ impl TableDisplay for TagsList {
             table.add_row(vec![
                 &row.id,
                 &row.name,
+                row.color.as_deref().unwrap_or(""),
                 &row.project_count.to_string(),
+                &row.total_project_count.to_string(),
                 &usage_cell,
             ]);
         }
@@ -249,19 +274,23 @@ impl TableDisplay for TagsList {
             &format!("Total: {} tags", self.total_count),
             "",
             "",
+            "",
+            "",
         ]);
 
         table
     }
 
     fn to_csv<W: std::io::Write>(&self, writer: &mut csv::Writer<W>) -> Result<(), CliError> {
-        writer.write_record(["id", "name", "project_count", "usage_percentage"]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["id", "name", "color", "project_count", "total_project_count", "usage_percentage"]).map_err(|e| -> CliError { e.into() })?;
         for row in &self.displayed {
             writer
                 .write_record([
                     row.id.as_str(),
                     row.name.as_str(),
+                    row.color.as_deref().unwrap_or(""),
                     &row.project_count.to_string(),
+                    &row.total_project_count.to_string(),
                     row.usage_percentage.as_str(),
                 ])
                 .map_err(|e| -> CliError { e.into() })?;
@@ -274,6 +303,7 @@ impl TableDisplay for TagsList {
 pub struct TagCreateResult {
     pub id: String,
     pub name: String,
+    pub color: Option<String>,
     pub success: bool,
 }
 
@@ -291,6 +321,9 @@ impl TableDisplay for TagCreateResult {
         table_row!(table, "Result", result_cell);
         table_row!(table, "ID", self.id);
         table_row!(table, "Name", self.name);
+        if let Some(ref color) = self.color {
+            table_row!(table, "Color", color);
+        }
 
         table
     }
@@ -300,6 +333,7 @@ impl TableDisplay for TagCreateResult {
         writer.write_record(["result", if self.success { "Tag Created" } else { "Failed" }]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["id", &self.id]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["name", &self.name]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["color", self.color.as_deref().unwrap_or("")]).map_err(|e| -> CliError { e.into() })?;
         Ok(())
     }
 }
@@ -1,9 +1,10 @@
 use crate::cli::commands::{CliCommand, CliContext};
 use crate::cli::output::{MessageType, OutputFormatter, TableDisplay};
 use crate::cli::{CliError, TaskCommands};
-use crate::database::tasks::TaskAnalytics;
+use crate::database::tasks::{ArrangementPosition, ProjectTask, TaskAnalytics, TaskDetails};
 use crate::database::LiveSetDatabase;
 use crate::{colored_cell, table_row};
+use chrono::{Local, NaiveDate, TimeZone};
 use colored::Colorize;
 use comfy_table::Table;
 use serde::Serialize;
@@ -16,7 +17,7 @@ pub struct TaskCommand;
 impl CliCommand for TaskCommand {
     async fn execute(&self, _ctx: &CliContext) -> Result<(), CliError> {
         // This is a placeholder command. Use TaskCommands for actual functionality.
-        println!("Use 'seula task list', 'seula task create', 'seula task complete', or 'seula task delete' for task operations");
+        println!("Use 'seula task list', 'seula task create', 'seula task complete', 'seula task move', 'seula task overdue', or 'seula task delete' for task operations");
         Ok(())
     }
 }
@@ -27,14 +28,34 @@ impl CliCommand for TaskCommands {
         let formatter = OutputFormatter::new(ctx.output_format.clone(), ctx.no_color);
 
         match self {
-            TaskCommands::List { project_id, completed } => {
-                let tasks_list = self.get_tasks_list(&ctx.db, project_id.as_deref(), *completed).await?;
+            TaskCommands::List { project_id, completed, arrangement } => {
+                let tasks_list = self
+                    .get_tasks_list(&ctx.db, project_id.as_deref(), *completed, *arrangement)
+                    .await?;
                 formatter.print(&tasks_list)?;
             }
-            TaskCommands::Create { project_id, description, priority } => {
-                let create_result = self.create_task(&ctx.db, project_id, description, *priority).await?;
+            TaskCommands::Create { project_id, description, priority, due, assignee, at, track } => {
+                let details = TaskDetails {
+                    priority: *priority as i32,
+                    due_date: due.as_deref().map(parse_due_date).transpose()?,
+                    assignee: assignee.clone(),
+                    arrangement_position: at
+                        .as_deref()
+                        .map(|at| at.parse::<ArrangementPosition>())
+                        .transpose()?,
+                    track_name: track.clone(),
+                };
+                let create_result = self.create_task(&ctx.db, project_id, description, details).await?;
                 formatter.print(&create_result)?;
             }
+            TaskCommands::Move { id, position } => {
+                let move_result = self.move_task(&ctx.db, id, *position).await?;
+                formatter.print(&move_result)?;
+            }
+            TaskCommands::Overdue => {
+                let tasks_list = self.get_overdue_tasks(&ctx.db).await?;
+                formatter.print(&tasks_list)?;
+            }
             TaskCommands::Complete { id } => {
                 let complete_result = self.complete_task(&ctx.db, id).await?;
                 formatter.print(&complete_result)?;
@@ -55,16 +76,21 @@ impl TaskCommands {
         db: &Arc<TokioMutex<LiveSetDatabase>>,
         project_id: Option<&str>,
         show_completed: bool,
+        by_arrangement: bool,
     ) -> Result<TasksList, CliError> {
         let mut db_guard = db.lock().await;
         
         let tasks = if let Some(pid) = project_id {
             // Get tasks for specific project
-            let all_tasks = db_guard.get_project_tasks(pid)?;
+            let all_tasks = if by_arrangement {
+                db_guard.get_project_tasks_by_arrangement(pid)?
+            } else {
+                db_guard.get_project_tasks(pid)?
+            };
             if show_completed {
                 all_tasks
             } else {
-                all_tasks.into_iter().filter(|(_, _, completed, _)| !completed).collect()
+                all_tasks.into_iter().filter(|task| !task.completed).collect()
             }
         } else {
             // Get all tasks from all projects (this would require a new database method)
@@ -75,15 +101,7 @@ impl TaskCommands {
             )) as CliError);
         };
 
-        let displayed = tasks
-            .into_iter()
-            .map(|(id, description, completed, created_at)| TaskRow {
-                id: id[..8].to_string(), // Show only first 8 chars of UUID
-                description,
-                status: if completed { "Completed" } else { "Pending" },
-                created_at,
-            })
-            .collect::<Vec<_>>();
+        let displayed = tasks.into_iter().map(TaskRow::from).collect::<Vec<_>>();
 
         let total_count = displayed.len();
 
@@ -92,6 +110,29 @@ impl TaskCommands {
             total_count,
             project_id: project_id.map(|s| s.to_string()),
             show_completed,
+            overdue_only: false,
+        })
+    }
+
+    async fn get_overdue_tasks(
+        &self,
+        db: &Arc<TokioMutex<LiveSetDatabase>>,
+    ) -> Result<TasksList, CliError> {
+        let mut db_guard = db.lock().await;
+        let displayed = db_guard
+            .get_overdue_tasks()?
+            .into_iter()
+            .map(TaskRow::from)
+            .collect::<Vec<_>>();
+
+        let total_count = displayed.len();
+
+        Ok(TasksList {
+            displayed,
+            total_count,
+            project_id: None,
+            show_completed: false,
+            overdue_only: true,
         })
     }
 
@@ -100,19 +141,54 @@ impl TaskCommands {
         db: &Arc<TokioMutex<LiveSetDatabase>>,
         project_id: &str,
         description: &str,
-        _priority: u8, // Priority is not stored in current database schema
+        details: TaskDetails,
     ) -> Result<TaskCreateResult, CliError> {
         let mut db_guard = db.lock().await;
-        let task_id = db_guard.add_task(project_id, description)?;
+        let task_id = db_guard.add_task_with_details(project_id, description, &details)?;
 
         Ok(TaskCreateResult {
             id: task_id[..8].to_string(),
             project_id: project_id[..8].to_string(),
             description: description.to_string(),
+            priority: details.priority,
+            due_date: details.due_date,
+            assignee: details.assignee,
+            at: details.arrangement_position.map(|p| p.to_string()),
+            track_name: details.track_name,
             success: true,
         })
     }
 
+    async fn move_task(
+        &self,
+        db: &Arc<TokioMutex<LiveSetDatabase>>,
+        task_id: &str,
+        position: i32,
+    ) -> Result<TaskActionResult, CliError> {
+        let mut db_guard = db.lock().await;
+
+        let task_data = db_guard.get_task(task_id)?;
+        match task_data {
+            Some(task) => {
+                db_guard.reorder_task(task_id, position)?;
+                Ok(TaskActionResult {
+                    id: task_id[..8].to_string(),
+                    project_id: task.project_id[..8].to_string(),
+                    description: task.description,
+                    action: "Move".to_string(),
+                    success: true,
+                    message: format!("Task moved to position {}", position),
+                })
+            }
+            None => {
+                Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Task {} not found", task_id)
+                )) as CliError)
+            }
+        }
+    }
+
     async fn complete_task(
         &self,
         db: &Arc<TokioMutex<LiveSetDatabase>>,
@@ -123,7 +199,7 @@ impl TaskCommands {
         // Verify task exists and get its details
         let task_data = db_guard.get_task(task_id)?;
         match task_data {
-            Some((_, project_id, description, already_completed, _)) => {
+            Some(ProjectTask { project_id, description, completed: already_completed, .. }) => {
                 if already_completed {
                     Ok(TaskActionResult {
                         id: task_id[..8].to_string(),
@@ -164,7 +240,7 @@ impl TaskCommands {
         // Verify task exists and get its details
         let task_data = db_guard.get_task(task_id)?;
         match task_data {
-            Some((_, project_id, description, _, _)) => {
+            Some(ProjectTask { project_id, description, .. }) => {
                 db_guard.remove_task(task_id)?;
                 Ok(TaskActionResult {
                     id: task_id[..8].to_string(),
@@ -185,12 +261,84 @@ impl TaskCommands {
     }
 }
 
+/// Parse a `YYYY-MM-DD` due date into a timestamp at the end of that local day
+fn parse_due_date(date: &str) -> Result<i64, CliError> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| -> CliError {
+        Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid due date '{}' (expected YYYY-MM-DD): {}", date, e),
+        ))
+    })?;
+    let end_of_day = date.and_hms_opt(23, 59, 59).expect("valid time of day");
+    Local
+        .from_local_datetime(&end_of_day)
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .ok_or_else(|| -> CliError {
+            Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Due date '{}' does not exist in the local timezone", date),
+            ))
+        })
+}
+
+/// Format seconds as m:ss for display next to arrangement positions
+fn format_seconds(seconds: f64) -> String {
+    let total = seconds.round() as i64;
+    format!("{}:{:02}", total / 60, total % 60)
+}
+
+fn format_date(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
 #[derive(Serialize)]
 pub struct TaskRow {
     pub id: String,
+    pub project_id: String,
+    pub position: i32,
+    pub priority: i32,
     pub description: String,
     pub status: &'static str,
+    pub due_date: Option<i64>,
+    pub assignee: Option<String>,
     pub created_at: i64,
+    pub completed_at: Option<i64>,
+    pub at: Option<String>,
+    pub arrangement_seconds: Option<f64>,
+    pub track_name: Option<String>,
+}
+
+impl From<ProjectTask> for TaskRow {
+    fn from(task: ProjectTask) -> Self {
+        let overdue = !task.completed
+            && task.due_date.is_some_and(|due| due < Local::now().timestamp());
+        TaskRow {
+            id: task.id[..8].to_string(), // Show only first 8 chars of UUID
+            project_id: task.project_id[..8].to_string(),
+            position: task.position,
+            priority: task.priority,
+            description: task.description,
+            status: if task.completed {
+                "Completed"
+            } else if overdue {
+                "Overdue"
+            } else {
+                "Pending"
+            },
+            due_date: task.due_date,
+            assignee: task.assignee,
+            created_at: task.created_at,
+            completed_at: task.completed_at,
+            at: task.arrangement_position.map(|p| p.to_string()),
+            arrangement_seconds: task.arrangement_seconds,
+            track_name: task.track_name,
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -199,17 +347,19 @@ pub struct TasksList {
     pub total_count: usize,
     pub project_id: Option<String>,
     pub show_completed: bool,
+    pub overdue_only: bool,
 }
 
 impl TableDisplay for TasksList {
     fn to_table(&self) -> Table {
         let mut table = Table::new();
-        table.set_header(vec!["ID", "Description", "Status", "Created"]);
+        table.set_header(vec!["#", "ID", "Priority", "Description", "At", "Track", "Status", "Due", "Assignee", "Created"]);
 
         for row in &self.displayed {
             let status_cell = match row.status {
                 "Completed" => colored_cell!("Completed", green),
                 "Pending" => colored_cell!("Pending", yellow),
+                "Overdue" => colored_cell!("Overdue", red),
                 _ => row.status.to_string(),
             };
 
@@ -218,10 +368,24 @@ impl TableDisplay for TasksList {
                 .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
                 .unwrap_or_else(|| "Unknown".to_string());
 
+            let due_date = row.due_date.map(format_date).unwrap_or_default();
+
+            let at = match (&row.at, row.arrangement_seconds) {
+                (Some(at), Some(seconds)) => format!("{} ({})", at, format_seconds(seconds)),
+                (Some(at), None) => at.clone(),
+                _ => String::new(),
+            };
+
             table.add_row(vec![
+                &row.position.to_string(),
                 &row.id,
+                &row.priority.to_string(),
                 &row.description,
+                &at,
+                row.track_name.as_deref().unwrap_or(""),
                 &status_cell,
+                &due_date,
+                row.assignee.as_deref().unwrap_or(""),
                 &created_date,
             ]);
         }
@@ -233,32 +397,54 @@ impl TableDisplay for TasksList {
             "All Projects".to_string()
         };
         
-        let status_info = if self.show_completed {
+        let status_info = if self.overdue_only {
+            "Overdue Tasks".to_string()
+        } else if self.show_completed {
             "All Tasks".to_string()
         } else {
             "Pending Tasks".to_string()
         };
 
         table.add_row(vec![
+            "",
+            "",
             "",
             &format!("Total: {} tasks", self.total_count),
+            "",
+            "",
             &format!("{} | {}", project_info, status_info),
             "",
+            "",
+            "",
         ]);
 
         table
     }
 
     fn to_csv<W: std::io::Write>(&self, writer: &mut csv::Writer<W>) -> Result<(), CliError> {
-        writer.write_record(["id", "description", "status", "created_at", "project_id"]).map_err(|e| -> CliError { e.into() })?;
+        writer
+            .write_record([
+                "id", "position", "priority", "description", "status", "due_date", "assignee",
+                "created_at", "completed_at", "arrangement_position", "arrangement_seconds",
+                "track_name", "project_id",
+            ])
+            .map_err(|e| -> CliError { e.into() })?;
         for row in &self.displayed {
             writer
                 .write_record([
                     row.id.as_str(),
+                    &row.position.to_string(),
+                    &row.priority.to_string(),
                     row.description.as_str(),
                     row.status,
+                    &row.due_date.map(|d| d.to_string()).unwrap_or_default(),
+                    row.assignee.as_deref().unwrap_or(""),
                     &row.created_at.to_string(),
-                    self.project_id.as_deref().unwrap_or(""),
+                    &row.completed_at.map(|d| d.to_string()).unwrap_or_default(),
+                    row.at.as_deref().unwrap_or(""),
+                    &row.arrangement_seconds.map(|s| format!("{:.3}", s)).unwrap_or_default(),
+                    row.track_name.as_deref().unwrap_or(""),
+                    row.project_id.as_str(),
                 ])
                 .map_err(|e| -> CliError { e.into() })?;
         }
@@ -271,6 +457,11 @@ pub struct TaskCreateResult {
     pub id: String,
     pub project_id: String,
     pub description: String,
+    pub priority: i32,
+    pub due_date: Option<i64>,
+    pub assignee: Option<String>,
+    pub at: Option<String>,
+    pub track_name: Option<String>,
     pub success: bool,
 }
 
@@ -289,6 +480,19 @@ impl TableDisplay for TaskCreateResult {
         table_row!(table, "Task ID", self.id);
         table_row!(table, "Project ID", self.project_id);
         table_row!(table, "Description", self.description);
+        table_row!(table, "Priority", self.priority);
+        if let Some(due_date) = self.due_date {
+            table_row!(table, "Due", format_date(due_date));
+        }
+        if let Some(ref assignee) = self.assignee {
+            table_row!(table, "Assignee", assignee);
+        }
+        if let Some(ref at) = self.at {
+            table_row!(table, "At", at);
+        }
+        if let Some(ref track_name) = self.track_name {
+            table_row!(table, "Track", track_name);
+        }
 
         table
     }
@@ -299,6 +503,11 @@ impl TableDisplay for TaskCreateResult {
         writer.write_record(["task_id", &self.id]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["project_id", &self.project_id]).map_err(|e| -> CliError { e.into() })?;
         writer.write_record(["description", &self.description]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["priority", &self.priority.to_string()]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["due_date", &self.due_date.map(|d| d.to_string()).unwrap_or_default()]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["assignee", self.assignee.as_deref().unwrap_or("")]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["arrangement_position", self.at.as_deref().unwrap_or("")]).map_err(|e| -> CliError { e.into() })?;
+        writer.write_record(["track_name", self.track_name.as_deref().unwrap_or("")]).map_err(|e| -> CliError { e.into() })?;
         Ok(())
     }
 }
@@ -173,13 +173,17 @@ impl InteractiveCli {
         println!("  {}", "search <query> [--limit N] [--offset N]".italic());
         println!("  {}", "project list [--deleted] [--limit=50]".italic());
         println!("  {}", "project show <id>".italic());
+        println!("  {}", "project status <id> [status] [--clear]".italic());
+        println!("  {}", "project rate <id> <0-5>".italic());
+        println!("  {}", "project gems [--min-rating=4] [--months=6]".italic());
         println!("  {}", "sample list [--limit=50] [--offset=0]".italic());
         println!("  {}", "sample search <query> [--limit=50]".italic());
         println!("  {}", "collection list".italic());
         println!("  {}", "collection create <name> [description]".italic());
         println!("  {}", "tag list".italic());
         println!("  {}", "tag create <name> [--color=hex]".italic());
-        println!("  {}", "task list [--project=id] [--completed]".italic());
+        println!("  {}", "task list [--project=id] [--completed] [--arrangement]".italic());
+        println!("  {}", "task overdue".italic());
         println!("  {}", "plugin list [--vendor=name] [--installed]".italic());
         println!("  {}", "plugin search <query> [--format=VST3]".italic());
         println!("  {}", "config show".italic());
@@ -227,6 +231,29 @@ impl InteractiveCli {
         // This gives us more control over the interactive experience and error handling
         // Each command has its own argument parser that matches the clap-based CLI interface
         match args[0] {
+            "scan" if args.get(1) == Some(&"errors") => {
+                use crate::cli::commands::CliCommand;
+                use crate::cli::ScanCommands;
+
+                // parse flags and paths: scan errors [--quarantined] [--retry] [PATH ...]
+                let mut quarantined = false;
+                let mut retry = false;
+                let mut paths: Vec<std::path::PathBuf> = Vec::new();
+                for &arg in &args[2..] {
+                    match arg {
+                        "--quarantined" => quarantined = true,
+                        "--retry" => retry = true,
+                        _ => paths.push(std::path::PathBuf::from(arg)),
+                    }
+                }
+
+                let cmd = ScanCommands::Errors {
+                    quarantined,
+                    retry,
+                    paths,
+                };
+                cmd.execute(&self.context).await?;
+            }
             "scan" => {
                 use crate::cli::commands::{CliCommand, ScanCommand};
 
@@ -300,7 +327,7 @@ impl InteractiveCli {
             }
             "project" => {
                 if args.len() < 2 {
-                    println!("{}", "Usage: project <list|show|update|delete|restore|rescan|stats> [OPTIONS]".red());
+                    println!("{}", "Usage: project <list|show|update|delete|restore|rescan|stats|status|rate|favorite|gems> [OPTIONS]".red());
                     return Ok(());
                 }
                 
@@ -337,8 +364,58 @@ impl InteractiveCli {
                         ProjectCommands::Show { id: args[2].to_string() }
                     }
                     "stats" => ProjectCommands::Stats,
+                    "status" => {
+                        if args.len() < 3 {
+                            println!("{}", "Usage: project status <id> [status] [--clear]".red());
+                            return Ok(());
+                        }
+                        let clear = args[3..].contains(&"--clear");
+                        let status = args[3..].iter().find(|arg| !arg.starts_with("--")).map(|s| s.to_string());
+                        ProjectCommands::Status { id: args[2].to_string(), status, clear }
+                    }
+                    "rate" => {
+                        let stars = args.get(3).and_then(|s| s.parse::<u8>().ok()).filter(|s| *s <= 5);
+                        match stars {
+                            Some(stars) => ProjectCommands::Rate { id: args[2].to_string(), stars },
+                            None => {
+                                println!("{}", "Usage: project rate <id> <0-5>".red());
+                                return Ok(());
+                            }
+                        }
+                    }
+                    "favorite" => {
+                        if args.len() < 3 {
+                            println!("{}", "Usage: project favorite <id> [--remove]".red());
+                            return Ok(());
+                        }
+                        let remove = args[3..].contains(&"--remove");
+                        ProjectCommands::Favorite { id: args[2].to_string(), remove }
+                    }
+                    "gems" => {
+                        let mut min_rating = 4;
+                        let mut months = 6;
+                        let mut limit = 20;
+
+                        for &arg in &args[2..] {
+                            if arg.starts_with("--min-rating=") {
+                                if let Ok(v) = arg.split('=').nth(1).unwrap_or("").parse::<u8>() {
+                                    min_rating = v;
+                                }
+                            } else if arg.starts_with("--months=") {
+                                if let Ok(v) = arg.split('=').nth(1).unwrap_or("").parse::<u32>() {
+                                    months = v;
+                                }
+                            } else if arg.starts_with("--limit=") {
+                                if let Ok(v) = arg.split('=').nth(1).unwrap_or("").parse::<usize>() {
+                                    limit = v;
+                                }
+                            }
+                        }
+
+                        ProjectCommands::Gems { min_rating, months, limit }
+                    }
                     _ => {
-                        println!("{}", format!("Unknown project subcommand: {}. Available: list, show, stats", args[1]).red());
+                        println!("{}", format!("Unknown project subcommand: {}. Available: list, show, stats, status, rate, favorite, gems", args[1]).red());
                         return Ok(());
                     }
                 };
@@ -451,22 +528,35 @@ impl InteractiveCli {
                 use crate::cli::{TagCommands, CliCommand};
                 
                 let subcommand = match args[1] {
-                    "list" => TagCommands::List,
+                    "list" => {
+                        let mut group = None;
+                        
+                        for &arg in &args[2..] {
+                            if arg.starts_with("--group=") {
+                                group = Some(arg.split('=').nth(1).unwrap_or("").to_string());
+                            }
+                        }
+                        
+                        TagCommands::List { group }
+                    }
                     "create" => {
                         if args.len() < 3 {
-                            println!("{}", "Usage: tag create <name> [--color=hex]".red());
+                            println!("{}", "Usage: tag create <name> [--color=hex] [--parent=id]".red());
                             return Ok(());
                         }
                         let name = args[2].to_string();
                         let mut color = None;
+                        let mut parent = None;
                         
                         for &arg in &args[3..] {
                             if arg.starts_with("--color=") {
                                 color = Some(arg.split('=').nth(1).unwrap_or("").to_string());
+                            } else if arg.starts_with("--parent=") {
+                                parent = Some(arg.split('=').nth(1).unwrap_or("").to_string());
                             }
                         }
                         
-                        TagCommands::Create { name, color }
+                        TagCommands::Create { name, color, parent }
                     }
                     "search" => {
                         if args.len() < 3 {
@@ -485,7 +575,7 @@ impl InteractiveCli {
             }
             "task" => {
                 if args.len() < 2 {
-                    println!("{}", "Usage: task <list|create|complete|delete> [OPTIONS]".red());
+                    println!("{}", "Usage: task <list|create|complete|move|overdue|delete> [OPTIONS]".red());
                     return Ok(());
                 }
                 
@@ -495,36 +585,66 @@ impl InteractiveCli {
                     "list" => {
                         let mut project_id = None;
                         let mut completed = false;
+                        let mut arrangement = false;
                         
                         for &arg in &args[2..] {
                             if arg == "--completed" {
                                 completed = true;
+                            } else if arg == "--arrangement" {
+                                arrangement = true;
                             } else if arg.starts_with("--project=") {
                                 project_id = Some(arg.split('=').nth(1).unwrap_or("").to_string());
                             }
                         }
                         
-                        TaskCommands::List { project_id, completed }
+                        TaskCommands::List { project_id, completed, arrangement }
                     }
                     "create" => {
                         if args.len() < 4 {
-                            println!("{}", "Usage: task create <project_id> <description> [--priority=1-5]".red());
+                            println!("{}", "Usage: task create <project_id> <description> [--priority=1-5] [--due=YYYY-MM-DD] [--assignee=name] [--at=bar.beat] [--track=name]".red());
                             return Ok(());
                         }
                         let project_id = args[2].to_string();
                         let description = args[3].to_string();
                         let mut priority = 3u8; // Default priority
+                        let mut due = None;
+                        let mut assignee = None;
+                        let mut at = None;
+                        let mut track = None;
                         
                         for &arg in &args[4..] {
                             if arg.starts_with("--priority=") {
                                 if let Ok(p) = arg.split('=').nth(1).unwrap_or("").parse::<u8>() {
                                     priority = p.clamp(1, 5);
                                 }
+                            } else if arg.starts_with("--due=") {
+                                due = Some(arg.split('=').nth(1).unwrap_or("").to_string());
+                            } else if arg.starts_with("--assignee=") {
+                                assignee = Some(arg.split('=').nth(1).unwrap_or("").to_string());
+                            } else if arg.starts_with("--at=") {
+                                at = Some(arg.split('=').nth(1).unwrap_or("").to_string());
+                            } else if arg.starts_with("--track=") {
+                                track = Some(arg.split('=').nth(1).unwrap_or("").to_string());
                             }
                         }
                         
-                        TaskCommands::Create { project_id, description, priority }
+                        TaskCommands::Create { project_id, description, priority, due, assignee, at, track }
+                    }
+                    "move" => {
+                        if args.len() < 4 {
+                            println!("{}", "Usage: task move <id> <position>".red());
+                            return Ok(());
+                        }
+                        let position = match args[3].parse::<i32>() {
+                            Ok(p) => p,
+                            Err(_) => {
+                                println!("{}", "Position must be a number".red());
+                                return Ok(());
+                            }
+                        };
+                        TaskCommands::Move { id: args[2].to_string(), position }
                     }
+                    "overdue" => TaskCommands::Overdue,
                     "complete" => {
                         if args.len() < 3 {
                             println!("{}", "Usage: task complete <id>".red());
@@ -540,7 +660,7 @@ impl InteractiveCli {
                         TaskCommands::Delete { id: args[2].to_string() }
                     }
                     _ => {
-                        println!("{}", format!("Unknown task subcommand: {}. Available: list, create, complete, delete", args[1]).red());
+                        println!("{}", format!("Unknown task subcommand: {}. Available: list, create, complete, move, overdue, delete", args[1]).red());
                         return Ok(());
                     }
                 };
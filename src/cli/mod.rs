@@ -66,7 +66,11 @@ pub enum OutputFormat {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Scan directories for Ableton Live projects
+    #[command(args_conflicts_with_subcommands = true)]
     Scan {
+        #[command(subcommand)]
+        subcommand: Option<ScanCommands>,
+
         /// Directories to scan (uses config paths if not specified)
         paths: Vec<PathBuf>,
 
@@ -138,6 +142,23 @@ pub enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum ScanCommands {
+    /// List sets that failed to parse
+    Errors {
+        /// Only show sets skipped by scans until they change on disk
+        #[arg(long)]
+        quarantined: bool,
+
+        /// Parse the failed sets again, including quarantined ones
+        #[arg(long)]
+        retry: bool,
+
+        /// Sets to retry (retries every failed set if not specified)
+        paths: Vec<PathBuf>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum ProjectCommands {
     /// List all projects
@@ -195,6 +216,54 @@ pub enum ProjectCommands {
 
     /// Show project statistics
     Stats,
+
+    /// Show or set a project's workflow status
+    Status {
+        /// Project ID
+        id: String,
+
+        /// New status (must be a stage of the status pipeline)
+        status: Option<String>,
+
+        /// Clear the project's status
+        #[arg(long, conflicts_with = "status")]
+        clear: bool,
+    },
+
+    /// Rate a project from 1 to 5 stars (0 clears the rating)
+    Rate {
+        /// Project ID
+        id: String,
+
+        /// Number of stars
+        #[arg(value_parser = clap::value_parser!(u8).range(0..=5))]
+        stars: u8,
+    },
+
+    /// Mark a project as favorite
+    Favorite {
+        /// Project ID
+        id: String,
+
+        /// Remove the favorite mark instead
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// List highly rated projects that haven't been touched in a while
+    Gems {
+        /// Minimum star rating
+        #[arg(long, default_value = "4")]
+        min_rating: u8,
+
+        /// Untouched for at least this many months
+        #[arg(long, default_value = "6")]
+        months: u32,
+
+        /// Limit number of results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
 }
 
 #[derive(Subcommand)]
@@ -270,16 +339,24 @@ pub enum CollectionCommands {
 #[derive(Subcommand)]
 pub enum TagCommands {
     /// List all tags
-    List,
+    List {
+        /// Only show tags in this group (e.g. genre)
+        #[arg(long)]
+        group: Option<String>,
+    },
 
     /// Create a new tag
     Create {
-        /// Tag name
+        /// Tag name (use / for nested tags, e.g. genre/techno)
         name: String,
 
         /// Tag color (hex format)
         #[arg(long)]
         color: Option<String>,
+
+        /// Parent tag ID
+        #[arg(long)]
+        parent: Option<String>,
     },
 
     /// Assign tag to project
@@ -300,7 +377,7 @@ pub enum TagCommands {
         tag_id: String,
     },
 
-    /// Search projects by tag
+    /// Search projects by tag (including its child tags)
     Search {
         /// Tag name or ID
         tag: String,
@@ -317,6 +394,10 @@ pub enum TaskCommands {
         /// Show completed tasks
         #[arg(long)]
         completed: bool,
+
+        /// Sort by arrangement position instead of manual order
+        #[arg(long)]
+        arrangement: bool,
     },
 
     /// Create a new task
@@ -330,8 +411,36 @@ pub enum TaskCommands {
         /// Task priority (1-5, where 5 is highest)
         #[arg(long, default_value = "3")]
         priority: u8,
+
+        /// Due date (YYYY-MM-DD)
+        #[arg(long)]
+        due: Option<String>,
+
+        /// Person responsible for the task
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// Arrangement position the task refers to (bar[.beat[.sixteenth]], e.g. 33.1)
+        #[arg(long)]
+        at: Option<String>,
+
+        /// Track the task refers to
+        #[arg(long)]
+        track: Option<String>,
+    },
+
+    /// Move a task to a new position within its project
+    Move {
+        /// Task ID
+        id: String,
+
+        /// New position (0-based)
+        position: i32,
     },
 
+    /// List incomplete tasks past their due date across all projects
+    Overdue,
+
     /// Complete a task
     Complete {
         /// Task ID
@@ -353,15 +462,26 @@ pub enum SystemCommands {
     /// Show system statistics
     Stats,
 
-    /// Export system data
+    /// Export statistics as JSON, CSV, a Markdown report or an HTML year in review
     Export {
-        /// Export format (json, csv)
-        #[arg(long, default_value = "json")]
-        format: String,
+        /// Export format
+        #[arg(long, value_enum, default_value = "json")]
+        format: StatsExportFormat,
 
-        /// Output file path
+        /// Output file path, named after the export time by default
         #[arg(long)]
-        output: PathBuf,
+        output: Option<PathBuf>,
+    },
+
+    /// Chart saves, work sessions and hours at work over the last days
+    Activity {
+        /// Number of days to show, today included
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+
+        /// Minutes without a save that end a work session
+        #[arg(long, default_value_t = 30)]
+        session_gap: u32,
     },
 
     /// Start/stop file watcher
@@ -374,6 +494,14 @@ pub enum SystemCommands {
     ScanStatus,
 }
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum StatsExportFormat {
+    Json,
+    Csv,
+    Markdown,
+    Html,
+}
+
 #[derive(clap::ValueEnum, Clone, Debug)]
 pub enum WatchAction {
     Start,
@@ -109,3 +109,20 @@ macro_rules! colored_cell {
 pub fn should_use_color() -> bool {
     !std::env::var("NO_COLOR").is_ok() && colored::control::SHOULD_COLORIZE.should_colorize()
 }
+
+/// Draw values as a one-line chart of block characters, scaled to the largest
+pub fn sparkline(values: &[f64]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    values
+        .iter()
+        .map(|value| {
+            if *value <= 0.0 || max <= 0.0 {
+                ' '
+            } else {
+                let level = (value / max * (BLOCKS.len() - 1) as f64).round() as usize;
+                BLOCKS[level.min(BLOCKS.len() - 1)]
+            }
+        })
+        .collect()
+}
@@ -13,6 +13,15 @@ pub const DEFAULT_MAX_COVER_ART_SIZE_MB: u32 = 10;
 /// Default maximum audio file size in MB
 pub const DEFAULT_MAX_AUDIO_FILE_SIZE_MB: u32 = 50;
 
+/// Default seconds between polls of polled watch paths
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 30;
+
+/// Default number of sets parsed at once during a scan
+pub const DEFAULT_SCAN_WORKERS: usize = 4;
+
+/// Default seconds a single set may take to parse
+pub const DEFAULT_SCAN_FILE_TIMEOUT_SECS: u64 = 120;
+
 /// Generates a default configuration file content
 pub fn generate_default_config() -> Result<String, ConfigError> {
     let local_data_dir = dirs::data_local_dir()
@@ -53,11 +62,40 @@ media_storage_dir = '{}'
 # Media file size limits (in MB) - Optional, 0 = no limit, omit to use defaults
 # max_cover_art_size_mb = 10
 # max_audio_file_size_mb = 50
+
+# Uploads are checked against the format their extension claims and decoded. Set to
+# false to accept files that fail these checks with a warning - anything executable
+# is rejected either way
+# strict_media_validation = true
+
+# Watcher polling - for network shares (SMB/NFS) and cloud-synced folders, where
+# filesystem notifications are unreliable. List entries of `paths` to poll instead.
+# poll_paths = []
+# poll_interval_secs = {}
+
+# Scanning - how many sets are parsed at once, and how many seconds a single set may
+# take before it's given up on (0 = no limit)
+# scan_workers = {}
+# scan_file_timeout_secs = {}
+
+# Per-path scan settings - add a [[roots]] table for any entry of `paths` that needs
+# folders left out, a depth limit, symlinks left alone, backups indexed, or a tag
+# applied to every project found in it
+# [[roots]]
+# path = '{{USER_HOME}}\Music\Projects'
+# exclude = ["**/Old Stuff/**", "**/_Freeze/**"]
+# max_depth = 4
+# follow_symlinks = false
+# include_backups = false
+# default_tag = "main"
 "#,
         live_database_path.display(),
         DEFAULT_GRPC_PORT,
         DEFAULT_LOG_LEVEL,
-        media_storage_path.display()
+        media_storage_path.display(),
+        DEFAULT_POLL_INTERVAL_SECS,
+        DEFAULT_SCAN_WORKERS,
+        DEFAULT_SCAN_FILE_TIMEOUT_SECS
     );
 
     Ok(config_content)
@@ -73,6 +111,22 @@ pub fn default_max_audio_file_size() -> Option<u32> {
     None // Use media module default
 }
 
+pub fn default_strict_media_validation() -> bool {
+    true
+}
+
+pub fn default_poll_interval() -> u64 {
+    DEFAULT_POLL_INTERVAL_SECS
+}
+
+pub fn default_scan_workers() -> usize {
+    DEFAULT_SCAN_WORKERS
+}
+
+pub fn default_scan_file_timeout() -> u64 {
+    DEFAULT_SCAN_FILE_TIMEOUT_SECS
+}
+
 pub fn default_grpc_port() -> u16 {
     DEFAULT_GRPC_PORT
 }
@@ -56,6 +56,9 @@ fn process_user_home_placeholders(config: &mut Config) -> Result<(), ConfigError
         .iter()
         .map(|path| path.replace("{USER_HOME}", home_dir_str))
         .collect();
+    for root in &mut config.roots {
+        root.path = root.path.replace("{USER_HOME}", home_dir_str);
+    }
     config.database_path = config
         .database_path
         .as_ref()
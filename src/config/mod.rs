@@ -3,6 +3,7 @@ pub mod loader;
 pub mod validator;
 pub mod paths;
 pub mod defaults;
+pub mod roots;
 
 use crate::error::ConfigError;
 use once_cell::sync::Lazy;
@@ -11,8 +12,10 @@ use serde::{Deserialize, Serialize};
 // Re-export constants from submodules for backward compatibility
 pub use defaults::{
     DEFAULT_GRPC_PORT, DEFAULT_LOG_LEVEL, DEFAULT_MAX_COVER_ART_SIZE_MB, DEFAULT_MAX_AUDIO_FILE_SIZE_MB,
+    DEFAULT_POLL_INTERVAL_SECS, DEFAULT_SCAN_FILE_TIMEOUT_SECS, DEFAULT_SCAN_WORKERS,
 };
 pub use paths::MAX_PATH_LENGTH;
+pub use roots::RootSettings;
 pub use loader::MAX_DIRECTORY_TRAVERSAL_DEPTH;
 
 /// Configuration for the Seula application
@@ -43,6 +46,29 @@ pub use loader::MAX_DIRECTORY_TRAVERSAL_DEPTH;
 /// # Media file size limits (optional, 0 = no limit, omit to use defaults)
 /// # max_cover_art_size_mb = 10
 /// # max_audio_file_size_mb = 50
+///
+/// # Reject uploads that don't parse and decode as the format their extension claims
+/// # (false = accept them with a warning; executable content is always rejected)
+/// # strict_media_validation = true
+///
+/// # Watched paths to poll instead of relying on filesystem notifications
+/// # (network shares, cloud-synced folders), and how often to poll them
+/// # poll_paths = ["\\\\nas\\music\\Projects"]
+/// # poll_interval_secs = 30
+///
+/// # How many sets are parsed at once, and how long one set may take before it's
+/// # given up on (0 = no limit)
+/// # scan_workers = 4
+/// # scan_file_timeout_secs = 120
+///
+/// # Scan settings for individual project paths
+/// # [[roots]]
+/// # path = "C:\\Users\\username\\Documents\\Music Projects"
+/// # exclude = ["**/Old Stuff/**", "**/_Freeze/**"]
+/// # max_depth = 4
+/// # follow_symlinks = false
+/// # include_backups = false
+/// # default_tag = "main"
 /// ```
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Config {
@@ -67,6 +93,24 @@ pub struct Config {
     /// Maximum audio file size in MB (0 = no limit, None = use media module default)
     #[serde(default = "defaults::default_max_audio_file_size")]
     pub max_audio_file_size_mb: Option<u32>,
+    /// Reject uploads that don't parse and decode as the format their extension claims
+    #[serde(default = "defaults::default_strict_media_validation")]
+    pub strict_media_validation: bool,
+    /// Entries of `paths` that are polled instead of relying on filesystem notifications
+    #[serde(default)]
+    pub poll_paths: Vec<String>,
+    /// Seconds between polls of `poll_paths`
+    #[serde(default = "defaults::default_poll_interval")]
+    pub poll_interval_secs: u64,
+    /// Number of sets parsed at once during a scan
+    #[serde(default = "defaults::default_scan_workers")]
+    pub scan_workers: usize,
+    /// Seconds a single set may take to parse before it's abandoned (0 = no limit)
+    #[serde(default = "defaults::default_scan_file_timeout")]
+    pub scan_file_timeout_secs: u64,
+    /// Scan settings for entries of `paths`; paths without an entry use the defaults
+    #[serde(default)]
+    pub roots: Vec<RootSettings>,
 }
 
 impl Config {
@@ -90,6 +134,36 @@ impl Config {
             .or(self.database_path.clone())
     }
 
+    /// Returns true if the watched path should be polled rather than notified
+    pub fn is_poll_path(&self, path: &str) -> bool {
+        self.poll_paths.iter().any(|p| p == path)
+    }
+
+    /// Returns the polling interval, never shorter than a second
+    pub fn poll_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.poll_interval_secs.max(1))
+    }
+
+    /// Returns the number of parser workers, at least one
+    pub fn scan_workers(&self) -> usize {
+        self.scan_workers.max(1)
+    }
+
+    /// Returns how long a single set may take to parse, if limited
+    pub fn scan_file_timeout(&self) -> Option<std::time::Duration> {
+        (self.scan_file_timeout_secs > 0)
+            .then(|| std::time::Duration::from_secs(self.scan_file_timeout_secs))
+    }
+
+    /// Returns the scan settings for a project path, or the defaults if it has none
+    pub fn root_settings(&self, path: &str) -> RootSettings {
+        self.roots
+            .iter()
+            .find(|root| root.path == path)
+            .cloned()
+            .unwrap_or_else(|| RootSettings::new(path))
+    }
+
     /// Returns true if the application needs initial setup (no paths configured)
     pub fn needs_setup(&self) -> bool {
         self.paths.is_empty()
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// Scan settings for one of the configured project paths
+///
+/// ```toml
+/// [[roots]]
+/// path = "{USER_HOME}\\Music\\Projects"
+/// exclude = ["**/Old Stuff/**", "**/_Freeze/**"]
+/// max_depth = 4
+/// follow_symlinks = false
+/// include_backups = false
+/// default_tag = "projects/main"
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RootSettings {
+    /// Entry of `paths` these settings apply to
+    pub path: String,
+    /// Glob patterns, relative to the root, of files and folders to leave out.
+    /// `*` and `?` stay within a folder, `**` spans any number of folders.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// How many levels below the root to look for sets (1 = only sets directly in the root)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+    /// Whether symlinked folders are followed
+    #[serde(default = "default_follow_symlinks")]
+    pub follow_symlinks: bool,
+    /// Whether Live's timestamped backup copies are indexed too
+    #[serde(default)]
+    pub include_backups: bool,
+    /// Tag applied to every project found under the root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_tag: Option<String>,
+}
+
+fn default_follow_symlinks() -> bool {
+    true
+}
+
+impl RootSettings {
+    /// Settings that find every set under `path`, as scans did before roots were configurable
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            exclude: Vec::new(),
+            max_depth: None,
+            follow_symlinks: default_follow_symlinks(),
+            include_backups: false,
+            default_tag: None,
+        }
+    }
+}
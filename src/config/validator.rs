@@ -31,6 +31,36 @@ impl Config {
         let path_warnings = self.validate_paths()?;
         warnings.extend(path_warnings);
 
+        // Polling only applies to watched paths
+        for poll_path in &self.poll_paths {
+            if !self.paths.contains(poll_path) {
+                warnings.push(format!(
+                    "Poll path is not one of the project paths and will be ignored: {}",
+                    poll_path
+                ));
+            }
+        }
+
+        // Root settings only apply to project paths
+        for root in &self.roots {
+            if !self.paths.contains(&root.path) {
+                warnings.push(format!(
+                    "Root settings are not for one of the project paths and will be ignored: {}",
+                    root.path
+                ));
+            }
+            if root.max_depth == Some(0) {
+                warnings.push(format!(
+                    "max_depth is 0 for {} - no sets will be found there",
+                    root.path
+                ));
+            }
+        }
+
+        if self.scan_workers == 0 {
+            warnings.push("scan_workers is 0 - scans will use a single worker".to_string());
+        }
+
         Ok(warnings)
     }
 
@@ -0,0 +1,198 @@
+use crate::error::DatabaseError;
+use chrono::{Days, Local, NaiveDate, TimeZone};
+use rusqlite::params;
+
+use super::LiveSetDatabase;
+
+/// Saves further apart than this start a new work session
+pub const DEFAULT_SESSION_GAP_MINUTES: i64 = 30;
+/// Work before a session's first save, which no save shows
+pub const SESSION_LEAD_IN_MINUTES: i64 = 15;
+
+/// Saves close enough together to be one stretch of work, across projects
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct WorkSession {
+    pub start: i64,
+    pub end: i64,
+    pub saves: i32,
+}
+
+impl WorkSession {
+    /// Estimated time at work, from a little before the first save to the last
+    pub fn seconds(&self) -> i64 {
+        self.end - self.start + SESSION_LEAD_IN_MINUTES * 60
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DailyActivity {
+    pub date: NaiveDate,
+    pub saves: i32,
+    /// Sessions that started on this day
+    pub sessions: i32,
+    pub seconds: i64,
+}
+
+/// How long a project has been worked on, from its first save to its latest
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProjectTimeline {
+    pub project_id: String,
+    pub name: String,
+    pub first_save_at: i64,
+    pub last_save_at: i64,
+    pub saves: i32,
+}
+
+impl ProjectTimeline {
+    pub fn seconds(&self) -> i64 {
+        self.last_save_at - self.first_save_at
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ActivityAnalytics {
+    /// Every day of the period in local time, oldest first, including days without saves
+    pub days: Vec<DailyActivity>,
+    pub sessions: Vec<WorkSession>,
+    /// Projects saved during the period, most recently saved first
+    pub projects: Vec<ProjectTimeline>,
+}
+
+impl ActivityAnalytics {
+    pub fn total_saves(&self) -> i32 {
+        self.days.iter().map(|day| day.saves).sum()
+    }
+
+    pub fn total_seconds(&self) -> i64 {
+        self.sessions.iter().map(WorkSession::seconds).sum()
+    }
+
+    /// Median time from first save to last of the projects saved during the period
+    pub fn median_project_seconds(&self) -> Option<i64> {
+        let mut seconds: Vec<i64> = self.projects.iter().map(ProjectTimeline::seconds).collect();
+        if seconds.is_empty() {
+            return None;
+        }
+        seconds.sort_unstable();
+        let middle = seconds.len() / 2;
+        Some(if seconds.len().is_multiple_of(2) {
+            (seconds[middle - 1] + seconds[middle]) / 2
+        } else {
+            seconds[middle]
+        })
+    }
+}
+
+impl LiveSetDatabase {
+    /// Save times of active projects at or after `since`, oldest first
+    pub fn get_project_saves(&self, since: i64) -> Result<Vec<(String, i64)>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.project_id, s.saved_at
+             FROM project_saves s
+             JOIN projects p ON p.id = s.project_id
+             WHERE p.is_active = true AND p.set_kind = 'project' AND s.saved_at >= ?
+             ORDER BY s.saved_at",
+        )?;
+        let saves = stmt
+            .query_map([since], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(saves)
+    }
+
+    /// Saves, work sessions and project timelines of the last `days` days, today
+    /// included. Saves at most `session_gap_minutes` apart are one session.
+    pub fn get_activity_analytics(
+        &self,
+        days: u32,
+        session_gap_minutes: i64,
+    ) -> Result<ActivityAnalytics, DatabaseError> {
+        let today = Local::now().date_naive();
+        let first_day = today
+            .checked_sub_days(Days::new(days.saturating_sub(1) as u64))
+            .ok_or_else(|| {
+                DatabaseError::InvalidOperation(format!("Invalid period: {} days", days))
+            })?;
+        let since = Local
+            .from_local_datetime(&first_day.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .earliest()
+            .map(|start| start.timestamp())
+            .unwrap_or_default();
+
+        let saves = self.get_project_saves(since)?;
+        let save_times: Vec<i64> = saves.iter().map(|(_, saved_at)| *saved_at).collect();
+        let sessions = group_sessions(&save_times, session_gap_minutes * 60);
+
+        let mut daily: Vec<DailyActivity> = first_day
+            .iter_days()
+            .take_while(|date| *date <= today)
+            .map(|date| DailyActivity {
+                date,
+                saves: 0,
+                sessions: 0,
+                seconds: 0,
+            })
+            .collect();
+        let day_index = |timestamp: i64| {
+            let date = Local.timestamp_opt(timestamp, 0).single()?.date_naive();
+            usize::try_from((date - first_day).num_days()).ok()
+        };
+        for saved_at in &save_times {
+            if let Some(day) = day_index(*saved_at).and_then(|i| daily.get_mut(i)) {
+                day.saves += 1;
+            }
+        }
+        for session in &sessions {
+            if let Some(day) = day_index(session.start).and_then(|i| daily.get_mut(i)) {
+                day.sessions += 1;
+                day.seconds += session.seconds();
+            }
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT p.id, p.name, MIN(s.saved_at), MAX(s.saved_at), COUNT(*)
+             FROM project_saves s
+             JOIN projects p ON p.id = s.project_id
+             WHERE p.is_active = true AND p.set_kind = 'project'
+             GROUP BY p.id
+             HAVING MAX(s.saved_at) >= ?
+             ORDER BY MAX(s.saved_at) DESC",
+        )?;
+        let projects = stmt
+            .query_map(params![since], |row| {
+                Ok(ProjectTimeline {
+                    project_id: row.get(0)?,
+                    name: row.get(1)?,
+                    first_save_at: row.get(2)?,
+                    last_save_at: row.get(3)?,
+                    saves: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ActivityAnalytics {
+            days: daily,
+            sessions,
+            projects,
+        })
+    }
+}
+
+/// Split sorted save times into sessions wherever saves are more than
+/// `gap_seconds` apart
+pub fn group_sessions(save_times: &[i64], gap_seconds: i64) -> Vec<WorkSession> {
+    let mut sessions: Vec<WorkSession> = Vec::new();
+    for &saved_at in save_times {
+        match sessions.last_mut() {
+            Some(session) if saved_at - session.end <= gap_seconds => {
+                session.end = saved_at;
+                session.saves += 1;
+            }
+            _ => sessions.push(WorkSession {
+                start: saved_at,
+                end: saved_at,
+                saves: 1,
+            }),
+        }
+    }
+    sessions
+}
@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use super::helpers::record_set_location;
 use super::models::SqlDateTime;
 use crate::error::DatabaseError;
 use crate::live_set::LiveSet;
@@ -281,6 +282,7 @@ impl<'a> BatchTransaction<'a> {
                     None::<String>,
                 ],
             )?;
+            record_set_location(&self.tx, &project_id, &live_set.file_path)?;
 
             // Link plugins using the mapped IDs
             for plugin in &live_set.plugins {
@@ -834,6 +834,70 @@ impl LiveSetDatabase {
             |row| row.get(0),
         ).optional()?;
 
+        // Get project count per workflow status
+        let status_counts: Vec<(Option<String>, i32)> = {
+            let mut stmt = self.conn.prepare(
+                r#"
+                SELECT p.status, COUNT(*) as count
+                FROM collection_projects cp
+                JOIN projects p ON p.id = cp.project_id
+                WHERE cp.collection_id = ?
+                GROUP BY p.status
+                "#,
+            )?;
+            let counts = stmt
+                .query_map([collection_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .filter_map(|r| r.ok())
+                .collect();
+            counts
+        };
+        let projects_without_status = status_counts
+            .iter()
+            .filter(|(status, _)| status.is_none())
+            .map(|(_, count)| count)
+            .sum();
+        let status_breakdown = self
+            .get_status_pipeline()?
+            .into_iter()
+            .map(|stage| {
+                let count = status_counts
+                    .iter()
+                    .find(|(status, _)| status.as_deref() == Some(stage.as_str()))
+                    .map_or(0, |(_, count)| *count);
+                (stage, count)
+            })
+            .collect();
+
+        // Loudness of the primary audio file of each analyzed project
+        let (
+            analyzed_project_count,
+            average_lufs,
+            min_lufs,
+            max_lufs,
+            max_true_peak_dbtp,
+            average_loudness_range_lu,
+        ) = self.conn.query_row(
+            r#"
+            SELECT COUNT(aa.integrated_lufs), AVG(aa.integrated_lufs), MIN(aa.integrated_lufs),
+                MAX(aa.integrated_lufs), MAX(aa.true_peak_dbtp), AVG(aa.loudness_range_lu)
+            FROM collection_projects cp
+            JOIN project_audio_files pa ON pa.project_id = cp.project_id AND pa.is_primary = true
+            JOIN audio_analysis aa ON aa.media_file_id = pa.media_file_id
+            WHERE cp.collection_id = ?
+            "#,
+            [collection_id],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            },
+        )?;
+
         let stats = CollectionStatistics {
             project_count,
             total_duration_seconds: total_duration,
@@ -843,6 +907,14 @@ impl LiveSetDatabase {
             total_tags,
             most_common_key,
             most_common_time_signature,
+            status_breakdown,
+            projects_without_status,
+            analyzed_project_count,
+            average_lufs,
+            min_lufs,
+            max_lufs,
+            max_true_peak_dbtp,
+            average_loudness_range_lu,
         };
 
         debug!(
@@ -1,3 +1,4 @@
+use super::project_audio::DEFAULT_AUDIO_LABEL;
 use crate::error::DatabaseError;
 use chrono::{DateTime, Local, TimeZone};
 use log::{debug, info};
@@ -30,6 +31,7 @@ impl LiveSetDatabase {
                 path TEXT NOT NULL UNIQUE,
                 name TEXT NOT NULL,
                 hash TEXT NOT NULL,
+                file_size INTEGER,
                 notes TEXT,
                 created_at DATETIME NOT NULL,
                 modified_at DATETIME NOT NULL,
@@ -47,8 +49,15 @@ impl LiveSetDatabase {
                 ableton_version_minor INTEGER NOT NULL,
                 ableton_version_patch INTEGER NOT NULL,
                 ableton_version_beta BOOLEAN NOT NULL,
-                audio_file_id TEXT,
-                FOREIGN KEY (audio_file_id) REFERENCES media_files(id) ON DELETE SET NULL
+                status TEXT,
+                status_changed_at DATETIME,
+                rating INTEGER,
+                is_favorite BOOLEAN NOT NULL DEFAULT false,
+                last_opened_at DATETIME,
+                open_count INTEGER NOT NULL DEFAULT 0,
+                deleted_at DATETIME,
+                set_kind TEXT NOT NULL DEFAULT 'project',
+                project_folder TEXT
             );
 
             CREATE TABLE IF NOT EXISTS plugins (
@@ -86,10 +95,83 @@ impl LiveSetDatabase {
                 checksum TEXT NOT NULL
             );
 
+            -- Stored content, one per SHA-256 checksum, with the number of media files
+            -- using it. Kept up to date by the triggers below.
+            CREATE TABLE IF NOT EXISTS media_blobs (
+                checksum TEXT PRIMARY KEY,
+                file_size_bytes INTEGER NOT NULL,
+                ref_count INTEGER NOT NULL
+            );
+
+            CREATE TRIGGER IF NOT EXISTS media_files_ai AFTER INSERT ON media_files BEGIN
+                INSERT INTO media_blobs (checksum, file_size_bytes, ref_count)
+                VALUES (new.checksum, new.file_size_bytes, 1)
+                ON CONFLICT(checksum) DO UPDATE SET ref_count = ref_count + 1;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS media_files_ad AFTER DELETE ON media_files BEGIN
+                UPDATE media_blobs SET ref_count = ref_count - 1 WHERE checksum = old.checksum;
+                DELETE FROM media_blobs WHERE checksum = old.checksum AND ref_count <= 0;
+            END;
+
+            -- Files derived from a media file, such as cover art thumbnails
+            CREATE TABLE IF NOT EXISTS media_variants (
+                media_file_id TEXT NOT NULL,
+                size TEXT NOT NULL,
+                file_extension TEXT NOT NULL,
+                mime_type TEXT NOT NULL,
+                file_size_bytes INTEGER NOT NULL,
+                width INTEGER NOT NULL,
+                height INTEGER NOT NULL,
+                checksum TEXT NOT NULL,
+                PRIMARY KEY (media_file_id, size),
+                FOREIGN KEY (media_file_id) REFERENCES media_files(id) ON DELETE CASCADE
+            );
+
+            -- Bounces, mixdowns, masters and stems of a project. Versions count up per label.
+            CREATE TABLE IF NOT EXISTS project_audio_files (
+                project_id TEXT NOT NULL,
+                media_file_id TEXT NOT NULL,
+                label TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                is_primary BOOLEAN NOT NULL DEFAULT false,
+                uploaded_at DATETIME NOT NULL,
+                PRIMARY KEY (project_id, media_file_id),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (media_file_id) REFERENCES media_files(id) ON DELETE CASCADE
+            );
+
+            -- Loudness, tempo and key measured from an audio file
+            CREATE TABLE IF NOT EXISTS audio_analysis (
+                media_file_id TEXT PRIMARY KEY,
+                integrated_lufs REAL,
+                true_peak_dbtp REAL,
+                loudness_range_lu REAL,
+                rms_dbfs REAL,
+                bpm REAL,
+                key_tonic TEXT,
+                key_scale TEXT,
+                analyzed_at DATETIME NOT NULL,
+                FOREIGN KEY (media_file_id) REFERENCES media_files(id) ON DELETE CASCADE
+            );
+
+            -- Resumable uploads in progress. Their bytes are kept with the media files.
+            CREATE TABLE IF NOT EXISTS upload_sessions (
+                id TEXT PRIMARY KEY,
+                media_type TEXT NOT NULL,
+                filename TEXT NOT NULL,
+                total_size_bytes INTEGER NOT NULL,
+                created_at DATETIME NOT NULL,
+                updated_at DATETIME NOT NULL
+            );
+
             CREATE TABLE IF NOT EXISTS tags (
                 id TEXT PRIMARY KEY,
-                name TEXT NOT NULL UNIQUE,
-                created_at DATETIME NOT NULL
+                name TEXT NOT NULL UNIQUE,  -- Full path, e.g. genre/techno
+                created_at DATETIME NOT NULL,
+                color TEXT,
+                parent_id TEXT,
+                FOREIGN KEY (parent_id) REFERENCES tags(id) ON DELETE CASCADE
             );
 
             CREATE TABLE IF NOT EXISTS collections (
@@ -146,9 +228,66 @@ impl LiveSetDatabase {
                 description TEXT NOT NULL,
                 completed BOOLEAN NOT NULL DEFAULT FALSE,
                 created_at DATETIME NOT NULL,
+                priority INTEGER NOT NULL DEFAULT 3,
+                due_date DATETIME,
+                position INTEGER NOT NULL DEFAULT 0,
+                assignee TEXT,
+                completed_at DATETIME,
+                arrangement_bar INTEGER,
+                arrangement_beat REAL,
+                track_name TEXT,
                 FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
             );
 
+            -- Ordered workflow stages a project moves through
+            CREATE TABLE IF NOT EXISTS workflow_statuses (
+                name TEXT PRIMARY KEY,
+                position INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS project_status_history (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                from_status TEXT,
+                to_status TEXT,
+                changed_at DATETIME NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- Every save of a project that a scan or the watcher picked up, for
+            -- activity analytics. Kept up to date by the triggers below.
+            CREATE TABLE IF NOT EXISTS project_saves (
+                project_id TEXT NOT NULL,
+                saved_at DATETIME NOT NULL,
+                PRIMARY KEY (project_id, saved_at),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            CREATE TRIGGER IF NOT EXISTS projects_saves_ai AFTER INSERT ON projects BEGIN
+                INSERT OR IGNORE INTO project_saves (project_id, saved_at)
+                VALUES (new.id, new.modified_at);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS projects_saves_au AFTER UPDATE OF modified_at ON projects
+            WHEN new.modified_at IS NOT old.modified_at BEGIN
+                INSERT OR IGNORE INTO project_saves (project_id, saved_at)
+                VALUES (new.id, new.modified_at);
+            END;
+
+            -- Sets that failed to parse; ones that keep failing are skipped until they change
+            CREATE TABLE IF NOT EXISTS scan_errors (
+                path TEXT PRIMARY KEY,
+                error_kind TEXT NOT NULL,
+                message TEXT NOT NULL,
+                line INTEGER,
+                ableton_version TEXT,
+                file_size INTEGER,
+                modified_at INTEGER,
+                failure_count INTEGER NOT NULL DEFAULT 1,
+                first_failed_at DATETIME NOT NULL,
+                last_failed_at DATETIME NOT NULL
+            );
+
             -- Basic indexes for performance
             CREATE INDEX IF NOT EXISTS idx_projects_path ON projects(path);
             CREATE INDEX IF NOT EXISTS idx_plugins_name ON plugins(name);
@@ -157,6 +296,9 @@ impl LiveSetDatabase {
             CREATE INDEX IF NOT EXISTS idx_collection_projects_position ON collection_projects(collection_id, position);
             CREATE INDEX IF NOT EXISTS idx_projects_is_active ON projects(is_active);
             CREATE INDEX IF NOT EXISTS idx_media_files_type ON media_files(media_type);
+            CREATE INDEX IF NOT EXISTS idx_project_audio_files_media ON project_audio_files(media_file_id);
+            CREATE INDEX IF NOT EXISTS idx_project_status_history_project ON project_status_history(project_id, changed_at);
+            CREATE INDEX IF NOT EXISTS idx_project_saves_saved_at ON project_saves(saved_at);
 
             -- Full-text search
             CREATE VIRTUAL TABLE IF NOT EXISTS project_search USING fts5(
@@ -258,6 +400,18 @@ impl LiveSetDatabase {
                 WHERE project_id = new.project_id;
             END;
 
+            -- Renaming a tag (or one of its ancestors) changes the indexed tag paths
+            CREATE TRIGGER IF NOT EXISTS tags_au AFTER UPDATE OF name ON tags BEGIN
+                UPDATE project_search SET
+                    tags = COALESCE((
+                        SELECT GROUP_CONCAT(t.name, ' ')
+                        FROM tags t
+                        JOIN project_tags pt ON pt.tag_id = t.id
+                        WHERE pt.project_id = project_search.project_id
+                    ), '')
+                WHERE project_id IN (SELECT project_id FROM project_tags WHERE tag_id = new.id);
+            END;
+
             CREATE TRIGGER IF NOT EXISTS project_tags_ad AFTER DELETE ON project_tags BEGIN
                 UPDATE project_search SET
                     tags = COALESCE((
@@ -273,12 +427,155 @@ impl LiveSetDatabase {
 
         debug!("Database schema initialized successfully");
 
+        self.migrate_schema()?;
+
         // Rebuild FTS5 table to fix any NULL values in existing data
         self.rebuild_fts5_table()?;
 
         Ok(())
     }
 
+    /// Bring databases created by older versions up to the current schema.
+    /// `CREATE TABLE IF NOT EXISTS` leaves existing tables untouched, so columns
+    /// added after the initial release have to be added here.
+    fn migrate_schema(&mut self) -> Result<(), DatabaseError> {
+        debug!("Checking database schema for missing columns");
+
+        self.add_column_if_missing("project_tasks", "priority", "INTEGER NOT NULL DEFAULT 3")?;
+        self.add_column_if_missing("project_tasks", "due_date", "DATETIME")?;
+        if self.add_column_if_missing("project_tasks", "position", "INTEGER NOT NULL DEFAULT 0")? {
+            // Existing tasks keep their creation order
+            self.conn.execute(
+                r#"
+                UPDATE project_tasks SET position = (
+                    SELECT COUNT(*) FROM project_tasks t
+                    WHERE t.project_id = project_tasks.project_id
+                    AND (t.created_at < project_tasks.created_at
+                         OR (t.created_at = project_tasks.created_at AND t.id < project_tasks.id))
+                )
+                "#,
+                [],
+            )?;
+        }
+        self.add_column_if_missing("project_tasks", "assignee", "TEXT")?;
+        self.add_column_if_missing("project_tasks", "completed_at", "DATETIME")?;
+        self.add_column_if_missing("project_tasks", "arrangement_bar", "INTEGER")?;
+        self.add_column_if_missing("project_tasks", "arrangement_beat", "REAL")?;
+        self.add_column_if_missing("project_tasks", "track_name", "TEXT")?;
+        self.add_column_if_missing("projects", "status", "TEXT")?;
+        self.add_column_if_missing("projects", "status_changed_at", "DATETIME")?;
+        self.add_column_if_missing("projects", "rating", "INTEGER")?;
+        self.add_column_if_missing("projects", "is_favorite", "BOOLEAN NOT NULL DEFAULT false")?;
+        self.add_column_if_missing("projects", "last_opened_at", "DATETIME")?;
+        self.add_column_if_missing("projects", "open_count", "INTEGER NOT NULL DEFAULT 0")?;
+        self.add_column_if_missing("projects", "deleted_at", "DATETIME")?;
+        self.add_column_if_missing("projects", "file_size", "INTEGER")?;
+        let set_kind_added =
+            self.add_column_if_missing("projects", "set_kind", "TEXT NOT NULL DEFAULT 'project'")?;
+        self.add_column_if_missing("projects", "project_folder", "TEXT")?;
+        if set_kind_added {
+            // Templates and default sets indexed before they were told apart
+            self.refresh_set_locations()?;
+        }
+        if self.has_column("projects", "audio_file_id")? {
+            // Projects used to have a single audio file. It becomes their primary one;
+            // the column stays behind empty, as SQLite can't drop a foreign key column.
+            let migrated = self.conn.execute(
+                r#"
+                INSERT OR IGNORE INTO project_audio_files
+                    (project_id, media_file_id, label, version, is_primary, uploaded_at)
+                SELECT p.id, m.id, ?, 1, true, m.uploaded_at
+                FROM projects p JOIN media_files m ON m.id = p.audio_file_id
+                "#,
+                [DEFAULT_AUDIO_LABEL],
+            )?;
+            self.conn.execute(
+                "UPDATE projects SET audio_file_id = NULL WHERE audio_file_id IS NOT NULL",
+                [],
+            )?;
+            if migrated > 0 {
+                info!("Moved {} project audio files to project_audio_files", migrated);
+            }
+        }
+        // Media files stored before content addressing start out counted
+        let counted = self.conn.execute(
+            r#"
+            INSERT INTO media_blobs (checksum, file_size_bytes, ref_count)
+            SELECT checksum, MAX(file_size_bytes), COUNT(*) FROM media_files
+            WHERE NOT EXISTS (SELECT 1 FROM media_blobs)
+            GROUP BY checksum
+            "#,
+            [],
+        )?;
+        if counted > 0 {
+            info!("Counted references to {} stored media files", counted);
+        }
+        // Projects indexed before saves were logged start out with their last save
+        let saves_logged: bool = self
+            .conn
+            .query_row("SELECT EXISTS (SELECT 1 FROM project_saves)", [], |row| row.get(0))?;
+        if !saves_logged {
+            let logged = self.conn.execute(
+                r#"
+                INSERT OR IGNORE INTO project_saves (project_id, saved_at)
+                SELECT id, modified_at FROM projects
+                "#,
+                [],
+            )?;
+            if logged > 0 {
+                info!("Logged {} saves of existing projects", logged);
+            }
+        }
+        self.add_column_if_missing("tags", "color", "TEXT")?;
+        self.add_column_if_missing("tags", "parent_id", "TEXT REFERENCES tags(id) ON DELETE CASCADE")?;
+
+        // Indexes on migrated columns must be created after the columns exist
+        self.conn.execute_batch(
+            r#"--sql
+            CREATE INDEX IF NOT EXISTS idx_project_tasks_position ON project_tasks(project_id, position);
+            CREATE INDEX IF NOT EXISTS idx_project_tasks_due_date ON project_tasks(due_date);
+            CREATE INDEX IF NOT EXISTS idx_tags_parent_id ON tags(parent_id);
+            CREATE INDEX IF NOT EXISTS idx_projects_status ON projects(status);
+            CREATE INDEX IF NOT EXISTS idx_projects_rating ON projects(rating);
+            CREATE INDEX IF NOT EXISTS idx_projects_last_opened_at ON projects(last_opened_at);
+            CREATE INDEX IF NOT EXISTS idx_projects_set_kind ON projects(set_kind);
+            CREATE INDEX IF NOT EXISTS idx_projects_project_folder ON projects(project_folder);
+            "#,
+        )?;
+
+        self.seed_default_status_pipeline()?;
+
+        Ok(())
+    }
+
+    /// Returns true if the column had to be added
+    fn add_column_if_missing(
+        &self,
+        table: &str,
+        column: &str,
+        definition: &str,
+    ) -> Result<bool, DatabaseError> {
+        let exists = self.has_column(table, column)?;
+
+        if !exists {
+            info!("Adding missing column {}.{}", table, column);
+            self.conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )?;
+        }
+        Ok(!exists)
+    }
+
+    fn has_column(&self, table: &str, column: &str) -> Result<bool, DatabaseError> {
+        let mut stmt = self.conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|r| r.ok())
+            .any(|name| name == column);
+        Ok(exists)
+    }
+
     pub fn get_last_scanned_time(
         &self,
         path: &Path,
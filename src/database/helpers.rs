@@ -2,9 +2,10 @@ use crate::error::DatabaseError;
 use crate::live_set::LiveSet;
 use crate::models::{AbletonVersion, KeySignature, Plugin, Sample, TimeSignature};
 use chrono::{Local, TimeZone};
-use rusqlite::{params, Row, Transaction};
+use crate::scan::project_folder::SetLocation;
+use rusqlite::{params, Connection, Row, Transaction};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Insert a plugin into the database
@@ -73,6 +74,27 @@ pub fn link_project_sample(
     Ok(())
 }
 
+/// Store whether the project is a regular set, a template or the default set,
+/// and which project folder it's in, as found at `path`
+pub fn record_set_location(
+    conn: &Connection,
+    project_id: &str,
+    path: &Path,
+) -> Result<(), DatabaseError> {
+    let location = SetLocation::detect(path);
+    conn.execute(
+        "UPDATE projects SET set_kind = ?, project_folder = ? WHERE id = ?",
+        params![
+            location.kind.as_str(),
+            location
+                .project_folder
+                .map(|folder| folder.to_string_lossy().to_string()),
+            project_id,
+        ],
+    )?;
+    Ok(())
+}
+
 /// Convert a database row to a LiveSet object
 pub fn row_to_live_set(row: &Row) -> rusqlite::Result<LiveSet> {
     let id: String = row.get("id")?;
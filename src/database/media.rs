@@ -1,10 +1,12 @@
 use super::core::LiveSetDatabase;
 use super::models::SqlDateTime;
+use super::project_audio::promote_primary_audio_files;
 use crate::error::DatabaseError;
-use crate::media::{MediaFile, MediaType};
-use chrono::DateTime;
+use crate::media::{AudioAnalysis, MediaFile, MediaType, MediaVariant, ThumbnailSize};
+use crate::models::KeySignature;
+use chrono::{DateTime, Utc};
 use log::{debug, info, warn};
-use rusqlite::{params, OptionalExtension, Row};
+use rusqlite::{params, Connection, OptionalExtension, Row};
 
 impl LiveSetDatabase {
     /// Insert a new media file record into the database
@@ -60,23 +62,170 @@ impl LiveSetDatabase {
         Ok(media_file)
     }
 
-    /// Delete a media file from the database
-    pub fn delete_media_file(&mut self, file_id: &str) -> Result<(), DatabaseError> {
+    /// Delete a media file from the database. Returns whether its content is now
+    /// unused, so the stored file can go too.
+    pub fn delete_media_file(&mut self, file_id: &str) -> Result<bool, DatabaseError> {
         debug!("Deleting media file: {}", file_id);
 
-        let rows_affected = self
-            .conn
-            .execute("DELETE FROM media_files WHERE id = ?", params![file_id])?;
-
-        if rows_affected > 0 {
+        let tx = self.conn.transaction()?;
+        let checksum: Option<String> = tx
+            .query_row(
+                "SELECT checksum FROM media_files WHERE id = ?",
+                [file_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        tx.execute("DELETE FROM media_files WHERE id = ?", params![file_id])?;
+        // Projects whose primary audio file this was fall back to another one
+        promote_primary_audio_files(&tx)?;
+        let content_unused = match &checksum {
+            Some(checksum) => media_blob_references(&tx, checksum)? == 0,
+            None => false,
+        };
+        tx.commit()?;
+
+        if checksum.is_some() {
             info!("Successfully deleted media file: {}", file_id);
         } else {
             warn!("No media file found to delete: {}", file_id);
         }
 
+        Ok(content_unused)
+    }
+
+    /// Number of media files whose content has this checksum
+    pub fn get_media_blob_references(&self, checksum: &str) -> Result<u32, DatabaseError> {
+        media_blob_references(&self.conn, checksum)
+    }
+
+    /// Number and total size of the files actually stored, each shared by every
+    /// media file with the same content
+    pub fn get_media_blob_statistics(&self) -> Result<(i32, i64), DatabaseError> {
+        let stats = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(file_size_bytes), 0) FROM media_blobs",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(stats)
+    }
+
+    /// Record files derived from a media file, replacing any of the same size
+    pub fn insert_media_variants(&mut self, variants: &[MediaVariant]) -> Result<(), DatabaseError> {
+        let tx = self.conn.transaction()?;
+        for variant in variants {
+            tx.execute(
+                "INSERT OR REPLACE INTO media_variants (
+                    media_file_id, size, file_extension, mime_type, file_size_bytes,
+                    width, height, checksum
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    variant.media_file_id,
+                    variant.size.as_str(),
+                    variant.file_extension,
+                    variant.mime_type,
+                    variant.file_size_bytes as i64,
+                    variant.width,
+                    variant.height,
+                    variant.checksum,
+                ],
+            )?;
+        }
+        tx.commit()?;
+
+        debug!("Inserted {} media variants", variants.len());
         Ok(())
     }
 
+    pub fn get_media_variant(
+        &self,
+        file_id: &str,
+        size: ThumbnailSize,
+    ) -> Result<Option<MediaVariant>, DatabaseError> {
+        let variant = self
+            .conn
+            .query_row(
+                "SELECT media_file_id, size, file_extension, mime_type, file_size_bytes,
+                    width, height, checksum
+                 FROM media_variants
+                 WHERE media_file_id = ? AND size = ?",
+                params![file_id, size.as_str()],
+                row_to_media_variant,
+            )
+            .optional()?;
+        Ok(variant)
+    }
+
+    /// Variants of a media file, smallest first
+    pub fn get_media_variants(&self, file_id: &str) -> Result<Vec<MediaVariant>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT media_file_id, size, file_extension, mime_type, file_size_bytes,
+                width, height, checksum
+             FROM media_variants
+             WHERE media_file_id = ?
+             ORDER BY width * height",
+        )?;
+        let variants = stmt
+            .query_map([file_id], row_to_media_variant)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(variants)
+    }
+
+    /// Number and total size of derived files
+    pub fn get_media_variant_statistics(&self) -> Result<(i32, i64), DatabaseError> {
+        let stats = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(file_size_bytes), 0) FROM media_variants",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(stats)
+    }
+
+    /// Store what was measured from an audio file, replacing an earlier analysis
+    pub fn insert_audio_analysis(
+        &mut self,
+        media_file_id: &str,
+        analysis: &AudioAnalysis,
+    ) -> Result<(), DatabaseError> {
+        let (key_tonic, key_scale) = match &analysis.key {
+            Some(key) => (Some(key.tonic.to_string()), Some(key.scale.to_string())),
+            None => (None, None),
+        };
+        self.conn.execute(
+            "INSERT OR REPLACE INTO audio_analysis (
+                media_file_id, integrated_lufs, true_peak_dbtp, loudness_range_lu,
+                rms_dbfs, bpm, key_tonic, key_scale, analyzed_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                media_file_id,
+                analysis.integrated_lufs,
+                analysis.true_peak_dbtp,
+                analysis.loudness_range_lu,
+                analysis.rms_dbfs,
+                analysis.bpm,
+                key_tonic,
+                key_scale,
+                SqlDateTime::from_utc(Utc::now()),
+            ],
+        )?;
+        debug!("Stored audio analysis of {}", media_file_id);
+        Ok(())
+    }
+
+    pub fn get_audio_analysis(
+        &self,
+        media_file_id: &str,
+    ) -> Result<Option<AudioAnalysis>, DatabaseError> {
+        let analysis = self
+            .conn
+            .query_row(
+                "SELECT * FROM audio_analysis WHERE media_file_id = ?",
+                [media_file_id],
+                row_to_audio_analysis,
+            )
+            .optional()?;
+        Ok(analysis.flatten())
+    }
+
     /// Update collection cover art
     pub fn update_collection_cover_art(
         &mut self,
@@ -109,7 +258,8 @@ impl LiveSetDatabase {
         Ok(())
     }
 
-    /// Update project audio file
+    /// Set the primary audio file of a project, attaching it if needed, or detach all of
+    /// its audio files
     pub fn update_project_audio_file(
         &mut self,
         project_id: &str,
@@ -120,21 +270,28 @@ impl LiveSetDatabase {
             project_id, audio_file_id
         );
 
-        let rows_affected = self.conn.execute(
-            "UPDATE projects SET audio_file_id = ? WHERE id = ?",
-            params![audio_file_id, project_id],
-        )?;
-
-        if rows_affected > 0 {
-            info!("Successfully updated project audio file: {}", project_id);
-        } else {
-            warn!("Project not found: {}", project_id);
-            return Err(DatabaseError::NotFound(format!(
-                "Project with ID {} not found",
-                project_id
-            )));
+        match audio_file_id {
+            Some(audio_file_id) => {
+                self.attach_project_audio_file(project_id, audio_file_id, None, None, true)?;
+            }
+            None => {
+                let project_exists: bool = self.conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?)",
+                    [project_id],
+                    |row| row.get(0),
+                )?;
+                if !project_exists {
+                    warn!("Project not found: {}", project_id);
+                    return Err(DatabaseError::NotFound(format!(
+                        "Project with ID {} not found",
+                        project_id
+                    )));
+                }
+                self.detach_project_audio_files(project_id)?;
+            }
         }
 
+        info!("Successfully updated project audio file: {}", project_id);
         Ok(())
     }
 
@@ -203,7 +360,7 @@ impl LiveSetDatabase {
             SELECT id, original_filename, file_extension, media_type, file_size_bytes, mime_type, uploaded_at, checksum
             FROM media_files 
             WHERE id NOT IN (
-                SELECT DISTINCT audio_file_id FROM projects WHERE audio_file_id IS NOT NULL
+                SELECT media_file_id FROM project_audio_files
                 UNION
                 SELECT DISTINCT cover_art_id FROM collections WHERE cover_art_id IS NOT NULL
             )
@@ -231,10 +388,13 @@ impl LiveSetDatabase {
 
     /// Get media statistics
     pub fn get_media_statistics(&self) -> Result<(i32, i64, i32, i32, i32, i64), DatabaseError> {
-        // Get total files and size
-        let mut stmt = self
-            .conn
-            .prepare("SELECT COUNT(*), COALESCE(SUM(file_size_bytes), 0) FROM media_files")?;
+        // Get total files and size, including the files derived from them
+        let mut stmt = self.conn.prepare(
+            "SELECT COUNT(*),
+                COALESCE(SUM(file_size_bytes), 0)
+                + (SELECT COALESCE(SUM(file_size_bytes), 0) FROM media_variants)
+             FROM media_files",
+        )?;
         let (total_files, total_size): (i32, i64) =
             stmt.query_row([], |row| Ok((row.get(0)?, row.get(1)?)))?;
 
@@ -250,13 +410,17 @@ impl LiveSetDatabase {
             .prepare("SELECT COUNT(*) FROM media_files WHERE media_type = 'audio_file'")?;
         let audio_file_count: i32 = stmt.query_row([], |row| row.get(0))?;
 
-        // Get orphaned files count and size
+        // Get orphaned files count and size, including their derived files
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT COUNT(*), COALESCE(SUM(file_size_bytes), 0)
+            SELECT COUNT(*),
+                COALESCE(SUM(file_size_bytes + (
+                    SELECT COALESCE(SUM(v.file_size_bytes), 0)
+                    FROM media_variants v WHERE v.media_file_id = media_files.id
+                )), 0)
             FROM media_files 
             WHERE id NOT IN (
-                SELECT DISTINCT audio_file_id FROM projects WHERE audio_file_id IS NOT NULL
+                SELECT media_file_id FROM project_audio_files
                 UNION
                 SELECT DISTINCT cover_art_id FROM collections WHERE cover_art_id IS NOT NULL
             )
@@ -298,7 +462,7 @@ impl LiveSetDatabase {
             SELECT COUNT(*) 
             FROM media_files 
             WHERE id NOT IN (
-                SELECT DISTINCT audio_file_id FROM projects WHERE audio_file_id IS NOT NULL
+                SELECT media_file_id FROM project_audio_files
                 UNION
                 SELECT DISTINCT cover_art_id FROM collections WHERE cover_art_id IS NOT NULL
             )
@@ -308,7 +472,7 @@ impl LiveSetDatabase {
         Ok(count)
     }
 
-    /// Get the audio file for a project
+    /// Get the primary audio file of a project
     pub fn get_project_audio_file(
         &self,
         project_id: &str,
@@ -319,8 +483,10 @@ impl LiveSetDatabase {
             "SELECT m.id, m.original_filename, m.file_extension, m.media_type, m.file_size_bytes,
                     m.mime_type, m.uploaded_at, m.checksum
              FROM media_files m
-             JOIN projects p ON p.audio_file_id = m.id
-             WHERE p.id = ?",
+             JOIN project_audio_files pa ON pa.media_file_id = m.id
+             WHERE pa.project_id = ?
+             ORDER BY pa.is_primary DESC, pa.uploaded_at DESC
+             LIMIT 1",
             params![project_id],
             |row| self.row_to_media_file(row),
         ).optional()?;
@@ -367,7 +533,7 @@ impl LiveSetDatabase {
     }
 
     /// Convert a database row to a MediaFile
-    fn row_to_media_file(&self, row: &Row) -> Result<MediaFile, rusqlite::Error> {
+    pub(super) fn row_to_media_file(&self, row: &Row) -> Result<MediaFile, rusqlite::Error> {
         let media_type_str: String = row.get("media_type")?;
         let media_type = MediaType::from_str(&media_type_str)
             .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid media type".into()))?;
@@ -389,6 +555,63 @@ impl LiveSetDatabase {
     }
 }
 
+fn row_to_media_variant(row: &Row) -> Result<MediaVariant, rusqlite::Error> {
+    let size: String = row.get(1)?;
+    Ok(MediaVariant {
+        media_file_id: row.get(0)?,
+        size: size
+            .parse()
+            .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid variant size".into()))?,
+        file_extension: row.get(2)?,
+        mime_type: row.get(3)?,
+        file_size_bytes: row.get::<_, i64>(4)? as u64,
+        width: row.get(5)?,
+        height: row.get(6)?,
+        checksum: row.get(7)?,
+    })
+}
+
+fn media_blob_references(conn: &Connection, checksum: &str) -> Result<u32, DatabaseError> {
+    let references: Option<u32> = conn
+        .query_row(
+            "SELECT ref_count FROM media_blobs WHERE checksum = ?",
+            [checksum],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(references.unwrap_or(0))
+}
+
+/// Analysis columns of a row, if the row has an analysis. Joined rows without
+/// one have no `analyzed_at`.
+pub(super) fn row_to_audio_analysis(row: &Row) -> Result<Option<AudioAnalysis>, rusqlite::Error> {
+    let analyzed_at: Option<i64> = row.get("analyzed_at")?;
+    if analyzed_at.is_none() {
+        return Ok(None);
+    }
+    let key_tonic: Option<String> = row.get("key_tonic")?;
+    let key_scale: Option<String> = row.get("key_scale")?;
+    let key = match (key_tonic, key_scale) {
+        (Some(tonic), Some(scale)) => Some(KeySignature {
+            tonic: tonic
+                .parse()
+                .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid key tonic".into()))?,
+            scale: scale
+                .parse()
+                .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid key scale".into()))?,
+        }),
+        _ => None,
+    };
+    Ok(Some(AudioAnalysis {
+        integrated_lufs: row.get("integrated_lufs")?,
+        true_peak_dbtp: row.get("true_peak_dbtp")?,
+        loudness_range_lu: row.get("loudness_range_lu")?,
+        rms_dbfs: row.get("rms_dbfs")?,
+        bpm: row.get("bpm")?,
+        key,
+    }))
+}
+
 /// Statistics about media files in the database
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -1,3 +1,4 @@
+pub mod activity;
 pub mod batch;
 mod collections;
 mod core;
@@ -6,15 +7,29 @@ mod media;
 mod models;
 pub mod notes;
 pub mod plugins;
+pub mod project_audio;
+pub mod project_folders;
 pub mod projects;
+pub mod ratings;
+pub mod reconcile;
 pub mod samples;
+pub mod scan_errors;
+pub mod scan_state;
 pub mod search;
 pub mod stats;
 pub mod tags;
 pub mod tasks;
+pub mod uploads;
+pub mod workflow;
+pub mod year_in_review;
 
+pub use activity::ActivityAnalytics;
 pub use batch::BatchInsertManager;
 pub use core::LiveSetDatabase;
 pub use plugins::PluginStats;
+pub use project_audio::ProjectAudioFile;
+pub use project_folders::ProjectFolder;
 pub use samples::{SampleStats, SampleUsageInfo};
 pub use stats::ProjectStatistics;
+pub use uploads::UploadSession;
+pub use year_in_review::YearInReview;
@@ -0,0 +1,337 @@
+use crate::error::DatabaseError;
+use crate::media::{AudioAnalysis, MediaFile, MediaType};
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+
+use super::media::row_to_audio_analysis;
+use super::models::SqlDateTime;
+use super::LiveSetDatabase;
+
+/// Label of audio attached to a project without one
+pub const DEFAULT_AUDIO_LABEL: &str = "Bounce";
+
+const PROJECT_AUDIO_COLUMNS: &str = "m.id, m.original_filename, m.file_extension, m.media_type,
+    m.file_size_bytes, m.mime_type, m.uploaded_at, m.checksum,
+    pa.project_id, pa.label, pa.version, pa.is_primary, pa.uploaded_at AS attached_at,
+    aa.integrated_lufs, aa.true_peak_dbtp, aa.loudness_range_lu, aa.rms_dbfs, aa.bpm,
+    aa.key_tonic, aa.key_scale, aa.analyzed_at";
+
+/// An audio file attached to a project: a bounce, mixdown, master or stem
+#[derive(Debug, Clone)]
+pub struct ProjectAudioFile {
+    pub project_id: String,
+    pub media_file: MediaFile,
+    pub label: String,
+    /// Counts up per label, so a project can have mixdown 1, 2 and 3
+    pub version: u32,
+    /// The audio shown for the project and drawn as its waveform
+    pub is_primary: bool,
+    /// When the audio was added to the project
+    pub uploaded_at: DateTime<Utc>,
+    /// Loudness, tempo and key, once the audio has been analyzed
+    pub analysis: Option<AudioAnalysis>,
+}
+
+impl LiveSetDatabase {
+    /// Attach an audio file to a project, or relabel it if it's already attached.
+    /// Without a version, the next one for the label is used. The first audio file
+    /// of a project becomes its primary one.
+    pub fn attach_project_audio_file(
+        &mut self,
+        project_id: &str,
+        media_file_id: &str,
+        label: Option<&str>,
+        version: Option<u32>,
+        make_primary: bool,
+    ) -> Result<ProjectAudioFile, DatabaseError> {
+        let tx = self.conn.transaction()?;
+        let project_exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?)",
+            [project_id],
+            |row| row.get(0),
+        )?;
+        if !project_exists {
+            return Err(DatabaseError::NotFound(format!(
+                "Project with ID {} not found",
+                project_id
+            )));
+        }
+        let media_type: Option<String> = tx
+            .query_row(
+                "SELECT media_type FROM media_files WHERE id = ?",
+                [media_file_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match media_type {
+            None => {
+                return Err(DatabaseError::NotFound(format!(
+                    "Media file with ID {} not found",
+                    media_file_id
+                )))
+            }
+            Some(media_type) if media_type != MediaType::AudioFile.as_str() => {
+                return Err(DatabaseError::InvalidOperation(format!(
+                    "Media file {} is not an audio file",
+                    media_file_id
+                )))
+            }
+            Some(_) => {}
+        }
+
+        let attached: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM project_audio_files WHERE project_id = ? AND media_file_id = ?)",
+            params![project_id, media_file_id],
+            |row| row.get(0),
+        )?;
+        if attached {
+            update_details(&tx, project_id, media_file_id, label, version)?;
+        } else {
+            let label = label.unwrap_or(DEFAULT_AUDIO_LABEL);
+            let version = match version {
+                Some(version) => version,
+                None => next_version(&tx, project_id, label)?,
+            };
+            tx.execute(
+                "INSERT INTO project_audio_files
+                    (project_id, media_file_id, label, version, is_primary, uploaded_at)
+                 VALUES (?, ?, ?, ?, false, ?)",
+                params![
+                    project_id,
+                    media_file_id,
+                    label,
+                    version,
+                    SqlDateTime::from_utc(Utc::now())
+                ],
+            )?;
+        }
+
+        let has_primary: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM project_audio_files WHERE project_id = ? AND is_primary = true)",
+            [project_id],
+            |row| row.get(0),
+        )?;
+        if make_primary || !has_primary {
+            set_primary(&tx, project_id, media_file_id)?;
+        }
+        tx.commit()?;
+
+        info!("Attached audio file {} to project {}", media_file_id, project_id);
+        self.get_project_audio_file_entry(project_id, media_file_id)?
+            .ok_or_else(|| DatabaseError::NotFound(media_file_id.to_string()))
+    }
+
+    /// Change the label or version of an attached audio file
+    pub fn update_project_audio_file_details(
+        &mut self,
+        project_id: &str,
+        media_file_id: &str,
+        label: Option<&str>,
+        version: Option<u32>,
+    ) -> Result<ProjectAudioFile, DatabaseError> {
+        let tx = self.conn.transaction()?;
+        update_details(&tx, project_id, media_file_id, label, version)?;
+        tx.commit()?;
+        self.get_project_audio_file_entry(project_id, media_file_id)?
+            .ok_or_else(|| not_attached(project_id, media_file_id))
+    }
+
+    /// Make an attached audio file the primary one of its project
+    pub fn set_primary_project_audio_file(
+        &mut self,
+        project_id: &str,
+        media_file_id: &str,
+    ) -> Result<(), DatabaseError> {
+        let tx = self.conn.transaction()?;
+        set_primary(&tx, project_id, media_file_id)?;
+        tx.commit()?;
+        debug!("Audio file {} is now primary for project {}", media_file_id, project_id);
+        Ok(())
+    }
+
+    /// Detach an audio file from a project. If it was the primary one, the most
+    /// recently added remaining audio file takes over.
+    pub fn detach_project_audio_file(
+        &mut self,
+        project_id: &str,
+        media_file_id: &str,
+    ) -> Result<(), DatabaseError> {
+        let tx = self.conn.transaction()?;
+        let was_primary: Option<bool> = tx
+            .query_row(
+                "SELECT is_primary FROM project_audio_files WHERE project_id = ? AND media_file_id = ?",
+                params![project_id, media_file_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let was_primary = was_primary.ok_or_else(|| not_attached(project_id, media_file_id))?;
+
+        tx.execute(
+            "DELETE FROM project_audio_files WHERE project_id = ? AND media_file_id = ?",
+            params![project_id, media_file_id],
+        )?;
+        if was_primary {
+            promote_primary_audio_files(&tx)?;
+        }
+        tx.commit()?;
+
+        info!("Detached audio file {} from project {}", media_file_id, project_id);
+        Ok(())
+    }
+
+    /// Detach every audio file from a project
+    pub fn detach_project_audio_files(&mut self, project_id: &str) -> Result<usize, DatabaseError> {
+        let removed = self.conn.execute(
+            "DELETE FROM project_audio_files WHERE project_id = ?",
+            [project_id],
+        )?;
+        debug!("Detached {} audio files from project {}", removed, project_id);
+        Ok(removed)
+    }
+
+    /// Audio files of a project, the primary one first and then the most recent
+    pub fn get_project_audio_files(
+        &self,
+        project_id: &str,
+    ) -> Result<Vec<ProjectAudioFile>, DatabaseError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM project_audio_files pa
+             JOIN media_files m ON m.id = pa.media_file_id
+             LEFT JOIN audio_analysis aa ON aa.media_file_id = pa.media_file_id
+             WHERE pa.project_id = ?
+             ORDER BY pa.is_primary DESC, pa.uploaded_at DESC, pa.label, pa.version DESC",
+            PROJECT_AUDIO_COLUMNS
+        ))?;
+        let audio_files = stmt
+            .query_map([project_id], |row| self.row_to_project_audio_file(row))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(audio_files)
+    }
+
+    /// One audio file of a project, if it's attached to it
+    pub fn get_project_audio_file_entry(
+        &self,
+        project_id: &str,
+        media_file_id: &str,
+    ) -> Result<Option<ProjectAudioFile>, DatabaseError> {
+        let audio_file = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM project_audio_files pa
+                     JOIN media_files m ON m.id = pa.media_file_id
+                     LEFT JOIN audio_analysis aa ON aa.media_file_id = pa.media_file_id
+                     WHERE pa.project_id = ? AND pa.media_file_id = ?",
+                    PROJECT_AUDIO_COLUMNS
+                ),
+                params![project_id, media_file_id],
+                |row| self.row_to_project_audio_file(row),
+            )
+            .optional()?;
+        Ok(audio_file)
+    }
+
+    fn row_to_project_audio_file(&self, row: &Row) -> Result<ProjectAudioFile, rusqlite::Error> {
+        let attached_at: i64 = row.get("attached_at")?;
+        Ok(ProjectAudioFile {
+            project_id: row.get("project_id")?,
+            media_file: self.row_to_media_file(row)?,
+            label: row.get("label")?,
+            version: row.get("version")?,
+            is_primary: row.get("is_primary")?,
+            uploaded_at: DateTime::from_timestamp(attached_at, 0).unwrap_or_default(),
+            analysis: row_to_audio_analysis(row)?,
+        })
+    }
+}
+
+/// Give projects that lost their primary audio file a new one, the most recently
+/// added of the rest
+pub(super) fn promote_primary_audio_files(conn: &Connection) -> Result<usize, DatabaseError> {
+    let promoted = conn.execute(
+        "UPDATE project_audio_files AS pa SET is_primary = true
+         WHERE NOT EXISTS (
+             SELECT 1 FROM project_audio_files p
+             WHERE p.project_id = pa.project_id AND p.is_primary = true
+         )
+         AND pa.media_file_id = (
+             SELECT p.media_file_id FROM project_audio_files p
+             WHERE p.project_id = pa.project_id
+             ORDER BY p.uploaded_at DESC, p.version DESC LIMIT 1
+         )",
+        [],
+    )?;
+    Ok(promoted)
+}
+
+fn not_attached(project_id: &str, media_file_id: &str) -> DatabaseError {
+    DatabaseError::NotFound(format!(
+        "Audio file {} is not attached to project {}",
+        media_file_id, project_id
+    ))
+}
+
+fn next_version(tx: &Transaction, project_id: &str, label: &str) -> Result<u32, DatabaseError> {
+    let version: u32 = tx.query_row(
+        "SELECT COALESCE(MAX(version), 0) + 1 FROM project_audio_files
+         WHERE project_id = ? AND label = ?",
+        params![project_id, label],
+        |row| row.get(0),
+    )?;
+    Ok(version)
+}
+
+fn update_details(
+    tx: &Transaction,
+    project_id: &str,
+    media_file_id: &str,
+    label: Option<&str>,
+    version: Option<u32>,
+) -> Result<(), DatabaseError> {
+    // A new label starts its own count unless a version is given
+    let version = match (label, version) {
+        (_, Some(version)) => Some(version),
+        (Some(label), None) => {
+            let current: Option<String> = tx
+                .query_row(
+                    "SELECT label FROM project_audio_files WHERE project_id = ? AND media_file_id = ?",
+                    params![project_id, media_file_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match current {
+                Some(current) if current != label => Some(next_version(tx, project_id, label)?),
+                _ => None,
+            }
+        }
+        (None, None) => None,
+    };
+
+    let updated = tx.execute(
+        "UPDATE project_audio_files
+         SET label = COALESCE(?, label), version = COALESCE(?, version)
+         WHERE project_id = ? AND media_file_id = ?",
+        params![label, version, project_id, media_file_id],
+    )?;
+    if updated == 0 {
+        return Err(not_attached(project_id, media_file_id));
+    }
+    Ok(())
+}
+
+fn set_primary(tx: &Transaction, project_id: &str, media_file_id: &str) -> Result<(), DatabaseError> {
+    let is_attached: bool = tx.query_row(
+        "SELECT EXISTS(SELECT 1 FROM project_audio_files WHERE project_id = ? AND media_file_id = ?)",
+        params![project_id, media_file_id],
+        |row| row.get(0),
+    )?;
+    if !is_attached {
+        return Err(not_attached(project_id, media_file_id));
+    }
+    tx.execute(
+        "UPDATE project_audio_files SET is_primary = (media_file_id = ?) WHERE project_id = ?",
+        params![media_file_id, project_id],
+    )?;
+    Ok(())
+}
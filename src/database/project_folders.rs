@@ -0,0 +1,122 @@
+use crate::error::DatabaseError;
+use crate::models::SetKind;
+use log::{debug, info};
+use rusqlite::OptionalExtension;
+use std::path::Path;
+
+use super::helpers::record_set_location;
+use super::LiveSetDatabase;
+
+/// A Live project folder and the indexed sets saved in it
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ProjectFolder {
+    pub path: String,
+    /// Name of the folder itself
+    pub name: String,
+    /// Sets in the folder, most recently modified first
+    pub project_ids: Vec<String>,
+    /// Modification time of the newest set
+    pub last_modified_at: i64,
+}
+
+impl ProjectFolder {
+    /// The set most recently worked on
+    pub fn latest_project_id(&self) -> Option<&str> {
+        self.project_ids.first().map(String::as_str)
+    }
+}
+
+impl LiveSetDatabase {
+    /// Classify every indexed set again from its path and look up its project folder.
+    /// Returns the number of sets checked.
+    pub fn refresh_set_locations(&mut self) -> Result<usize, DatabaseError> {
+        let tx = self.conn.transaction()?;
+        let projects = {
+            let mut stmt = tx.prepare("SELECT id, path FROM projects")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        for (project_id, path) in &projects {
+            record_set_location(&tx, project_id, Path::new(path))?;
+        }
+        tx.commit()?;
+
+        info!("Refreshed set kind and project folder of {} sets", projects.len());
+        Ok(projects.len())
+    }
+
+    /// Kind and project folder stored for a project
+    pub fn get_project_location(
+        &self,
+        project_id: &str,
+    ) -> Result<Option<(SetKind, Option<String>)>, DatabaseError> {
+        let location = self
+            .conn
+            .query_row(
+                "SELECT set_kind, project_folder FROM projects WHERE id = ?",
+                [project_id],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)),
+            )
+            .optional()?;
+
+        Ok(location.map(|(kind, folder)| (kind.parse().unwrap_or_default(), folder)))
+    }
+
+    /// Active sets grouped by the project folder they're saved in, most recently
+    /// modified folder first. Sets outside a project folder are left out.
+    pub fn get_project_folders(&self) -> Result<Vec<ProjectFolder>, DatabaseError> {
+        let mut folders = self.load_project_folders(None)?;
+        folders.sort_by(|a, b| {
+            b.last_modified_at
+                .cmp(&a.last_modified_at)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        debug!("Found {} project folders", folders.len());
+        Ok(folders)
+    }
+
+    /// The project folder at `path`, if any active set is saved in it
+    pub fn get_project_folder(&self, path: &str) -> Result<Option<ProjectFolder>, DatabaseError> {
+        Ok(self.load_project_folders(Some(path))?.into_iter().next())
+    }
+
+    fn load_project_folders(&self, path: Option<&str>) -> Result<Vec<ProjectFolder>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT project_folder, id, modified_at FROM projects
+             WHERE is_active = true AND project_folder IS NOT NULL
+             AND (?1 IS NULL OR project_folder = ?1)
+             ORDER BY project_folder, modified_at DESC, id",
+        )?;
+        let rows = stmt
+            .query_map([path], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut folders: Vec<ProjectFolder> = Vec::new();
+        for (folder_path, project_id, modified_at) in rows {
+            match folders.last_mut() {
+                Some(folder) if folder.path == folder_path => folder.project_ids.push(project_id),
+                _ => {
+                    let name = Path::new(&folder_path)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| folder_path.clone());
+                    folders.push(ProjectFolder {
+                        path: folder_path,
+                        name,
+                        project_ids: vec![project_id],
+                        last_modified_at: modified_at,
+                    });
+                }
+            }
+        }
+        Ok(folders)
+    }
+}
@@ -1,11 +1,12 @@
 use super::helpers::{
-    insert_plugin, insert_sample, link_project_plugin, link_project_sample, row_to_live_set,
+    insert_plugin, insert_sample, link_project_plugin, link_project_sample, record_set_location,
+    row_to_live_set,
 };
 use super::models::SqlDateTime;
 use crate::error::DatabaseError;
 use crate::live_set::LiveSet;
-use crate::models::{AbletonVersion, KeySignature, Plugin, Sample, TimeSignature};
-use crate::utils::metadata::load_file_hash;
+use crate::models::{AbletonVersion, KeySignature, Plugin, Sample, SetKind, TimeSignature};
+use crate::utils::metadata::{load_file_hash, load_file_name};
 use chrono::{Local, TimeZone, Utc};
 use log::{debug, info};
 use rusqlite::{params, OptionalExtension, Result as SqliteResult};
@@ -385,6 +386,19 @@ impl LiveSetDatabase {
         Ok(Some(project))
     }
 
+    /// ID and active flag of the project stored at `path`, without loading it
+    pub fn get_project_id_by_path(&self, path: &str) -> Result<Option<(String, bool)>, DatabaseError> {
+        let project = self
+            .conn
+            .query_row(
+                "SELECT id, is_active FROM projects WHERE path = ?",
+                [path],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(project)
+    }
+
     pub fn get_project_by_path(&mut self, path: &str) -> Result<Option<LiveSet>, DatabaseError> {
         debug!("Retrieving project by path: {}", path);
         let tx = self.conn.transaction()?;
@@ -613,6 +627,7 @@ impl LiveSetDatabase {
                 None::<String>, // notes starts as NULL
             ],
         )?;
+        record_set_location(&tx, &project_id, &live_set.file_path)?;
 
         // Insert plugins
         debug!("Inserting {} plugins", live_set.plugins.len());
@@ -698,8 +713,8 @@ impl LiveSetDatabase {
     pub fn mark_project_deleted(&mut self, project_id: &Uuid) -> Result<(), DatabaseError> {
         self.conn
             .execute(
-                "UPDATE projects SET is_active = false WHERE id = ?",
-                params![project_id.to_string()],
+                "UPDATE projects SET is_active = false, deleted_at = ? WHERE id = ?",
+                params![Utc::now().timestamp(), project_id.to_string()],
             )
             .map_err(DatabaseError::from)?;
         Ok(())
@@ -714,6 +729,7 @@ impl LiveSetDatabase {
             .execute(
                 "UPDATE projects SET 
                 is_active = true,
+                deleted_at = NULL,
                 path = ?,
                 modified_at = ?
              WHERE id = ?",
@@ -724,6 +740,43 @@ impl LiveSetDatabase {
                 ],
             )
             .map_err(DatabaseError::from)?;
+        record_set_location(&self.conn, &project_id.to_string(), new_path)?;
+        Ok(())
+    }
+
+    /// Point a project at its new location after a rename or move, keeping its ID and
+    /// everything attached to it. A name that still matches the old file name follows
+    /// the file; a custom name is kept.
+    pub fn update_project_path(
+        &mut self,
+        project_id: &str,
+        new_path: &Path,
+    ) -> Result<(), DatabaseError> {
+        debug!("Updating path of project {} to {}", project_id, new_path.display());
+        let (old_path, name): (String, String) = self
+            .conn
+            .query_row(
+                "SELECT path, name FROM projects WHERE id = ?",
+                [project_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| DatabaseError::NotFound(format!("Project {} not found", project_id)))?;
+
+        let old_file_name = Path::new(&old_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string());
+        let name = if old_file_name.as_deref() == Some(name.as_str()) {
+            load_file_name(&new_path.to_path_buf())?
+        } else {
+            name
+        };
+
+        self.conn.execute(
+            "UPDATE projects SET is_active = true, deleted_at = NULL, name = ?, path = ? WHERE id = ?",
+            params![name, new_path.to_string_lossy().to_string(), project_id],
+        )?;
+        record_set_location(&self.conn, project_id, new_path)?;
         Ok(())
     }
 
@@ -1164,6 +1217,9 @@ impl LiveSetDatabase {
         modified_after: Option<i64>,
         modified_before: Option<i64>,
         has_audio_file: Option<bool>,
+        min_rating: Option<i32>,
+        is_favorite: Option<bool>,
+        set_kind: Option<SetKind>,
     ) -> Result<(Vec<LiveSet>, i32), DatabaseError> {
         let sort_column = match sort_by.as_deref() {
             Some("name") => "name",
@@ -1173,6 +1229,10 @@ impl LiveSetDatabase {
             Some("tempo") => "tempo",
             Some("duration_seconds") => "duration_seconds",
             Some("ableton_version_major") => "ableton_version_major",
+            Some("rating") => "rating",
+            Some("is_favorite") => "is_favorite",
+            Some("last_opened_at") => "last_opened_at",
+            Some("open_count") => "open_count",
             _ => "name", // default sort
         };
 
@@ -1253,12 +1313,27 @@ impl LiveSetDatabase {
 
         if let Some(has_audio) = has_audio_file {
             if has_audio {
-                conditions.push("audio_file_id IS NOT NULL");
+                conditions.push("id IN (SELECT project_id FROM project_audio_files)");
             } else {
-                conditions.push("audio_file_id IS NULL");
+                conditions.push("id NOT IN (SELECT project_id FROM project_audio_files)");
             }
         }
 
+        if let Some(rating) = min_rating {
+            conditions.push("rating >= ?");
+            params.push(Box::new(rating));
+        }
+
+        if let Some(favorite) = is_favorite {
+            conditions.push("is_favorite = ?");
+            params.push(Box::new(favorite));
+        }
+
+        if let Some(kind) = set_kind {
+            conditions.push("set_kind = ?");
+            params.push(Box::new(kind.as_str()));
+        }
+
         let where_clause = format!("WHERE {}", conditions.join(" AND "));
 
         // Get total count with filters
@@ -1418,8 +1493,8 @@ impl LiveSetDatabase {
             }
         }
 
-        // Parse the project file
-        let new_live_set = match crate::LiveSet::new(file_path.clone()) {
+        // Parse the project file ahead of any bulk scan that's running
+        let new_live_set = match crate::scan::SCAN_JOBS.parse_now(file_path.clone()) {
             Ok(live_set) => live_set,
             Err(e) => {
                 return Ok(RescanProjectResult {
@@ -1432,6 +1507,20 @@ impl LiveSetDatabase {
             }
         };
 
+        self.update_project_from_live_set(project_id, &new_live_set)
+    }
+
+    /// Update a project from a freshly parsed set, keeping its ID and everything
+    /// attached to it
+    pub fn update_project_from_live_set(
+        &mut self,
+        project_id: &str,
+        new_live_set: &LiveSet,
+    ) -> Result<RescanProjectResult, DatabaseError> {
+        let existing_project = self
+            .get_project_by_id_any_status(project_id)?
+            .ok_or_else(|| DatabaseError::NotFound(format!("Project {} not found", project_id)))?;
+
         // Compare with existing project to detect changes
         let mut changes = Vec::new();
         
@@ -1495,7 +1584,6 @@ impl LiveSetDatabase {
         // Delete existing project data
         tx.execute("DELETE FROM project_plugins WHERE project_id = ?", params![project_id])?;
         tx.execute("DELETE FROM project_samples WHERE project_id = ?", params![project_id])?;
-        tx.execute("DELETE FROM project_tags WHERE project_id = ?", params![project_id])?;
         tx.execute("DELETE FROM project_search WHERE project_id = ?", params![project_id])?;
 
         // Update the project record
@@ -1511,7 +1599,7 @@ impl LiveSetDatabase {
                 new_live_set.name,
                 new_live_set.file_path.to_string_lossy().to_string(),
                 new_live_set.file_hash,
-                chrono::Utc::now().timestamp(),
+                SqlDateTime::from(new_live_set.modified_time),
                 chrono::Utc::now().timestamp(),
                 new_live_set.tempo,
                 new_live_set.time_signature.numerator,
@@ -1527,6 +1615,7 @@ impl LiveSetDatabase {
                 project_id,
             ],
         )?;
+        record_set_location(&tx, project_id, &new_live_set.file_path)?;
 
         // Insert new plugins
         for plugin in &new_live_set.plugins {
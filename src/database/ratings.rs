@@ -0,0 +1,177 @@
+use crate::error::DatabaseError;
+use crate::live_set::LiveSet;
+use chrono::{Duration, Local};
+use log::debug;
+use rusqlite::{params, OptionalExtension};
+
+use super::LiveSetDatabase;
+
+/// Highest star rating a project can have
+pub const MAX_RATING: i32 = 5;
+
+/// Forgotten gems defaults: rated at least this well...
+pub const DEFAULT_GEM_MIN_RATING: i32 = 4;
+/// ...and untouched for this many months
+pub const DEFAULT_GEM_MONTHS: u32 = 6;
+
+/// Opens within this window of the previous one count as the same session
+pub const OPEN_SESSION_SECONDS: i64 = 30 * 60;
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ProjectEngagement {
+    /// 1-5 stars, None when unrated
+    pub rating: Option<i32>,
+    pub is_favorite: bool,
+    pub last_opened_at: Option<i64>,
+    /// Number of distinct sessions the project was opened in
+    pub open_count: i32,
+}
+
+impl LiveSetDatabase {
+    pub(crate) fn ensure_project_exists(&self, project_id: &str) -> Result<(), DatabaseError> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?)",
+            [project_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(DatabaseError::NotFound(format!(
+                "Project {} not found",
+                project_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Rating, favorite flag and open tracking of a project
+    pub fn get_project_engagement(
+        &self,
+        project_id: &str,
+    ) -> Result<Option<ProjectEngagement>, DatabaseError> {
+        let engagement = self
+            .conn
+            .query_row(
+                "SELECT rating, is_favorite, last_opened_at, open_count FROM projects WHERE id = ?",
+                [project_id],
+                |row| {
+                    Ok(ProjectEngagement {
+                        rating: row.get(0)?,
+                        is_favorite: row.get(1)?,
+                        last_opened_at: row.get(2)?,
+                        open_count: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(engagement)
+    }
+
+    /// Set a 1-5 star rating, or clear it with None
+    pub fn set_project_rating(
+        &mut self,
+        project_id: &str,
+        rating: Option<i32>,
+    ) -> Result<(), DatabaseError> {
+        debug!("Setting rating of project {} to {:?}", project_id, rating);
+        if let Some(rating) = rating {
+            if !(1..=MAX_RATING).contains(&rating) {
+                return Err(DatabaseError::InvalidOperation(format!(
+                    "Rating must be between 1 and {}, got {}",
+                    MAX_RATING, rating
+                )));
+            }
+        }
+        self.ensure_project_exists(project_id)?;
+
+        self.conn.execute(
+            "UPDATE projects SET rating = ? WHERE id = ?",
+            params![rating, project_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_project_favorite(
+        &mut self,
+        project_id: &str,
+        is_favorite: bool,
+    ) -> Result<(), DatabaseError> {
+        debug!("Setting favorite of project {} to {}", project_id, is_favorite);
+        self.ensure_project_exists(project_id)?;
+
+        self.conn.execute(
+            "UPDATE projects SET is_favorite = ? WHERE id = ?",
+            params![is_favorite, project_id],
+        )?;
+        Ok(())
+    }
+
+    /// Record that a project was opened. Repeated opens within the same session only
+    /// move `last_opened_at` forward and are not counted again.
+    pub fn record_project_opened(
+        &mut self,
+        project_id: &str,
+        opened_at: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        let opened_at = opened_at.unwrap_or_else(|| Local::now().timestamp());
+        debug!("Recording project {} opened at {}", project_id, opened_at);
+        self.ensure_project_exists(project_id)?;
+
+        self.conn.execute(
+            r#"
+            UPDATE projects SET
+                open_count = open_count + CASE
+                    WHEN last_opened_at IS NULL OR ?1 - last_opened_at > ?2 THEN 1
+                    ELSE 0
+                END,
+                last_opened_at = MAX(COALESCE(last_opened_at, ?1), ?1)
+            WHERE id = ?3
+            "#,
+            params![opened_at, OPEN_SESSION_SECONDS, project_id],
+        )?;
+        Ok(())
+    }
+
+    /// Highly rated projects that haven't been opened or modified in `months` months,
+    /// best rated and longest forgotten first
+    pub fn get_forgotten_gems(
+        &mut self,
+        min_rating: i32,
+        months: u32,
+        limit: Option<i32>,
+    ) -> Result<Vec<LiveSet>, DatabaseError> {
+        debug!(
+            "Getting projects rated {}+ untouched for {} months",
+            min_rating, months
+        );
+        let cutoff = (Local::now() - Duration::days(30 * months as i64)).timestamp();
+
+        let project_ids: Vec<String> = {
+            let mut stmt = self.conn.prepare(
+                r#"
+                SELECT id FROM projects
+                WHERE is_active = true
+                AND rating >= ?
+                AND MAX(modified_at, COALESCE(last_opened_at, 0)) < ?
+                ORDER BY rating DESC, MAX(modified_at, COALESCE(last_opened_at, 0)) ASC
+                LIMIT ?
+                "#,
+            )?;
+            let ids = stmt
+                .query_map(params![min_rating, cutoff, limit.unwrap_or(-1)], |row| {
+                    row.get(0)
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+            ids
+        };
+
+        let mut projects = Vec::new();
+        for project_id in project_ids {
+            if let Some(project) = self.get_project_by_id(&project_id)? {
+                projects.push(project);
+            }
+        }
+        debug!("Found {} forgotten gems", projects.len());
+        Ok(projects)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::error::DatabaseError;
+use crate::live_set::LiveSet;
+use chrono::{Duration, Utc};
+use log::{debug, info};
+use rusqlite::params;
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::projects::RescanProjectResult;
+use super::LiveSetDatabase;
+
+/// Projects deleted within this many days can be matched by name and content
+/// fingerprint. Hash matches are exact and apply regardless of age.
+pub const MOVE_FINGERPRINT_WINDOW_DAYS: i64 = 30;
+
+/// How a newly discovered set was matched to an existing project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveMatch {
+    /// Identical file content
+    Hash,
+    /// Same file name and the same parsed tempo, signatures, version, plugins and samples
+    Fingerprint,
+}
+
+/// A project that no longer has a file where it was indexed: either marked deleted,
+/// or still active but missing on disk because nothing has noticed yet
+fn is_vacated(is_active: bool, path: &str) -> bool {
+    !is_active || !Path::new(path).exists()
+}
+
+fn same_fingerprint(a: &LiveSet, b: &LiveSet) -> bool {
+    let plugins = |set: &LiveSet| {
+        set.plugins
+            .iter()
+            .map(|p| p.name.clone())
+            .collect::<HashSet<_>>()
+    };
+    let samples = |set: &LiveSet| {
+        set.samples
+            .iter()
+            .map(|s| s.name.clone())
+            .collect::<HashSet<_>>()
+    };
+    a.tempo == b.tempo
+        && a.time_signature == b.time_signature
+        && a.key_signature == b.key_signature
+        && a.ableton_version == b.ableton_version
+        && plugins(a) == plugins(b)
+        && samples(a) == samples(b)
+}
+
+impl LiveSetDatabase {
+    /// A vacated project whose file had exactly this content
+    pub fn find_moved_project_by_hash(
+        &self,
+        new_path: &Path,
+        hash: &str,
+    ) -> Result<Option<String>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, path, is_active FROM projects
+             WHERE hash = ? AND path != ?
+             ORDER BY is_active ASC, deleted_at DESC",
+        )?;
+        let candidates = stmt
+            .query_map(
+                params![hash, new_path.to_string_lossy().to_string()],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, bool>(2)?,
+                    ))
+                },
+            )?
+            .filter_map(|r| r.ok());
+
+        for (project_id, path, is_active) in candidates {
+            if is_vacated(is_active, &path) {
+                return Ok(Some(project_id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// A recently vacated project with the same file name whose parsed content matches.
+    /// Ambiguous matches are ignored rather than guessed.
+    pub fn find_moved_project_by_fingerprint(
+        &mut self,
+        live_set: &LiveSet,
+    ) -> Result<Option<String>, DatabaseError> {
+        let cutoff = (Utc::now() - Duration::days(MOVE_FINGERPRINT_WINDOW_DAYS)).timestamp();
+        let candidates: Vec<(String, String, bool)> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT id, path, is_active FROM projects
+                 WHERE name = ? AND path != ?
+                 AND (is_active = true OR deleted_at >= ?)",
+            )?;
+            let candidates = stmt
+                .query_map(
+                    params![
+                        live_set.name,
+                        live_set.file_path.to_string_lossy().to_string(),
+                        cutoff
+                    ],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )?
+                .filter_map(|r| r.ok())
+                .collect();
+            candidates
+        };
+
+        let mut matches = Vec::new();
+        for (project_id, path, is_active) in candidates {
+            if !is_vacated(is_active, &path) {
+                continue;
+            }
+            if let Some(project) = self.get_project_by_id_any_status(&project_id)? {
+                if same_fingerprint(&project, live_set) {
+                    matches.push(project_id);
+                }
+            }
+        }
+
+        if matches.len() > 1 {
+            debug!(
+                "{} projects match the fingerprint of {}, not re-attaching",
+                matches.len(),
+                live_set.file_path.display()
+            );
+            return Ok(None);
+        }
+        Ok(matches.pop())
+    }
+
+    /// The existing project a newly discovered set belongs to, if it was moved or renamed
+    pub fn find_moved_project(
+        &mut self,
+        live_set: &LiveSet,
+    ) -> Result<Option<(String, MoveMatch)>, DatabaseError> {
+        if let Some(project_id) =
+            self.find_moved_project_by_hash(&live_set.file_path, &live_set.file_hash)?
+        {
+            return Ok(Some((project_id, MoveMatch::Hash)));
+        }
+        Ok(self
+            .find_moved_project_by_fingerprint(live_set)?
+            .map(|project_id| (project_id, MoveMatch::Fingerprint)))
+    }
+
+    /// Point a project at the set's new location and update it from the parsed content
+    pub fn reattach_moved_project(
+        &mut self,
+        project_id: &str,
+        live_set: &LiveSet,
+    ) -> Result<RescanProjectResult, DatabaseError> {
+        info!(
+            "Re-attaching project {} at {}",
+            project_id,
+            live_set.file_path.display()
+        );
+        self.update_project_path(project_id, &live_set.file_path)?;
+        self.update_project_from_live_set(project_id, live_set)
+    }
+
+    /// Re-attach every newly discovered set that belongs to a moved project. Returns the
+    /// sets still to be inserted and the number re-attached.
+    pub fn reconcile_moved_projects(
+        &mut self,
+        live_sets: Vec<LiveSet>,
+    ) -> Result<(Vec<LiveSet>, usize), DatabaseError> {
+        let mut new_sets = Vec::with_capacity(live_sets.len());
+        let mut reattached = 0;
+
+        for live_set in live_sets {
+            let path = live_set.file_path.to_string_lossy().to_string();
+            if self.get_project_id_by_path(&path)?.is_some() {
+                new_sets.push(live_set);
+                continue;
+            }
+            match self.find_moved_project(&live_set)? {
+                Some((project_id, how)) => {
+                    debug!("{} matches project {} by {:?}", path, project_id, how);
+                    self.reattach_moved_project(&project_id, &live_set)?;
+                    reattached += 1;
+                }
+                None => new_sets.push(live_set),
+            }
+        }
+
+        if reattached > 0 {
+            info!("Re-attached {} moved projects", reattached);
+        }
+        Ok((new_sets, reattached))
+    }
+}
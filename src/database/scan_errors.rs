@@ -0,0 +1,161 @@
+use crate::error::{DatabaseError, LiveSetError};
+use chrono::Utc;
+use log::{debug, info};
+use rusqlite::{params, OptionalExtension};
+use std::path::Path;
+
+use super::LiveSetDatabase;
+
+/// Failed parses in a row after which scans skip a set until its file changes
+pub const QUARANTINE_AFTER_FAILURES: u32 = 2;
+
+/// A set that failed to parse
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanErrorRecord {
+    pub path: String,
+    /// Short name from [`LiveSetError::kind`]
+    pub error_kind: String,
+    pub message: String,
+    /// Line of the set the parser had reached
+    pub line: Option<u32>,
+    pub ableton_version: Option<String>,
+    /// Size and modification time of the file when it last failed
+    pub file_size: Option<u64>,
+    pub modified_at: Option<i64>,
+    /// Failed parses in a row of this version of the file
+    pub failure_count: u32,
+    pub first_failed_at: i64,
+    pub last_failed_at: i64,
+}
+
+impl ScanErrorRecord {
+    /// True if scans skip the set until it changes on disk
+    pub fn is_quarantined(&self) -> bool {
+        self.failure_count >= QUARANTINE_AFTER_FAILURES
+    }
+
+    /// True if a file with this size and modification time is the one that failed
+    pub fn matches_file(&self, size: u64, modified_at: i64) -> bool {
+        self.file_size == Some(size) && self.modified_at == Some(modified_at)
+    }
+}
+
+impl LiveSetDatabase {
+    /// Record a failed parse. Failures of the same file add up; a file that changed
+    /// since it last failed starts over. Returns the number of failures in a row.
+    pub fn record_scan_error(
+        &mut self,
+        path: &Path,
+        error: &LiveSetError,
+        file_state: Option<(u64, i64)>,
+    ) -> Result<u32, DatabaseError> {
+        let path_str = path.to_string_lossy().to_string();
+        let now = Utc::now().timestamp();
+        let file_size = file_state.map(|(size, _)| size as i64);
+        let modified_at = file_state.map(|(_, modified_at)| modified_at);
+
+        let tx = self.conn.transaction()?;
+        let previous: Option<(Option<i64>, Option<i64>, u32, i64)> = tx
+            .query_row(
+                "SELECT file_size, modified_at, failure_count, first_failed_at
+                 FROM scan_errors WHERE path = ?",
+                [&path_str],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()?;
+
+        let (failure_count, first_failed_at) = match previous {
+            Some((size, modified, count, first))
+                if file_state.is_some() && size == file_size && modified == modified_at =>
+            {
+                (count + 1, first)
+            }
+            _ => (1, now),
+        };
+
+        tx.execute(
+            "INSERT OR REPLACE INTO scan_errors (
+                path, error_kind, message, line, ableton_version, file_size, modified_at,
+                failure_count, first_failed_at, last_failed_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                path_str,
+                error.kind(),
+                error.to_string(),
+                error.line().map(|line| line as i64),
+                error.live_version(),
+                file_size,
+                modified_at,
+                failure_count,
+                first_failed_at,
+                now
+            ],
+        )?;
+        tx.commit()?;
+
+        if failure_count == QUARANTINE_AFTER_FAILURES {
+            info!(
+                "Skipping {} until it changes: failed to parse {} times",
+                path.display(),
+                failure_count
+            );
+        }
+        Ok(failure_count)
+    }
+
+    /// Forget the failures of sets that have since parsed. Returns how many were cleared.
+    pub fn clear_scan_errors(&mut self, paths: &[&Path]) -> Result<usize, DatabaseError> {
+        let tx = self.conn.transaction()?;
+        let mut cleared = 0;
+        {
+            let mut stmt = tx.prepare("DELETE FROM scan_errors WHERE path = ?")?;
+            for path in paths {
+                cleared += stmt.execute([path.to_string_lossy().to_string()])?;
+            }
+        }
+        tx.commit()?;
+
+        if cleared > 0 {
+            debug!("Cleared {} scan errors", cleared);
+        }
+        Ok(cleared)
+    }
+
+    /// Sets that failed to parse, most recent failure first
+    pub fn get_scan_errors(
+        &self,
+        quarantined_only: bool,
+    ) -> Result<Vec<ScanErrorRecord>, DatabaseError> {
+        let min_failures = if quarantined_only {
+            QUARANTINE_AFTER_FAILURES
+        } else {
+            1
+        };
+        let mut stmt = self.conn.prepare(
+            "SELECT path, error_kind, message, line, ableton_version, file_size, modified_at,
+                    failure_count, first_failed_at, last_failed_at
+             FROM scan_errors
+             WHERE failure_count >= ?
+             ORDER BY last_failed_at DESC, path",
+        )?;
+        let errors = stmt
+            .query_map([min_failures], |row| {
+                Ok(ScanErrorRecord {
+                    path: row.get(0)?,
+                    error_kind: row.get(1)?,
+                    message: row.get(2)?,
+                    line: row.get(3)?,
+                    ableton_version: row.get(4)?,
+                    file_size: row.get::<_, Option<i64>>(5)?.map(|size| size as u64),
+                    modified_at: row.get(6)?,
+                    failure_count: row.get(7)?,
+                    first_failed_at: row.get(8)?,
+                    last_failed_at: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        debug!("Loaded {} scan errors", errors.len());
+        Ok(errors)
+    }
+}
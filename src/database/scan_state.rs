@@ -0,0 +1,100 @@
+use crate::error::DatabaseError;
+use chrono::Utc;
+use log::{debug, info};
+use rusqlite::params;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::LiveSetDatabase;
+
+/// What the database knows about an indexed set's file, used by incremental scans to
+/// decide whether it needs parsing again
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedFile {
+    pub project_id: String,
+    pub hash: String,
+    /// None for projects indexed before file sizes were recorded
+    pub file_size: Option<u64>,
+    /// File modification time as a unix timestamp
+    pub modified_at: i64,
+}
+
+impl LiveSetDatabase {
+    /// Every active project keyed by path, loaded in one query so a rescan doesn't
+    /// hit the database once per file
+    pub fn get_indexed_files(&self) -> Result<HashMap<String, IndexedFile>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT path, id, hash, file_size, modified_at FROM projects WHERE is_active = true",
+        )?;
+        let files = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    IndexedFile {
+                        project_id: row.get(1)?,
+                        hash: row.get(2)?,
+                        file_size: row.get::<_, Option<i64>>(3)?.map(|size| size as u64),
+                        modified_at: row.get(4)?,
+                    },
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .collect::<HashMap<_, _>>();
+
+        debug!("Loaded {} indexed files", files.len());
+        Ok(files)
+    }
+
+    /// Record the size and modification time a set's file had when it was last
+    /// checked, so the next scan can skip it without hashing
+    pub fn record_file_states(
+        &mut self,
+        states: &[(&Path, u64, i64)],
+    ) -> Result<(), DatabaseError> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE projects SET file_size = ?, modified_at = ? WHERE path = ?",
+            )?;
+            for (path, size, modified_at) in states {
+                stmt.execute(params![
+                    *size as i64,
+                    modified_at,
+                    path.to_string_lossy().to_string()
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Mark projects deleted whose files disappeared during a scan. A project that was
+    /// re-attached to a new path in the meantime is left alone. Returns how many were marked.
+    pub fn mark_removed_projects(
+        &mut self,
+        removed: &[(String, PathBuf)],
+    ) -> Result<u32, DatabaseError> {
+        let now = Utc::now().timestamp();
+        let tx = self.conn.transaction()?;
+        let mut marked = 0;
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE projects SET is_active = false, deleted_at = ?
+                 WHERE id = ? AND path = ? AND is_active = true",
+            )?;
+            for (project_id, path) in removed {
+                marked += stmt.execute(params![
+                    now,
+                    project_id,
+                    path.to_string_lossy().to_string()
+                ])? as u32;
+            }
+        }
+        tx.commit()?;
+
+        if marked > 0 {
+            info!("Marked {} removed projects as deleted", marked);
+        }
+        Ok(marked)
+    }
+}
@@ -8,6 +8,7 @@ use std::collections::HashSet;
 use std::path::PathBuf;
 use uuid::Uuid;
 
+use super::tags::TAG_SUBTREE_CTE;
 use super::LiveSetDatabase;
 
 #[derive(Debug, Default)]
@@ -31,6 +32,17 @@ pub struct SearchQuery {
     pub sample: Option<String>,
     pub tag: Option<String>,
 
+    // Workflow properties
+    pub status: Option<String>,
+    pub rating: Option<String>,
+    pub favorite: Option<String>,
+    pub opened: Option<String>,
+
+    // Audio properties, measured from the project's primary audio file
+    pub lufs: Option<String>,
+    pub true_peak: Option<String>,
+    pub loudness_range: Option<String>,
+
     // Full text search
     pub text: String,
 }
@@ -56,6 +68,13 @@ pub enum MatchReason {
     Notes(String),
     DateCreated(String),
     DateModified(String),
+    Status(String),
+    Rating(String),
+    Favorite(String),
+    LastOpened(String),
+    Loudness(String),
+    TruePeak(String),
+    LoudnessRange(String),
 }
 
 impl SearchQuery {
@@ -123,6 +142,13 @@ impl SearchQuery {
                             "plugin" => query.plugin = Some(cleaned_value),
                             "sample" => query.sample = Some(cleaned_value),
                             "tag" => query.tag = Some(cleaned_value),
+                            "status" => query.status = Some(cleaned_value),
+                            "rating" => query.rating = Some(cleaned_value),
+                            "fav" | "favorite" => query.favorite = Some(cleaned_value),
+                            "opened" => query.opened = Some(cleaned_value),
+                            "lufs" => query.lufs = Some(cleaned_value),
+                            "peak" => query.true_peak = Some(cleaned_value),
+                            "lra" => query.loudness_range = Some(cleaned_value),
                             _ => {
                                 debug!("Unknown operator '{}', treating as text", operator);
                                 remaining_text.push(&rest[..term_end]);
@@ -150,6 +176,35 @@ impl SearchQuery {
         query
     }
 
+    fn post_filter_match_reasons(&self) -> Vec<MatchReason> {
+        let mut reasons = Vec::new();
+        if let Some(tag) = &self.tag {
+            reasons.push(MatchReason::Tag(tag.clone()));
+        }
+        if let Some(status) = &self.status {
+            reasons.push(MatchReason::Status(status.clone()));
+        }
+        if let Some(rating) = &self.rating {
+            reasons.push(MatchReason::Rating(rating.clone()));
+        }
+        if let Some(favorite) = &self.favorite {
+            reasons.push(MatchReason::Favorite(favorite.clone()));
+        }
+        if let Some(opened) = &self.opened {
+            reasons.push(MatchReason::LastOpened(opened.clone()));
+        }
+        if let Some(lufs) = &self.lufs {
+            reasons.push(MatchReason::Loudness(lufs.clone()));
+        }
+        if let Some(true_peak) = &self.true_peak {
+            reasons.push(MatchReason::TruePeak(true_peak.clone()));
+        }
+        if let Some(loudness_range) = &self.loudness_range {
+            reasons.push(MatchReason::LoudnessRange(loudness_range.clone()));
+        }
+        reasons
+    }
+
     fn build_fts5_query(&self) -> (String, Vec<String>) {
         let mut conditions = Vec::new();
         let mut params = Vec::new();
@@ -191,9 +246,6 @@ impl SearchQuery {
         if let Some(ref sample) = self.sample {
             add_column_condition("samples", sample);
         }
-        if let Some(ref tag) = self.tag {
-            add_column_condition("tags", tag);
-        }
         if let Some(ref created) = self.date_created {
             add_column_condition("created_at", created);
         }
@@ -438,9 +490,16 @@ impl LiveSetDatabase {
     pub fn search_fts(&mut self, query: &SearchQuery) -> Result<Vec<SearchResult>, DatabaseError> {
         debug!("Performing FTS5 search with query: {:?}", query);
 
+        // Tag, workflow and rating properties are not matched through the FTS index, so they
+        // filter the matches afterwards
+        let filtered_project_ids = self.get_post_filter_project_ids(query)?;
+
         // Check if query is effectively empty
         let (sql_query, params) = query.build_fts5_query();
         if params.is_empty() || params[0].is_empty() {
+            if let Some(project_ids) = filtered_project_ids {
+                return self.search_by_post_filters(query, project_ids);
+            }
             debug!("Empty query detected, returning empty results");
             return Ok(Vec::new());
         }
@@ -483,6 +542,11 @@ impl LiveSetDatabase {
         let mut search_results = Vec::new();
         #[allow(unused)]
         for (project_id, rank, name, path, plugins, samples) in matching_paths {
+            if let Some(ref project_ids) = filtered_project_ids {
+                if !project_ids.contains(&project_id) {
+                    continue;
+                }
+            }
             debug!("Processing match: {} ({})", name, path);
             if let Ok(Some(project)) = self.get_project_by_path(&path) {
                 let mut match_reason = Vec::new();
@@ -509,6 +573,7 @@ impl LiveSetDatabase {
                 if let Some(date_modified) = &query.date_modified {
                     match_reason.push(MatchReason::DateModified(date_modified.clone()));
                 }
+                match_reason.extend(query.post_filter_match_reasons());
 
                 search_results.push(SearchResult {
                     project,
@@ -521,4 +586,179 @@ impl LiveSetDatabase {
         debug!("Successfully built {} search results", search_results.len());
         Ok(search_results)
     }
+
+    /// IDs of active projects matching the operators that aren't part of the FTS index,
+    /// or None if the query uses none of them
+    fn get_post_filter_project_ids(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<Option<HashSet<String>>, DatabaseError> {
+        let mut conditions = vec!["is_active = true".to_string()];
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+        if let Some(ref tag) = query.tag {
+            // tag:genre matches projects tagged genre or any tag below it, like genre/techno
+            let tag_id: Option<String> = self
+                .conn
+                .query_row(
+                    "SELECT id FROM tags WHERE name = ? COLLATE NOCASE",
+                    [tag.trim()],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match tag_id {
+                Some(tag_id) => {
+                    conditions.push(format!(
+                        "id IN (SELECT project_id FROM project_tags
+                            WHERE tag_id IN ({} SELECT id FROM subtree))",
+                        TAG_SUBTREE_CTE
+                    ));
+                    params.push(Box::new(tag_id));
+                }
+                None => {
+                    debug!("Unknown tag filter '{}', matching nothing", tag);
+                    conditions.push("0".to_string());
+                }
+            }
+        }
+
+        if let Some(ref status) = query.status {
+            conditions.push("status = ? COLLATE NOCASE".to_string());
+            params.push(Box::new(status.trim().to_string()));
+        }
+
+        if let Some(ref rating) = query.rating {
+            // rating:4 matches exactly four stars, rating:4+ four stars or more
+            let (value, at_least) = match rating.strip_suffix('+') {
+                Some(value) => (value, true),
+                None => (rating.as_str(), false),
+            };
+            match value.parse::<i32>() {
+                Ok(value) => {
+                    conditions.push(if at_least { "rating >= ?" } else { "rating = ?" }.to_string());
+                    params.push(Box::new(value));
+                }
+                Err(_) => {
+                    debug!("Invalid rating filter '{}', matching nothing", rating);
+                    conditions.push("0".to_string());
+                }
+            }
+        }
+
+        if let Some(ref favorite) = query.favorite {
+            match favorite.to_lowercase().as_str() {
+                "true" | "yes" | "1" => conditions.push("is_favorite = true".to_string()),
+                "false" | "no" | "0" => conditions.push("is_favorite = false".to_string()),
+                _ => {
+                    debug!("Invalid favorite filter '{}', matching nothing", favorite);
+                    conditions.push("0".to_string());
+                }
+            }
+        }
+
+        if let Some(ref opened) = query.opened {
+            // opened:never, or opened:<N>[d|w|m] for projects opened within the last N days/weeks/months
+            if opened.eq_ignore_ascii_case("never") {
+                conditions.push("last_opened_at IS NULL".to_string());
+            } else {
+                let (value, days_per_unit) = match opened.chars().last() {
+                    Some('d') => (&opened[..opened.len() - 1], 1),
+                    Some('w') => (&opened[..opened.len() - 1], 7),
+                    Some('m') => (&opened[..opened.len() - 1], 30),
+                    _ => (opened.as_str(), 1),
+                };
+                let cutoff = value.parse::<i64>().ok().and_then(|value| {
+                    value
+                        .checked_mul(days_per_unit * 86400)
+                        .and_then(|seconds| Local::now().timestamp().checked_sub(seconds))
+                });
+                match cutoff {
+                    Some(cutoff) => {
+                        conditions.push("last_opened_at >= ?".to_string());
+                        params.push(Box::new(cutoff));
+                    }
+                    None => {
+                        debug!("Invalid opened filter '{}', matching nothing", opened);
+                        conditions.push("0".to_string());
+                    }
+                }
+            }
+        }
+
+        // lufs:<-10, peak:>=-1 or lra:8, the last matching within half a unit
+        for (filter, column) in [
+            (&query.lufs, "integrated_lufs"),
+            (&query.true_peak, "true_peak_dbtp"),
+            (&query.loudness_range, "loudness_range_lu"),
+        ] {
+            let Some(filter) = filter else {
+                continue;
+            };
+            let (operator, value) = ["<=", ">=", "<", ">", "="]
+                .into_iter()
+                .find_map(|operator| filter.strip_prefix(operator).map(|value| (operator, value)))
+                .unwrap_or(("", filter.as_str()));
+            let Ok(value) = value.trim().parse::<f64>() else {
+                debug!("Invalid {} filter '{}', matching nothing", column, filter);
+                conditions.push("0".to_string());
+                continue;
+            };
+            let comparison = match operator {
+                "" | "=" => {
+                    params.push(Box::new(value - 0.5));
+                    params.push(Box::new(value + 0.5));
+                    format!("aa.{} BETWEEN ? AND ?", column)
+                }
+                operator => {
+                    params.push(Box::new(value));
+                    format!("aa.{} {} ?", column, operator)
+                }
+            };
+            conditions.push(format!(
+                "id IN (SELECT pa.project_id FROM project_audio_files pa
+                    JOIN audio_analysis aa ON aa.media_file_id = pa.media_file_id
+                    WHERE pa.is_primary = true AND {})",
+                comparison
+            ));
+        }
+
+        if conditions.len() == 1 {
+            return Ok(None);
+        }
+
+        let sql = format!("SELECT id FROM projects WHERE {}", conditions.join(" AND "));
+        let mut stmt = self.conn.prepare(&sql)?;
+        let ids = stmt
+            .query_map(
+                rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
+                |row| row.get(0),
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(Some(ids))
+    }
+
+    /// Results for a query that only uses operators outside the FTS index
+    fn search_by_post_filters(
+        &mut self,
+        query: &SearchQuery,
+        project_ids: HashSet<String>,
+    ) -> Result<Vec<SearchResult>, DatabaseError> {
+        let mut projects = Vec::new();
+        for project_id in project_ids {
+            if let Some(project) = self.get_project_by_id(&project_id)? {
+                projects.push(project);
+            }
+        }
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(projects
+            .into_iter()
+            .map(|project| SearchResult {
+                project,
+                rank: 0.0,
+                match_reason: query.post_filter_match_reasons(),
+            })
+            .collect())
+    }
 }
@@ -7,7 +7,7 @@ impl LiveSetDatabase {
     // Statistics methods
     pub fn get_basic_counts(&self) -> Result<(i32, i32, i32, i32, i32, i32), DatabaseError> {
         let total_projects: i32 = self.conn.query_row(
-            "SELECT COUNT(*) FROM projects WHERE is_active = true",
+            "SELECT COUNT(*) FROM projects WHERE is_active = true AND set_kind = 'project'",
             [],
             |row| row.get(0),
         )?;
@@ -43,7 +43,7 @@ impl LiveSetDatabase {
              FROM plugins p
              JOIN project_plugins pp ON p.id = pp.plugin_id
              JOIN projects proj ON pp.project_id = proj.id
-             WHERE proj.is_active = true
+             WHERE proj.is_active = true AND proj.set_kind = 'project'
              GROUP BY p.id
              ORDER BY usage_count DESC
              LIMIT ?",
@@ -73,7 +73,7 @@ impl LiveSetDatabase {
              FROM plugins p
              JOIN project_plugins pp ON p.id = pp.plugin_id
              JOIN projects proj ON pp.project_id = proj.id
-             WHERE proj.is_active = true
+             WHERE proj.is_active = true AND proj.set_kind = 'project'
              GROUP BY vendor
              ORDER BY usage_count DESC
              LIMIT ?",
@@ -112,7 +112,7 @@ impl LiveSetDatabase {
                 END as tempo_range,
                 COUNT(*) as count
              FROM projects
-             WHERE is_active = true AND tempo > 0
+             WHERE is_active = true AND set_kind = 'project' AND tempo > 0
              GROUP BY tempo_range
              ORDER BY tempo_range",
         )?;
@@ -136,7 +136,7 @@ impl LiveSetDatabase {
                 END as key_sig,
                 COUNT(*) as count
              FROM projects
-             WHERE is_active = true
+             WHERE is_active = true AND set_kind = 'project'
              GROUP BY key_sig
              ORDER BY count DESC",
         )?;
@@ -156,7 +156,7 @@ impl LiveSetDatabase {
         let mut stmt = self.conn.prepare(
             "SELECT time_signature_numerator, time_signature_denominator, COUNT(*) as count
              FROM projects
-             WHERE is_active = true
+             WHERE is_active = true AND set_kind = 'project'
              GROUP BY time_signature_numerator, time_signature_denominator
              ORDER BY count DESC",
         )?;
@@ -182,7 +182,7 @@ impl LiveSetDatabase {
                 CAST(strftime('%Y', datetime(created_at, 'unixepoch')) AS INTEGER) as year,
                 COUNT(*) as count
              FROM projects
-             WHERE is_active = true AND created_at IS NOT NULL
+             WHERE is_active = true AND set_kind = 'project' AND created_at IS NOT NULL
              GROUP BY year
              ORDER BY year DESC",
         )?;
@@ -206,7 +206,7 @@ impl LiveSetDatabase {
                 CAST(strftime('%m', datetime(created_at, 'unixepoch')) AS INTEGER) as month,
                 COUNT(*) as count
              FROM projects
-             WHERE is_active = true AND created_at IS NOT NULL
+             WHERE is_active = true AND set_kind = 'project' AND created_at IS NOT NULL
              GROUP BY year, month
              ORDER BY year DESC, month DESC
              LIMIT ?",
@@ -229,19 +229,19 @@ impl LiveSetDatabase {
 
     pub fn get_duration_analytics(&self) -> Result<(f64, i32, Option<String>), DatabaseError> {
         let avg_duration: f64 = self.conn.query_row(
-            "SELECT AVG(CAST(duration_seconds AS REAL)) FROM projects WHERE is_active = true AND duration_seconds IS NOT NULL",
+            "SELECT AVG(CAST(duration_seconds AS REAL)) FROM projects WHERE is_active = true AND set_kind = 'project' AND duration_seconds IS NOT NULL",
             [],
             |row| row.get(0)
         ).unwrap_or(0.0);
 
         let short_projects: i32 = self.conn.query_row(
-            "SELECT COUNT(*) FROM projects WHERE is_active = true AND duration_seconds IS NOT NULL AND duration_seconds < 40",
+            "SELECT COUNT(*) FROM projects WHERE is_active = true AND set_kind = 'project' AND duration_seconds IS NOT NULL AND duration_seconds < 40",
             [],
             |row| row.get(0)
         )?;
 
         let longest_project: Option<String> = self.conn.query_row(
-            "SELECT id FROM projects WHERE is_active = true AND duration_seconds IS NOT NULL ORDER BY duration_seconds DESC LIMIT 1",
+            "SELECT id FROM projects WHERE is_active = true AND set_kind = 'project' AND duration_seconds IS NOT NULL ORDER BY duration_seconds DESC LIMIT 1",
             [],
             |row| row.get(0)
         ).optional()?;
@@ -257,7 +257,7 @@ impl LiveSetDatabase {
                 SELECT COUNT(*) as plugin_count
                 FROM project_plugins pp
                 JOIN projects p ON pp.project_id = p.id
-                WHERE p.is_active = true
+                WHERE p.is_active = true AND p.set_kind = 'project'
                 GROUP BY pp.project_id
             )",
                 [],
@@ -272,7 +272,7 @@ impl LiveSetDatabase {
                 SELECT COUNT(*) as sample_count
                 FROM project_samples ps
                 JOIN projects p ON ps.project_id = p.id
-                WHERE p.is_active = true
+                WHERE p.is_active = true AND p.set_kind = 'project'
                 GROUP BY ps.project_id
             )",
                 [],
@@ -304,7 +304,7 @@ impl LiveSetDatabase {
                  FROM project_samples
                  GROUP BY project_id
              ) sample_counts ON p.id = sample_counts.project_id
-             WHERE p.is_active = true
+             WHERE p.is_active = true AND p.set_kind = 'project'
              ORDER BY complexity_score DESC
              LIMIT ?"
         )?;
@@ -331,7 +331,7 @@ impl LiveSetDatabase {
              FROM samples s
              JOIN project_samples ps ON s.id = ps.sample_id
              JOIN projects p ON ps.project_id = p.id
-             WHERE p.is_active = true
+             WHERE p.is_active = true AND p.set_kind = 'project'
              GROUP BY s.id
              ORDER BY usage_count DESC
              LIMIT ?",
@@ -358,7 +358,7 @@ impl LiveSetDatabase {
              FROM tags t
              JOIN project_tags pt ON t.id = pt.tag_id
              JOIN projects p ON pt.project_id = p.id
-             WHERE p.is_active = true
+             WHERE p.is_active = true AND p.set_kind = 'project'
              GROUP BY t.id
              ORDER BY usage_count DESC
              LIMIT ?",
@@ -415,7 +415,7 @@ impl LiveSetDatabase {
                      COUNT(*) as projects_created,
                      0 as projects_modified
                  FROM projects
-                 WHERE is_active = true AND created_at IS NOT NULL AND datetime(created_at, 'unixepoch') >= DATE('now', '-' || ? || ' days')
+                 WHERE is_active = true AND set_kind = 'project' AND created_at IS NOT NULL AND datetime(created_at, 'unixepoch') >= DATE('now', '-' || ? || ' days')
                  GROUP BY DATE(datetime(created_at, 'unixepoch'))
                  UNION ALL
                  SELECT 
@@ -423,7 +423,7 @@ impl LiveSetDatabase {
                      0 as projects_created,
                      COUNT(*) as projects_modified
                  FROM projects
-                 WHERE is_active = true AND modified_at IS NOT NULL AND datetime(modified_at, 'unixepoch') >= DATE('now', '-' || ? || ' days')
+                 WHERE is_active = true AND set_kind = 'project' AND modified_at IS NOT NULL AND datetime(modified_at, 'unixepoch') >= DATE('now', '-' || ? || ' days')
                  GROUP BY DATE(datetime(modified_at, 'unixepoch'))
              )
              WHERE date IS NOT NULL
@@ -457,7 +457,7 @@ impl LiveSetDatabase {
                 CASE WHEN ableton_version_beta = true THEN ' beta' ELSE '' END as version,
                 COUNT(*) as count
              FROM projects
-             WHERE is_active = true
+             WHERE is_active = true AND set_kind = 'project'
              GROUP BY version
              ORDER BY count DESC",
         )?;
@@ -540,7 +540,7 @@ impl LiveSetDatabase {
         has_audio_file: Option<bool>,
     ) -> Result<ProjectStatistics, DatabaseError> {
         // Build WHERE conditions for filtering
-        let mut conditions = vec!["is_active = true"];
+        let mut conditions = vec!["is_active = true", "set_kind = 'project'"];
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
         if let Some(min_tempo_val) = min_tempo {
@@ -600,9 +600,9 @@ impl LiveSetDatabase {
 
         if let Some(has_audio) = has_audio_file {
             if has_audio {
-                conditions.push("audio_file_id IS NOT NULL");
+                conditions.push("id IN (SELECT project_id FROM project_audio_files)");
             } else {
-                conditions.push("audio_file_id IS NULL");
+                conditions.push("id NOT IN (SELECT project_id FROM project_audio_files)");
             }
         }
 
@@ -620,7 +620,7 @@ impl LiveSetDatabase {
         )?;
 
         let projects_with_audio_files: i32 = self.conn.query_row(
-            &format!("SELECT COUNT(*) FROM projects {} AND audio_file_id IS NOT NULL", where_clause),
+            &format!("SELECT COUNT(*) FROM projects {} AND id IN (SELECT project_id FROM project_audio_files)", where_clause),
             rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
             |row| row.get(0),
         )?;
@@ -695,6 +695,7 @@ impl LiveSetDatabase {
         let projects_per_year = self.get_project_year_distribution(&where_clause, &params)?;
         let projects_per_month = self.get_project_month_distribution(&where_clause, &params)?;
         let most_complex_projects = self.get_project_complexity_statistics(&where_clause, &params)?;
+        let status_stages = self.get_project_status_stage_statistics(&where_clause, &params)?;
 
         Ok(ProjectStatistics {
             total_projects,
@@ -716,9 +717,84 @@ impl LiveSetDatabase {
             projects_per_year,
             projects_per_month,
             most_complex_projects,
+            status_stages,
         })
     }
 
+    /// Time spent in each workflow stage across all active projects
+    pub fn get_status_stage_statistics(&self) -> Result<Vec<StatusStageStatistics>, DatabaseError> {
+        self.get_project_status_stage_statistics("WHERE is_active = true AND set_kind = 'project'", &[])
+    }
+
+    fn get_project_status_stage_statistics(
+        &self,
+        where_clause: &str,
+        params: &[Box<dyn rusqlite::ToSql>],
+    ) -> Result<Vec<StatusStageStatistics>, DatabaseError> {
+        let mut stages: Vec<StatusStageStatistics> = self
+            .get_status_pipeline()?
+            .into_iter()
+            .enumerate()
+            .map(|(position, status)| StatusStageStatistics {
+                status,
+                position: position as i32,
+                current_projects: 0,
+                times_entered: 0,
+                total_seconds: 0,
+                average_seconds: 0.0,
+            })
+            .collect();
+
+        let current_query = format!(
+            "SELECT status, COUNT(*) FROM projects {} AND status IS NOT NULL GROUP BY status",
+            where_clause
+        );
+        let mut stmt = self.conn.prepare(&current_query)?;
+        let rows = stmt.query_map(
+            rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?)),
+        )?;
+        for row in rows {
+            let (status, count) = row?;
+            if let Some(stage) = stages.iter_mut().find(|stage| stage.status == status) {
+                stage.current_projects = count;
+            }
+        }
+
+        // A stay lasts until the project's next transition, or until now for the current stage
+        let duration_query = format!(
+            "WITH stays AS (
+                SELECT h.to_status AS status,
+                       COALESCE(
+                           LEAD(h.changed_at) OVER (PARTITION BY h.project_id ORDER BY h.changed_at, h.rowid),
+                           CAST(strftime('%s', 'now') AS INTEGER)
+                       ) - h.changed_at AS seconds
+                FROM project_status_history h
+                WHERE h.project_id IN (SELECT id FROM projects {})
+             )
+             SELECT status, COUNT(*), SUM(seconds)
+             FROM stays
+             WHERE status IS NOT NULL
+             GROUP BY status",
+            where_clause
+        );
+        let mut stmt = self.conn.prepare(&duration_query)?;
+        let rows = stmt.query_map(
+            rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?, row.get::<_, i64>(2)?)),
+        )?;
+        for row in rows {
+            let (status, times_entered, total_seconds) = row?;
+            if let Some(stage) = stages.iter_mut().find(|stage| stage.status == status) {
+                stage.times_entered = times_entered;
+                stage.total_seconds = total_seconds.max(0);
+                stage.average_seconds = stage.total_seconds as f64 / times_entered as f64;
+            }
+        }
+
+        Ok(stages)
+    }
+
     fn get_project_tempo_distribution(
         &self,
         where_clause: &str,
@@ -991,4 +1067,17 @@ pub struct ProjectStatistics {
     pub projects_per_year: Vec<(i32, i32)>,
     pub projects_per_month: Vec<(i32, i32, i32)>,
     pub most_complex_projects: Vec<(String, String, i32, i32, i32, f64)>,
+    pub status_stages: Vec<StatusStageStatistics>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StatusStageStatistics {
+    pub status: String,
+    pub position: i32,
+    /// Projects currently in this stage
+    pub current_projects: i32,
+    /// How many times projects entered this stage
+    pub times_entered: i32,
+    pub total_seconds: i64,
+    pub average_seconds: f64,
 }
@@ -11,34 +11,305 @@ use uuid::Uuid;
 
 use super::LiveSetDatabase;
 
+/// Separator between the segments of a hierarchical tag name, e.g. `genre/techno`
+pub const TAG_PATH_SEPARATOR: char = '/';
+
+const TAG_COLUMNS: &str = "id, name, created_at, color, parent_id";
+
+/// Selects the given tag and all of its descendants as `subtree(id)`
+pub(super) const TAG_SUBTREE_CTE: &str = r#"
+    WITH RECURSIVE subtree(id) AS (
+        SELECT ?
+        UNION
+        SELECT t.id FROM tags t JOIN subtree s ON t.parent_id = s.id
+    )
+"#;
+
+/// Pairs every tag with itself and each of its descendants as `tag_tree(ancestor_id, tag_id)`
+const TAG_TREE_CTE: &str = r#"
+    WITH RECURSIVE tag_tree(ancestor_id, tag_id) AS (
+        SELECT id, id FROM tags
+        UNION ALL
+        SELECT tt.ancestor_id, c.id FROM tags c JOIN tag_tree tt ON c.parent_id = tt.tag_id
+    )
+"#;
+
+/// Usage columns shared by the tag usage queries; expects `tags t LEFT JOIN project_tags pt`
+const TAG_USAGE_COLUMNS: &str = r#"
+    t.id, t.name, COUNT(pt.project_id) as usage_count,
+    CAST(COUNT(pt.project_id) AS REAL) / (SELECT COUNT(*) FROM projects WHERE is_active = true) * 100.0 as usage_percentage,
+    t.color, t.parent_id,
+    (SELECT COUNT(DISTINCT tpt.project_id)
+     FROM tag_tree tt
+     JOIN project_tags tpt ON tpt.tag_id = tt.tag_id
+     WHERE tt.ancestor_id = t.id) as total_usage_count
+"#;
+
+fn row_to_tag_usage(row: &rusqlite::Row) -> rusqlite::Result<TagUsageInfo> {
+    Ok(TagUsageInfo {
+        tag_id: row.get(0)?,
+        name: row.get(1)?,
+        project_count: row.get(2)?,
+        usage_percentage: row.get::<_, Option<f64>>(3)?.unwrap_or(0.0),
+        color: row.get(4)?,
+        parent_id: row.get(5)?,
+        total_project_count: row.get(6)?,
+    })
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TagInfo {
+    pub id: String,
+    /// Full path of the tag, including its ancestors
+    pub name: String,
+    pub created_at: i64,
+    pub color: Option<String>,
+    pub parent_id: Option<String>,
+}
+
+impl TagInfo {
+    /// Namespace of the tag (`genre` for `genre/techno`), None for top-level tags
+    pub fn group(&self) -> Option<&str> {
+        self.name
+            .split_once(TAG_PATH_SEPARATOR)
+            .map(|(group, _)| group)
+    }
+
+    /// Last segment of the tag path (`techno` for `genre/techno`)
+    pub fn leaf_name(&self) -> &str {
+        self.name
+            .rsplit_once(TAG_PATH_SEPARATOR)
+            .map_or(self.name.as_str(), |(_, leaf)| leaf)
+    }
+}
+
+fn row_to_tag(row: &rusqlite::Row) -> rusqlite::Result<TagInfo> {
+    Ok(TagInfo {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        created_at: row.get(2)?,
+        color: row.get(3)?,
+        parent_id: row.get(4)?,
+    })
+}
+
+/// Trim whitespace around each path segment and reject empty segments.
+/// Flat names are stored as given.
+fn normalize_tag_name(name: &str) -> Result<String, DatabaseError> {
+    if !name.contains(TAG_PATH_SEPARATOR) {
+        return Ok(name.to_string());
+    }
+
+    let segments: Vec<&str> = name.split(TAG_PATH_SEPARATOR).map(str::trim).collect();
+    if segments.iter().any(|segment| segment.is_empty()) {
+        return Err(DatabaseError::InvalidOperation(format!(
+            "Invalid tag name '{}'",
+            name
+        )));
+    }
+    Ok(segments.join(&TAG_PATH_SEPARATOR.to_string()))
+}
+
+/// Colors are stored as `#rrggbb` (or the `#rgb` shorthand)
+pub fn validate_tag_color(color: &str) -> Result<(), DatabaseError> {
+    let hex = color.strip_prefix('#').unwrap_or("");
+    if !matches!(hex.len(), 3 | 6) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(DatabaseError::InvalidOperation(format!(
+            "Invalid tag color '{}', expected hex format like #ff8800",
+            color
+        )));
+    }
+    Ok(())
+}
+
+/// Look up the parent of a tag path, creating any missing ancestors
+fn ensure_tag_parent(
+    tx: &rusqlite::Transaction,
+    name: &str,
+    now: &SqlDateTime,
+) -> Result<Option<String>, DatabaseError> {
+    let Some((parent_name, _)) = name.rsplit_once(TAG_PATH_SEPARATOR) else {
+        return Ok(None);
+    };
+
+    let mut parent_id: Option<String> = None;
+    let mut path = String::new();
+    for segment in parent_name.split(TAG_PATH_SEPARATOR) {
+        if !path.is_empty() {
+            path.push(TAG_PATH_SEPARATOR);
+        }
+        path.push_str(segment);
+
+        let existing: Option<String> = tx
+            .query_row("SELECT id FROM tags WHERE name = ?", [&path], |row| row.get(0))
+            .optional()?;
+        let id = match existing {
+            Some(id) => id,
+            None => {
+                let id = Uuid::new_v4().to_string();
+                debug!("Creating parent tag: {} ({})", path, id);
+                tx.execute(
+                    "INSERT INTO tags (id, name, created_at, parent_id) VALUES (?, ?, ?, ?)",
+                    params![id, path, now, parent_id],
+                )?;
+                id
+            }
+        };
+        parent_id = Some(id);
+    }
+
+    Ok(parent_id)
+}
+
 impl LiveSetDatabase {
     pub fn add_tag(&mut self, name: &str) -> Result<String, DatabaseError> {
+        self.add_tag_with_color(name, None)
+    }
+
+    /// Add a tag, creating any missing parents for hierarchical names like `genre/techno`
+    pub fn add_tag_with_color(
+        &mut self,
+        name: &str,
+        color: Option<&str>,
+    ) -> Result<String, DatabaseError> {
         debug!("Adding tag: {}", name);
+        let name = normalize_tag_name(name)?;
+        if let Some(color) = color {
+            validate_tag_color(color)?;
+        }
+
         let tag_id = Uuid::new_v4().to_string();
-        let now = Local::now();
+        let now = SqlDateTime::from(Local::now());
 
-        self.conn.execute(
-            "INSERT INTO tags (id, name, created_at) VALUES (?, ?, ?)",
-            params![tag_id, name, SqlDateTime::from(now)],
+        let tx = self.conn.transaction()?;
+        let parent_id = ensure_tag_parent(&tx, &name, &now)?;
+        tx.execute(
+            "INSERT INTO tags (id, name, created_at, color, parent_id) VALUES (?, ?, ?, ?, ?)",
+            params![tag_id, name, now, color, parent_id],
         )?;
+        tx.commit()?;
 
         debug!("Successfully added tag: {} ({})", name, tag_id);
         Ok(tag_id)
     }
 
+    /// Look up a tag by name, adding it if there's none yet
+    pub fn get_or_add_tag(&mut self, name: &str) -> Result<String, DatabaseError> {
+        let normalized = normalize_tag_name(name)?;
+        let existing: Option<String> = self
+            .conn
+            .query_row("SELECT id FROM tags WHERE name = ?", [&normalized], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        match existing {
+            Some(tag_id) => Ok(tag_id),
+            None => self.add_tag(&normalized),
+        }
+    }
+
+    /// Remove a tag along with all of its child tags
     pub fn remove_tag(&mut self, tag_id: &str) -> Result<(), DatabaseError> {
         debug!("Removing tag: {}", tag_id);
-        self.conn
-            .execute("DELETE FROM tags WHERE id = ?", [tag_id])?;
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            &format!(
+                "{} DELETE FROM project_tags WHERE tag_id IN (SELECT id FROM subtree)",
+                TAG_SUBTREE_CTE
+            ),
+            [tag_id],
+        )?;
+        tx.execute(
+            &format!(
+                "{} DELETE FROM tags WHERE id IN (SELECT id FROM subtree)",
+                TAG_SUBTREE_CTE
+            ),
+            [tag_id],
+        )?;
+        tx.commit()?;
         debug!("Successfully removed tag: {}", tag_id);
         Ok(())
     }
 
+    /// Rename a tag. A name with path segments moves the tag (and its children)
+    /// under that parent, creating missing parents as needed.
     pub fn update_tag(&mut self, tag_id: &str, name: &str) -> Result<(), DatabaseError> {
         debug!("Updating tag {} to name: {}", tag_id, name);
+        let name = normalize_tag_name(name)?;
+
+        let tx = self.conn.transaction()?;
+        let old_name: String = tx
+            .query_row("SELECT name FROM tags WHERE id = ?", [tag_id], |row| row.get(0))
+            .optional()?
+            .ok_or_else(|| DatabaseError::NotFound(format!("Tag with id {} not found", tag_id)))?;
+
+        if name.starts_with(&format!("{}{}", old_name, TAG_PATH_SEPARATOR)) {
+            return Err(DatabaseError::InvalidOperation(format!(
+                "Cannot move tag '{}' underneath itself",
+                old_name
+            )));
+        }
+
+        let parent_id = ensure_tag_parent(&tx, &name, &SqlDateTime::from(Local::now()))?;
+        tx.execute(
+            "UPDATE tags SET name = ?, parent_id = ? WHERE id = ?",
+            params![name, parent_id, tag_id],
+        )?;
+
+        // Children keep their place in the hierarchy, only their path prefix changes
+        tx.execute(
+            r#"
+            UPDATE tags SET name = ?1 || substr(name, length(?2) + 1)
+            WHERE substr(name, 1, length(?2) + 1) = ?2 || '/'
+            "#,
+            params![name, old_name],
+        )?;
+        tx.commit()?;
+
+        debug!("Successfully updated tag: {}", tag_id);
+        Ok(())
+    }
+
+    /// Move a tag under a new parent (or to the top level), keeping its leaf name
+    pub fn set_tag_parent(
+        &mut self,
+        tag_id: &str,
+        parent_id: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        debug!("Setting parent of tag {} to {:?}", tag_id, parent_id);
+        let tag = self
+            .get_tag_by_id(tag_id)?
+            .ok_or_else(|| DatabaseError::NotFound(format!("Tag with id {} not found", tag_id)))?;
+
+        if parent_id == Some(tag_id) {
+            return Err(DatabaseError::InvalidOperation(format!(
+                "Tag '{}' cannot be its own parent",
+                tag.name
+            )));
+        }
+
+        let name = match parent_id {
+            Some(parent_id) => {
+                let parent = self.get_tag_by_id(parent_id)?.ok_or_else(|| {
+                    DatabaseError::NotFound(format!("Tag with id {} not found", parent_id))
+                })?;
+                format!("{}{}{}", parent.name, TAG_PATH_SEPARATOR, tag.leaf_name())
+            }
+            None => tag.leaf_name().to_string(),
+        };
+
+        self.update_tag(tag_id, &name)
+    }
+
+    pub fn set_tag_color(&mut self, tag_id: &str, color: Option<&str>) -> Result<(), DatabaseError> {
+        debug!("Setting color of tag {} to {:?}", tag_id, color);
+        if let Some(color) = color {
+            validate_tag_color(color)?;
+        }
+
         let rows_affected = self.conn.execute(
-            "UPDATE tags SET name = ? WHERE id = ?",
-            params![name, tag_id],
+            "UPDATE tags SET color = ? WHERE id = ?",
+            params![color, tag_id],
         )?;
 
         if rows_affected == 0 {
@@ -48,10 +319,21 @@ impl LiveSetDatabase {
             )));
         }
 
-        debug!("Successfully updated tag: {}", tag_id);
         Ok(())
     }
 
+    /// IDs of the given tag and all of its descendants
+    pub fn get_tag_subtree_ids(&mut self, tag_id: &str) -> Result<Vec<String>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("{} SELECT id FROM subtree", TAG_SUBTREE_CTE))?;
+        let ids = stmt
+            .query_map([tag_id], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(ids)
+    }
+
     pub fn tag_project(&mut self, project_id: &str, tag_id: &str) -> Result<(), DatabaseError> {
         debug!("Tagging project {} with tag {}", project_id, tag_id);
         let now = Local::now();
@@ -125,14 +407,11 @@ impl LiveSetDatabase {
     }
 
     /// Get tag data with creation timestamps for a project (for gRPC responses)
-    pub fn get_project_tag_data(
-        &mut self,
-        project_id: &str,
-    ) -> Result<Vec<(String, String, i64)>, DatabaseError> {
+    pub fn get_project_tag_data(&mut self, project_id: &str) -> Result<Vec<TagInfo>, DatabaseError> {
         debug!("Getting tag data for project: {}", project_id);
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT t.id, t.name, t.created_at
+            SELECT t.id, t.name, t.created_at, t.color, t.parent_id
             FROM tags t
             JOIN project_tags pt ON pt.tag_id = t.id
             WHERE pt.project_id = ?
@@ -140,17 +419,8 @@ impl LiveSetDatabase {
             "#,
         )?;
 
-        let tag_data: Vec<(String, String, i64)> = stmt
-            .query_map([project_id], |row| {
-                let tag_id: String = row.get(0)?;
-                let tag_name: String = row.get(1)?;
-                let created_at: i64 = row.get(2)?;
-                debug!(
-                    "Found tag: {} ({}) created at {}",
-                    tag_name, tag_id, created_at
-                );
-                Ok((tag_id, tag_name, created_at))
-            })?
+        let tag_data: Vec<TagInfo> = stmt
+            .query_map([project_id], row_to_tag)?
             .filter_map(|r| r.ok())
             .collect();
 
@@ -158,19 +428,22 @@ impl LiveSetDatabase {
         Ok(tag_data)
     }
 
+    /// Get projects tagged with the given tag or any of its child tags
     pub fn get_projects_by_tag(&mut self, tag_id: &str) -> Result<Vec<LiveSet>, DatabaseError> {
         debug!("Getting projects with tag: {}", tag_id);
         let tx = self.conn.transaction()?;
 
         let project_paths = {
-            let mut stmt = tx.prepare(
+            let mut stmt = tx.prepare(&format!(
                 r#"
+                {}
                 SELECT DISTINCT p.path 
                 FROM projects p
                 JOIN project_tags pt ON pt.project_id = p.id
-                WHERE pt.tag_id = ?
+                WHERE pt.tag_id IN (SELECT id FROM subtree)
                 "#,
-            )?;
+                TAG_SUBTREE_CTE
+            ))?;
 
             let paths: Vec<String> = stmt
                 .query_map([tag_id], |row| {
@@ -382,20 +655,14 @@ impl LiveSetDatabase {
         Ok(results)
     }
 
-    pub fn list_tags(&mut self) -> Result<Vec<(String, String, i64)>, DatabaseError> {
+    pub fn list_tags(&mut self) -> Result<Vec<TagInfo>, DatabaseError> {
         debug!("Listing all tags");
         let mut stmt = self
             .conn
-            .prepare("SELECT id, name, created_at FROM tags ORDER BY name")?;
+            .prepare(&format!("SELECT {} FROM tags ORDER BY name", TAG_COLUMNS))?;
 
         let tags = stmt
-            .query_map([], |row| {
-                let id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let created_at: i64 = row.get(2)?;
-                debug!("Found tag: {} ({}) created at {}", name, id, created_at);
-                Ok((id, name, created_at))
-            })?
+            .query_map([], row_to_tag)?
             .filter_map(|r| r.ok())
             .collect();
 
@@ -403,24 +670,29 @@ impl LiveSetDatabase {
         Ok(tags)
     }
 
-    pub fn get_tag_by_id(
-        &mut self,
-        tag_id: &str,
-    ) -> Result<Option<(String, String, i64)>, DatabaseError> {
+    /// List the tags in a namespace, e.g. `genre` and everything under `genre/`
+    pub fn list_tags_in_group(&mut self, group: &str) -> Result<Vec<TagInfo>, DatabaseError> {
+        debug!("Listing tags in group: {}", group);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM tags WHERE name = ?1 OR substr(name, 1, length(?1) + 1) = ?1 || '/' ORDER BY name",
+            TAG_COLUMNS
+        ))?;
+
+        let tags = stmt
+            .query_map([group], row_to_tag)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(tags)
+    }
+
+    pub fn get_tag_by_id(&mut self, tag_id: &str) -> Result<Option<TagInfo>, DatabaseError> {
         debug!("Getting tag by ID: {}", tag_id);
         let mut stmt = self
             .conn
-            .prepare("SELECT id, name, created_at FROM tags WHERE id = ?")?;
-
-        let tag = stmt
-            .query_row([tag_id], |row| {
-                let id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let created_at: i64 = row.get(2)?;
-                debug!("Found tag: {} ({}) created at {}", name, id, created_at);
-                Ok((id, name, created_at))
-            })
-            .optional()?;
+            .prepare(&format!("SELECT {} FROM tags WHERE id = ?", TAG_COLUMNS))?;
+
+        let tag = stmt.query_row([tag_id], row_to_tag).optional()?;
 
         debug!("Retrieved tag by ID");
         Ok(tag)
@@ -432,7 +704,7 @@ impl LiveSetDatabase {
         query: &str,
         limit: Option<i32>,
         offset: Option<i32>,
-    ) -> Result<(Vec<(String, String, i64)>, i32), DatabaseError> {
+    ) -> Result<(Vec<TagInfo>, i32), DatabaseError> {
         debug!("Searching tags with query: {}", query);
 
         // Get total count
@@ -442,19 +714,17 @@ impl LiveSetDatabase {
         let total_count: i32 = count_stmt.query_row([&search_param], |row| row.get(0))?;
 
         // Get results with pagination
-        let main_query = "SELECT id, name, created_at FROM tags WHERE name LIKE ? ORDER BY name LIMIT ? OFFSET ?";
-        let mut stmt = self.conn.prepare(main_query)?;
+        let main_query = format!(
+            "SELECT {} FROM tags WHERE name LIKE ? ORDER BY name LIMIT ? OFFSET ?",
+            TAG_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&main_query)?;
         
         let limit_val = limit.unwrap_or(50);
         let offset_val = offset.unwrap_or(0);
         
-        let tags: Vec<(String, String, i64)> = stmt
-            .query_map([&search_param, &limit_val.to_string(), &offset_val.to_string()], |row| {
-                let id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let created_at: i64 = row.get(2)?;
-                Ok((id, name, created_at))
-            })?
+        let tags: Vec<TagInfo> = stmt
+            .query_map([&search_param, &limit_val.to_string(), &offset_val.to_string()], row_to_tag)?
             .filter_map(|r| r.ok())
             .collect();
 
@@ -528,8 +798,8 @@ impl LiveSetDatabase {
         let order = if least_used { "ASC" } else { "DESC" };
         let query = format!(
             r#"
-            SELECT t.id, t.name, COUNT(pt.project_id) as usage_count,
-                   CAST(COUNT(pt.project_id) AS REAL) / (SELECT COUNT(*) FROM projects WHERE is_active = true) * 100.0 as usage_percentage
+            {}
+            SELECT {}
             FROM tags t
             LEFT JOIN project_tags pt ON pt.tag_id = t.id
             GROUP BY t.id, t.name
@@ -537,23 +807,12 @@ impl LiveSetDatabase {
             ORDER BY usage_count {} 
             LIMIT ?
             "#,
-            order
+            TAG_TREE_CTE, TAG_USAGE_COLUMNS, order
         );
 
         let mut stmt = self.conn.prepare(&query)?;
         let tags = stmt
-            .query_map([limit], |row| {
-                let tag_id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let project_count: i32 = row.get(2)?;
-                let usage_percentage: f64 = row.get(3)?;
-                Ok(TagUsageInfo {
-                    tag_id,
-                    name,
-                    project_count,
-                    usage_percentage,
-                })
-            })?
+            .query_map([limit], row_to_tag_usage)?
             .filter_map(|r| r.ok())
             .collect();
 
@@ -568,15 +827,24 @@ impl LiveSetDatabase {
         sort_by: Option<String>,
         sort_desc: Option<bool>,
         min_usage_count: Option<i32>,
+        group: Option<&str>,
     ) -> Result<(Vec<TagUsageInfo>, i32), DatabaseError> {
         debug!("Getting all tags with usage information");
 
-        // Build WHERE clause for filtering
-        let mut conditions = Vec::new();
+        // Build WHERE and HAVING clauses for filtering
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
+        let group_clause = match group {
+            Some(group) => {
+                params.push(Box::new(group.to_string()));
+                "WHERE t.name = ?1 OR substr(t.name, 1, length(?1) + 1) = ?1 || '/'"
+            }
+            None => "",
+        };
+
+        let mut conditions = Vec::new();
         if let Some(min_usage) = min_usage_count {
-            conditions.push("usage_count >= ?");
+            conditions.push(format!("usage_count >= ?{}", params.len() + 1));
             params.push(Box::new(min_usage));
         }
 
@@ -591,6 +859,7 @@ impl LiveSetDatabase {
             Some("name") => "t.name",
             Some("usage_count") => "usage_count",
             Some("created_at") => "t.created_at",
+            Some("total_usage_count") => "total_usage_count",
             _ => "t.name", // default sort
         };
 
@@ -600,14 +869,15 @@ impl LiveSetDatabase {
         let count_query = format!(
             r#"
             SELECT COUNT(*) FROM (
-                SELECT t.id
+                SELECT t.id, COUNT(pt.project_id) as usage_count
                 FROM tags t
                 LEFT JOIN project_tags pt ON pt.tag_id = t.id
+                {}
                 GROUP BY t.id, t.name
                 {}
             )
             "#,
-            where_clause
+            group_clause, where_clause
         );
         let mut count_stmt = self.conn.prepare(&count_query)?;
         let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
@@ -616,16 +886,24 @@ impl LiveSetDatabase {
         // Get results with pagination
         let main_query = format!(
             r#"
-            SELECT t.id, t.name, COUNT(pt.project_id) as usage_count,
-                   CAST(COUNT(pt.project_id) AS REAL) / (SELECT COUNT(*) FROM projects WHERE is_active = true) * 100.0 as usage_percentage
+            {}
+            SELECT {}
             FROM tags t
             LEFT JOIN project_tags pt ON pt.tag_id = t.id
+            {}
             GROUP BY t.id, t.name
             {}
             ORDER BY {} {}
-            LIMIT ? OFFSET ?
+            LIMIT ?{} OFFSET ?{}
             "#,
-            where_clause, sort_column, sort_order
+            TAG_TREE_CTE,
+            TAG_USAGE_COLUMNS,
+            group_clause,
+            where_clause,
+            sort_column,
+            sort_order,
+            params.len() + 1,
+            params.len() + 2
         );
 
         let limit_val = limit.unwrap_or(50);
@@ -637,18 +915,7 @@ impl LiveSetDatabase {
         let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
         
         let tags: Vec<TagUsageInfo> = stmt
-            .query_map(param_refs.as_slice(), |row| {
-                let tag_id: String = row.get(0)?;
-                let name: String = row.get(1)?;
-                let project_count: i32 = row.get(2)?;
-                let usage_percentage: f64 = row.get(3)?;
-                Ok(TagUsageInfo {
-                    tag_id,
-                    name,
-                    project_count,
-                    usage_percentage,
-                })
-            })?
+            .query_map(param_refs.as_slice(), row_to_tag_usage)?
             .filter_map(|r| r.ok())
             .collect();
 
@@ -778,4 +1045,8 @@ pub struct TagUsageInfo {
     pub name: String,
     pub project_count: i32,
     pub usage_percentage: f64,
+    pub color: Option<String>,
+    pub parent_id: Option<String>,
+    /// Projects tagged with this tag or any of its child tags
+    pub total_project_count: i32,
 }
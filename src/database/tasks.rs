@@ -1,27 +1,125 @@
 use crate::database::models::SqlDateTime;
 use crate::error::DatabaseError;
+use crate::models::TimeSignature;
 use chrono::Local;
 use log::debug;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension, Row};
 use uuid::Uuid;
 
 use super::LiveSetDatabase;
 
+/// Task columns plus the owning project's tempo and time signature, which are needed to
+/// convert arrangement positions to seconds. Callers append their own WHERE/ORDER BY.
+const TASK_SELECT: &str = r#"
+    SELECT t.id, t.project_id, t.description, t.completed, t.created_at, t.priority, t.due_date,
+           t.position, t.assignee, t.completed_at, t.arrangement_bar, t.arrangement_beat, t.track_name,
+           p.tempo, p.time_signature_numerator, p.time_signature_denominator
+    FROM project_tasks t
+    JOIN projects p ON p.id = t.project_id
+"#;
+
+/// Set a task's completion to ?1 at time ?2. A task that is already done keeps
+/// its original completion time.
+const COMPLETE_TASK: &str = "UPDATE project_tasks
+     SET completed_at = CASE WHEN NOT ?1 THEN NULL WHEN completed THEN completed_at ELSE ?2 END,
+         completed = ?1
+     WHERE id = ?3";
+
+fn row_to_task(row: &Row) -> rusqlite::Result<ProjectTask> {
+    let arrangement_bar: Option<i32> = row.get("arrangement_bar")?;
+    let arrangement_beat: Option<f64> = row.get("arrangement_beat")?;
+    let arrangement_position = arrangement_bar.map(|bar| ArrangementPosition {
+        bar,
+        beat: arrangement_beat.unwrap_or(1.0),
+    });
+
+    let time_signature = TimeSignature {
+        numerator: row.get("time_signature_numerator")?,
+        denominator: row.get("time_signature_denominator")?,
+    };
+    let tempo: f64 = row.get("tempo")?;
+    let arrangement_seconds = arrangement_position
+        .and_then(|pos| time_signature.position_to_seconds(pos.bar, pos.beat, tempo));
+
+    Ok(ProjectTask {
+        id: row.get("id")?,
+        project_id: row.get("project_id")?,
+        description: row.get("description")?,
+        completed: row.get("completed")?,
+        created_at: row.get("created_at")?,
+        priority: row.get("priority")?,
+        due_date: row.get("due_date")?,
+        position: row.get("position")?,
+        assignee: row.get("assignee")?,
+        completed_at: row.get("completed_at")?,
+        arrangement_position,
+        arrangement_seconds,
+        track_name: row.get("track_name")?,
+    })
+}
+
 impl LiveSetDatabase {
     pub fn add_task(
         &mut self,
         project_id: &str,
         description: &str,
+    ) -> Result<String, DatabaseError> {
+        self.add_task_with_details(project_id, description, &TaskDetails::default())
+    }
+
+    pub fn add_task_with_details(
+        &mut self,
+        project_id: &str,
+        description: &str,
+        details: &TaskDetails,
     ) -> Result<String, DatabaseError> {
         debug!("Adding task to project {}: {}", project_id, description);
+        validate_priority(details.priority)?;
+        if let Some(position) = details.arrangement_position {
+            let beats_per_bar = self
+                .conn
+                .query_row(
+                    "SELECT time_signature_numerator FROM projects WHERE id = ?",
+                    [project_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            validate_position_in_bar(position, beats_per_bar)?;
+        }
         let task_id = Uuid::new_v4().to_string();
         let now = Local::now();
 
-        self.conn.execute(
-            "INSERT INTO project_tasks (id, project_id, description, completed, created_at) VALUES (?, ?, ?, ?, ?)",
-            params![task_id, project_id, description, false, SqlDateTime::from(now)],
+        let tx = self.conn.transaction()?;
+
+        // New tasks go to the end of the project's list
+        let max_position: i32 = tx.query_row(
+            "SELECT COALESCE(MAX(position), -1) FROM project_tasks WHERE project_id = ?",
+            [project_id],
+            |row| row.get(0),
+        )?;
+
+        tx.execute(
+            "INSERT INTO project_tasks (
+                id, project_id, description, completed, created_at, priority, due_date, position,
+                assignee, arrangement_bar, arrangement_beat, track_name
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                task_id,
+                project_id,
+                description,
+                false,
+                SqlDateTime::from(now),
+                details.priority,
+                details.due_date,
+                max_position + 1,
+                details.assignee,
+                details.arrangement_position.map(|p| p.bar),
+                details.arrangement_position.map(|p| p.beat),
+                details.track_name,
+            ],
         )?;
 
+        tx.commit()?;
         debug!("Successfully added task: {}", task_id);
         Ok(task_id)
     }
@@ -31,10 +129,7 @@ impl LiveSetDatabase {
             "Setting task {} completion status to {}",
             task_id, completed
         );
-        self.conn.execute(
-            "UPDATE project_tasks SET completed = ? WHERE id = ?",
-            params![completed, task_id],
-        )?;
+        self.conn.execute(COMPLETE_TASK, params![completed, Local::now().timestamp(), task_id])?;
         debug!("Successfully updated task completion status");
         Ok(())
     }
@@ -53,26 +148,165 @@ impl LiveSetDatabase {
         Ok(())
     }
 
-    pub fn get_task(
+    pub fn set_task_priority(&mut self, task_id: &str, priority: i32) -> Result<(), DatabaseError> {
+        debug!("Setting task {} priority to {}", task_id, priority);
+        validate_priority(priority)?;
+        self.conn.execute(
+            "UPDATE project_tasks SET priority = ? WHERE id = ?",
+            params![priority, task_id],
+        )?;
+        debug!("Successfully updated task priority");
+        Ok(())
+    }
+
+    pub fn set_task_due_date(
         &mut self,
         task_id: &str,
-    ) -> Result<Option<(String, String, String, bool, i64)>, DatabaseError> {
-        debug!("Getting task by ID: {}", task_id);
-        let mut stmt = self.conn.prepare(
-            "SELECT id, project_id, description, completed, created_at FROM project_tasks WHERE id = ?"
+        due_date: Option<i64>,
+    ) -> Result<(), DatabaseError> {
+        debug!("Setting task {} due date to {:?}", task_id, due_date);
+        self.conn.execute(
+            "UPDATE project_tasks SET due_date = ? WHERE id = ?",
+            params![due_date, task_id],
+        )?;
+        debug!("Successfully updated task due date");
+        Ok(())
+    }
+
+    pub fn set_task_assignee(
+        &mut self,
+        task_id: &str,
+        assignee: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        debug!("Setting task {} assignee to {:?}", task_id, assignee);
+        self.conn.execute(
+            "UPDATE project_tasks SET assignee = ? WHERE id = ?",
+            params![assignee, task_id],
         )?;
+        debug!("Successfully updated task assignee");
+        Ok(())
+    }
+
+    /// Anchor a task to a bar/beat in the arrangement, or clear the anchor with `None`
+    pub fn set_task_arrangement_position(
+        &mut self,
+        task_id: &str,
+        position: Option<ArrangementPosition>,
+    ) -> Result<(), DatabaseError> {
+        debug!("Setting task {} arrangement position to {:?}", task_id, position);
+        if let Some(position) = position {
+            self.validate_task_arrangement_position(task_id, position)?;
+        }
+        self.conn.execute(
+            "UPDATE project_tasks SET arrangement_bar = ?, arrangement_beat = ? WHERE id = ?",
+            params![position.map(|p| p.bar), position.map(|p| p.beat), task_id],
+        )?;
+        debug!("Successfully updated task arrangement position");
+        Ok(())
+    }
+
+    /// Check a position against the time signature of the task's project
+    pub fn validate_task_arrangement_position(
+        &self,
+        task_id: &str,
+        position: ArrangementPosition,
+    ) -> Result<(), DatabaseError> {
+        let beats_per_bar = self
+            .conn
+            .query_row(
+                "SELECT p.time_signature_numerator FROM project_tasks t
+                 JOIN projects p ON p.id = t.project_id
+                 WHERE t.id = ?",
+                [task_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        validate_position_in_bar(position, beats_per_bar)
+    }
+
+    pub fn set_task_track_name(
+        &mut self,
+        task_id: &str,
+        track_name: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        debug!("Setting task {} track name to {:?}", task_id, track_name);
+        self.conn.execute(
+            "UPDATE project_tasks SET track_name = ? WHERE id = ?",
+            params![track_name, task_id],
+        )?;
+        debug!("Successfully updated task track name");
+        Ok(())
+    }
+
+    /// Move a task to a new position within its project, shifting the tasks in between
+    pub fn reorder_task(&mut self, task_id: &str, new_position: i32) -> Result<(), DatabaseError> {
+        debug!("Moving task {} to position {}", task_id, new_position);
+
+        let tx = self.conn.transaction()?;
+
+        let (project_id, current_position): (String, i32) = tx.query_row(
+            "SELECT project_id, position FROM project_tasks WHERE id = ?",
+            [task_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let max_position: i32 = tx.query_row(
+            "SELECT COALESCE(MAX(position), 0) FROM project_tasks WHERE project_id = ?",
+            [&project_id],
+            |row| row.get(0),
+        )?;
+        let new_position = new_position.clamp(0, max_position);
+
+        if current_position == new_position {
+            debug!("Task is already at position {}", new_position);
+            return Ok(());
+        }
+
+        if current_position < new_position {
+            // Moving down: shift intermediate tasks up
+            tx.execute(
+                "UPDATE project_tasks
+                 SET position = position - 1
+                 WHERE project_id = ?
+                 AND position > ?
+                 AND position <= ?",
+                params![project_id, current_position, new_position],
+            )?;
+        } else {
+            // Moving up: shift intermediate tasks down
+            tx.execute(
+                "UPDATE project_tasks
+                 SET position = position + 1
+                 WHERE project_id = ?
+                 AND position >= ?
+                 AND position < ?",
+                params![project_id, new_position, current_position],
+            )?;
+        }
+
+        tx.execute(
+            "UPDATE project_tasks SET position = ? WHERE id = ?",
+            params![new_position, task_id],
+        )?;
+
+        tx.commit()?;
+        debug!("Successfully reordered task");
+        Ok(())
+    }
+
+    pub fn get_task(&mut self, task_id: &str) -> Result<Option<ProjectTask>, DatabaseError> {
+        debug!("Getting task by ID: {}", task_id);
+        let mut stmt = self
+            .conn
+            .prepare(&format!("{} WHERE t.id = ?", TASK_SELECT))?;
 
         let result = stmt.query_row([task_id], |row| {
-            let id: String = row.get(0)?;
-            let project_id: String = row.get(1)?;
-            let description: String = row.get(2)?;
-            let completed: bool = row.get(3)?;
-            let created_at: i64 = row.get(4)?;
+            let task = row_to_task(row)?;
             debug!(
                 "Found task: {} ({}) for project {}",
-                description, id, project_id
+                task.description, task.id, task.project_id
             );
-            Ok((id, project_id, description, completed, created_at))
+            Ok(task)
         });
 
         match result {
@@ -90,32 +324,67 @@ impl LiveSetDatabase {
 
     pub fn remove_task(&mut self, task_id: &str) -> Result<(), DatabaseError> {
         debug!("Removing task {}", task_id);
-        self.conn
-            .execute("DELETE FROM project_tasks WHERE id = ?", [task_id])?;
+        let tx = self.conn.transaction()?;
+
+        let removed: Option<(String, i32)> = tx
+            .query_row(
+                "SELECT project_id, position FROM project_tasks WHERE id = ?",
+                [task_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        tx.execute("DELETE FROM project_tasks WHERE id = ?", [task_id])?;
+
+        // Close the gap left in the project's task order
+        if let Some((project_id, position)) = removed {
+            tx.execute(
+                "UPDATE project_tasks SET position = position - 1 WHERE project_id = ? AND position > ?",
+                params![project_id, position],
+            )?;
+        }
+
+        tx.commit()?;
         debug!("Successfully removed task");
         Ok(())
     }
 
-    pub fn get_project_tasks(
+    /// Get a project's tasks in their manual order
+    pub fn get_project_tasks(&mut self, project_id: &str) -> Result<Vec<ProjectTask>, DatabaseError> {
+        self.query_project_tasks(project_id, "t.position, t.created_at")
+    }
+
+    /// Get a project's tasks in arrangement order. Tasks without an arrangement position
+    /// follow, in their manual order.
+    pub fn get_project_tasks_by_arrangement(
         &mut self,
         project_id: &str,
-    ) -> Result<Vec<(String, String, bool, i64)>, DatabaseError> {
+    ) -> Result<Vec<ProjectTask>, DatabaseError> {
+        self.query_project_tasks(
+            project_id,
+            "t.arrangement_bar IS NULL, t.arrangement_bar, t.arrangement_beat, t.position, t.created_at",
+        )
+    }
+
+    fn query_project_tasks(
+        &mut self,
+        project_id: &str,
+        order_by: &str,
+    ) -> Result<Vec<ProjectTask>, DatabaseError> {
         debug!("Getting tasks for project {}", project_id);
-        let mut stmt = self.conn.prepare(
-            "SELECT id, description, completed, created_at FROM project_tasks WHERE project_id = ? ORDER BY created_at"
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "{} WHERE t.project_id = ? ORDER BY {}",
+            TASK_SELECT, order_by
+        ))?;
 
         let tasks = stmt
             .query_map([project_id], |row| {
-                let id: String = row.get(0)?;
-                let description: String = row.get(1)?;
-                let completed: bool = row.get(2)?;
-                let created_at: i64 = row.get(3)?;
+                let task = row_to_task(row)?;
                 debug!(
                     "Found task: {} ({}) created at {}",
-                    description, id, created_at
+                    task.description, task.id, task.created_at
                 );
-                Ok((id, description, completed, created_at))
+                Ok(task)
             })?
             .filter_map(|r| r.ok())
             .collect();
@@ -124,37 +393,62 @@ impl LiveSetDatabase {
         Ok(tasks)
     }
 
+    /// Get all incomplete tasks whose due date has passed, across all active projects
+    pub fn get_overdue_tasks(&mut self) -> Result<Vec<ProjectTask>, DatabaseError> {
+        debug!("Getting overdue tasks");
+        let now = Local::now().timestamp();
+        let mut stmt = self.conn.prepare(&format!(
+            r#"{}
+            WHERE p.is_active = true
+            AND t.completed = 0
+            AND t.due_date IS NOT NULL
+            AND t.due_date < ?
+            ORDER BY t.due_date, t.priority DESC
+            "#,
+            TASK_SELECT
+        ))?;
+
+        let tasks: Vec<ProjectTask> = stmt
+            .query_map([now], row_to_task)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        debug!("Found {} overdue tasks", tasks.len());
+        Ok(tasks)
+    }
+
+    /// Get tasks for all projects in a collection, paired with the owning project's name
     pub fn get_collection_tasks(
         &mut self,
         collection_id: &str,
-    ) -> Result<Vec<(String, String, String, bool, i64)>, DatabaseError> {
+    ) -> Result<Vec<(String, ProjectTask)>, DatabaseError> {
         debug!(
             "Getting tasks for all projects in collection {}",
             collection_id
         );
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT t.id, p.name, t.description, t.completed, t.created_at
+            SELECT p.name AS project_name, t.id, t.project_id, t.description, t.completed, t.created_at,
+                   t.priority, t.due_date, t.position, t.assignee, t.completed_at, t.arrangement_bar,
+                   t.arrangement_beat, t.track_name, p.tempo, p.time_signature_numerator,
+                   p.time_signature_denominator
             FROM project_tasks t
             JOIN projects p ON p.id = t.project_id
             JOIN collection_projects cp ON cp.project_id = p.id
             WHERE cp.collection_id = ?
-            ORDER BY cp.position, t.created_at
+            ORDER BY cp.position, t.position, t.created_at
             "#,
         )?;
 
         let tasks = stmt
             .query_map([collection_id], |row| {
-                let id: String = row.get(0)?;
-                let project_name: String = row.get(1)?;
-                let description: String = row.get(2)?;
-                let completed: bool = row.get(3)?;
-                let created_at: i64 = row.get(4)?;
+                let project_name: String = row.get("project_name")?;
+                let task = row_to_task(row)?;
                 debug!(
                     "Found task: {} ({}) from project {} created at {}",
-                    description, id, project_name, created_at
+                    task.description, task.id, project_name, task.created_at
                 );
-                Ok((id, project_name, description, completed, created_at))
+                Ok((project_name, task))
             })?
             .filter_map(|r| r.ok())
             .collect();
@@ -223,11 +517,10 @@ impl LiveSetDatabase {
         let tx = self.conn.transaction()?;
         let mut results = Vec::new();
 
+        let now = Local::now().timestamp();
+
         for task_id in task_ids {
-            let result = tx.execute(
-                "UPDATE project_tasks SET completed = ? WHERE id = ?",
-                params![completed, task_id],
-            );
+            let result = tx.execute(COMPLETE_TASK, params![completed, now, task_id]);
 
             match result {
                 Ok(rows_affected) => {
@@ -312,42 +605,66 @@ impl LiveSetDatabase {
         query: &str,
         limit: Option<i32>,
         offset: Option<i32>,
-        completed_only: Option<bool>,
-        pending_only: Option<bool>,
-    ) -> Result<(Vec<(String, String, bool, i64)>, i32), DatabaseError> {
+        filters: &TaskSearchFilters,
+    ) -> Result<(Vec<ProjectTask>, i32), DatabaseError> {
         debug!("Searching tasks in project {} with query: {}", project_id, query);
 
         // Build WHERE conditions
-        let mut conditions = vec!["project_id = ?"];
+        let mut conditions = vec!["t.project_id = ?"];
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(project_id.to_string())];
 
         // Add text search condition
-        conditions.push("description LIKE ?");
+        conditions.push("t.description LIKE ?");
         params.push(Box::new(format!("%{}%", query)));
 
         // Add completion status filters
-        if let Some(completed) = completed_only {
+        if let Some(completed) = filters.completed_only {
             if completed {
-                conditions.push("completed = 1");
+                conditions.push("t.completed = 1");
             }
-        } else if let Some(pending) = pending_only {
+        } else if let Some(pending) = filters.pending_only {
             if pending {
-                conditions.push("completed = 0");
+                conditions.push("t.completed = 0");
             }
         }
 
+        if let Some(min_priority) = filters.min_priority {
+            conditions.push("t.priority >= ?");
+            params.push(Box::new(min_priority));
+        }
+
+        if let Some(ref assignee) = filters.assignee {
+            conditions.push("t.assignee = ? COLLATE NOCASE");
+            params.push(Box::new(assignee.clone()));
+        }
+
+        if let Some(due_before) = filters.due_before {
+            conditions.push("t.due_date IS NOT NULL AND t.due_date < ?");
+            params.push(Box::new(due_before));
+        }
+
+        if let Some(due_after) = filters.due_after {
+            conditions.push("t.due_date IS NOT NULL AND t.due_date >= ?");
+            params.push(Box::new(due_after));
+        }
+
+        if filters.overdue_only.unwrap_or(false) {
+            conditions.push("t.completed = 0 AND t.due_date IS NOT NULL AND t.due_date < ?");
+            params.push(Box::new(Local::now().timestamp()));
+        }
+
         let where_clause = conditions.join(" AND ");
 
         // Get total count
-        let count_query = format!("SELECT COUNT(*) FROM project_tasks WHERE {}", where_clause);
+        let count_query = format!("SELECT COUNT(*) FROM project_tasks t WHERE {}", where_clause);
         let mut count_stmt = self.conn.prepare(&count_query)?;
         let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
         let total_count: i32 = count_stmt.query_row(param_refs.as_slice(), |row| row.get(0))?;
 
         // Build main query with pagination
         let main_query = format!(
-            "SELECT id, description, completed, created_at FROM project_tasks WHERE {} ORDER BY created_at DESC LIMIT ? OFFSET ?",
-            where_clause
+            "{} WHERE {} ORDER BY t.created_at DESC LIMIT ? OFFSET ?",
+            TASK_SELECT, where_clause
         );
 
         // Add pagination parameters
@@ -359,14 +676,8 @@ impl LiveSetDatabase {
         let mut stmt = self.conn.prepare(&main_query)?;
         let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
-        let tasks: Vec<(String, String, bool, i64)> = stmt
-            .query_map(param_refs.as_slice(), |row| {
-                let id: String = row.get(0)?;
-                let description: String = row.get(1)?;
-                let completed: bool = row.get(2)?;
-                let created_at: i64 = row.get(3)?;
-                Ok((id, description, completed, created_at))
-            })?
+        let tasks: Vec<ProjectTask> = stmt
+            .query_map(param_refs.as_slice(), row_to_task)?
             .filter_map(|r| r.ok())
             .collect();
 
@@ -400,32 +711,48 @@ impl LiveSetDatabase {
             0.0
         };
 
-        // Get weekly stats
-        let weekly_query = if where_clause.is_empty() {
-            "SELECT COUNT(*), COUNT(CASE WHEN completed = 1 THEN 1 END) FROM project_tasks WHERE created_at >= strftime('%s', 'now', '-7 days')".to_string()
-        } else {
-            format!("SELECT COUNT(*), COUNT(CASE WHEN completed = 1 THEN 1 END) FROM project_tasks {} AND created_at >= strftime('%s', 'now', '-7 days')", where_clause)
-        };
+        // Get weekly stats. Completions are counted by when they happened; tasks completed
+        // before completion timestamps were recorded fall back to their creation time.
+        let weekly_query = format!(
+            "SELECT COUNT(CASE WHEN created_at >= strftime('%s', 'now', '-7 days') THEN 1 END), \
+             COUNT(CASE WHEN completed = 1 AND COALESCE(completed_at, created_at) >= strftime('%s', 'now', '-7 days') THEN 1 END) \
+             FROM project_tasks {}",
+            where_clause
+        );
         let mut weekly_stmt = self.conn.prepare(&weekly_query)?;
         let (tasks_created_this_week, tasks_completed_this_week): (i32, i32) = weekly_stmt.query_row(param_refs.as_slice(), |row| {
             Ok((row.get(0)?, row.get(1)?))
         })?;
 
-        // Get monthly stats  
-        let monthly_query = if where_clause.is_empty() {
-            "SELECT COUNT(*), COUNT(CASE WHEN completed = 1 THEN 1 END) FROM project_tasks WHERE created_at >= strftime('%s', 'now', '-30 days')".to_string()
-        } else {
-            format!("SELECT COUNT(*), COUNT(CASE WHEN completed = 1 THEN 1 END) FROM project_tasks {} AND created_at >= strftime('%s', 'now', '-30 days')", where_clause)
-        };
+        // Get monthly stats
+        let monthly_query = format!(
+            "SELECT COUNT(CASE WHEN created_at >= strftime('%s', 'now', '-30 days') THEN 1 END), \
+             COUNT(CASE WHEN completed = 1 AND COALESCE(completed_at, created_at) >= strftime('%s', 'now', '-30 days') THEN 1 END) \
+             FROM project_tasks {}",
+            where_clause
+        );
         let mut monthly_stmt = self.conn.prepare(&monthly_query)?;
         let (tasks_created_this_month, tasks_completed_this_month): (i32, i32) = monthly_stmt.query_row(param_refs.as_slice(), |row| {
             Ok((row.get(0)?, row.get(1)?))
         })?;
 
+        // Overdue tasks and average time from creation to completion
+        let throughput_query = format!(
+            "SELECT COUNT(CASE WHEN completed = 0 AND due_date IS NOT NULL AND due_date < strftime('%s', 'now') THEN 1 END), \
+             AVG(CASE WHEN completed = 1 AND completed_at IS NOT NULL THEN completed_at - created_at END) \
+             FROM project_tasks {}",
+            where_clause
+        );
+        let mut throughput_stmt = self.conn.prepare(&throughput_query)?;
+        let (overdue_tasks, average_completion_seconds): (i32, Option<f64>) = throughput_stmt.query_row(param_refs.as_slice(), |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+
         // Get monthly trends (reuse existing method) - we'll do this separately to avoid borrowing issues
         drop(stmt); // Drop the statement to release the borrow
         drop(weekly_stmt);
         drop(monthly_stmt);
+        drop(throughput_stmt);
         
         let monthly_trends = if let Some(pid) = project_id {
             // For specific project, we need a different query
@@ -443,6 +770,8 @@ impl LiveSetDatabase {
             tasks_completed_this_week,
             tasks_created_this_month,
             tasks_completed_this_month,
+            overdue_tasks,
+            average_completion_seconds,
             monthly_trends,
         })
     }
@@ -499,5 +828,158 @@ pub struct TaskAnalytics {
     pub tasks_completed_this_week: i32,
     pub tasks_created_this_month: i32,
     pub tasks_completed_this_month: i32,
+    pub overdue_tasks: i32,
+    pub average_completion_seconds: Option<f64>,
     pub monthly_trends: Vec<(i32, i32, i32, i32, f64)>, // (year, month, completed, total, rate)
 }
+
+/// Tasks are prioritised 1-5, where 5 is highest
+pub const DEFAULT_TASK_PRIORITY: i32 = 3;
+pub const MIN_TASK_PRIORITY: i32 = 1;
+pub const MAX_TASK_PRIORITY: i32 = 5;
+
+pub fn validate_priority(priority: i32) -> Result<(), DatabaseError> {
+    if !(MIN_TASK_PRIORITY..=MAX_TASK_PRIORITY).contains(&priority) {
+        return Err(DatabaseError::InvalidOperation(format!(
+            "Task priority must be between {} and {}, got {}",
+            MIN_TASK_PRIORITY, MAX_TASK_PRIORITY, priority
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProjectTask {
+    pub id: String,
+    pub project_id: String,
+    pub description: String,
+    pub completed: bool,
+    pub created_at: i64,
+    pub priority: i32,
+    pub due_date: Option<i64>,
+    pub position: i32,
+    pub assignee: Option<String>,
+    pub completed_at: Option<i64>,
+    pub arrangement_position: Option<ArrangementPosition>,
+    /// Arrangement position converted to seconds using the project's tempo and time signature
+    pub arrangement_seconds: Option<f64>,
+    pub track_name: Option<String>,
+}
+
+/// A 1-based bar/beat position in the arrangement, as shown in Live's position display.
+/// Beats may be fractional (e.g. 3.5 is halfway through the third beat).
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct ArrangementPosition {
+    pub bar: i32,
+    pub beat: f64,
+}
+
+impl std::str::FromStr for ArrangementPosition {
+    type Err = DatabaseError;
+
+    /// Parse Live's `bar.beat.sixteenth` notation, where trailing parts are optional
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            DatabaseError::InvalidOperation(format!(
+                "Invalid arrangement position '{}' (expected bar[.beat[.sixteenth]])",
+                s
+            ))
+        };
+        let mut parts = s.trim().split('.');
+        let bar: i32 = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        let beat: f64 = match parts.next() {
+            Some(p) => p.parse::<u32>().map_err(|_| invalid())? as f64,
+            None => 1.0,
+        };
+        let sixteenth: f64 = match parts.next() {
+            Some(p) => p.parse::<u32>().map_err(|_| invalid())? as f64,
+            None => 1.0,
+        };
+        if parts.next().is_some() || sixteenth < 1.0 {
+            return Err(invalid());
+        }
+
+        let position = ArrangementPosition {
+            bar,
+            beat: beat + (sixteenth - 1.0) / 4.0,
+        };
+        validate_arrangement_position(position)?;
+        Ok(position)
+    }
+}
+
+impl std::fmt::Display for ArrangementPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let beat = self.beat.floor();
+        let sixteenth = (self.beat - beat) * 4.0 + 1.0;
+        if sixteenth == 1.0 {
+            write!(f, "{}.{}", self.bar, beat)
+        } else if sixteenth.fract() == 0.0 {
+            write!(f, "{}.{}.{}", self.bar, beat, sixteenth)
+        } else {
+            write!(f, "{}.{}.{:.2}", self.bar, beat, sixteenth)
+        }
+    }
+}
+
+fn validate_arrangement_position(position: ArrangementPosition) -> Result<(), DatabaseError> {
+    if position.bar < 1 || position.beat < 1.0 {
+        return Err(DatabaseError::InvalidOperation(format!(
+            "Arrangement position must be at or after bar 1 beat 1, got {}.{}",
+            position.bar, position.beat
+        )));
+    }
+    Ok(())
+}
+
+/// Validate a position and, when the project's time signature is known, check that the
+/// beat falls inside the bar (a 4/4 bar has beats 1 up to but not including 5)
+fn validate_position_in_bar(
+    position: ArrangementPosition,
+    beats_per_bar: Option<i32>,
+) -> Result<(), DatabaseError> {
+    validate_arrangement_position(position)?;
+    if let Some(beats_per_bar) = beats_per_bar {
+        if position.beat >= beats_per_bar as f64 + 1.0 {
+            return Err(DatabaseError::InvalidOperation(format!(
+                "Beat {} is past the end of a bar of {} beats",
+                position.beat, beats_per_bar
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Optional task properties set at creation time
+#[derive(Debug, Clone)]
+pub struct TaskDetails {
+    pub priority: i32,
+    pub due_date: Option<i64>,
+    pub assignee: Option<String>,
+    pub arrangement_position: Option<ArrangementPosition>,
+    pub track_name: Option<String>,
+}
+
+impl Default for TaskDetails {
+    fn default() -> Self {
+        Self {
+            priority: DEFAULT_TASK_PRIORITY,
+            due_date: None,
+            assignee: None,
+            arrangement_position: None,
+            track_name: None,
+        }
+    }
+}
+
+/// Optional filters for `search_tasks`
+#[derive(Debug, Default, Clone)]
+pub struct TaskSearchFilters {
+    pub completed_only: Option<bool>,
+    pub pending_only: Option<bool>,
+    pub min_priority: Option<i32>,
+    pub assignee: Option<String>,
+    pub due_before: Option<i64>,
+    pub due_after: Option<i64>,
+    pub overdue_only: Option<bool>,
+}
@@ -0,0 +1,129 @@
+use crate::error::DatabaseError;
+use crate::media::MediaType;
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use rusqlite::{params, OptionalExtension, Row};
+use uuid::Uuid;
+
+use super::models::SqlDateTime;
+use super::LiveSetDatabase;
+
+/// A resumable upload that hasn't received all of its bytes yet
+#[derive(Debug, Clone)]
+pub struct UploadSession {
+    pub id: String,
+    pub media_type: MediaType,
+    pub filename: String,
+    pub total_size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+    /// When the last chunk arrived
+    pub updated_at: DateTime<Utc>,
+}
+
+impl LiveSetDatabase {
+    pub fn create_upload_session(
+        &mut self,
+        media_type: MediaType,
+        filename: &str,
+        total_size_bytes: u64,
+    ) -> Result<UploadSession, DatabaseError> {
+        let now = Utc::now();
+        let session = UploadSession {
+            id: Uuid::new_v4().to_string(),
+            media_type,
+            filename: filename.to_string(),
+            total_size_bytes,
+            created_at: now,
+            updated_at: now,
+        };
+        self.conn.execute(
+            "INSERT INTO upload_sessions (id, media_type, filename, total_size_bytes, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+            params![
+                session.id,
+                session.media_type.as_str(),
+                session.filename,
+                session.total_size_bytes as i64,
+                SqlDateTime::from_utc(now),
+                SqlDateTime::from_utc(now),
+            ],
+        )?;
+        info!(
+            "Started upload {} of {} ({} bytes)",
+            session.id, session.filename, total_size_bytes
+        );
+        Ok(session)
+    }
+
+    pub fn get_upload_session(&self, upload_id: &str) -> Result<Option<UploadSession>, DatabaseError> {
+        let session = self
+            .conn
+            .query_row(
+                "SELECT * FROM upload_sessions WHERE id = ?",
+                [upload_id],
+                row_to_upload_session,
+            )
+            .optional()?;
+        Ok(session)
+    }
+
+    /// Record that a chunk of an upload arrived, which keeps it from expiring
+    pub fn touch_upload_session(&mut self, upload_id: &str) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            "UPDATE upload_sessions SET updated_at = ? WHERE id = ?",
+            params![SqlDateTime::from_utc(Utc::now()), upload_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_upload_session(&mut self, upload_id: &str) -> Result<bool, DatabaseError> {
+        let removed = self
+            .conn
+            .execute("DELETE FROM upload_sessions WHERE id = ?", [upload_id])?;
+        if removed > 0 {
+            debug!("Deleted upload session {}", upload_id);
+        }
+        Ok(removed > 0)
+    }
+
+    /// Delete sessions that received nothing since `before` and return their IDs
+    pub fn expire_upload_sessions(
+        &mut self,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare("DELETE FROM upload_sessions WHERE updated_at < ? RETURNING id")?;
+        let expired = stmt
+            .query_map([SqlDateTime::from_utc(before)], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        if !expired.is_empty() {
+            info!("Expired {} abandoned upload sessions", expired.len());
+        }
+        Ok(expired)
+    }
+
+    pub fn get_upload_session_ids(&self) -> Result<Vec<String>, DatabaseError> {
+        let mut stmt = self.conn.prepare("SELECT id FROM upload_sessions")?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(ids)
+    }
+}
+
+fn row_to_upload_session(row: &Row) -> Result<UploadSession, rusqlite::Error> {
+    let media_type: String = row.get("media_type")?;
+    let media_type = MediaType::from_str(&media_type)
+        .map_err(|_| rusqlite::Error::InvalidParameterName("Invalid media type".into()))?;
+    let created_at: i64 = row.get("created_at")?;
+    let updated_at: i64 = row.get("updated_at")?;
+    Ok(UploadSession {
+        id: row.get("id")?,
+        media_type,
+        filename: row.get("filename")?,
+        total_size_bytes: row.get::<_, i64>("total_size_bytes")? as u64,
+        created_at: DateTime::from_timestamp(created_at, 0).unwrap_or_default(),
+        updated_at: DateTime::from_timestamp(updated_at, 0).unwrap_or_default(),
+    })
+}
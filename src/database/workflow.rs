@@ -0,0 +1,211 @@
+use crate::database::models::SqlDateTime;
+use crate::error::DatabaseError;
+use chrono::Local;
+use log::debug;
+use rusqlite::{params, OptionalExtension};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use super::LiveSetDatabase;
+
+/// Stages used until the pipeline is configured
+pub const DEFAULT_STATUS_PIPELINE: [&str; 5] = ["idea", "arrangement", "mixing", "mastering", "released"];
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StatusTransition {
+    pub id: String,
+    pub project_id: String,
+    /// None when the project had no status before this transition
+    pub from_status: Option<String>,
+    /// None when the status was cleared
+    pub to_status: Option<String>,
+    pub changed_at: i64,
+}
+
+impl LiveSetDatabase {
+    pub(crate) fn seed_default_status_pipeline(&mut self) -> Result<(), DatabaseError> {
+        let count: i32 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM workflow_statuses", [], |row| row.get(0))?;
+        if count > 0 {
+            return Ok(());
+        }
+
+        debug!("Seeding default status pipeline");
+        let tx = self.conn.transaction()?;
+        for (position, name) in DEFAULT_STATUS_PIPELINE.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO workflow_statuses (name, position) VALUES (?, ?)",
+                params![name, position as i32],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Workflow stages in pipeline order
+    pub fn get_status_pipeline(&self) -> Result<Vec<String>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name FROM workflow_statuses ORDER BY position")?;
+        let statuses = stmt
+            .query_map([], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(statuses)
+    }
+
+    /// Replace the pipeline. Stages still assigned to a project cannot be removed.
+    pub fn set_status_pipeline(&mut self, statuses: &[String]) -> Result<(), DatabaseError> {
+        debug!("Setting status pipeline: {:?}", statuses);
+
+        let mut seen = HashSet::new();
+        for status in statuses {
+            let normalized = status.trim().to_lowercase();
+            if normalized.is_empty() || normalized.contains(char::is_whitespace) {
+                return Err(DatabaseError::InvalidOperation(format!(
+                    "Invalid status name '{}'",
+                    status
+                )));
+            }
+            if !seen.insert(normalized) {
+                return Err(DatabaseError::InvalidOperation(format!(
+                    "Duplicate status '{}'",
+                    status
+                )));
+            }
+        }
+        if seen.is_empty() {
+            return Err(DatabaseError::InvalidOperation(
+                "The status pipeline needs at least one stage".to_string(),
+            ));
+        }
+
+        let tx = self.conn.transaction()?;
+        let in_use: Vec<String> = {
+            let mut stmt = tx.prepare(
+                "SELECT DISTINCT status FROM projects WHERE status IS NOT NULL ORDER BY status",
+            )?;
+            let statuses = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .filter_map(|r| r.ok())
+                .filter(|status| !seen.contains(status))
+                .collect();
+            statuses
+        };
+        if !in_use.is_empty() {
+            return Err(DatabaseError::InvalidOperation(format!(
+                "Statuses still assigned to projects: {}",
+                in_use.join(", ")
+            )));
+        }
+
+        tx.execute("DELETE FROM workflow_statuses", [])?;
+        for (position, status) in statuses.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO workflow_statuses (name, position) VALUES (?, ?)",
+                params![status.trim().to_lowercase(), position as i32],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Current status of a project and when it was entered
+    pub fn get_project_status(
+        &self,
+        project_id: &str,
+    ) -> Result<Option<(String, i64)>, DatabaseError> {
+        let status = self
+            .conn
+            .query_row(
+                "SELECT status, status_changed_at FROM projects WHERE id = ? AND status IS NOT NULL",
+                [project_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(status)
+    }
+
+    /// Move a project to a pipeline stage (or clear its status), recording the transition
+    pub fn set_project_status(
+        &mut self,
+        project_id: &str,
+        status: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        debug!("Setting status of project {} to {:?}", project_id, status);
+        let status = status.map(|s| s.trim().to_lowercase());
+
+        let tx = self.conn.transaction()?;
+        let current: Option<String> = tx
+            .query_row(
+                "SELECT status FROM projects WHERE id = ?",
+                [project_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| DatabaseError::NotFound(format!("Project {} not found", project_id)))?;
+
+        if let Some(ref status) = status {
+            let known: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM workflow_statuses WHERE name = ?)",
+                [status],
+                |row| row.get(0),
+            )?;
+            if !known {
+                return Err(DatabaseError::InvalidOperation(format!(
+                    "Unknown status '{}'",
+                    status
+                )));
+            }
+        }
+
+        if current == status {
+            debug!("Project {} already has status {:?}", project_id, status);
+            return Ok(());
+        }
+
+        let now = SqlDateTime::from(Local::now());
+        tx.execute(
+            "UPDATE projects SET status = ?, status_changed_at = ? WHERE id = ?",
+            params![status, now, project_id],
+        )?;
+        tx.execute(
+            "INSERT INTO project_status_history (id, project_id, from_status, to_status, changed_at)
+             VALUES (?, ?, ?, ?, ?)",
+            params![Uuid::new_v4().to_string(), project_id, current, status, now],
+        )?;
+        tx.commit()?;
+
+        debug!("Successfully set status of project {}", project_id);
+        Ok(())
+    }
+
+    /// Status transitions of a project, oldest first
+    pub fn get_project_status_history(
+        &self,
+        project_id: &str,
+    ) -> Result<Vec<StatusTransition>, DatabaseError> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, project_id, from_status, to_status, changed_at
+            FROM project_status_history
+            WHERE project_id = ?
+            ORDER BY changed_at, rowid
+            "#,
+        )?;
+        let history = stmt
+            .query_map([project_id], |row| {
+                Ok(StatusTransition {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    from_status: row.get(2)?,
+                    to_status: row.get(3)?,
+                    changed_at: row.get(4)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(history)
+    }
+}
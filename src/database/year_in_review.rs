@@ -0,0 +1,316 @@
+use crate::error::DatabaseError;
+use chrono::{Local, NaiveDate, TimeZone};
+use rusqlite::{params, OptionalExtension};
+
+use super::LiveSetDatabase;
+
+/// Projects touched in a year: created or last saved between ?1 and ?2
+const WORKED_ON: &str = "SELECT id FROM projects
+     WHERE is_active = true AND set_kind = 'project'
+       AND ((created_at >= ?1 AND created_at < ?2) OR (modified_at >= ?1 AND modified_at < ?2))";
+
+/// Highlights of a year of work. Times are bucketed in local time, and a
+/// project's save is its latest modification time.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct YearInReview {
+    pub year: i32,
+    /// Projects created or saved during the year
+    pub projects_worked_on: i32,
+    pub projects_started: i32,
+    /// Projects that reached the last stage of the status pipeline during the year
+    pub projects_finished: i32,
+    /// Name, vendor and the number of projects worked on that used the plugin
+    pub top_plugins: Vec<(String, String, i32)>,
+    /// Name, path and the number of projects worked on that used the sample
+    pub top_samples: Vec<(String, String, i32)>,
+    /// Plugins whose first project was started this year
+    pub new_plugins: Vec<(String, String, i32)>,
+    /// Saves in each month, January first
+    pub saves_per_month: [i32; 12],
+    /// Saves on each weekday, Monday first
+    pub saves_per_weekday: [i32; 7],
+    pub saves_per_hour: [i32; 24],
+    pub longest_project_id: Option<String>,
+    /// Most common key and how many projects were in it
+    pub favorite_key: Option<(String, i32)>,
+    /// Most common 10 BPM range, by its lowest tempo, and how many projects were in it
+    pub favorite_tempo_range: Option<(f64, i32)>,
+    pub average_tempo: Option<f64>,
+    /// Days on which a project was created, saved or moved to another stage
+    pub active_days: i32,
+    pub longest_streak: Option<Streak>,
+}
+
+/// Consecutive days with activity
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Streak {
+    pub start: NaiveDate,
+    pub days: i32,
+}
+
+impl YearInReview {
+    /// Month with the most saves, 1 for January
+    pub fn busiest_month(&self) -> Option<u32> {
+        busiest(&self.saves_per_month).map(|month| month + 1)
+    }
+
+    /// Weekday with the most saves, 1 for Monday
+    pub fn busiest_weekday(&self) -> Option<u32> {
+        busiest(&self.saves_per_weekday).map(|weekday| weekday + 1)
+    }
+
+    pub fn busiest_hour(&self) -> Option<u32> {
+        busiest(&self.saves_per_hour)
+    }
+}
+
+impl LiveSetDatabase {
+    pub fn get_year_in_review(&self, year: i32) -> Result<YearInReview, DatabaseError> {
+        let (start, end) = year_bounds(year)?;
+
+        let projects_worked_on: i32 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({})", WORKED_ON),
+            params![start, end],
+            |row| row.get(0),
+        )?;
+        let projects_started: i32 = self.conn.query_row(
+            "SELECT COUNT(*) FROM projects
+             WHERE is_active = true AND set_kind = 'project' AND created_at >= ?1 AND created_at < ?2",
+            params![start, end],
+            |row| row.get(0),
+        )?;
+        let projects_finished = match self.get_status_pipeline()?.last() {
+            Some(last_stage) => self.conn.query_row(
+                "SELECT COUNT(DISTINCT h.project_id)
+                 FROM project_status_history h
+                 JOIN projects p ON p.id = h.project_id
+                 WHERE p.is_active = true AND p.set_kind = 'project'
+                   AND h.to_status = ?3 AND h.changed_at >= ?1 AND h.changed_at < ?2",
+                params![start, end, last_stage],
+                |row| row.get(0),
+            )?,
+            None => 0,
+        };
+
+        let top_plugins = self.query_usage(
+            &format!(
+                "SELECT pl.name, COALESCE(pl.vendor, 'Unknown'), COUNT(*) AS usage_count
+                 FROM project_plugins pp
+                 JOIN plugins pl ON pl.id = pp.plugin_id
+                 WHERE pp.project_id IN ({})
+                 GROUP BY pl.id
+                 ORDER BY usage_count DESC, pl.name
+                 LIMIT 10",
+                WORKED_ON
+            ),
+            start,
+            end,
+        )?;
+        let top_samples = self.query_usage(
+            &format!(
+                "SELECT s.name, s.path, COUNT(*) AS usage_count
+                 FROM project_samples ps
+                 JOIN samples s ON s.id = ps.sample_id
+                 WHERE ps.project_id IN ({})
+                 GROUP BY s.id
+                 ORDER BY usage_count DESC, s.name
+                 LIMIT 10",
+                WORKED_ON
+            ),
+            start,
+            end,
+        )?;
+        let new_plugins = self.query_usage(
+            &format!(
+                "SELECT pl.name, COALESCE(pl.vendor, 'Unknown'),
+                        SUM(pp.project_id IN ({})) AS usage_count
+                 FROM project_plugins pp
+                 JOIN plugins pl ON pl.id = pp.plugin_id
+                 JOIN projects p ON p.id = pp.project_id
+                 WHERE p.is_active = true AND p.set_kind = 'project'
+                 GROUP BY pl.id
+                 HAVING MIN(p.created_at) >= ?1 AND MIN(p.created_at) < ?2
+                 ORDER BY usage_count DESC, pl.name
+                 LIMIT 10",
+                WORKED_ON
+            ),
+            start,
+            end,
+        )?;
+
+        let mut saves_per_month = [0; 12];
+        let mut saves_per_weekday = [0; 7];
+        let mut saves_per_hour = [0; 24];
+        let mut stmt = self.conn.prepare(
+            "SELECT CAST(strftime('%m', modified_at, 'unixepoch', 'localtime') AS INTEGER),
+                    CAST(strftime('%w', modified_at, 'unixepoch', 'localtime') AS INTEGER),
+                    CAST(strftime('%H', modified_at, 'unixepoch', 'localtime') AS INTEGER)
+             FROM projects
+             WHERE is_active = true AND set_kind = 'project' AND modified_at >= ?1 AND modified_at < ?2",
+        )?;
+        let saves = stmt.query_map(params![start, end], |row| {
+            Ok((
+                row.get::<_, usize>(0)?,
+                row.get::<_, usize>(1)?,
+                row.get::<_, usize>(2)?,
+            ))
+        })?;
+        for save in saves {
+            let (month, weekday, hour) = save?;
+            saves_per_month[month - 1] += 1;
+            // SQLite counts weekdays from Sunday
+            saves_per_weekday[(weekday + 6) % 7] += 1;
+            saves_per_hour[hour] += 1;
+        }
+
+        let longest_project_id = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT id FROM projects
+                     WHERE id IN ({}) AND duration_seconds IS NOT NULL
+                     ORDER BY duration_seconds DESC
+                     LIMIT 1",
+                    WORKED_ON
+                ),
+                params![start, end],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let favorite_key = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT key_signature_tonic || ' ' || key_signature_scale AS key_sig, COUNT(*) AS count
+                     FROM projects
+                     WHERE id IN ({}) AND key_signature_tonic IS NOT NULL AND key_signature_scale IS NOT NULL
+                     GROUP BY key_sig
+                     ORDER BY count DESC, key_sig
+                     LIMIT 1",
+                    WORKED_ON
+                ),
+                params![start, end],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let favorite_tempo_range = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT CAST(tempo / 10 AS INTEGER) * 10.0 AS tempo_range, COUNT(*) AS count
+                     FROM projects
+                     WHERE id IN ({}) AND tempo > 0
+                     GROUP BY tempo_range
+                     ORDER BY count DESC, tempo_range
+                     LIMIT 1",
+                    WORKED_ON
+                ),
+                params![start, end],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let average_tempo: Option<f64> = self.conn.query_row(
+            &format!(
+                "SELECT AVG(tempo) FROM projects WHERE id IN ({}) AND tempo > 0",
+                WORKED_ON
+            ),
+            params![start, end],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT day FROM (
+                 SELECT date(created_at, 'unixepoch', 'localtime') AS day, created_at AS at
+                 FROM projects WHERE is_active = true AND set_kind = 'project'
+                 UNION ALL
+                 SELECT date(modified_at, 'unixepoch', 'localtime'), modified_at
+                 FROM projects WHERE is_active = true AND set_kind = 'project'
+                 UNION ALL
+                 SELECT date(changed_at, 'unixepoch', 'localtime'), changed_at
+                 FROM project_status_history
+             )
+             WHERE at >= ?1 AND at < ?2
+             ORDER BY day",
+        )?;
+        let active_days = stmt
+            .query_map(params![start, end], |row| row.get::<_, String>(0))?
+            .filter_map(|day| NaiveDate::parse_from_str(&day.ok()?, "%Y-%m-%d").ok())
+            .collect::<Vec<_>>();
+
+        Ok(YearInReview {
+            year,
+            projects_worked_on,
+            projects_started,
+            projects_finished,
+            top_plugins,
+            top_samples,
+            new_plugins,
+            saves_per_month,
+            saves_per_weekday,
+            saves_per_hour,
+            longest_project_id,
+            favorite_key,
+            favorite_tempo_range,
+            average_tempo,
+            active_days: active_days.len() as i32,
+            longest_streak: longest_streak(&active_days),
+        })
+    }
+
+    fn query_usage(
+        &self,
+        query: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<(String, String, i32)>, DatabaseError> {
+        let mut stmt = self.conn.prepare(query)?;
+        let rows = stmt
+            .query_map(params![start, end], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
+
+/// Timestamps of the first moment of a year and of the next one, in local time
+fn year_bounds(year: i32) -> Result<(i64, i64), DatabaseError> {
+    let start_of = |year: i32| {
+        Local
+            .with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+            .earliest()
+            .map(|start| start.timestamp())
+            .ok_or_else(|| DatabaseError::InvalidOperation(format!("Invalid year: {}", year)))
+    };
+    Ok((start_of(year)?, start_of(year + 1)?))
+}
+
+fn busiest(counts: &[i32]) -> Option<u32> {
+    // The first of equally busy ones wins
+    let (index, count) = counts
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|(_, count)| **count)?;
+    (*count > 0).then_some(index as u32)
+}
+
+/// The longest run of consecutive days, the earliest one if several are as long
+fn longest_streak(days: &[NaiveDate]) -> Option<Streak> {
+    let mut longest: Option<Streak> = None;
+    let mut start = 0;
+    for (i, day) in days.iter().enumerate() {
+        if i > 0 && days[i - 1].succ_opt() != Some(*day) {
+            start = i;
+        }
+        let length = (i - start + 1) as i32;
+        if longest.as_ref().is_none_or(|longest| length > longest.days) {
+            longest = Some(Streak {
+                start: days[start],
+                days: length,
+            });
+        }
+    }
+    longest
+}
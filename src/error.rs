@@ -375,6 +375,70 @@ pub enum LiveSetError {
 
     #[error("Invalid project: {0}")]
     InvalidProject(String),
+
+    #[error("Parsing took longer than {0} seconds")]
+    ParseTimeout(u64),
+
+    #[error("Scan cancelled")]
+    Cancelled,
+
+    #[error("{source} (line {line})")]
+    ParseFailed {
+        line: usize,
+        /// Live version from the set's root element, if it was read before the failure
+        version: Option<String>,
+        #[source]
+        source: Box<LiveSetError>,
+    },
+}
+
+impl LiveSetError {
+    /// Short, stable name for the kind of failure, used to group scan errors
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LiveSetError::IoError(_) => "io",
+            LiveSetError::XmlError(e) => match e {
+                XmlParseError::Utf8Error(_) => "xml_encoding",
+                XmlParseError::AttrError(_) | XmlParseError::MissingRequiredAttribute(_) => {
+                    "xml_attribute"
+                }
+                XmlParseError::QuickXmlError(_) => "xml_syntax",
+                _ => "xml_structure",
+            },
+            LiveSetError::InvalidVersion(_) | LiveSetError::VersionError(_) => "invalid_version",
+            LiveSetError::UnsupportedVersion(_) => "unsupported_version",
+            LiveSetError::MissingVersion => "missing_version",
+            LiveSetError::TempoError(_) => "tempo",
+            LiveSetError::TimeSignatureError(_) => "time_signature",
+            LiveSetError::SampleError(_) => "sample",
+            LiveSetError::FileError(_) => "file",
+            LiveSetError::AttributeError(_) | LiveSetError::AttrError(_) => "xml_attribute",
+            LiveSetError::PluginError(_) => "plugin",
+            LiveSetError::PatternError(_) => "pattern",
+            LiveSetError::CreateLiveSetError(_) | LiveSetError::InvalidProject(_) => "invalid_project",
+            LiveSetError::DatabaseError(_) => "database",
+            LiveSetError::ConfigError(_) => "config",
+            LiveSetError::ParseTimeout(_) => "timeout",
+            LiveSetError::Cancelled => "cancelled",
+            LiveSetError::ParseFailed { source, .. } => source.kind(),
+        }
+    }
+
+    /// Line of the set the parser had reached when it failed
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            LiveSetError::ParseFailed { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+
+    /// Live version of the set that failed, if it was read
+    pub fn live_version(&self) -> Option<&str> {
+        match self {
+            LiveSetError::ParseFailed { version, .. } => version.as_deref(),
+            _ => None,
+        }
+    }
 }
 
 impl From<quick_xml::Error> for LiveSetError {
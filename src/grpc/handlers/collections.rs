@@ -5,6 +5,7 @@ use tonic::{Code, Request, Response, Status};
 
 use super::super::collections::*;
 use super::super::common::*;
+use super::utils::convert_task_to_proto;
 use crate::database::LiveSetDatabase;
 
 // MOVE FROM server.rs:
@@ -572,17 +573,14 @@ impl CollectionsHandler {
                 let mut tasks = Vec::new();
                 let mut completed_count = 0;
 
-                for (id, project_name, description, completed, created_at) in tasks_data {
-                    if completed {
+                for (project_name, task) in tasks_data {
+                    if task.completed {
                         completed_count += 1;
                     }
 
                     tasks.push(Task {
-                        id,
                         project_id: project_name, // Using project_name in project_id field to show which project the task belongs to
-                        description,
-                        completed,
-                        created_at,
+                        ..convert_task_to_proto(task)
                     });
                 }
 
@@ -714,6 +712,18 @@ impl CollectionsHandler {
                     total_tags: stats.total_tags,
                     most_common_key: stats.most_common_key,
                     most_common_time_signature: stats.most_common_time_signature,
+                    status_breakdown: stats
+                        .status_breakdown
+                        .into_iter()
+                        .map(|(status, count)| StatusCount { status, count })
+                        .collect(),
+                    projects_without_status: stats.projects_without_status,
+                    analyzed_project_count: stats.analyzed_project_count,
+                    average_lufs: stats.average_lufs,
+                    min_lufs: stats.min_lufs,
+                    max_lufs: stats.max_lufs,
+                    max_true_peak_dbtp: stats.max_true_peak_dbtp,
+                    average_loudness_range_lu: stats.average_loudness_range_lu,
                 };
                 Ok(Response::new(response))
             }
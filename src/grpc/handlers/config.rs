@@ -38,6 +38,12 @@ impl ConfigHandler {
             max_audio_file_size_mb: config.max_audio_file_size_mb,
             needs_setup: config.needs_setup(),
             status_message: config.get_status_message(),
+            poll_paths: config.poll_paths.clone(),
+            poll_interval_secs: config.poll_interval_secs,
+            scan_workers: config.scan_workers as u32,
+            scan_file_timeout_secs: config.scan_file_timeout_secs,
+            roots: config.roots.iter().map(root_settings_to_proto).collect(),
+            strict_media_validation: config.strict_media_validation,
         };
 
         let response = GetConfigResponse {
@@ -236,6 +242,12 @@ impl ConfigHandler {
                     max_audio_file_size_mb: new_config.max_audio_file_size_mb,
                     needs_setup: new_config.needs_setup(),
                     status_message: new_config.get_status_message(),
+                    poll_paths: new_config.poll_paths.clone(),
+                    poll_interval_secs: new_config.poll_interval_secs,
+                    scan_workers: new_config.scan_workers as u32,
+                    scan_file_timeout_secs: new_config.scan_file_timeout_secs,
+                    roots: new_config.roots.iter().map(root_settings_to_proto).collect(),
+                    strict_media_validation: new_config.strict_media_validation,
                 };
 
                 let response = ReloadConfigResponse {
@@ -293,3 +305,14 @@ impl ConfigHandler {
         }
     }
 }
+
+fn root_settings_to_proto(root: &crate::config::RootSettings) -> RootSettings {
+    RootSettings {
+        path: root.path.clone(),
+        exclude: root.exclude.clone(),
+        max_depth: root.max_depth.map(|depth| depth as u32),
+        follow_symlinks: root.follow_symlinks,
+        include_backups: root.include_backups,
+        default_tag: root.default_tag.clone(),
+    }
+}
@@ -0,0 +1,583 @@
+// Renderers for ExportStatistics. Every format is produced from the same
+// GetStatisticsResponse, so they all show what GetStatistics returns for the
+// request's filters.
+
+use chrono::Utc;
+use std::fmt::Write;
+
+use super::super::common::ExportFormat;
+use super::super::system::GetStatisticsResponse;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Name of the file an export is saved as
+pub fn export_filename(format: ExportFormat) -> String {
+    let extension = match format {
+        ExportFormat::ExportCsv => "csv",
+        ExportFormat::ExportJson => "json",
+        ExportFormat::ExportMarkdown => "md",
+        ExportFormat::ExportHtml => "html",
+    };
+    format!(
+        "statistics_{}.{}",
+        Utc::now().format("%Y%m%d_%H%M%S"),
+        extension
+    )
+}
+
+pub fn statistics_to_csv(stats: &GetStatisticsResponse) -> String {
+    let mut csv_content = String::new();
+
+    // Basic statistics
+    csv_content.push_str("Category,Value\n");
+    csv_content.push_str(&format!("Total Projects,{}\n", stats.total_projects));
+    csv_content.push_str(&format!("Total Plugins,{}\n", stats.total_plugins));
+    csv_content.push_str(&format!("Total Samples,{}\n", stats.total_samples));
+    csv_content.push_str(&format!("Total Collections,{}\n", stats.total_collections));
+    csv_content.push_str(&format!("Total Tags,{}\n", stats.total_tags));
+    csv_content.push_str(&format!("Total Tasks,{}\n", stats.total_tasks));
+    csv_content.push_str(&format!("Completed Tasks,{}\n", stats.completed_tasks));
+    csv_content.push_str(&format!("Pending Tasks,{}\n", stats.pending_tasks));
+    csv_content.push_str(&format!(
+        "Task Completion Rate,{:.2}%\n",
+        stats.task_completion_rate * 100.0
+    ));
+    csv_content.push_str(&format!(
+        "Average Project Duration,{:.2} seconds\n",
+        stats.average_project_duration_seconds
+    ));
+    csv_content.push_str(&format!(
+        "Average Projects per Collection,{:.2}\n",
+        stats.average_projects_per_collection
+    ));
+    csv_content.push_str(&format!(
+        "Average Plugins per Project,{:.2}\n",
+        stats.average_plugins_per_project
+    ));
+    csv_content.push_str(&format!(
+        "Average Samples per Project,{:.2}\n",
+        stats.average_samples_per_project
+    ));
+    csv_content.push('\n');
+
+    // Top plugins
+    csv_content.push_str("Top Plugins\n");
+    csv_content.push_str("Plugin Name,Vendor,Usage Count\n");
+    for plugin in &stats.top_plugins {
+        csv_content.push_str(&format!(
+            "{},{},{}\n",
+            plugin.name, plugin.vendor, plugin.usage_count
+        ));
+    }
+    csv_content.push('\n');
+
+    // Tempo distribution
+    csv_content.push_str("Tempo Distribution\n");
+    csv_content.push_str("Tempo,Count\n");
+    for tempo in &stats.tempo_distribution {
+        csv_content.push_str(&format!("{},{}\n", tempo.tempo, tempo.count));
+    }
+    csv_content.push('\n');
+
+    // Key distribution
+    csv_content.push_str("Key Distribution\n");
+    csv_content.push_str("Key,Count\n");
+    for key in &stats.key_distribution {
+        csv_content.push_str(&format!("{},{}\n", key.key, key.count));
+    }
+
+    csv_content
+}
+
+/// A report with one table per statistic, for reading or pasting into notes
+pub fn statistics_to_markdown(stats: &GetStatisticsResponse) -> String {
+    let mut md = String::new();
+    let _ = writeln!(md, "# Statistics Report\n");
+    let _ = writeln!(md, "_Generated {}_\n", Utc::now().format("%Y-%m-%d %H:%M UTC"));
+
+    md.push_str("## Overview\n\n");
+    markdown_table(&mut md, &["Metric", "Value"], overview_rows(stats));
+
+    if !stats.projects_per_year.is_empty() {
+        md.push_str("## Projects per Year\n\n");
+        let rows = stats
+            .projects_per_year
+            .iter()
+            .map(|year| vec![year.year.to_string(), year.count.to_string()]);
+        markdown_table(&mut md, &["Year", "Projects"], rows);
+    }
+    if !stats.projects_per_month.is_empty() {
+        md.push_str("## Projects per Month\n\n");
+        let rows = stats
+            .projects_per_month
+            .iter()
+            .rev()
+            .map(|month| vec![month_label(month.year, month.month), month.count.to_string()]);
+        markdown_table(&mut md, &["Month", "Projects"], rows);
+    }
+    if !stats.top_plugins.is_empty() {
+        md.push_str("## Top Plugins\n\n");
+        let rows = stats.top_plugins.iter().enumerate().map(|(i, plugin)| {
+            vec![
+                (i + 1).to_string(),
+                plugin.name.clone(),
+                plugin.vendor.clone(),
+                plugin.usage_count.to_string(),
+            ]
+        });
+        markdown_table(&mut md, &["#", "Plugin", "Vendor", "Uses"], rows);
+    }
+    if !stats.top_vendors.is_empty() {
+        md.push_str("## Top Vendors\n\n");
+        let rows = stats.top_vendors.iter().map(|vendor| {
+            vec![
+                vendor.vendor.clone(),
+                vendor.plugin_count.to_string(),
+                vendor.usage_count.to_string(),
+            ]
+        });
+        markdown_table(&mut md, &["Vendor", "Plugins", "Uses"], rows);
+    }
+    if !stats.top_samples.is_empty() {
+        md.push_str("## Top Samples\n\n");
+        let rows = stats.top_samples.iter().enumerate().map(|(i, sample)| {
+            vec![
+                (i + 1).to_string(),
+                sample.name.clone(),
+                sample.usage_count.to_string(),
+            ]
+        });
+        markdown_table(&mut md, &["#", "Sample", "Uses"], rows);
+    }
+    if !stats.tempo_distribution.is_empty() {
+        md.push_str("## Tempo Distribution\n\n");
+        let rows = stats
+            .tempo_distribution
+            .iter()
+            .map(|tempo| vec![tempo_label(tempo.tempo), tempo.count.to_string()]);
+        markdown_table(&mut md, &["Tempo", "Projects"], rows);
+    }
+    if !stats.key_distribution.is_empty() {
+        md.push_str("## Keys\n\n");
+        let rows = stats
+            .key_distribution
+            .iter()
+            .map(|key| vec![key.key.clone(), key.count.to_string()]);
+        markdown_table(&mut md, &["Key", "Projects"], rows);
+    }
+    if !stats.time_signature_distribution.is_empty() {
+        md.push_str("## Time Signatures\n\n");
+        let rows = stats.time_signature_distribution.iter().map(|signature| {
+            vec![
+                format!("{}/{}", signature.numerator, signature.denominator),
+                signature.count.to_string(),
+            ]
+        });
+        markdown_table(&mut md, &["Time Signature", "Projects"], rows);
+    }
+    if !stats.most_complex_projects.is_empty() {
+        md.push_str("## Most Complex Projects\n\n");
+        let rows = stats.most_complex_projects.iter().map(|complex| {
+            vec![
+                complex
+                    .project
+                    .as_ref()
+                    .map(|project| project.name.clone())
+                    .unwrap_or_default(),
+                complex.plugin_count.to_string(),
+                complex.sample_count.to_string(),
+                complex.complexity_score.to_string(),
+            ]
+        });
+        markdown_table(&mut md, &["Project", "Plugins", "Samples", "Score"], rows);
+    }
+    if !stats.top_tags.is_empty() {
+        md.push_str("## Top Tags\n\n");
+        let rows = stats
+            .top_tags
+            .iter()
+            .map(|tag| vec![tag.name.clone(), tag.usage_count.to_string()]);
+        markdown_table(&mut md, &["Tag", "Projects"], rows);
+    }
+    if !stats.ableton_versions.is_empty() {
+        md.push_str("## Ableton Versions\n\n");
+        let rows = stats
+            .ableton_versions
+            .iter()
+            .map(|version| vec![version.version.clone(), version.count.to_string()]);
+        markdown_table(&mut md, &["Version", "Projects"], rows);
+    }
+    if !stats.task_completion_trends.is_empty() {
+        md.push_str("## Task Completion\n\n");
+        let rows = stats.task_completion_trends.iter().map(|trend| {
+            vec![
+                month_label(trend.year, trend.month),
+                trend.completed_tasks.to_string(),
+                trend.total_tasks.to_string(),
+                format!("{:.0}%", trend.completion_rate * 100.0),
+            ]
+        });
+        markdown_table(&mut md, &["Month", "Completed", "Total", "Rate"], rows);
+    }
+    md
+}
+
+/// A standalone "year in review" page. Charts are inline SVG and styles are
+/// embedded, so the file can be opened or shared without anything else.
+pub fn statistics_to_html(stats: &GetStatisticsResponse) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str("<title>Year in Review</title>\n<style>\n");
+    html.push_str(
+        "body{font-family:-apple-system,BlinkMacSystemFont,\"Segoe UI\",sans-serif;\
+         background:#16161d;color:#e8e8ee;margin:0;padding:2rem;}\n\
+         main{max-width:960px;margin:0 auto;}\n\
+         h1{font-size:2.4rem;margin-bottom:0;}\n\
+         h2{margin-top:2.5rem;border-bottom:1px solid #33333f;padding-bottom:.3rem;}\n\
+         .generated{color:#8a8a99;}\n\
+         .cards{display:grid;grid-template-columns:repeat(auto-fill,minmax(160px,1fr));gap:1rem;}\n\
+         .card{background:#22222c;border-radius:10px;padding:1rem;}\n\
+         .card .value{font-size:1.8rem;font-weight:600;color:#ff9f43;}\n\
+         .card .label{color:#a0a0b0;font-size:.9rem;}\n\
+         svg{width:100%;height:auto;}\n\
+         svg text{fill:#c8c8d4;font-size:12px;}\n",
+    );
+    html.push_str("</style>\n</head>\n<body>\n<main>\n");
+    html.push_str("<h1>Year in Review</h1>\n");
+    let _ = writeln!(
+        html,
+        "<p class=\"generated\">Generated {}</p>",
+        Utc::now().format("%B %-d, %Y")
+    );
+
+    html.push_str("<div class=\"cards\">\n");
+    let cards = [
+        ("Projects", stats.total_projects.to_string()),
+        ("Plugins", stats.total_plugins.to_string()),
+        ("Samples", stats.total_samples.to_string()),
+        ("Collections", stats.total_collections.to_string()),
+        ("Projects a month", format!("{:.1}", stats.average_monthly_projects)),
+        (
+            "Average length",
+            format_duration(stats.average_project_duration_seconds),
+        ),
+        (
+            "Tasks done",
+            format!("{:.0}%", stats.task_completion_rate * 100.0),
+        ),
+    ];
+    for (label, value) in cards {
+        let _ = writeln!(
+            html,
+            "<div class=\"card\"><div class=\"value\">{}</div><div class=\"label\">{}</div></div>",
+            escape_html(&value),
+            label
+        );
+    }
+    if let Some(project) = &stats.longest_project {
+        let _ = writeln!(
+            html,
+            "<div class=\"card\"><div class=\"value\">{}</div><div class=\"label\">Longest: {}</div></div>",
+            format_duration(project.duration_seconds.unwrap_or_default()),
+            escape_html(&project.name)
+        );
+    }
+    html.push_str("</div>\n");
+
+    let months: Vec<(String, f64)> = stats
+        .projects_per_month
+        .iter()
+        .rev()
+        .map(|month| (month_label(month.year, month.month), month.count as f64))
+        .collect();
+    html_section(&mut html, "Projects per Month", &svg_column_chart(&months));
+
+    let plugins: Vec<(String, f64)> = stats
+        .top_plugins
+        .iter()
+        .map(|plugin| (plugin.name.clone(), plugin.usage_count as f64))
+        .collect();
+    html_section(&mut html, "Top Plugins", &svg_bar_chart(&plugins));
+
+    let samples: Vec<(String, f64)> = stats
+        .top_samples
+        .iter()
+        .map(|sample| (sample.name.clone(), sample.usage_count as f64))
+        .collect();
+    html_section(&mut html, "Top Samples", &svg_bar_chart(&samples));
+
+    let tempos: Vec<(String, f64)> = stats
+        .tempo_distribution
+        .iter()
+        .map(|tempo| (tempo_label(tempo.tempo), tempo.count as f64))
+        .collect();
+    html_section(&mut html, "Tempo", &svg_column_chart(&tempos));
+
+    let keys: Vec<(String, f64)> = stats
+        .key_distribution
+        .iter()
+        .map(|key| (key.key.clone(), key.count as f64))
+        .collect();
+    html_section(&mut html, "Keys", &svg_bar_chart(&keys));
+
+    let tags: Vec<(String, f64)> = stats
+        .top_tags
+        .iter()
+        .map(|tag| (tag.name.clone(), tag.usage_count as f64))
+        .collect();
+    html_section(&mut html, "Tags", &svg_bar_chart(&tags));
+
+    let versions: Vec<(String, f64)> = stats
+        .ableton_versions
+        .iter()
+        .map(|version| (version.version.clone(), version.count as f64))
+        .collect();
+    html_section(&mut html, "Ableton Versions", &svg_bar_chart(&versions));
+
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+fn overview_rows(stats: &GetStatisticsResponse) -> Vec<Vec<String>> {
+    let mut rows = vec![
+        vec!["Projects".to_string(), stats.total_projects.to_string()],
+        vec!["Plugins".to_string(), stats.total_plugins.to_string()],
+        vec!["Samples".to_string(), stats.total_samples.to_string()],
+        vec!["Collections".to_string(), stats.total_collections.to_string()],
+        vec!["Tags".to_string(), stats.total_tags.to_string()],
+        vec![
+            "Tasks".to_string(),
+            format!(
+                "{} ({} completed, {} pending)",
+                stats.total_tasks, stats.completed_tasks, stats.pending_tasks
+            ),
+        ],
+        vec![
+            "Task completion rate".to_string(),
+            format!("{:.1}%", stats.task_completion_rate * 100.0),
+        ],
+        vec![
+            "Projects per month".to_string(),
+            format!("{:.1}", stats.average_monthly_projects),
+        ],
+        vec![
+            "Average project length".to_string(),
+            format_duration(stats.average_project_duration_seconds),
+        ],
+        vec![
+            "Projects under 40 seconds".to_string(),
+            stats.projects_under_40_seconds.to_string(),
+        ],
+        vec![
+            "Plugins per project".to_string(),
+            format!("{:.1}", stats.average_plugins_per_project),
+        ],
+        vec![
+            "Samples per project".to_string(),
+            format!("{:.1}", stats.average_samples_per_project),
+        ],
+        vec![
+            "Projects per collection".to_string(),
+            format!("{:.1}", stats.average_projects_per_collection),
+        ],
+    ];
+    if let Some(project) = &stats.longest_project {
+        rows.push(vec![
+            "Longest project".to_string(),
+            format!(
+                "{} ({})",
+                project.name,
+                format_duration(project.duration_seconds.unwrap_or_default())
+            ),
+        ]);
+    }
+    if let Some(collection) = &stats.largest_collection {
+        rows.push(vec![
+            "Largest collection".to_string(),
+            format!("{} ({} projects)", collection.name, collection.project_count),
+        ]);
+    }
+    rows
+}
+
+fn markdown_table(
+    md: &mut String,
+    headers: &[&str],
+    rows: impl IntoIterator<Item = Vec<String>>,
+) {
+    let _ = writeln!(md, "| {} |", headers.join(" | "));
+    let _ = writeln!(md, "|{}", "---|".repeat(headers.len()));
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| cell.replace('|', "\\|").replace('\n', " "))
+            .collect();
+        let _ = writeln!(md, "| {} |", cells.join(" | "));
+    }
+    md.push('\n');
+}
+
+fn html_section(html: &mut String, title: &str, chart: &str) {
+    if chart.is_empty() {
+        return;
+    }
+    let _ = writeln!(html, "<section>\n<h2>{}</h2>\n{}\n</section>", title, chart);
+}
+
+/// Horizontal bars with their labels on the left, for ranked lists
+fn svg_bar_chart(bars: &[(String, f64)]) -> String {
+    if bars.is_empty() {
+        return String::new();
+    }
+    const ROW_HEIGHT: f64 = 26.0;
+    const LABEL_WIDTH: f64 = 220.0;
+    const BAR_WIDTH: f64 = 480.0;
+    let max = bars.iter().map(|(_, value)| *value).fold(0.0, f64::max).max(1.0);
+    let height = bars.len() as f64 * ROW_HEIGHT;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" role=\"img\">",
+        LABEL_WIDTH + BAR_WIDTH + 60.0,
+        height
+    );
+    for (i, (label, value)) in bars.iter().enumerate() {
+        let y = i as f64 * ROW_HEIGHT;
+        let width = (value / max * BAR_WIDTH).max(1.0);
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.0}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            LABEL_WIDTH - 8.0,
+            y + ROW_HEIGHT * 0.65,
+            escape_html(&truncate(label, 32))
+        );
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.0}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" rx=\"3\" fill=\"#ff9f43\"/>",
+            LABEL_WIDTH,
+            y + 4.0,
+            width,
+            ROW_HEIGHT - 8.0
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+            LABEL_WIDTH + width + 6.0,
+            y + ROW_HEIGHT * 0.65,
+            value
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Vertical columns in order, for distributions and time series
+fn svg_column_chart(columns: &[(String, f64)]) -> String {
+    if columns.is_empty() {
+        return String::new();
+    }
+    const WIDTH: f64 = 760.0;
+    const CHART_HEIGHT: f64 = 200.0;
+    const LABEL_HEIGHT: f64 = 24.0;
+    const VALUE_HEIGHT: f64 = 16.0;
+    let max = columns
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(0.0, f64::max)
+        .max(1.0);
+    let slot = WIDTH / columns.len() as f64;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" role=\"img\">",
+        WIDTH,
+        VALUE_HEIGHT + CHART_HEIGHT + LABEL_HEIGHT
+    );
+    for (i, (label, value)) in columns.iter().enumerate() {
+        let height = (value / max * CHART_HEIGHT).max(1.0);
+        let x = i as f64 * slot;
+        let center = x + slot / 2.0;
+        let top = VALUE_HEIGHT + CHART_HEIGHT - height;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" rx=\"3\" fill=\"#ff9f43\"/>",
+            x + slot * 0.15,
+            top,
+            slot * 0.7,
+            height
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            center,
+            top - 4.0,
+            value
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            center,
+            VALUE_HEIGHT + CHART_HEIGHT + LABEL_HEIGHT * 0.7,
+            escape_html(label)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn month_label(year: i32, month: i32) -> String {
+    match MONTHS.get((month - 1) as usize) {
+        Some(name) => format!("{} {}", name, year),
+        None => format!("{}-{:02}", year, month),
+    }
+}
+
+/// Tempos are grouped in ranges of 10 BPM starting at the one given, with
+/// everything below 90 and from 180 in the outer ranges
+fn tempo_label(tempo: f64) -> String {
+    if tempo < 90.0 {
+        "< 90".to_string()
+    } else if tempo >= 180.0 {
+        "180+".to_string()
+    } else {
+        format!("{:.0}–{:.0}", tempo, tempo + 9.0)
+    }
+}
+
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.max(0.0).round() as u64;
+    if seconds >= 3600 {
+        format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
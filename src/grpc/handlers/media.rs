@@ -1,14 +1,52 @@
 use log::{debug, error, info, warn};
+use std::collections::HashSet;
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::sync::{mpsc, Mutex, MutexGuard};
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 
-use crate::database::LiveSetDatabase;
+use crate::database::{LiveSetDatabase, UploadSession};
+use crate::error::DatabaseError;
 use super::super::media::*;
 use super::super::collections::*;
 use super::super::common::*;
-use crate::media::{MediaStorageManager, MediaType};
+use super::utils::{convert_audio_analysis_to_proto, convert_project_audio_file_to_proto};
+use crate::media::uploads::UPLOAD_SESSION_TTL_HOURS;
+use crate::media::{
+    MediaError, MediaInfo, MediaStorageManager, MediaType, MediaVariant, PreparedFile,
+    ThumbnailSize,
+};
+
+/// Download chunk size unless the client asks for another
+const DEFAULT_DOWNLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Chunk sizes a client can ask for, kept well under the gRPC message limit
+const DOWNLOAD_CHUNK_SIZES: std::ops::RangeInclusive<usize> = 1024..=1024 * 1024;
+
+/// How often the session of a resumable upload is kept alive while its chunks arrive
+const UPLOAD_TOUCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What an upload stream delivered. Chunks of resumable uploads are written to the
+/// session's partial file as they arrive, so a dropped connection loses nothing.
+#[derive(Default)]
+struct ReceivedUpload {
+    session: Option<UploadSession>,
+    /// Where the next chunk of a resumable upload goes
+    offset: Option<u64>,
+    /// When the session was last kept alive
+    touched_at: Option<Instant>,
+    data: Vec<u8>,
+}
+
+enum UploadData {
+    Complete(Vec<u8>),
+    Incomplete {
+        bytes_received: u64,
+        total_size_bytes: u64,
+    },
+}
 
 #[derive(Clone)]
 pub struct MediaHandler {
@@ -30,7 +68,7 @@ impl MediaHandler {
         let mut stream = request.into_inner();
         let mut collection_id: Option<String> = None;
         let mut filename: Option<String> = None;
-        let mut data_chunks: Vec<u8> = Vec::new();
+        let mut upload = ReceivedUpload::default();
 
         // Process the streaming request
         while let Some(chunk_result) = stream.message().await? {
@@ -45,61 +83,96 @@ impl MediaHandler {
                         filename = Some(name);
                     }
                     upload_cover_art_request::Data::Chunk(bytes) => {
-                        data_chunks.extend(bytes);
+                        self.receive_chunk(&mut upload, bytes).await?;
+                    }
+                    upload_cover_art_request::Data::UploadId(id) => {
+                        self.resume_upload(&mut upload, &id, MediaType::CoverArt).await?;
+                    }
+                    upload_cover_art_request::Data::Offset(offset) => {
+                        upload.offset = Some(offset);
                     }
                 }
             }
         }
 
+        // Resumable uploads can take several streams
+        let session = upload.session.clone();
+        let data_chunks = match self
+            .collect_upload(upload)
+            .await
+            .map_err(upload_error_to_status)?
+        {
+            UploadData::Complete(data) => data,
+            UploadData::Incomplete {
+                bytes_received,
+                total_size_bytes,
+            } => {
+                return Ok(Response::new(UploadCoverArtResponse {
+                    media_file_id: String::new(),
+                    success: false,
+                    error_message: Some(format!(
+                        "Upload incomplete: received {} of {} bytes",
+                        bytes_received, total_size_bytes
+                    )),
+                    format_info: None,
+                    bytes_received: Some(bytes_received),
+                }));
+            }
+        };
+        let bytes_received = session.as_ref().map(|session| session.total_size_bytes);
+
         // Validate we have all required data
         let collection_id =
             collection_id.ok_or_else(|| Status::invalid_argument("Collection ID is required"))?;
 
         // we dont seem to actually need a file name here, but ill leave it for now
-        let filename = filename.ok_or_else(|| Status::invalid_argument("Filename is required"))?;
+        let filename = match &session {
+            Some(session) => session.filename.clone(),
+            None => filename.ok_or_else(|| Status::invalid_argument("Filename is required"))?,
+        };
 
         if data_chunks.is_empty() {
             return Err(Status::invalid_argument("No file data received"));
         }
 
-        // Store the file using MediaStorageManager
-        let media_file =
-            match self
-                .media_storage
-                .store_file(&data_chunks, &filename, MediaType::CoverArt)
-            {
-                Ok(file) => file,
-                Err(e) => {
-                    error!("Failed to store cover art file: {:?}", e);
-                    return Ok(Response::new(UploadCoverArtResponse {
-                        media_file_id: String::new(),
-                        success: false,
-                        error_message: Some(format!("Failed to store file: {}", e)),
-                    }));
-                }
-            };
+        // Validate before taking the database lock
+        let upload_size = data_chunks.len();
+        let prepared = self
+            .prepare_upload(data_chunks, &filename, MediaType::CoverArt)
+            .await;
+        if let Some(session) = &session {
+            self.end_upload(&session.id).await;
+        }
+        let stored = match prepared {
+            Ok(prepared) => self
+                .store_prepared(prepared)
+                .await
+                .map(|(prepared, _)| prepared),
+            Err(e) => Err(e),
+        };
+        let PreparedFile {
+            media_file,
+            info: media_info,
+            image,
+            ..
+        } = match stored {
+            Ok(stored) => stored,
+            Err(error_message) => {
+                error!("Failed to store cover art file: {}", error_message);
+                return Ok(Response::new(UploadCoverArtResponse {
+                    media_file_id: String::new(),
+                    success: false,
+                    error_message: Some(error_message),
+                    format_info: None,
+                    bytes_received,
+                }));
+            }
+        };
 
-        // Store the media file metadata in the database
+        // Rendered without the database lock, which decoding a large image would hold up
+        let thumbnails = self.render_thumbnails(&media_file, image).await;
         let mut db = self.db.lock().await;
-        if let Err(e) = db.insert_media_file(&media_file) {
-            error!("Failed to insert media file into database: {:?}", e);
-            // Clean up the stored file
-            if let Err(cleanup_err) = self.media_storage.delete_file(
-                &media_file.id,
-                &media_file.file_extension,
-                &media_file.media_type,
-            ) {
-                error!(
-                    "Failed to cleanup stored file after database error: {:?}",
-                    cleanup_err
-                );
-            }
-            return Ok(Response::new(UploadCoverArtResponse {
-                media_file_id: String::new(),
-                success: false,
-                error_message: Some(format!("Failed to store metadata: {}", e)),
-            }));
-        }
+        self.record_thumbnails(&mut db, &media_file, &thumbnails);
 
         // Optionally set as collection cover art if collection_id was provided
         if let Err(e) = db.update_collection_cover_art(&collection_id, Some(&media_file.id)) {
@@ -109,7 +182,7 @@ impl MediaHandler {
 
         info!(
             "Successfully uploaded cover art: {} bytes for collection {}",
-            data_chunks.len(),
+            upload_size,
             collection_id
         );
 
@@ -117,6 +190,8 @@ impl MediaHandler {
             media_file_id: media_file.id,
             success: true,
             error_message: None,
+            format_info: Some(media_info_to_proto(&media_info)),
+            bytes_received,
         };
 
         Ok(Response::new(response))
@@ -131,7 +206,10 @@ impl MediaHandler {
         let mut stream = request.into_inner();
         let mut project_id: Option<String> = None;
         let mut filename: Option<String> = None;
-        let mut data_chunks: Vec<u8> = Vec::new();
+        let mut label: Option<String> = None;
+        let mut version: Option<u32> = None;
+        let mut make_primary = false;
+        let mut upload = ReceivedUpload::default();
 
         // Process the streaming request
         while let Some(chunk_result) = stream.message().await? {
@@ -146,70 +224,113 @@ impl MediaHandler {
                         filename = Some(name);
                     }
                     upload_audio_file_request::Data::Chunk(bytes) => {
-                        data_chunks.extend(bytes);
+                        self.receive_chunk(&mut upload, bytes).await?;
+                    }
+                    upload_audio_file_request::Data::Label(name) => {
+                        label = Some(name);
+                    }
+                    upload_audio_file_request::Data::Version(number) => {
+                        version = Some(number);
+                    }
+                    upload_audio_file_request::Data::MakePrimary(primary) => {
+                        make_primary = primary;
+                    }
+                    upload_audio_file_request::Data::UploadId(id) => {
+                        self.resume_upload(&mut upload, &id, MediaType::AudioFile).await?;
+                    }
+                    upload_audio_file_request::Data::Offset(offset) => {
+                        upload.offset = Some(offset);
                     }
                 }
             }
         }
 
+        // Resumable uploads can take several streams
+        let session = upload.session.clone();
+        let data_chunks = match self
+            .collect_upload(upload)
+            .await
+            .map_err(upload_error_to_status)?
+        {
+            UploadData::Complete(data) => data,
+            UploadData::Incomplete {
+                bytes_received,
+                total_size_bytes,
+            } => {
+                return Ok(Response::new(UploadAudioFileResponse {
+                    media_file_id: String::new(),
+                    success: false,
+                    error_message: Some(format!(
+                        "Upload incomplete: received {} of {} bytes",
+                        bytes_received, total_size_bytes
+                    )),
+                    format_info: None,
+                    bytes_received: Some(bytes_received),
+                }));
+            }
+        };
+        let bytes_received = session.as_ref().map(|session| session.total_size_bytes);
+
         // Validate we have all required data
         let project_id =
             project_id.ok_or_else(|| Status::invalid_argument("Project ID is required"))?;
 
-        let filename = filename.ok_or_else(|| Status::invalid_argument("Filename is required"))?;
+        let filename = match &session {
+            Some(session) => session.filename.clone(),
+            None => filename.ok_or_else(|| Status::invalid_argument("Filename is required"))?,
+        };
 
         if data_chunks.is_empty() {
             return Err(Status::invalid_argument("No file data received"));
         }
+        let label = parse_audio_label(label);
+
+        // Validate before taking the database lock
+        let upload_size = data_chunks.len();
+        let prepared = self
+            .prepare_upload(data_chunks, &filename, MediaType::AudioFile)
+            .await;
+        if let Some(session) = &session {
+            self.end_upload(&session.id).await;
+        }
+        let stored = match prepared {
+            Ok(prepared) => self
+                .store_prepared(prepared)
+                .await
+                .map(|(prepared, db)| (prepared.media_file, prepared.info, db)),
+            Err(e) => Err(e),
+        };
+        let (media_file, media_info, mut db) = match stored {
+            Ok(stored) => stored,
+            Err(error_message) => {
+                error!("Failed to store audio file: {}", error_message);
+                return Ok(Response::new(UploadAudioFileResponse {
+                    media_file_id: String::new(),
+                    success: false,
+                    error_message: Some(error_message),
+                    format_info: None,
+                    bytes_received,
+                }));
+            }
+        };
 
-        // Store the file using MediaStorageManager
-        let media_file =
-            match self
-                .media_storage
-                .store_file(&data_chunks, &filename, MediaType::AudioFile)
-            {
-                Ok(file) => file,
-                Err(e) => {
-                    error!("Failed to store audio file: {:?}", e);
-                    return Ok(Response::new(UploadAudioFileResponse {
-                        media_file_id: String::new(),
-                        success: false,
-                        error_message: Some(format!("Failed to store file: {}", e)),
-                    }));
-                }
-            };
-
-        // Store the media file metadata in the database
-        let mut db = self.db.lock().await;
-        if let Err(e) = db.insert_media_file(&media_file) {
-            error!("Failed to insert media file into database: {:?}", e);
-            // Clean up the stored file
-            if let Err(cleanup_err) = self.media_storage.delete_file(
-                &media_file.id,
-                &media_file.file_extension,
-                &media_file.media_type,
-            ) {
-                error!(
-                    "Failed to cleanup stored file after database error: {:?}",
-                    cleanup_err
-                );
-            }
-            return Ok(Response::new(UploadAudioFileResponse {
-                media_file_id: String::new(),
-                success: false,
-                error_message: Some(format!("Failed to store metadata: {}", e)),
-            }));
-        }
-
-        // Optionally set as project audio file if project_id was provided
-        if let Err(e) = db.update_project_audio_file(&project_id, Some(&media_file.id)) {
-            warn!("Failed to set project audio file: {:?}", e);
+        // Add to the project's audio files, the first one becomes primary
+        if let Err(e) = db.attach_project_audio_file(
+            &project_id,
+            &media_file.id,
+            label.as_deref(),
+            version,
+            make_primary,
+        ) {
+            warn!("Failed to attach project audio file: {:?}", e);
             // Don't fail the upload, just log the warning
         }
 
+        self.prepare_audio_file(media_file.clone());
+
         info!(
             "Successfully uploaded audio file: {} bytes for project {}",
-            data_chunks.len(),
+            upload_size,
             project_id
         );
 
@@ -217,6 +338,8 @@ impl MediaHandler {
             media_file_id: media_file.id,
             success: true,
             error_message: None,
+            format_info: Some(media_info_to_proto(&media_info)),
+            bytes_received,
         };
 
         Ok(Response::new(response))
@@ -229,7 +352,28 @@ impl MediaHandler {
         debug!("DownloadMedia request: {:?}", request);
 
         let req = request.into_inner();
-        let db = self.db.lock().await;
+        let size = match req.size.as_deref() {
+            None | Some("") | Some("original") => None,
+            Some(size) => Some(
+                size.parse::<ThumbnailSize>()
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?,
+            ),
+        };
+        let chunk_size = match req.chunk_size {
+            None | Some(0) => DEFAULT_DOWNLOAD_CHUNK_SIZE,
+            Some(chunk_size) if DOWNLOAD_CHUNK_SIZES.contains(&(chunk_size as usize)) => {
+                chunk_size as usize
+            }
+            Some(chunk_size) => {
+                return Err(Status::invalid_argument(format!(
+                    "Chunk size {} must be between {} and {} bytes",
+                    chunk_size,
+                    DOWNLOAD_CHUNK_SIZES.start(),
+                    DOWNLOAD_CHUNK_SIZES.end()
+                )));
+            }
+        };
+        let mut db = self.db.lock().await;
 
         // Get media file metadata
         let media_file = match db.get_media_file(&req.media_file_id) {
@@ -242,72 +386,219 @@ impl MediaHandler {
                 return Err(Status::internal(format!("Database error: {}", e)));
             }
         };
+        if size.is_some() && media_file.media_type != MediaType::CoverArt {
+            return Err(Status::invalid_argument("Only cover art has sizes"));
+        }
 
-        // Clone values needed for later use
-        let file_id = media_file.id.clone();
-        let file_extension = media_file.file_extension.clone();
-        let media_type = media_file.media_type.clone();
+        // Files uploaded before thumbnails existed get them on first request. Images
+        // that can't be decoded have none, so the original is sent instead.
+        let variant = match size {
+            Some(size) => match db.get_media_variant(&media_file.id, size) {
+                Ok(Some(variant)) => Some(variant),
+                Ok(None) => {
+                    // Rendering can take a while, so other requests get the database meanwhile
+                    drop(db);
+                    let thumbnails = self.render_thumbnails(&media_file, None).await;
+                    db = self.db.lock().await;
+                    self.record_thumbnails(&mut db, &media_file, &thumbnails);
+                    thumbnails.into_iter().find(|variant| variant.size == size)
+                }
+                Err(e) => {
+                    error!("Failed to get media variant: {:?}", e);
+                    return Err(Status::internal(format!("Database error: {}", e)));
+                }
+            },
+            None => None,
+        };
+        drop(db);
 
-        let (tx, rx) = mpsc::channel(100);
+        // Get the file path of the original or the variant
+        let file_path = match &variant {
+            Some(variant) => self.media_storage.get_variant_path(
+                &media_file.id,
+                variant.size,
+                &variant.file_extension,
+            ),
+            None => self.media_storage.get_file_path(&media_file),
+        };
+        let mut file = match tokio::fs::File::open(&file_path).await {
+            Ok(file) => file,
+            Err(e) => {
+                error!("Failed to read file: {:?}", e);
+                return Err(Status::internal(format!("Failed to read file: {}", e)));
+            }
+        };
+        let file_size = file
+            .metadata()
+            .await
+            .map_err(|e| Status::internal(format!("Failed to read file: {}", e)))?
+            .len();
+
+        // Clamp the requested range to the file
+        let offset = req.offset.unwrap_or(0);
+        if offset > file_size {
+            return Err(Status::out_of_range(format!(
+                "Offset {} is past the end of the {} byte file",
+                offset, file_size
+            )));
+        }
+        let end = match req.length {
+            Some(length) => offset.saturating_add(length).min(file_size),
+            None => file_size,
+        };
 
-        // Convert our MediaFile to protobuf MediaFile
-        let proto_media_file = MediaFile {
-            id: media_file.id,
-            original_filename: media_file.original_filename,
-            file_extension: media_file.file_extension,
-            media_type: media_file.media_type.as_str().to_string(),
-            file_size_bytes: media_file.file_size_bytes as i64,
-            mime_type: media_file.mime_type,
-            uploaded_at: media_file.uploaded_at.timestamp(),
-            checksum: media_file.checksum,
+        // Convert our MediaFile to protobuf MediaFile, describing the variant if one
+        // was asked for
+        let proto_media_file = match &variant {
+            Some(variant) => MediaFile {
+                id: media_file.id,
+                original_filename: media_file.original_filename,
+                file_extension: variant.file_extension.clone(),
+                media_type: media_file.media_type.as_str().to_string(),
+                file_size_bytes: variant.file_size_bytes as i64,
+                mime_type: variant.mime_type.clone(),
+                uploaded_at: media_file.uploaded_at.timestamp(),
+                checksum: variant.checksum.clone(),
+            },
+            None => MediaFile {
+                id: media_file.id,
+                original_filename: media_file.original_filename,
+                file_extension: media_file.file_extension,
+                media_type: media_file.media_type.as_str().to_string(),
+                file_size_bytes: media_file.file_size_bytes as i64,
+                mime_type: media_file.mime_type,
+                uploaded_at: media_file.uploaded_at.timestamp(),
+                checksum: media_file.checksum,
+            },
         };
 
+        let (tx, rx) = mpsc::channel(16);
+
         // Send metadata first
         let metadata_response = DownloadMediaResponse {
             data: Some(download_media_response::Data::Metadata(proto_media_file)),
+            offset,
         };
 
         if tx.send(Ok(metadata_response)).await.is_err() {
             return Err(Status::internal("Failed to send metadata"));
         }
 
-        // Get the file path and stream the actual file data
-        let file_path =
-            match self
-                .media_storage
-                .get_file_path(&file_id, &file_extension, &media_type)
-            {
-                Ok(path) => path,
-                Err(e) => {
-                    error!("Failed to get file path: {:?}", e);
-                    return Err(Status::internal(format!("Failed to get file path: {}", e)));
+        // Stream the range from disk as the client reads it, so large files are
+        // never held in memory
+        tokio::spawn(async move {
+            if let Err(e) = file.seek(std::io::SeekFrom::Start(offset)).await {
+                let _ = tx
+                    .send(Err(Status::internal(format!("Failed to read file: {}", e))))
+                    .await;
+                return;
+            }
+            let mut position = offset;
+            while position < end {
+                let mut chunk = vec![0u8; chunk_size.min((end - position) as usize)];
+                if let Err(e) = file.read_exact(&mut chunk).await {
+                    error!("Failed to read file: {:?}", e);
+                    let _ = tx
+                        .send(Err(Status::internal(format!("Failed to read file: {}", e))))
+                        .await;
+                    return;
                 }
-            };
-
-        // Read and stream the file in chunks
-        match tokio::fs::read(&file_path).await {
-            Ok(file_data) => {
-                // Stream the file in chunks (e.g., 64KB chunks)
-                const CHUNK_SIZE: usize = 64 * 1024;
-                for chunk in file_data.chunks(CHUNK_SIZE) {
-                    let chunk_response = DownloadMediaResponse {
-                        data: Some(download_media_response::Data::Chunk(chunk.to_vec())),
-                    };
-
-                    if tx.send(Ok(chunk_response)).await.is_err() {
-                        return Err(Status::internal("Failed to send file chunk"));
-                    }
+                let chunk_len = chunk.len() as u64;
+                let chunk_response = DownloadMediaResponse {
+                    data: Some(download_media_response::Data::Chunk(chunk)),
+                    offset: position,
+                };
+                if tx.send(Ok(chunk_response)).await.is_err() {
+                    debug!("Download of {} cancelled by the client", file_path.display());
+                    return;
                 }
+                position += chunk_len;
             }
-            Err(e) => {
-                error!("Failed to read file: {:?}", e);
-                return Err(Status::internal(format!("Failed to read file: {}", e)));
-            }
-        }
+        });
 
         Ok(Response::new(ReceiverStream::new(rx)))
     }
 
+    /// Start a resumable upload. Chunks are then sent with the upload ID through
+    /// UploadCoverArt or UploadAudioFile, over as many streams as it takes.
+    pub async fn start_upload(
+        &self,
+        request: Request<StartUploadRequest>,
+    ) -> Result<Response<StartUploadResponse>, Status> {
+        debug!("StartUpload request: {:?}", request);
+
+        let req = request.into_inner();
+        let media_type = MediaType::from_str(&req.media_type)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        self.media_storage
+            .check_upload(&req.filename, req.total_size_bytes, &media_type)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let mut db = self.db.lock().await;
+        self.expire_uploads(&mut db);
+        let session = db
+            .create_upload_session(media_type, &req.filename, req.total_size_bytes)
+            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+
+        Ok(Response::new(StartUploadResponse {
+            expires_at: upload_expires_at(&session),
+            upload_id: session.id,
+        }))
+    }
+
+    /// How much of a resumable upload arrived, to continue from there
+    pub async fn get_upload_status(
+        &self,
+        request: Request<GetUploadStatusRequest>,
+    ) -> Result<Response<GetUploadStatusResponse>, Status> {
+        debug!("GetUploadStatus request: {:?}", request);
+
+        let req = request.into_inner();
+        let session = self
+            .db
+            .lock()
+            .await
+            .get_upload_session(&req.upload_id)
+            .map_err(|e| Status::internal(format!("Database error: {}", e)))?
+            .ok_or_else(|| Status::not_found(format!("Upload {} not found", req.upload_id)))?;
+        let bytes_received = self
+            .media_storage
+            .upload_progress(&session.id)
+            .map_err(upload_error_to_status)?;
+
+        Ok(Response::new(GetUploadStatusResponse {
+            expires_at: upload_expires_at(&session),
+            upload_id: session.id,
+            media_type: session.media_type.as_str().to_string(),
+            filename: session.filename,
+            bytes_received,
+            total_size_bytes: session.total_size_bytes,
+        }))
+    }
+
+    pub async fn cancel_upload(
+        &self,
+        request: Request<CancelUploadRequest>,
+    ) -> Result<Response<CancelUploadResponse>, Status> {
+        debug!("CancelUpload request: {:?}", request);
+
+        let req = request.into_inner();
+        let removed = self
+            .db
+            .lock()
+            .await
+            .delete_upload_session(&req.upload_id)
+            .map_err(|e| Status::internal(format!("Database error: {}", e)))?;
+        if !removed {
+            return Err(Status::not_found(format!("Upload {} not found", req.upload_id)));
+        }
+        if let Err(e) = self.media_storage.delete_upload(&req.upload_id) {
+            warn!("Failed to delete partial upload {}: {:?}", req.upload_id, e);
+        }
+
+        Ok(Response::new(CancelUploadResponse { success: true }))
+    }
+
     pub async fn delete_media(
         &self,
         request: Request<DeleteMediaRequest>,
@@ -320,22 +611,21 @@ impl MediaHandler {
         // First check if the media file exists and get its info
         match db.get_media_file(&req.media_file_id) {
             Ok(Some(media_file)) => {
-                // Clone the values we need for later use
-                let file_id = media_file.id.clone();
-                let file_extension = media_file.file_extension.clone();
-                let media_type = media_file.media_type.clone();
-
                 // Delete from database first
                 match db.delete_media_file(&req.media_file_id) {
-                    Ok(()) => {
-                        // Also delete physical file from storage
-                        if let Err(e) =
-                            self.media_storage
-                                .delete_file(&file_id, &file_extension, &media_type)
-                        {
+                    Ok(content_unused) => {
+                        // Also delete physical files from storage. Content other media
+                        // files share stays.
+                        if let Err(e) = self.media_storage.delete_file(&media_file) {
                             warn!("Failed to delete physical file from storage: {:?}", e);
                             // Don't fail the operation if physical file deletion fails
                         }
+                        if content_unused {
+                            if let Err(e) = self.media_storage.delete_content(&media_file.checksum)
+                            {
+                                warn!("Failed to delete stored content: {:?}", e);
+                            }
+                        }
 
                         info!("Successfully deleted media file: {}", req.media_file_id);
                         let response = DeleteMediaResponse {
@@ -435,10 +725,22 @@ impl MediaHandler {
         debug!("SetProjectAudioFile request: {:?}", request);
 
         let req = request.into_inner();
+        let label = parse_audio_label(req.label);
         let mut db = self.db.lock().await;
 
-        match db.update_project_audio_file(&req.project_id, Some(&req.media_file_id)) {
-            Ok(()) => {
+        match db.attach_project_audio_file(
+            &req.project_id,
+            &req.media_file_id,
+            label.as_deref(),
+            req.version,
+            true,
+        ) {
+            Ok(_) => {
+                match db.get_media_file(&req.media_file_id) {
+                    Ok(Some(media_file)) => self.prepare_audio_file(media_file),
+                    Ok(None) => {}
+                    Err(e) => warn!("Failed to load audio file for analysis: {:?}", e),
+                }
                 let response = SetProjectAudioFileResponse {
                     success: true,
                     error_message: None,
@@ -465,7 +767,11 @@ impl MediaHandler {
         let req = request.into_inner();
         let mut db = self.db.lock().await;
 
-        match db.update_project_audio_file(&req.project_id, None) {
+        let removed = match &req.media_file_id {
+            Some(media_file_id) => db.detach_project_audio_file(&req.project_id, media_file_id),
+            None => db.update_project_audio_file(&req.project_id, None),
+        };
+        match removed {
             Ok(()) => {
                 let response = RemoveProjectAudioFileResponse {
                     success: true,
@@ -484,6 +790,131 @@ impl MediaHandler {
         }
     }
 
+    /// Every audio file of a project, the primary one first
+    pub async fn get_project_audio_files(
+        &self,
+        request: Request<GetProjectAudioFilesRequest>,
+    ) -> Result<Response<GetProjectAudioFilesResponse>, Status> {
+        debug!("GetProjectAudioFiles request: {:?}", request);
+
+        let req = request.into_inner();
+        let db = self.db.lock().await;
+        db.ensure_project_exists(&req.project_id)
+            .map_err(audio_error_to_status)?;
+        let audio_files = db
+            .get_project_audio_files(&req.project_id)
+            .map_err(audio_error_to_status)?;
+
+        Ok(Response::new(GetProjectAudioFilesResponse {
+            audio_files: audio_files
+                .into_iter()
+                .map(convert_project_audio_file_to_proto)
+                .collect(),
+        }))
+    }
+
+    /// Relabel an audio file of a project or make it the primary one
+    pub async fn update_project_audio_file(
+        &self,
+        request: Request<UpdateProjectAudioFileRequest>,
+    ) -> Result<Response<UpdateProjectAudioFileResponse>, Status> {
+        debug!("UpdateProjectAudioFile request: {:?}", request);
+
+        let req = request.into_inner();
+        if req.is_primary == Some(false) {
+            return Err(Status::invalid_argument(
+                "A project always has a primary audio file, make another one primary instead",
+            ));
+        }
+        let label = parse_audio_label(req.label);
+
+        let mut db = self.db.lock().await;
+        let mut audio_file = db
+            .update_project_audio_file_details(
+                &req.project_id,
+                &req.media_file_id,
+                label.as_deref(),
+                req.version,
+            )
+            .map_err(audio_error_to_status)?;
+        if req.is_primary == Some(true) && !audio_file.is_primary {
+            db.set_primary_project_audio_file(&req.project_id, &req.media_file_id)
+                .map_err(audio_error_to_status)?;
+            audio_file.is_primary = true;
+            self.prepare_audio_file(audio_file.media_file.clone());
+        }
+
+        Ok(Response::new(UpdateProjectAudioFileResponse {
+            audio_file: Some(convert_project_audio_file_to_proto(audio_file)),
+        }))
+    }
+
+    /// Two audio files of a project with their format details, for A/B listening
+    pub async fn compare_project_audio_files(
+        &self,
+        request: Request<CompareProjectAudioFilesRequest>,
+    ) -> Result<Response<CompareProjectAudioFilesResponse>, Status> {
+        debug!("CompareProjectAudioFiles request: {:?}", request);
+
+        let req = request.into_inner();
+        if req.a_media_file_id == req.b_media_file_id {
+            return Err(Status::invalid_argument(
+                "Pick two different audio files to compare",
+            ));
+        }
+
+        let (a, b) = {
+            let db = self.db.lock().await;
+            let a = db
+                .get_project_audio_file_entry(&req.project_id, &req.a_media_file_id)
+                .map_err(audio_error_to_status)?;
+            let b = db
+                .get_project_audio_file_entry(&req.project_id, &req.b_media_file_id)
+                .map_err(audio_error_to_status)?;
+            match (a, b) {
+                (Some(a), Some(b)) => (a, b),
+                (a, _) => {
+                    let missing = if a.is_none() {
+                        &req.a_media_file_id
+                    } else {
+                        &req.b_media_file_id
+                    };
+                    return Err(Status::not_found(format!(
+                        "Audio file {} is not attached to project {}",
+                        missing, req.project_id
+                    )));
+                }
+            }
+        };
+
+        let media_storage = Arc::clone(&self.media_storage);
+        let (a_media, b_media) = (a.media_file.clone(), b.media_file.clone());
+        let (a_info, b_info) = tokio::task::spawn_blocking(move || {
+            let inspect = |media_file: &crate::media::MediaFile| {
+                media_storage
+                    .inspect_file(media_file)
+                    .map_err(|e| warn!("Failed to inspect audio file {}: {}", media_file.id, e))
+                    .ok()
+            };
+            (inspect(&a_media), inspect(&b_media))
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Inspect task failed: {}", e)))?;
+
+        let duration = |info: &Option<MediaInfo>| info.as_ref().and_then(|info| info.duration_seconds);
+        let duration_difference_seconds = duration(&a_info)
+            .zip(duration(&b_info))
+            .map(|(a_duration, b_duration)| b_duration - a_duration);
+
+        Ok(Response::new(CompareProjectAudioFilesResponse {
+            a: Some(convert_project_audio_file_to_proto(a)),
+            b: Some(convert_project_audio_file_to_proto(b)),
+            a_format_info: a_info.as_ref().map(media_info_to_proto),
+            b_format_info: b_info.as_ref().map(media_info_to_proto),
+            duration_difference_seconds,
+        }))
+    }
+
     /// List all media files with optional pagination
     pub async fn list_media_files(
         &self,
@@ -647,6 +1078,22 @@ impl MediaHandler {
             }
         };
 
+        let (derived_files_count, derived_files_size_bytes) = match db.get_media_variant_statistics() {
+            Ok(stats) => stats,
+            Err(e) => {
+                error!("Failed to get media variant statistics: {:?}", e);
+                return Err(Status::internal(format!("Database error: {}", e)));
+            }
+        };
+
+        let (stored_files_count, physical_size_bytes) = match db.get_media_blob_statistics() {
+            Ok(stats) => stats,
+            Err(e) => {
+                error!("Failed to get stored media statistics: {:?}", e);
+                return Err(Status::internal(format!("Database error: {}", e)));
+            }
+        };
+
         // Create a map of files by type
         let mut files_by_type = std::collections::HashMap::new();
         files_by_type.insert("cover_art".to_string(), cover_art_count);
@@ -660,6 +1107,11 @@ impl MediaHandler {
             orphaned_files_count: orphaned_count,
             orphaned_files_size_bytes: orphaned_size,
             files_by_type,
+            derived_files_count,
+            derived_files_size_bytes,
+            logical_size_bytes: total_size - derived_files_size_bytes,
+            physical_size_bytes,
+            stored_files_count,
         }))
     }
 
@@ -683,32 +1135,101 @@ impl MediaHandler {
         let mut deleted_file_ids = Vec::new();
         let mut bytes_freed = 0i64;
 
+        // Thumbnails go with the file they were made from
+        let mut variant_bytes = std::collections::HashMap::new();
+        for file in &orphaned_files {
+            match db.get_media_variants(&file.id) {
+                Ok(variants) => {
+                    let size: u64 = variants.iter().map(|variant| variant.file_size_bytes).sum();
+                    variant_bytes.insert(file.id.clone(), size as i64);
+                }
+                Err(e) => {
+                    error!("Failed to get media variants: {:?}", e);
+                    return Err(Status::internal(format!("Database error: {}", e)));
+                }
+            }
+        }
+
         if !req.dry_run {
             // Actually delete the files
             for file in &orphaned_files {
-                // Delete from storage
-                if let Err(e) =
-                    self.media_storage
-                        .delete_file(&file.id, &file.file_extension, &file.media_type)
-                {
+                // Delete from database
+                let content_unused = match db.delete_media_file(&file.id) {
+                    Ok(content_unused) => content_unused,
+                    Err(e) => {
+                        error!("Failed to delete media file from database: {:?}", e);
+                        continue;
+                    }
+                };
+
+                // Delete from storage, the content only once nothing shares it
+                if let Err(e) = self.media_storage.delete_file(file) {
                     warn!("Failed to delete physical file from storage: {:?}", e);
-                    // Continue with database deletion even if physical file deletion fails
                 }
-
-                // Delete from database
-                if let Err(e) = db.delete_media_file(&file.id) {
-                    error!("Failed to delete media file from database: {:?}", e);
-                    continue;
+                if content_unused {
+                    if let Err(e) = self.media_storage.delete_content(&file.checksum) {
+                        warn!("Failed to delete stored content: {:?}", e);
+                    }
+                    bytes_freed += file.file_size_bytes as i64;
                 }
 
                 deleted_file_ids.push(file.id.clone());
-                bytes_freed += file.file_size_bytes as i64;
+                bytes_freed += variant_bytes[&file.id];
             }
         } else {
-            // Dry run - just calculate what would be deleted
+            // Dry run - just calculate what would be deleted. Content is freed when
+            // every media file sharing it is orphaned.
+            let mut orphaned_references = std::collections::HashMap::new();
             for file in &orphaned_files {
                 deleted_file_ids.push(file.id.clone());
-                bytes_freed += file.file_size_bytes as i64;
+                bytes_freed += variant_bytes[&file.id];
+                *orphaned_references.entry(&file.checksum).or_insert(0) += 1;
+            }
+            for file in &orphaned_files {
+                let references = db.get_media_blob_references(&file.checksum).unwrap_or(0);
+                if orphaned_references.remove(&file.checksum) == Some(references) {
+                    bytes_freed += file.file_size_bytes as i64;
+                }
+            }
+        }
+
+        // Peaks left behind by audio files that are gone
+        let mut waveforms_cleaned = 0;
+        if !req.dry_run {
+            let audio_file_ids = match db.get_media_files_by_type(MediaType::AudioFile.as_str(), None, None) {
+                Ok(files) => files.into_iter().map(|file| file.id).collect::<HashSet<_>>(),
+                Err(e) => {
+                    error!("Failed to get audio files: {:?}", e);
+                    return Err(Status::internal(format!("Database error: {}", e)));
+                }
+            };
+            match self.media_storage.cleanup_waveforms(&audio_file_ids) {
+                Ok(stats) => {
+                    waveforms_cleaned = stats.files_deleted as i32;
+                    bytes_freed += stats.bytes_freed as i64;
+                }
+                Err(e) => warn!("Failed to clean up waveforms: {:?}", e),
+            }
+        }
+
+        // Resumable uploads nobody came back to, and partial files without a session
+        let mut uploads_cleaned = 0;
+        if !req.dry_run {
+            let (expired, expired_bytes) = self.expire_uploads(&mut db);
+            uploads_cleaned += expired as i32;
+            bytes_freed += expired_bytes as i64;
+            match db.get_upload_session_ids() {
+                Ok(upload_ids) => {
+                    let upload_ids = upload_ids.into_iter().collect::<HashSet<_>>();
+                    match self.media_storage.cleanup_uploads(&upload_ids) {
+                        Ok(stats) => {
+                            uploads_cleaned += stats.files_deleted as i32;
+                            bytes_freed += stats.bytes_freed as i64;
+                        }
+                        Err(e) => warn!("Failed to clean up uploads: {:?}", e),
+                    }
+                }
+                Err(e) => error!("Failed to get upload sessions: {:?}", e),
             }
         }
 
@@ -718,6 +1239,468 @@ impl MediaHandler {
             deleted_file_ids,
             success: true,
             error_message: None,
+            waveforms_cleaned,
+            uploads_cleaned,
+        }))
+    }
+
+    /// Peaks of an audio file at one zoom level, decoded on first request
+    pub async fn get_waveform(
+        &self,
+        request: Request<GetWaveformRequest>,
+    ) -> Result<Response<GetWaveformResponse>, Status> {
+        debug!("GetWaveform request: {:?}", request);
+
+        let req = request.into_inner();
+        let zoom_level = req.zoom_level.unwrap_or(0);
+        if zoom_level < 0 {
+            return Err(Status::invalid_argument("Zoom level cannot be negative"));
+        }
+
+        let media_file = {
+            let db = self.db.lock().await;
+            let found = match (req.media_file_id, req.project_id) {
+                (Some(media_file_id), _) => db.get_media_file(&media_file_id),
+                (None, Some(project_id)) => db.get_project_audio_file(&project_id),
+                (None, None) => {
+                    return Err(Status::invalid_argument(
+                        "Media file ID or project ID is required",
+                    ));
+                }
+            };
+            match found {
+                Ok(Some(file)) => file,
+                Ok(None) => return Err(Status::not_found("Audio file not found")),
+                Err(e) => {
+                    error!("Failed to get media file: {:?}", e);
+                    return Err(Status::internal(format!("Database error: {}", e)));
+                }
+            }
+        };
+        if media_file.media_type != MediaType::AudioFile {
+            return Err(Status::invalid_argument("Media file is not an audio file"));
+        }
+
+        let media_file_id = media_file.id.clone();
+        let media_storage = Arc::clone(&self.media_storage);
+        let waveform = tokio::task::spawn_blocking(move || media_storage.get_waveform(&media_file))
+            .await
+            .map_err(|e| Status::internal(format!("Waveform task failed: {}", e)))?
+            .map_err(|e| {
+                error!("Failed to get waveform for {}: {:?}", media_file_id, e);
+                decode_error_to_status(e)
+            })?;
+
+        let level = waveform.level(zoom_level as usize).ok_or_else(|| {
+            Status::invalid_argument(format!(
+                "Zoom level {} out of range, the waveform has {} levels",
+                zoom_level,
+                waveform.levels.len()
+            ))
+        })?;
+
+        Ok(Response::new(GetWaveformResponse {
+            media_file_id,
+            zoom_level,
+            zoom_levels: waveform.levels.len() as i32,
+            sample_rate: waveform.sample_rate as i32,
+            channels: waveform.channels as i32,
+            duration_seconds: waveform.duration_seconds(),
+            frames_per_peak: level.frames_per_peak as i32,
+            min_peaks: level.min.clone(),
+            max_peaks: level.max.clone(),
+        }))
+    }
+
+    /// Loudness, tempo and key of an audio file, measured now if it wasn't yet
+    pub async fn analyze_audio_file(
+        &self,
+        request: Request<AnalyzeAudioFileRequest>,
+    ) -> Result<Response<AnalyzeAudioFileResponse>, Status> {
+        debug!("AnalyzeAudioFile request: {:?}", request);
+
+        let req = request.into_inner();
+        let (media_file, stored) = {
+            let db = self.db.lock().await;
+            let found = match (req.media_file_id, req.project_id) {
+                (Some(media_file_id), _) => db.get_media_file(&media_file_id),
+                (None, Some(project_id)) => db.get_project_audio_file(&project_id),
+                (None, None) => {
+                    return Err(Status::invalid_argument(
+                        "Media file ID or project ID is required",
+                    ));
+                }
+            };
+            let media_file = match found {
+                Ok(Some(file)) => file,
+                Ok(None) => return Err(Status::not_found("Audio file not found")),
+                Err(e) => {
+                    error!("Failed to get media file: {:?}", e);
+                    return Err(Status::internal(format!("Database error: {}", e)));
+                }
+            };
+            let stored = db.get_audio_analysis(&media_file.id).map_err(|e| {
+                error!("Failed to get audio analysis: {:?}", e);
+                Status::internal(format!("Database error: {}", e))
+            })?;
+            (media_file, stored)
+        };
+        if media_file.media_type != MediaType::AudioFile {
+            return Err(Status::invalid_argument("Media file is not an audio file"));
+        }
+
+        let media_file_id = media_file.id.clone();
+        let analysis = match stored {
+            Some(analysis) if !req.reanalyze => analysis,
+            _ => {
+                let media_storage = Arc::clone(&self.media_storage);
+                let analysis =
+                    tokio::task::spawn_blocking(move || media_storage.analyze_audio(&media_file))
+                        .await
+                        .map_err(|e| Status::internal(format!("Analysis task failed: {}", e)))?
+                        .map_err(|e| {
+                            error!("Failed to analyze {}: {:?}", media_file_id, e);
+                            decode_error_to_status(e)
+                        })?;
+                let mut db = self.db.lock().await;
+                db.insert_audio_analysis(&media_file_id, &analysis)
+                    .map_err(|e| {
+                        error!("Failed to store audio analysis: {:?}", e);
+                        Status::internal(format!("Database error: {}", e))
+                    })?;
+                analysis
+            }
+        };
+
+        Ok(Response::new(AnalyzeAudioFileResponse {
+            media_file_id,
+            analysis: Some(convert_audio_analysis_to_proto(&analysis)),
         }))
     }
+
+    /// Continue a resumable upload in this stream
+    async fn resume_upload(
+        &self,
+        upload: &mut ReceivedUpload,
+        upload_id: &str,
+        media_type: MediaType,
+    ) -> Result<(), Status> {
+        if upload.session.is_some() || !upload.data.is_empty() {
+            return Err(Status::invalid_argument(
+                "Send the upload ID once, before any chunk",
+            ));
+        }
+        let mut db = self.db.lock().await;
+        let session = db
+            .get_upload_session(upload_id)
+            .map_err(|e| Status::internal(format!("Database error: {}", e)))?
+            .ok_or_else(|| Status::not_found(format!("Upload {} not found", upload_id)))?;
+        if session.media_type != media_type {
+            return Err(Status::invalid_argument(format!(
+                "Upload {} is for {}",
+                upload_id,
+                session.media_type.as_str()
+            )));
+        }
+        // Keep the session alive while it's being uploaded to
+        if let Err(e) = db.touch_upload_session(upload_id) {
+            warn!("Failed to update upload {}: {:?}", upload_id, e);
+        }
+        upload.session = Some(session);
+        upload.touched_at = Some(Instant::now());
+        Ok(())
+    }
+
+    async fn receive_chunk(&self, upload: &mut ReceivedUpload, chunk: Vec<u8>) -> Result<(), Status> {
+        let Some(session) = &upload.session else {
+            if upload.offset.is_some() {
+                return Err(Status::invalid_argument(
+                    "Offsets are only for resumable uploads",
+                ));
+            }
+            upload.data.extend(chunk);
+            return Ok(());
+        };
+        let upload_id = session.id.clone();
+        let total_size_bytes = session.total_size_bytes;
+        let offset = upload.offset;
+
+        let media_storage = Arc::clone(&self.media_storage);
+        let id = upload_id.clone();
+        let received = tokio::task::spawn_blocking(move || {
+            let offset = match offset {
+                Some(offset) => offset,
+                None => media_storage.upload_progress(&id)?,
+            };
+            media_storage.write_upload_chunk(&id, offset, &chunk, total_size_bytes)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Upload task failed: {}", e)))?
+        .map_err(upload_error_to_status)?;
+        upload.offset = Some(received);
+
+        // Keep the session alive during a long upload, without a write for every chunk
+        if upload
+            .touched_at
+            .is_none_or(|touched_at| touched_at.elapsed() >= UPLOAD_TOUCH_INTERVAL)
+        {
+            if let Err(e) = self.db.lock().await.touch_upload_session(&upload_id) {
+                warn!("Failed to update upload {}: {:?}", upload_id, e);
+            }
+            upload.touched_at = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// The uploaded file, once a resumable upload received all of its bytes
+    async fn collect_upload(&self, upload: ReceivedUpload) -> Result<UploadData, MediaError> {
+        let Some(session) = upload.session else {
+            return Ok(UploadData::Complete(upload.data));
+        };
+        let media_storage = Arc::clone(&self.media_storage);
+        tokio::task::spawn_blocking(move || {
+            let bytes_received = media_storage.upload_progress(&session.id)?;
+            if bytes_received < session.total_size_bytes {
+                return Ok(UploadData::Incomplete {
+                    bytes_received,
+                    total_size_bytes: session.total_size_bytes,
+                });
+            }
+            Ok(UploadData::Complete(media_storage.read_upload(&session.id)?))
+        })
+        .await
+        .map_err(|e| MediaError::IoError(format!("Upload task failed: {}", e)))?
+    }
+    /// Forget a resumable upload once its file was stored or rejected
+    async fn end_upload(&self, upload_id: &str) {
+        if let Err(e) = self.db.lock().await.delete_upload_session(upload_id) {
+            error!("Failed to delete upload session {}: {:?}", upload_id, e);
+        }
+        if let Err(e) = self.media_storage.delete_upload(upload_id) {
+            warn!("Failed to delete partial upload {}: {:?}", upload_id, e);
+        }
+    }
+
+    /// Drop resumable uploads nothing arrived for in a day. Returns the bytes freed.
+    fn expire_uploads(&self, db: &mut LiveSetDatabase) -> (usize, u64) {
+        let before = chrono::Utc::now() - chrono::Duration::hours(UPLOAD_SESSION_TTL_HOURS);
+        let expired = match db.expire_upload_sessions(before) {
+            Ok(expired) => expired,
+            Err(e) => {
+                error!("Failed to expire upload sessions: {:?}", e);
+                return (0, 0);
+            }
+        };
+        let mut bytes_freed = 0;
+        for upload_id in &expired {
+            bytes_freed += self.media_storage.upload_progress(upload_id).unwrap_or(0);
+            if let Err(e) = self.media_storage.delete_upload(upload_id) {
+                warn!("Failed to delete partial upload {}: {:?}", upload_id, e);
+            }
+        }
+        (expired.len(), bytes_freed)
+    }
+
+    /// Validate an upload and work out what to store off the async runtime, as
+    /// decoding a large file takes a while
+    async fn prepare_upload(
+        &self,
+        data: Vec<u8>,
+        filename: &str,
+        media_type: MediaType,
+    ) -> Result<PreparedFile<'static>, String> {
+        let media_storage = Arc::clone(&self.media_storage);
+        let filename = filename.to_string();
+        let prepared = tokio::task::spawn_blocking(move || {
+            media_storage.prepare_file(data, &filename, media_type)
+        })
+        .await;
+        match prepared {
+            Ok(Ok(prepared)) => Ok(prepared),
+            Ok(Err(e)) => Err(format!("Failed to store file: {}", e)),
+            Err(e) => Err(format!("Failed to store file: {:?}", e)),
+        }
+    }
+
+    /// Render every thumbnail size of cover art off the async runtime, from the image
+    /// when it was already decoded. Images that can't be decoded have none.
+    async fn render_thumbnails(
+        &self,
+        media_file: &crate::media::MediaFile,
+        image: Option<image::DynamicImage>,
+    ) -> Vec<MediaVariant> {
+        let media_storage = Arc::clone(&self.media_storage);
+        let cover_art = media_file.clone();
+        let rendered = tokio::task::spawn_blocking(move || match image {
+            Some(image) => media_storage.generate_thumbnails_from_image(&cover_art, &image),
+            None => media_storage.generate_thumbnails(&cover_art),
+        })
+        .await;
+        match rendered {
+            Ok(Ok(variants)) => variants,
+            Ok(Err(e)) => {
+                warn!("Failed to generate thumbnails for {}: {}", media_file.id, e);
+                Vec::new()
+            }
+            Err(e) => {
+                error!("Thumbnail task for {} failed: {:?}", media_file.id, e);
+                Vec::new()
+            }
+        }
+    }
+
+    fn record_thumbnails(
+        &self,
+        db: &mut LiveSetDatabase,
+        media_file: &crate::media::MediaFile,
+        variants: &[MediaVariant],
+    ) {
+        if variants.is_empty() {
+            return;
+        }
+        if let Err(e) = db.insert_media_variants(variants) {
+            error!("Failed to record thumbnails for {}: {:?}", media_file.id, e);
+            self.delete_unrecorded_thumbnails(&media_file.id);
+        }
+    }
+
+    fn delete_unrecorded_thumbnails(&self, media_file_id: &str) {
+        if let Err(e) = self.media_storage.delete_thumbnails(media_file_id) {
+            warn!("Failed to delete unrecorded thumbnails: {:?}", e);
+        }
+    }
+
+    /// Write the content of an upload and record its media file. Content is written
+    /// off the async runtime before taking the database lock, and written again under
+    /// it if deleting another file with the same content removed it in between. The
+    /// lock is handed back for the rest of the upload.
+    async fn store_prepared(
+        &self,
+        prepared: PreparedFile<'static>,
+    ) -> Result<(PreparedFile<'static>, MutexGuard<'_, LiveSetDatabase>), String> {
+        let media_storage = Arc::clone(&self.media_storage);
+        let (prepared, written) = tokio::task::spawn_blocking(move || {
+            let written = media_storage.write_prepared(&prepared);
+            (prepared, written)
+        })
+        .await
+        .map_err(|e| format!("Failed to store file: {:?}", e))?;
+        if let Err(e) = written {
+            return Err(format!("Failed to store file: {}", e));
+        }
+
+        let mut db = self.db.lock().await;
+        if let Err(e) = db.insert_media_file(&prepared.media_file) {
+            // Clean up the stored file, unless other media files share its content
+            self.delete_unused_content(&db, &prepared.media_file.checksum);
+            return Err(format!("Failed to store metadata: {}", e));
+        }
+        // Content that's already stored isn't written again
+        if let Err(e) = self.media_storage.write_prepared(&prepared) {
+            if let Err(e) = db.delete_media_file(&prepared.media_file.id) {
+                error!("Failed to remove media file without content: {:?}", e);
+            }
+            return Err(format!("Failed to store file: {}", e));
+        }
+        Ok((prepared, db))
+    }
+
+    /// Delete stored content if no media file uses it, such as after an upload
+    /// couldn't be recorded
+    fn delete_unused_content(&self, db: &LiveSetDatabase, checksum: &str) {
+        match db.get_media_blob_references(checksum) {
+            Ok(0) => {
+                if let Err(e) = self.media_storage.delete_content(checksum) {
+                    error!("Failed to cleanup stored file after database error: {:?}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => error!("Failed to count references to stored file: {:?}", e),
+        }
+    }
+
+    /// Decode a new project audio file in the background, caching its peaks and
+    /// storing its analysis, so the first request for either doesn't have to wait
+    fn prepare_audio_file(&self, media_file: crate::media::MediaFile) {
+        if media_file.media_type != MediaType::AudioFile {
+            return;
+        }
+        let db = Arc::clone(&self.db);
+        let media_storage = Arc::clone(&self.media_storage);
+        tokio::spawn(async move {
+            let analyzed = match db.lock().await.get_audio_analysis(&media_file.id) {
+                Ok(analysis) => analysis.is_some(),
+                Err(e) => {
+                    warn!("Failed to get audio analysis of {}: {:?}", media_file.id, e);
+                    true
+                }
+            };
+            let media_file_id = media_file.id.clone();
+            let analysis = tokio::task::spawn_blocking(move || {
+                if let Err(e) = media_storage.get_waveform(&media_file) {
+                    warn!("Failed to generate waveform for {}: {}", media_file.id, e);
+                }
+                if analyzed {
+                    return None;
+                }
+                media_storage
+                    .analyze_audio(&media_file)
+                    .map_err(|e| warn!("Failed to analyze {}: {}", media_file.id, e))
+                    .ok()
+            })
+            .await;
+            if let Ok(Some(analysis)) = analysis {
+                if let Err(e) = db.lock().await.insert_audio_analysis(&media_file_id, &analysis) {
+                    warn!("Failed to store audio analysis of {}: {:?}", media_file_id, e);
+                }
+            }
+        });
+    }
+}
+
+/// Blank labels fall back to the default one
+fn parse_audio_label(label: Option<String>) -> Option<String> {
+    label
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+}
+
+fn audio_error_to_status(e: DatabaseError) -> Status {
+    match e {
+        DatabaseError::InvalidOperation(msg) => Status::invalid_argument(msg),
+        DatabaseError::NotFound(msg) => Status::not_found(msg),
+        e => Status::internal(format!("Database error: {}", e)),
+    }
+}
+
+fn media_info_to_proto(info: &MediaInfo) -> MediaFormatInfo {
+    MediaFormatInfo {
+        format: info.format.map(|format| format.as_str().to_string()).unwrap_or_default(),
+        duration_seconds: info.duration_seconds,
+        sample_rate: info.sample_rate,
+        channels: info.channels.map(u32::from),
+        width: info.width,
+        height: info.height,
+    }
+}
+
+fn upload_expires_at(session: &UploadSession) -> i64 {
+    (session.updated_at + chrono::Duration::hours(UPLOAD_SESSION_TTL_HOURS)).timestamp()
+}
+
+fn upload_error_to_status(e: MediaError) -> Status {
+    match e {
+        MediaError::InvalidUpload(msg) => Status::failed_precondition(msg),
+        MediaError::InvalidFileId(id) => Status::invalid_argument(format!("Invalid upload ID: {}", id)),
+        MediaError::FileNotFound(msg) => Status::not_found(format!("Upload not found: {}", msg)),
+        e => Status::internal(e.to_string()),
+    }
+}
+
+fn decode_error_to_status(e: MediaError) -> Status {
+    match e {
+        MediaError::FileNotFound(msg) => Status::not_found(format!("File not found: {}", msg)),
+        MediaError::DecodeError(msg) => Status::failed_precondition(msg),
+        e => Status::internal(e.to_string()),
+    }
 }
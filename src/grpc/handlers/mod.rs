@@ -1,5 +1,6 @@
 pub mod collections;
 pub mod config;
+pub mod export;
 pub mod media;
 pub mod plugins;
 pub mod projects;
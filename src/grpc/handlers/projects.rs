@@ -1,4 +1,5 @@
 use log::{debug, error};
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tonic::{Code, Request, Response, Status};
@@ -6,8 +7,10 @@ use tonic::{Code, Request, Response, Status};
 use super::super::projects::*;
 use super::super::common::*;
 use super::utils::convert_live_set_to_proto;
+use crate::database::ratings::{DEFAULT_GEM_MIN_RATING, DEFAULT_GEM_MONTHS};
 use crate::database::LiveSetDatabase;
 use crate::error::DatabaseError;
+use crate::models::SetKind;
 use crate::LiveSet;
 
 // MOVE FROM server.rs:
@@ -44,6 +47,12 @@ impl ProjectsHandler {
         debug!("GetProjects request: {:?}", request);
 
         let req = request.into_inner();
+        let set_kind = req
+            .set_kind
+            .as_deref()
+            .map(SetKind::from_str)
+            .transpose()
+            .map_err(Status::invalid_argument)?;
         let mut db = self.db.lock().await;
 
         // Use the new filtering method if any filters are specified
@@ -61,6 +70,10 @@ impl ProjectsHandler {
             || req.modified_after.is_some()
             || req.modified_before.is_some()
             || req.has_audio_file.is_some()
+            || req.min_rating.is_some()
+            || req.is_favorite.is_some()
+            || set_kind.is_some()
+            || req.sort_by.is_some()
         {
             match db.get_projects_with_filters(
                 req.limit,
@@ -81,6 +94,9 @@ impl ProjectsHandler {
                 req.modified_after,
                 req.modified_before,
                 req.has_audio_file,
+                req.min_rating,
+                req.is_favorite,
+                set_kind,
             ) {
                 Ok(result) => result,
                 Err(e) => {
@@ -602,6 +618,19 @@ impl ProjectsHandler {
                     })
                     .collect();
 
+                let status_stages = stats
+                    .status_stages
+                    .into_iter()
+                    .map(|stage| StatusStageStatistic {
+                        status: stage.status,
+                        position: stage.position,
+                        current_projects: stage.current_projects,
+                        times_entered: stage.times_entered,
+                        total_seconds: stage.total_seconds,
+                        average_seconds: stage.average_seconds,
+                    })
+                    .collect();
+
                 let response = GetProjectStatisticsResponse {
                     total_projects: stats.total_projects,
                     projects_with_audio_files: stats.projects_with_audio_files,
@@ -622,6 +651,7 @@ impl ProjectsHandler {
                     projects_per_year,
                     projects_per_month,
                     most_complex_projects,
+                    status_stages,
                 };
 
                 Ok(Response::new(response))
@@ -678,4 +708,285 @@ impl ProjectsHandler {
             }
         }
     }
+
+    // Workflow Status
+    pub async fn get_status_pipeline(
+        &self,
+        _request: Request<GetStatusPipelineRequest>,
+    ) -> Result<Response<GetStatusPipelineResponse>, Status> {
+        debug!("GetStatusPipeline request");
+
+        let db = self.db.lock().await;
+        match db.get_status_pipeline() {
+            Ok(statuses) => Ok(Response::new(GetStatusPipelineResponse { statuses })),
+            Err(e) => {
+                error!("Failed to get status pipeline: {:?}", e);
+                Err(Status::internal(format!("Database error: {}", e)))
+            }
+        }
+    }
+
+    pub async fn update_status_pipeline(
+        &self,
+        request: Request<UpdateStatusPipelineRequest>,
+    ) -> Result<Response<UpdateStatusPipelineResponse>, Status> {
+        debug!("UpdateStatusPipeline request: {:?}", request);
+
+        let req = request.into_inner();
+        let mut db = self.db.lock().await;
+
+        if let Err(e) = db.set_status_pipeline(&req.statuses) {
+            error!("Failed to update status pipeline: {:?}", e);
+            return Err(match e {
+                DatabaseError::InvalidOperation(msg) => Status::invalid_argument(msg),
+                e => Status::internal(format!("Database error: {}", e)),
+            });
+        }
+
+        match db.get_status_pipeline() {
+            Ok(statuses) => Ok(Response::new(UpdateStatusPipelineResponse { statuses })),
+            Err(e) => Err(Status::internal(format!("Database error: {}", e))),
+        }
+    }
+
+    pub async fn set_project_status(
+        &self,
+        request: Request<SetProjectStatusRequest>,
+    ) -> Result<Response<SetProjectStatusResponse>, Status> {
+        debug!("SetProjectStatus request: {:?}", request);
+
+        let req = request.into_inner();
+        let mut db = self.db.lock().await;
+
+        if let Err(e) = db.set_project_status(&req.project_id, req.status.as_deref()) {
+            error!("Failed to set status of project {}: {:?}", req.project_id, e);
+            return Err(project_error_to_status(e));
+        }
+
+        let project = get_converted_project(&mut db, &req.project_id).map_err(project_error_to_status)?;
+        Ok(Response::new(SetProjectStatusResponse {
+            project: Some(project),
+        }))
+    }
+
+    pub async fn get_project_status_history(
+        &self,
+        request: Request<GetProjectStatusHistoryRequest>,
+    ) -> Result<Response<GetProjectStatusHistoryResponse>, Status> {
+        debug!("GetProjectStatusHistory request: {:?}", request);
+
+        let req = request.into_inner();
+        let db = self.db.lock().await;
+
+        match db.get_project_status_history(&req.project_id) {
+            Ok(history) => {
+                let transitions = history
+                    .into_iter()
+                    .map(|transition| StatusTransition {
+                        from_status: transition.from_status,
+                        to_status: transition.to_status,
+                        changed_at: transition.changed_at,
+                    })
+                    .collect();
+                Ok(Response::new(GetProjectStatusHistoryResponse { transitions }))
+            }
+            Err(e) => {
+                error!("Failed to get status history of project {}: {:?}", req.project_id, e);
+                Err(Status::internal(format!("Database error: {}", e)))
+            }
+        }
+    }
+
+    // Ratings and Usage
+    pub async fn set_project_rating(
+        &self,
+        request: Request<SetProjectRatingRequest>,
+    ) -> Result<Response<SetProjectRatingResponse>, Status> {
+        debug!("SetProjectRating request: {:?}", request);
+
+        let req = request.into_inner();
+        let mut db = self.db.lock().await;
+
+        if let Err(e) = db.set_project_rating(&req.project_id, req.rating) {
+            error!("Failed to rate project {}: {:?}", req.project_id, e);
+            return Err(project_error_to_status(e));
+        }
+
+        let project = get_converted_project(&mut db, &req.project_id).map_err(project_error_to_status)?;
+        Ok(Response::new(SetProjectRatingResponse {
+            project: Some(project),
+        }))
+    }
+
+    pub async fn set_project_favorite(
+        &self,
+        request: Request<SetProjectFavoriteRequest>,
+    ) -> Result<Response<SetProjectFavoriteResponse>, Status> {
+        debug!("SetProjectFavorite request: {:?}", request);
+
+        let req = request.into_inner();
+        let mut db = self.db.lock().await;
+
+        if let Err(e) = db.set_project_favorite(&req.project_id, req.is_favorite) {
+            error!("Failed to set favorite of project {}: {:?}", req.project_id, e);
+            return Err(project_error_to_status(e));
+        }
+
+        let project = get_converted_project(&mut db, &req.project_id).map_err(project_error_to_status)?;
+        Ok(Response::new(SetProjectFavoriteResponse {
+            project: Some(project),
+        }))
+    }
+
+    pub async fn record_project_opened(
+        &self,
+        request: Request<RecordProjectOpenedRequest>,
+    ) -> Result<Response<RecordProjectOpenedResponse>, Status> {
+        debug!("RecordProjectOpened request: {:?}", request);
+
+        let req = request.into_inner();
+        let mut db = self.db.lock().await;
+
+        if let Err(e) = db.record_project_opened(&req.project_id, req.opened_at) {
+            error!("Failed to record open of project {}: {:?}", req.project_id, e);
+            return Err(project_error_to_status(e));
+        }
+
+        let project = get_converted_project(&mut db, &req.project_id).map_err(project_error_to_status)?;
+        Ok(Response::new(RecordProjectOpenedResponse {
+            project: Some(project),
+        }))
+    }
+
+    pub async fn get_forgotten_gems(
+        &self,
+        request: Request<GetForgottenGemsRequest>,
+    ) -> Result<Response<GetForgottenGemsResponse>, Status> {
+        debug!("GetForgottenGems request: {:?}", request);
+
+        let req = request.into_inner();
+        let min_rating = req.min_rating.unwrap_or(DEFAULT_GEM_MIN_RATING);
+        let months = match req.months {
+            Some(months) if months < 0 => {
+                return Err(Status::invalid_argument("Months cannot be negative"));
+            }
+            Some(months) => months as u32,
+            None => DEFAULT_GEM_MONTHS,
+        };
+        let mut db = self.db.lock().await;
+
+        let gems = match db.get_forgotten_gems(min_rating, months, req.limit) {
+            Ok(gems) => gems,
+            Err(e) => {
+                error!("Failed to get forgotten gems: {:?}", e);
+                return Err(Status::internal(format!("Database error: {}", e)));
+            }
+        };
+
+        let mut projects = Vec::new();
+        for project in gems {
+            match convert_live_set_to_proto(project, &mut db) {
+                Ok(project) => projects.push(project),
+                Err(e) => return Err(Status::internal(format!("Database error: {}", e))),
+            }
+        }
+
+        Ok(Response::new(GetForgottenGemsResponse { projects }))
+    }
+
+    pub async fn get_project_folders(
+        &self,
+        request: Request<GetProjectFoldersRequest>,
+    ) -> Result<Response<GetProjectFoldersResponse>, Status> {
+        debug!("GetProjectFolders request: {:?}", request);
+
+        let req = request.into_inner();
+        let db = self.db.lock().await;
+
+        let folders = match db.get_project_folders() {
+            Ok(folders) => folders,
+            Err(e) => {
+                error!("Failed to get project folders: {:?}", e);
+                return Err(Status::internal(format!("Database error: {}", e)));
+            }
+        };
+
+        let total_count = folders.len() as i32;
+        let folders = folders
+            .into_iter()
+            .skip(req.offset.unwrap_or(0).max(0) as usize)
+            .take(req.limit.map_or(usize::MAX, |limit| limit.max(0) as usize))
+            .map(convert_project_folder_to_proto)
+            .collect();
+
+        Ok(Response::new(GetProjectFoldersResponse {
+            folders,
+            total_count,
+        }))
+    }
+
+    pub async fn get_project_folder(
+        &self,
+        request: Request<GetProjectFolderRequest>,
+    ) -> Result<Response<GetProjectFolderResponse>, Status> {
+        debug!("GetProjectFolder request: {:?}", request);
+
+        let req = request.into_inner();
+        let mut db = self.db.lock().await;
+
+        let folder = match db.get_project_folder(&req.path) {
+            Ok(Some(folder)) => folder,
+            Ok(None) => {
+                return Err(Status::not_found(format!(
+                    "Project folder {} not found",
+                    req.path
+                )));
+            }
+            Err(e) => {
+                error!("Failed to get project folder: {:?}", e);
+                return Err(Status::internal(format!("Database error: {}", e)));
+            }
+        };
+
+        let mut projects = Vec::new();
+        for project_id in &folder.project_ids {
+            projects.push(get_converted_project(&mut db, project_id).map_err(project_error_to_status)?);
+        }
+
+        Ok(Response::new(GetProjectFolderResponse {
+            folder: Some(convert_project_folder_to_proto(folder)),
+            projects,
+        }))
+    }
+}
+
+fn convert_project_folder_to_proto(folder: crate::database::ProjectFolder) -> ProjectFolder {
+    ProjectFolder {
+        latest_project_id: folder.latest_project_id().map(str::to_string),
+        path: folder.path,
+        name: folder.name,
+        project_ids: folder.project_ids,
+        last_modified_at: folder.last_modified_at,
+    }
+}
+
+fn get_converted_project(
+    db: &mut LiveSetDatabase,
+    project_id: &str,
+) -> Result<Project, DatabaseError> {
+    match db.get_project_by_id(project_id)? {
+        Some(project) => convert_live_set_to_proto(project, db),
+        None => Err(DatabaseError::NotFound(format!(
+            "Project {} not found",
+            project_id
+        ))),
+    }
+}
+
+fn project_error_to_status(e: DatabaseError) -> Status {
+    match e {
+        DatabaseError::InvalidOperation(msg) => Status::invalid_argument(msg),
+        DatabaseError::NotFound(msg) => Status::not_found(msg),
+        e => Status::internal(format!("Database error: {}", e)),
+    }
 }
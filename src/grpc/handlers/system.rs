@@ -1,22 +1,33 @@
-use chrono;
+use chrono::{self, Datelike};
 use log::{debug, error, info, warn};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{broadcast, mpsc, Mutex};
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Code, Request, Response, Status};
 
+use super::export::{
+    export_filename, statistics_to_csv, statistics_to_html, statistics_to_markdown,
+};
 use super::utils::convert_live_set_to_proto;
 use crate::config::CONFIG;
+use crate::database::activity::DEFAULT_SESSION_GAP_MINUTES;
 use crate::database::LiveSetDatabase;
 use super::super::system::*;
 use super::super::scanning::*;
 use super::super::watcher::*;
 use super::super::common::*;
 use crate::live_set::LiveSet;
-use crate::process_projects_with_progress;
-use crate::watcher::file_watcher::{FileEvent, FileWatcher};
+use crate::process_projects_with_options;
+use crate::error::LiveSetError;
+use crate::database::scan_errors::ScanErrorRecord;
+use crate::scan::quarantine::retry_failed_sets;
+use crate::scan::{ScanCounts, ScanOptions, ScanRoots, SCAN_JOBS};
+use crate::watcher::debouncer::DEFAULT_DEBOUNCE_WINDOW;
+use crate::watcher::events::{WatcherEvent, WatcherEventBus, WatcherEventKind};
+use crate::watcher::file_watcher::FileWatcher;
+use crate::watcher::indexer::spawn_indexer;
 
 #[derive(Clone)]
 pub struct SystemHandler {
@@ -24,7 +35,7 @@ pub struct SystemHandler {
     pub scan_status: Arc<Mutex<ScanStatus>>,
     pub scan_progress: Arc<Mutex<Option<ScanProgressResponse>>>,
     pub watcher: Arc<Mutex<Option<FileWatcher>>>,
-    pub watcher_events: Arc<Mutex<Option<std::sync::mpsc::Receiver<FileEvent>>>>,
+    pub watcher_events: Arc<WatcherEventBus>,
     pub start_time: Instant,
 }
 
@@ -34,7 +45,7 @@ impl SystemHandler {
         scan_status: Arc<Mutex<ScanStatus>>,
         scan_progress: Arc<Mutex<Option<ScanProgressResponse>>>,
         watcher: Arc<Mutex<Option<FileWatcher>>>,
-        watcher_events: Arc<Mutex<Option<std::sync::mpsc::Receiver<FileEvent>>>>,
+        watcher_events: Arc<WatcherEventBus>,
         start_time: Instant,
     ) -> Self {
         Self {
@@ -53,7 +64,10 @@ impl SystemHandler {
     ) -> Result<Response<ReceiverStream<Result<ScanProgressResponse, Status>>>, Status> {
         info!("ScanDirectories request: {:?}", request);
 
-        let _req = request.into_inner();
+        let req = request.into_inner();
+        let options = ScanOptions {
+            force: req.force_rescan.unwrap_or(false),
+        };
         let (tx, rx) = mpsc::channel(100);
 
         // Update scan status and clear progress
@@ -74,13 +88,20 @@ impl SystemHandler {
         tokio::spawn(async move {
             // Create progress callback that sends updates through the channel
             let progress_callback =
-                move |completed: u32, total: u32, progress: f32, message: String, phase: &str| {
+                move |completed: u32,
+                      total: u32,
+                      progress: f32,
+                      message: String,
+                      phase: &str,
+                      counts: &ScanCounts| {
                     let status = match phase {
                         "starting" => ScanStatus::ScanStarting,
                         "discovering" => ScanStatus::ScanDiscovering,
                         "preprocessing" | "parsing" => ScanStatus::ScanParsing,
                         "inserting" => ScanStatus::ScanInserting,
                         "completed" => ScanStatus::ScanCompleted,
+                        "paused" => ScanStatus::ScanPaused,
+                        "cancelled" => ScanStatus::ScanCancelled,
                         _ => ScanStatus::ScanStarting,
                     };
 
@@ -91,6 +112,12 @@ impl SystemHandler {
                         progress,
                         message: message.clone(),
                         status: status as i32,
+                        new_count: counts.new,
+                        updated_count: counts.updated,
+                        skipped_count: counts.skipped,
+                        removed_count: counts.removed,
+                        failed_count: counts.failed,
+                        quarantined_count: counts.quarantined,
                     };
 
                     // Update global scan status and progress
@@ -109,21 +136,32 @@ impl SystemHandler {
                 };
 
             // Run the scanning process with progress callbacks
-            match process_projects_with_progress(Some(progress_callback)) {
-                Ok(()) => {
-                    info!("Scan completed successfully");
+            match process_projects_with_options(&options, Some(progress_callback)) {
+                Ok(counts) => {
+                    info!("Scan completed successfully: {}", counts.summary());
                     let final_status = ScanStatus::ScanCompleted;
                     let final_progress = ScanProgressResponse {
                         completed: 100,
                         total: 100,
                         progress: 1.0,
-                        message: "Scan completed successfully".to_string(),
+                        message: format!("Scan completed successfully: {}", counts.summary()),
                         status: final_status as i32,
+                        new_count: counts.new,
+                        updated_count: counts.updated,
+                        skipped_count: counts.skipped,
+                        removed_count: counts.removed,
+                        failed_count: counts.failed,
+                        quarantined_count: counts.quarantined,
                     };
 
                     *scan_status.lock().await = final_status;
                     *scan_progress.lock().await = Some(final_progress);
                 }
+                Err(LiveSetError::Cancelled) => {
+                    // The last progress update already carries the counts
+                    info!("Scan cancelled");
+                    *scan_status_for_error.lock().await = ScanStatus::ScanCancelled;
+                }
                 Err(e) => {
                     error!("Scan failed: {:?}", e);
                     let error_status = ScanStatus::ScanError;
@@ -133,6 +171,7 @@ impl SystemHandler {
                         progress: 0.0,
                         message: format!("Scan failed: {}", e),
                         status: error_status as i32,
+                        ..Default::default()
                     };
 
                     *scan_status_for_error.lock().await = error_status;
@@ -165,6 +204,116 @@ impl SystemHandler {
         Ok(Response::new(response))
     }
 
+    pub async fn cancel_scan(
+        &self,
+        _request: Request<CancelScanRequest>,
+    ) -> Result<Response<CancelScanResponse>, Status> {
+        let success = SCAN_JOBS.cancel() > 0;
+        info!("CancelScan request: {}", if success { "cancelling" } else { "no scan running" });
+
+        Ok(Response::new(CancelScanResponse {
+            success,
+            status: *self.scan_status.lock().await as i32,
+        }))
+    }
+
+    pub async fn pause_scan(
+        &self,
+        _request: Request<PauseScanRequest>,
+    ) -> Result<Response<PauseScanResponse>, Status> {
+        let success = SCAN_JOBS.pause() > 0;
+        info!("PauseScan request: {}", if success { "paused" } else { "no scan running" });
+
+        // Only parsing can be paused. Retrying failed sets runs as a scan job too, but
+        // isn't the scan whose status is shown here.
+        let mut status = self.scan_status.lock().await;
+        if success && *status == ScanStatus::ScanParsing {
+            *status = ScanStatus::ScanPaused;
+        }
+        Ok(Response::new(PauseScanResponse {
+            success,
+            status: *status as i32,
+        }))
+    }
+
+    pub async fn resume_scan(
+        &self,
+        _request: Request<ResumeScanRequest>,
+    ) -> Result<Response<ResumeScanResponse>, Status> {
+        let success = SCAN_JOBS.resume() > 0;
+        info!("ResumeScan request: {}", if success { "resumed" } else { "no paused scan" });
+
+        // Only parsing can be paused, so that's where a paused scan picks up again. A
+        // scan that moved on since is reported as it is.
+        let mut status = self.scan_status.lock().await;
+        if success && *status == ScanStatus::ScanPaused {
+            *status = ScanStatus::ScanParsing;
+        }
+        Ok(Response::new(ResumeScanResponse {
+            success,
+            status: *status as i32,
+        }))
+    }
+
+    pub async fn get_scan_errors(
+        &self,
+        request: Request<GetScanErrorsRequest>,
+    ) -> Result<Response<GetScanErrorsResponse>, Status> {
+        let req = request.into_inner();
+        let db = self.db.lock().await;
+        match db.get_scan_errors(req.quarantined_only.unwrap_or(false)) {
+            Ok(records) => {
+                let errors: Vec<ScanError> = records.into_iter().map(scan_error_to_proto).collect();
+                Ok(Response::new(GetScanErrorsResponse {
+                    total_count: errors.len() as i32,
+                    errors,
+                }))
+            }
+            Err(e) => {
+                error!("Failed to get scan errors: {:?}", e);
+                Err(Status::internal(format!("Database error: {}", e)))
+            }
+        }
+    }
+
+    pub async fn retry_scan_errors(
+        &self,
+        request: Request<RetryScanErrorsRequest>,
+    ) -> Result<Response<RetryScanErrorsResponse>, Status> {
+        let req = request.into_inner();
+        info!("RetryScanErrors request for {} paths", req.paths.len());
+        let paths: Vec<PathBuf> = req.paths.iter().map(PathBuf::from).collect();
+
+        // Parses without holding the database lock, off the async runtime
+        let db = Arc::clone(&self.db);
+        let retry_paths = paths.clone();
+        let counts = tokio::task::spawn_blocking(move || retry_failed_sets(&db, &retry_paths))
+            .await
+            .map_err(|e| Status::internal(format!("Retry task failed: {}", e)))?
+            .map_err(|e| {
+                error!("Failed to retry scan errors: {:?}", e);
+                Status::internal(format!("Failed to retry scan errors: {}", e))
+            })?;
+        let errors = self
+            .db
+            .lock()
+            .await
+            .get_scan_errors(false)
+            .map_err(|e| Status::internal(format!("Database error: {}", e)))?
+            .into_iter()
+            .filter(|record| paths.is_empty() || paths.iter().any(|p| p.to_string_lossy() == record.path))
+            .map(scan_error_to_proto)
+            .collect();
+
+        Ok(Response::new(RetryScanErrorsResponse {
+            success: true,
+            fixed_count: counts.fixed as i32,
+            failed_count: counts.failed as i32,
+            missing_count: counts.missing as i32,
+            errors,
+        }))
+    }
+
     pub async fn add_single_project(
         &self,
         request: Request<AddSingleProjectRequest>,
@@ -350,7 +499,6 @@ impl SystemHandler {
         debug!("Starting file watcher");
 
         let mut watcher_guard = self.watcher.lock().await;
-        let mut events_guard = self.watcher_events.lock().await;
 
         // Check if watcher is already active
         if watcher_guard.is_some() {
@@ -365,15 +513,27 @@ impl SystemHandler {
                     .as_ref()
                     .map_err(|e| Status::internal(format!("Config error: {}", e)))?;
 
-                for path in &config.paths {
-                    if let Err(e) = watcher.add_watch_path(PathBuf::from(path)) {
+                let roots = ScanRoots::from_config(config)
+                    .map_err(|e| Status::internal(format!("Config error: {}", e)))?;
+                for rules in roots.iter() {
+                    let path = rules.root().to_string_lossy().to_string();
+                    if config.is_poll_path(&path) {
+                        watcher.add_poll_root(rules.clone(), config.poll_interval());
+                    } else if let Err(e) = watcher.add_watch_root(rules.clone()) {
                         warn!("Failed to add watch path {}: {}", path, e);
                     }
                 }
 
-                // Store watcher and event receiver
+                // Index changes as they settle, independent of any client streaming events
+                spawn_indexer(
+                    Arc::clone(&self.db),
+                    event_receiver,
+                    Arc::clone(&self.watcher_events),
+                    DEFAULT_DEBOUNCE_WINDOW,
+                    watcher.scan_roots().clone(),
+                );
+
                 *watcher_guard = Some(watcher);
-                *events_guard = Some(event_receiver);
 
                 info!("File watcher started successfully");
                 Ok(Response::new(StartWatcherResponse { success: true }))
@@ -392,16 +552,15 @@ impl SystemHandler {
         debug!("Stopping file watcher");
 
         let mut watcher_guard = self.watcher.lock().await;
-        let mut events_guard = self.watcher_events.lock().await;
 
         // Check if watcher is active
         if watcher_guard.is_none() {
             return Ok(Response::new(StopWatcherResponse { success: true }));
         }
 
-        // Stop the watcher by dropping it
+        // Stop the watcher by dropping it. Subscribers stay connected and pick up
+        // events again if the watcher is restarted.
         *watcher_guard = None;
-        *events_guard = None;
 
         info!("File watcher stopped successfully");
         Ok(Response::new(StopWatcherResponse { success: true }))
@@ -409,108 +568,63 @@ impl SystemHandler {
 
     pub async fn get_watcher_events(
         &self,
-        _request: Request<GetWatcherEventsRequest>,
+        request: Request<GetWatcherEventsRequest>,
     ) -> Result<Response<ReceiverStream<Result<WatcherEventResponse, Status>>>, Status> {
-        debug!("Getting watcher events stream");
-
-        let mut events_guard = self.watcher_events.lock().await;
-
-        // Check if watcher is active and has events
-        if let Some(event_receiver) = events_guard.take() {
-            let (tx, rx) = mpsc::channel(100);
-
-            // Clone the database reference for the spawn task
-            let db_clone = Arc::clone(&self.db);
-
-            // Spawn a task to convert FileEvent to WatcherEventResponse
-            tokio::spawn(async move {
-                while let Ok(file_event) = event_receiver.recv() {
-                    // Handle database operations for file events
-                    match &file_event {
-                        FileEvent::Deleted(path) => {
-                            debug!("Processing file deletion for database update: {:?}", path);
-                            let mut db = db_clone.lock().await;
-
-                            // Try to find the project by path and mark it as deleted
-                            match db.get_project_by_path(&path.to_string_lossy()) {
-                                Ok(Some(project)) => {
-                                    debug!(
-                                        "Found project for deleted file: {} ({})",
-                                        project.name, project.id
-                                    );
-                                    if let Err(e) = db.mark_project_deleted(&project.id) {
-                                        warn!("Failed to mark project as deleted: {}", e);
-                                    } else {
-                                        info!(
-                                            "Successfully marked project as deleted: {}",
-                                            project.name
-                                        );
-                                    }
-                                }
-                                Ok(None) => {
-                                    debug!("No project found for deleted file: {:?}", path);
-                                }
-                                Err(e) => {
-                                    warn!("Error looking up project for deleted file: {}", e);
-                                }
-                            }
-                        }
-                        FileEvent::Created(_) => {
-                            // TODO: Could trigger a scan of the new file in the future
-                            debug!("File created event - no database action needed");
-                        }
-                        FileEvent::Modified(_) => {
-                            // TODO: Could trigger a re-scan of the modified file in the future
-                            debug!("File modified event - no database action needed");
-                        }
-                        FileEvent::Renamed { from, to: _ } => {
-                            // TODO: Could update the project path in the database
-                            debug!(
-                                "File renamed event - no database action implemented for: {:?}",
-                                from
-                            );
-                        }
-                    }
+        let req = request.into_inner();
+        debug!(
+            "Getting watcher events stream since sequence {:?}",
+            req.since_sequence
+        );
 
-                    // Convert to WatcherEventResponse for streaming
-                    let watcher_event = match file_event {
-                        FileEvent::Created(path) => WatcherEventResponse {
-                            event_type: WatcherEventType::WatcherCreated as i32,
-                            path: path.to_string_lossy().to_string(),
-                            new_path: None,
-                            timestamp: chrono::Utc::now().timestamp(),
-                        },
-                        FileEvent::Modified(path) => WatcherEventResponse {
-                            event_type: WatcherEventType::WatcherModified as i32,
-                            path: path.to_string_lossy().to_string(),
-                            new_path: None,
-                            timestamp: chrono::Utc::now().timestamp(),
-                        },
-                        FileEvent::Deleted(path) => WatcherEventResponse {
-                            event_type: WatcherEventType::WatcherDeleted as i32,
-                            path: path.to_string_lossy().to_string(),
-                            new_path: None,
-                            timestamp: chrono::Utc::now().timestamp(),
-                        },
-                        FileEvent::Renamed { from, to } => WatcherEventResponse {
-                            event_type: WatcherEventType::WatcherRenamed as i32,
-                            path: from.to_string_lossy().to_string(),
-                            new_path: Some(to.to_string_lossy().to_string()),
-                            timestamp: chrono::Utc::now().timestamp(),
-                        },
-                    };
+        if self.watcher.lock().await.is_none() {
+            return Err(Status::failed_precondition("Watcher not active"));
+        }
+
+        let bus = Arc::clone(&self.watcher_events);
+        let subscription = bus.subscribe(req.since_sequence);
+        let (tx, rx) = mpsc::channel(100);
+
+        tokio::spawn(async move {
+            let mut receiver = subscription.receiver;
+            let mut missed = subscription.missed;
+            let mut last_sequence = subscription.resume_after;
+
+            for event in subscription.replay {
+                last_sequence = event.sequence;
+                let response = watcher_event_to_proto(event, std::mem::take(&mut missed));
+                if tx.send(Ok(response)).await.is_err() {
+                    return;
+                }
+            }
+
+            loop {
+                let events = match receiver.recv().await {
+                    Ok(event) => vec![event],
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        // Fell behind the channel; catch up from the buffer instead
+                        warn!("Watcher event subscriber lagged by {} events", skipped);
+                        let (events, dropped) = bus.events_since(last_sequence);
+                        missed |= dropped;
+                        events
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
 
-                    if tx.send(Ok(watcher_event)).await.is_err() {
-                        break;
+                for event in events {
+                    // Replayed or caught up already
+                    if event.sequence <= last_sequence {
+                        continue;
+                    }
+                    last_sequence = event.sequence;
+                    let response = watcher_event_to_proto(event, std::mem::take(&mut missed));
+                    if tx.send(Ok(response)).await.is_err() {
+                        return;
                     }
                 }
-            });
+            }
+        });
 
-            let stream = ReceiverStream::new(rx);
-            Ok(Response::new(stream))
-        } else {
-            Err(Status::failed_precondition("Watcher not active"))
-        }
+        Ok(Response::new(ReceiverStream::new(rx)))
     }
 
     pub async fn get_system_info(
@@ -841,86 +955,195 @@ impl SystemHandler {
         let stats_response = self.get_statistics(Request::new(stats_request)).await?;
         let stats = stats_response.into_inner();
 
-        match req.format() {
-            ExportFormat::ExportCsv => {
-                // Generate CSV export
-                let csv_data = self.generate_csv_export(stats).await?;
-                let filename = format!(
-                    "statistics_{}.csv",
-                    chrono::Utc::now().format("%Y%m%d_%H%M%S")
-                );
+        let format = req.format();
+        let data = match format {
+            ExportFormat::ExportCsv => statistics_to_csv(&stats).into_bytes(),
+            ExportFormat::ExportJson => serde_json::to_vec_pretty(&stats).map_err(|e| {
+                error!("Failed to serialize statistics: {:?}", e);
+                Status::internal(format!("Failed to serialize statistics: {}", e))
+            })?,
+            ExportFormat::ExportMarkdown => statistics_to_markdown(&stats).into_bytes(),
+            ExportFormat::ExportHtml => statistics_to_html(&stats).into_bytes(),
+        };
 
-                Ok(Response::new(ExportStatisticsResponse {
-                    data: csv_data,
-                    filename,
-                    success: true,
-                    error_message: None,
-                }))
-            }
-        }
+        Ok(Response::new(ExportStatisticsResponse {
+            data,
+            filename: export_filename(format),
+            success: true,
+            error_message: None,
+        }))
     }
 
-    async fn generate_csv_export(&self, stats: GetStatisticsResponse) -> Result<Vec<u8>, Status> {
-        let mut csv_content = String::new();
-
-        // Basic statistics
-        csv_content.push_str("Category,Value\n");
-        csv_content.push_str(&format!("Total Projects,{}\n", stats.total_projects));
-        csv_content.push_str(&format!("Total Plugins,{}\n", stats.total_plugins));
-        csv_content.push_str(&format!("Total Samples,{}\n", stats.total_samples));
-        csv_content.push_str(&format!("Total Collections,{}\n", stats.total_collections));
-        csv_content.push_str(&format!("Total Tags,{}\n", stats.total_tags));
-        csv_content.push_str(&format!("Total Tasks,{}\n", stats.total_tasks));
-        csv_content.push_str(&format!("Completed Tasks,{}\n", stats.completed_tasks));
-        csv_content.push_str(&format!("Pending Tasks,{}\n", stats.pending_tasks));
-        csv_content.push_str(&format!(
-            "Task Completion Rate,{:.2}%\n",
-            stats.task_completion_rate * 100.0
-        ));
-        csv_content.push_str(&format!(
-            "Average Project Duration,{:.2} seconds\n",
-            stats.average_project_duration_seconds
-        ));
-        csv_content.push_str(&format!(
-            "Average Projects per Collection,{:.2}\n",
-            stats.average_projects_per_collection
-        ));
-        csv_content.push_str(&format!(
-            "Average Plugins per Project,{:.2}\n",
-            stats.average_plugins_per_project
-        ));
-        csv_content.push_str(&format!(
-            "Average Samples per Project,{:.2}\n",
-            stats.average_samples_per_project
-        ));
-        csv_content.push_str("\n");
-
-        // Top plugins
-        csv_content.push_str("Top Plugins\n");
-        csv_content.push_str("Plugin Name,Vendor,Usage Count\n");
-        for plugin in stats.top_plugins {
-            csv_content.push_str(&format!(
-                "{},{},{}\n",
-                plugin.name, plugin.vendor, plugin.usage_count
-            ));
-        }
-        csv_content.push_str("\n");
+    pub async fn get_year_in_review(
+        &self,
+        request: Request<GetYearInReviewRequest>,
+    ) -> Result<Response<GetYearInReviewResponse>, Status> {
+        debug!("GetYearInReview request: {:?}", request);
+        let req = request.into_inner();
+        let year = req.year.unwrap_or_else(|| chrono::Local::now().year());
 
-        // Tempo distribution
-        csv_content.push_str("Tempo Distribution\n");
-        csv_content.push_str("Tempo,Count\n");
-        for tempo in stats.tempo_distribution {
-            csv_content.push_str(&format!("{},{}\n", tempo.tempo, tempo.count));
-        }
-        csv_content.push_str("\n");
+        let mut db = self.db.lock().await;
+        let review = db.get_year_in_review(year).map_err(|e| {
+            error!("Failed to get year in review: {:?}", e);
+            Status::internal(format!("Database error: {}", e))
+        })?;
+
+        let longest_project = match &review.longest_project_id {
+            Some(project_id) => match db.get_project_by_id(project_id) {
+                Ok(Some(project)) => convert_live_set_to_proto(project, &mut db).ok(),
+                _ => None,
+            },
+            None => None,
+        };
+        let plugin_statistics = |plugins: &[(String, String, i32)]| {
+            plugins
+                .iter()
+                .map(|(name, vendor, usage_count)| PluginStatistic {
+                    name: name.clone(),
+                    vendor: vendor.clone(),
+                    usage_count: *usage_count,
+                })
+                .collect()
+        };
+
+        Ok(Response::new(GetYearInReviewResponse {
+            year,
+            projects_worked_on: review.projects_worked_on,
+            projects_started: review.projects_started,
+            projects_finished: review.projects_finished,
+            top_plugins: plugin_statistics(&review.top_plugins),
+            top_samples: review
+                .top_samples
+                .iter()
+                .map(|(name, path, usage_count)| SampleStatistic {
+                    name: name.clone(),
+                    path: path.clone(),
+                    usage_count: *usage_count,
+                })
+                .collect(),
+            new_plugins: plugin_statistics(&review.new_plugins),
+            saves_per_month: review.saves_per_month.to_vec(),
+            saves_per_weekday: review.saves_per_weekday.to_vec(),
+            saves_per_hour: review.saves_per_hour.to_vec(),
+            busiest_month: review.busiest_month().map(|month| month as i32),
+            busiest_weekday: review.busiest_weekday().map(|weekday| weekday as i32),
+            busiest_hour: review.busiest_hour().map(|hour| hour as i32),
+            longest_project,
+            favorite_key: review.favorite_key.as_ref().map(|(key, _)| key.clone()),
+            favorite_key_projects: review.favorite_key.as_ref().map_or(0, |(_, count)| *count),
+            favorite_tempo_min: review.favorite_tempo_range.map(|(min, _)| min),
+            favorite_tempo_max: review.favorite_tempo_range.map(|(min, _)| min + 10.0),
+            favorite_tempo_projects: review.favorite_tempo_range.map_or(0, |(_, count)| count),
+            average_tempo: review.average_tempo,
+            active_days: review.active_days,
+            longest_streak_days: review.longest_streak.as_ref().map_or(0, |streak| streak.days),
+            longest_streak_start: review
+                .longest_streak
+                .as_ref()
+                .map(|streak| streak.start.format("%Y-%m-%d").to_string()),
+        }))
+    }
 
-        // Key distribution
-        csv_content.push_str("Key Distribution\n");
-        csv_content.push_str("Key,Count\n");
-        for key in stats.key_distribution {
-            csv_content.push_str(&format!("{},{}\n", key.key, key.count));
+    pub async fn get_activity_analytics(
+        &self,
+        request: Request<GetActivityAnalyticsRequest>,
+    ) -> Result<Response<GetActivityAnalyticsResponse>, Status> {
+        debug!("GetActivityAnalytics request: {:?}", request);
+        let req = request.into_inner();
+        let days = req.days.unwrap_or(30);
+        let session_gap_minutes = req
+            .session_gap_minutes
+            .map_or(DEFAULT_SESSION_GAP_MINUTES, i64::from);
+        if !(1..=3660).contains(&days) {
+            return Err(Status::invalid_argument("days must be between 1 and 3660"));
         }
+        if session_gap_minutes <= 0 {
+            return Err(Status::invalid_argument("session_gap_minutes must be positive"));
+        }
+
+        let db = self.db.lock().await;
+        let analytics = db
+            .get_activity_analytics(days as u32, session_gap_minutes)
+            .map_err(|e| {
+                error!("Failed to get activity analytics: {:?}", e);
+                Status::internal(format!("Database error: {}", e))
+            })?;
+
+        let total_sessions = analytics.sessions.len() as i32;
+        let total_seconds = analytics.total_seconds();
+        Ok(Response::new(GetActivityAnalyticsResponse {
+            days: analytics
+                .days
+                .iter()
+                .map(|day| DailyActivityStatistic {
+                    date: day.date.format("%Y-%m-%d").to_string(),
+                    saves: day.saves,
+                    sessions: day.sessions,
+                    hours: day.seconds as f64 / 3600.0,
+                })
+                .collect(),
+            total_saves: analytics.total_saves(),
+            total_sessions,
+            total_hours: total_seconds as f64 / 3600.0,
+            average_session_minutes: if total_sessions > 0 {
+                total_seconds as f64 / 60.0 / total_sessions as f64
+            } else {
+                0.0
+            },
+            median_project_days: analytics
+                .median_project_seconds()
+                .map(|seconds| seconds as f64 / 86400.0),
+            projects: analytics
+                .projects
+                .into_iter()
+                .map(|project| ProjectTimelineStatistic {
+                    days: project.seconds() as f64 / 86400.0,
+                    project_id: project.project_id,
+                    name: project.name,
+                    first_save_at: project.first_save_at,
+                    last_save_at: project.last_save_at,
+                    saves: project.saves,
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn watcher_event_to_proto(event: WatcherEvent, events_missed: bool) -> WatcherEventResponse {
+    let event_type = match event.kind {
+        WatcherEventKind::Created => WatcherEventType::WatcherCreated,
+        WatcherEventKind::Modified => WatcherEventType::WatcherModified,
+        WatcherEventKind::Deleted => WatcherEventType::WatcherDeleted,
+        WatcherEventKind::Renamed => WatcherEventType::WatcherRenamed,
+        WatcherEventKind::ProjectAdded => WatcherEventType::WatcherProjectAdded,
+        WatcherEventKind::ProjectUpdated => WatcherEventType::WatcherProjectUpdated,
+        WatcherEventKind::ProjectMoved => WatcherEventType::WatcherProjectMoved,
+        WatcherEventKind::ProjectRemoved => WatcherEventType::WatcherProjectRemoved,
+        WatcherEventKind::ProjectFailed => WatcherEventType::WatcherProjectFailed,
+    };
+
+    WatcherEventResponse {
+        event_type: event_type as i32,
+        path: event.path.to_string_lossy().to_string(),
+        new_path: event.new_path.map(|p| p.to_string_lossy().to_string()),
+        timestamp: event.timestamp,
+        sequence: event.sequence,
+        project_id: event.project_id,
+        change_summary: event.summary,
+        events_missed,
+    }
+}
 
-        Ok(csv_content.into_bytes())
+fn scan_error_to_proto(record: ScanErrorRecord) -> ScanError {
+    ScanError {
+        quarantined: record.is_quarantined(),
+        path: record.path,
+        error_kind: record.error_kind,
+        message: record.message,
+        line: record.line,
+        ableton_version: record.ableton_version,
+        failure_count: record.failure_count,
+        first_failed_at: record.first_failed_at,
+        last_failed_at: record.last_failed_at,
     }
 }
@@ -3,9 +3,12 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use tonic::{Code, Request, Response, Status};
 
+use crate::database::tags::{TagUsageInfo as DbTagUsageInfo, TAG_PATH_SEPARATOR};
 use crate::database::LiveSetDatabase;
+use crate::error::DatabaseError;
 use super::super::tags::*;
 use super::super::common::*;
+use super::utils::convert_tag_to_proto;
 
 // MOVE FROM server.rs:
 // - get_tags method (lines ~448-470)
@@ -36,21 +39,19 @@ impl TagsHandler {
 
     pub async fn get_tags(
         &self,
-        _request: Request<GetTagsRequest>,
+        request: Request<GetTagsRequest>,
     ) -> Result<Response<GetTagsResponse>, Status> {
         debug!("GetTags request");
 
+        let req = request.into_inner();
         let mut db = self.db.lock().await;
-        match db.list_tags() {
+        let result = match req.group.as_deref() {
+            Some(group) => db.list_tags_in_group(group),
+            None => db.list_tags(),
+        };
+        match result {
             Ok(tags_data) => {
-                let tags = tags_data
-                    .into_iter()
-                    .map(|(id, name, created_at)| Tag {
-                        id,
-                        name,
-                        created_at,
-                    })
-                    .collect();
+                let tags = tags_data.into_iter().map(convert_tag_to_proto).collect();
 
                 let response = GetTagsResponse { tags };
                 debug!("Successfully retrieved {} tags", response.tags.len());
@@ -75,19 +76,26 @@ impl TagsHandler {
         let req = request.into_inner();
         let mut db = self.db.lock().await;
 
-        match db.add_tag(&req.name) {
+        let name = match req.parent_id.as_deref() {
+            Some(parent_id) => match db.get_tag_by_id(parent_id) {
+                Ok(Some(parent)) => format!("{}{}{}", parent.name, TAG_PATH_SEPARATOR, req.name),
+                Ok(None) => {
+                    return Err(Status::not_found(format!("Parent tag {} not found", parent_id)))
+                }
+                Err(e) => return Err(Status::internal(format!("Database error: {}", e))),
+            },
+            None => req.name.clone(),
+        };
+
+        match db.add_tag_with_color(&name, req.color.as_deref()) {
             Ok(tag_id) => {
                 // Get the created tag details to return in response
                 match db.get_tag_by_id(&tag_id) {
-                    Ok(Some((id, name, created_at))) => {
-                        let tag = Tag {
-                            id,
-                            name,
-                            created_at,
+                    Ok(Some(tag)) => {
+                        let response = CreateTagResponse {
+                            tag: Some(convert_tag_to_proto(tag)),
                         };
-
-                        let response = CreateTagResponse { tag: Some(tag) };
-                        debug!("Successfully created tag: {}", req.name);
+                        debug!("Successfully created tag: {}", name);
                         Ok(Response::new(response))
                     }
                     Ok(None) => {
@@ -106,6 +114,7 @@ impl TagsHandler {
                     }
                 }
             }
+            Err(DatabaseError::InvalidOperation(msg)) => Err(Status::invalid_argument(msg)),
             Err(e) => {
                 error!("Failed to create tag: {}", e);
                 Err(Status::new(
@@ -125,36 +134,52 @@ impl TagsHandler {
         let req = request.into_inner();
         let mut db = self.db.lock().await;
 
-        match db.update_tag(&req.tag_id, &req.name) {
-            Ok(()) => {
-                // Get the updated tag details to return in response
-                match db.get_tag_by_id(&req.tag_id) {
-                    Ok(Some((id, name, created_at))) => {
-                        let tag = Tag {
-                            id,
-                            name,
-                            created_at,
-                        };
+        if let Some(name) = &req.name {
+            if let Err(e) = db.update_tag(&req.tag_id, name) {
+                error!("Failed to update tag '{}': {:?}", req.tag_id, e);
+                return Err(tag_error_to_status(e));
+            }
+        }
 
-                        let response = UpdateTagResponse { tag: Some(tag) };
-                        debug!("Successfully updated tag: {}", req.tag_id);
-                        Ok(Response::new(response))
-                    }
-                    Ok(None) => {
-                        error!("Tag {} not found after update", req.tag_id);
-                        Err(Status::new(Code::NotFound, "Tag not found"))
-                    }
-                    Err(e) => {
-                        error!("Failed to retrieve updated tag {}: {:?}", req.tag_id, e);
-                        Err(Status::new(
-                            Code::Internal,
-                            format!("Database error: {}", e),
-                        ))
-                    }
-                }
+        let parent_change = if req.clear_parent {
+            Some(None)
+        } else {
+            req.parent_id.as_deref().map(Some)
+        };
+        if let Some(parent_id) = parent_change {
+            if let Err(e) = db.set_tag_parent(&req.tag_id, parent_id) {
+                error!("Failed to move tag '{}': {:?}", req.tag_id, e);
+                return Err(tag_error_to_status(e));
+            }
+        }
+
+        let color_change = if req.clear_color {
+            Some(None)
+        } else {
+            req.color.as_deref().map(Some)
+        };
+        if let Some(color) = color_change {
+            if let Err(e) = db.set_tag_color(&req.tag_id, color) {
+                error!("Failed to set color of tag '{}': {:?}", req.tag_id, e);
+                return Err(tag_error_to_status(e));
+            }
+        }
+
+        // Get the updated tag details to return in response
+        match db.get_tag_by_id(&req.tag_id) {
+            Ok(Some(tag)) => {
+                let response = UpdateTagResponse {
+                    tag: Some(convert_tag_to_proto(tag)),
+                };
+                debug!("Successfully updated tag: {}", req.tag_id);
+                Ok(Response::new(response))
+            }
+            Ok(None) => {
+                error!("Tag {} not found after update", req.tag_id);
+                Err(Status::new(Code::NotFound, "Tag not found"))
             }
             Err(e) => {
-                error!("Failed to update tag '{}': {:?}", req.tag_id, e);
+                error!("Failed to retrieve updated tag {}: {:?}", req.tag_id, e);
                 Err(Status::new(
                     Code::Internal,
                     format!("Database error: {}", e),
@@ -329,14 +354,10 @@ impl TagsHandler {
         let mut db = self.db.lock().await;
 
         match db.get_tag_by_id(&req.tag_id) {
-            Ok(Some((id, name, created_at))) => {
-                let tag = Tag {
-                    id,
-                    name,
-                    created_at,
+            Ok(Some(tag)) => {
+                let response = GetTagResponse {
+                    tag: Some(convert_tag_to_proto(tag)),
                 };
-
-                let response = GetTagResponse { tag: Some(tag) };
                 Ok(Response::new(response))
             }
             Ok(None) => {
@@ -365,14 +386,7 @@ impl TagsHandler {
 
         match db.search_tags(&req.query, req.limit, req.offset) {
             Ok((tag_data, total_count)) => {
-                let tags = tag_data
-                    .into_iter()
-                    .map(|(id, name, created_at)| Tag {
-                        id,
-                        name,
-                        created_at,
-                    })
-                    .collect();
+                let tags = tag_data.into_iter().map(convert_tag_to_proto).collect();
 
                 let response = SearchTagsResponse { tags, total_count };
                 Ok(Response::new(response))
@@ -451,23 +465,13 @@ impl TagsHandler {
                 // Convert most used tags to proto format
                 let most_used_tags = stats.most_used_tags
                     .into_iter()
-                    .map(|info| super::super::tags::TagUsageInfo {
-                        tag_id: info.tag_id,
-                        name: info.name,
-                        project_count: info.project_count,
-                        usage_percentage: info.usage_percentage,
-                    })
+                    .map(convert_tag_usage_to_proto)
                     .collect();
 
                 // Convert least used tags to proto format
                 let least_used_tags = stats.least_used_tags
                     .into_iter()
-                    .map(|info| super::super::tags::TagUsageInfo {
-                        tag_id: info.tag_id,
-                        name: info.name,
-                        project_count: info.project_count,
-                        usage_percentage: info.usage_percentage,
-                    })
+                    .map(convert_tag_usage_to_proto)
                     .collect();
 
                 let proto_stats = super::super::tags::TagStatistics {
@@ -511,16 +515,12 @@ impl TagsHandler {
             req.sort_by,
             req.sort_desc,
             req.min_usage_count,
+            req.group.as_deref(),
         ) {
             Ok((tag_data, total_count)) => {
                 let tags = tag_data
                     .into_iter()
-                    .map(|info| super::super::tags::TagUsageInfo {
-                        tag_id: info.tag_id,
-                        name: info.name,
-                        project_count: info.project_count,
-                        usage_percentage: info.usage_percentage,
-                    })
+                    .map(convert_tag_usage_to_proto)
                     .collect();
 
                 let response = GetAllTagsWithUsageResponse { tags, total_count };
@@ -536,3 +536,23 @@ impl TagsHandler {
         }
     }
 }
+
+fn convert_tag_usage_to_proto(info: DbTagUsageInfo) -> TagUsageInfo {
+    TagUsageInfo {
+        tag_id: info.tag_id,
+        name: info.name,
+        project_count: info.project_count,
+        usage_percentage: info.usage_percentage,
+        color: info.color,
+        parent_id: info.parent_id,
+        total_project_count: info.total_project_count,
+    }
+}
+
+fn tag_error_to_status(e: DatabaseError) -> Status {
+    match e {
+        DatabaseError::InvalidOperation(msg) => Status::invalid_argument(msg),
+        DatabaseError::NotFound(msg) => Status::not_found(msg),
+        e => Status::internal(format!("Database error: {}", e)),
+    }
+}
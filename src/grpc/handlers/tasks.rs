@@ -3,9 +3,14 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use tonic::{Code, Request, Response, Status};
 
+use crate::database::tasks::{
+    validate_priority, ArrangementPosition, TaskDetails, TaskSearchFilters, DEFAULT_TASK_PRIORITY,
+};
 use crate::database::LiveSetDatabase;
+use crate::error::DatabaseError;
 use super::super::tasks::*;
 use super::super::common::*;
+use super::utils::convert_task_to_proto;
 
 // MOVE FROM server.rs:
 // - get_project_tasks method (lines ~542-564)
@@ -44,18 +49,14 @@ impl TasksHandler {
         let req = request.into_inner();
         let mut db = self.db.lock().await;
 
-        match db.get_project_tasks(&req.project_id) {
+        let result = match req.sort_order() {
+            TaskSortOrder::TaskSortManual => db.get_project_tasks(&req.project_id),
+            TaskSortOrder::TaskSortArrangement => db.get_project_tasks_by_arrangement(&req.project_id),
+        };
+
+        match result {
             Ok(task_data) => {
-                let tasks = task_data
-                    .into_iter()
-                    .map(|(id, description, completed, created_at)| Task {
-                        id,
-                        project_id: req.project_id.clone(),
-                        description,
-                        completed,
-                        created_at,
-                    })
-                    .collect();
+                let tasks = task_data.into_iter().map(convert_task_to_proto).collect();
 
                 let response = GetProjectTasksResponse { tasks };
                 Ok(Response::new(response))
@@ -80,20 +81,28 @@ impl TasksHandler {
         debug!("CreateTask request: {:?}", request);
 
         let req = request.into_inner();
+        if req.arrangement_beat.is_some() && req.arrangement_bar.is_none() {
+            return Err(Status::invalid_argument("arrangement_beat requires arrangement_bar"));
+        }
         let mut db = self.db.lock().await;
 
-        match db.add_task(&req.project_id, &req.description) {
+        let details = TaskDetails {
+            priority: req.priority.unwrap_or(DEFAULT_TASK_PRIORITY),
+            due_date: req.due_date,
+            assignee: req.assignee.clone(),
+            arrangement_position: req.arrangement_bar.map(|bar| ArrangementPosition {
+                bar,
+                beat: req.arrangement_beat.unwrap_or(1.0),
+            }),
+            track_name: req.track_name.clone(),
+        };
+
+        match db.add_task_with_details(&req.project_id, &req.description, &details) {
             Ok(task_id) => {
                 // Get the created task to return full details
                 match db.get_task(&task_id) {
-                    Ok(Some((id, project_id, description, completed, created_at))) => {
-                        let task = Task {
-                            id,
-                            project_id,
-                            description,
-                            completed,
-                            created_at,
-                        };
+                    Ok(Some(task_data)) => {
+                        let task = convert_task_to_proto(task_data);
 
                         let response = CreateTaskResponse { task: Some(task) };
                         Ok(Response::new(response))
@@ -108,6 +117,9 @@ impl TasksHandler {
                     }
                 }
             }
+            Err(DatabaseError::InvalidOperation(msg)) => {
+                Err(Status::invalid_argument(msg))
+            }
             Err(e) => {
                 error!(
                     "Failed to create task for project {}: {:?}",
@@ -128,7 +140,39 @@ impl TasksHandler {
         debug!("UpdateTask request: {:?}", request);
 
         let req = request.into_inner();
+
+        // Fields are written one at a time, so all of them are checked before the first
+        // is written and a rejected update changes nothing
+        let arrangement_position = if req.clear_arrangement_position {
+            Some(None)
+        } else {
+            req.arrangement_bar.map(|bar| {
+                Some(ArrangementPosition {
+                    bar,
+                    beat: req.arrangement_beat.unwrap_or(1.0),
+                })
+            })
+        };
+        if req.arrangement_beat.is_some() && req.arrangement_bar.is_none() {
+            return Err(Status::invalid_argument("arrangement_beat requires arrangement_bar"));
+        }
+        if let Some(priority) = req.priority {
+            validate_priority(priority).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        }
+
         let mut db = self.db.lock().await;
+        if let Some(Some(position)) = arrangement_position {
+            match db.validate_task_arrangement_position(&req.task_id, position) {
+                Ok(()) => {}
+                Err(DatabaseError::InvalidOperation(msg)) => {
+                    return Err(Status::invalid_argument(msg));
+                }
+                Err(e) => {
+                    error!("Failed to check task arrangement position for {}: {:?}", req.task_id, e);
+                    return Err(Status::internal(format!("Database error: {}", e)));
+                }
+            }
+        }
 
         // Update description if provided
         if let Some(description) = req.description {
@@ -158,16 +202,78 @@ impl TasksHandler {
             }
         }
 
+        // Update priority if provided
+        if let Some(priority) = req.priority {
+            match db.set_task_priority(&req.task_id, priority) {
+                Ok(()) => {}
+                Err(DatabaseError::InvalidOperation(msg)) => {
+                    return Err(Status::invalid_argument(msg));
+                }
+                Err(e) => {
+                    error!("Failed to update task priority for {}: {:?}", req.task_id, e);
+                    return Err(Status::internal(format!("Database error: {}", e)));
+                }
+            }
+        }
+
+        // Update or clear due date
+        let due_date = if req.clear_due_date { Some(None) } else { req.due_date.map(Some) };
+        if let Some(due_date) = due_date {
+            if let Err(e) = db.set_task_due_date(&req.task_id, due_date) {
+                error!("Failed to update task due date for {}: {:?}", req.task_id, e);
+                return Err(Status::internal(format!("Database error: {}", e)));
+            }
+        }
+
+        // Update or clear assignee
+        let assignee = if req.clear_assignee { Some(None) } else { req.assignee.as_deref().map(Some) };
+        if let Some(assignee) = assignee {
+            if let Err(e) = db.set_task_assignee(&req.task_id, assignee) {
+                error!("Failed to update task assignee for {}: {:?}", req.task_id, e);
+                return Err(Status::internal(format!("Database error: {}", e)));
+            }
+        }
+
+        // Update or clear arrangement anchor
+        if let Some(position) = arrangement_position {
+            match db.set_task_arrangement_position(&req.task_id, position) {
+                Ok(()) => {}
+                Err(DatabaseError::InvalidOperation(msg)) => {
+                    return Err(Status::invalid_argument(msg));
+                }
+                Err(e) => {
+                    error!("Failed to update task arrangement position for {}: {:?}", req.task_id, e);
+                    return Err(Status::internal(format!("Database error: {}", e)));
+                }
+            }
+        }
+
+        let track_name = if req.clear_track_name { Some(None) } else { req.track_name.as_deref().map(Some) };
+        if let Some(track_name) = track_name {
+            if let Err(e) = db.set_task_track_name(&req.task_id, track_name) {
+                error!("Failed to update task track name for {}: {:?}", req.task_id, e);
+                return Err(Status::internal(format!("Database error: {}", e)));
+            }
+        }
+
+        // Move task within its project if requested
+        if let Some(position) = req.position {
+            match db.reorder_task(&req.task_id, position) {
+                Ok(()) => {}
+                Err(DatabaseError::SqliteError(rusqlite::Error::QueryReturnedNoRows)) => {
+                    return Err(Status::not_found(format!("Task not found: {}", req.task_id)));
+                }
+                Err(e) => {
+                    error!("Failed to reorder task {}: {:?}", req.task_id, e);
+                    return Err(Status::internal(format!("Database error: {}", e)));
+                }
+            }
+        }
+
         // Get the updated task to return
         match db.get_task(&req.task_id) {
-            Ok(Some((id, project_id, description, completed, created_at))) => {
-                let task = Task {
-                    id,
-                    project_id,
-                    description,
-                    completed,
-                    created_at,
-                };
+            Ok(Some(task_data)) => {
+                let task = convert_task_to_proto(task_data);
 
                 let response = UpdateTaskResponse { task: Some(task) };
                 Ok(Response::new(response))
@@ -228,6 +334,26 @@ impl TasksHandler {
         }
     }
 
+    pub async fn get_overdue_tasks(
+        &self,
+        request: Request<GetOverdueTasksRequest>,
+    ) -> Result<Response<GetOverdueTasksResponse>, Status> {
+        debug!("GetOverdueTasks request: {:?}", request);
+
+        let mut db = self.db.lock().await;
+
+        match db.get_overdue_tasks() {
+            Ok(task_data) => {
+                let tasks = task_data.into_iter().map(convert_task_to_proto).collect();
+                Ok(Response::new(GetOverdueTasksResponse { tasks }))
+            }
+            Err(e) => {
+                error!("Failed to get overdue tasks: {:?}", e);
+                Err(Status::internal(format!("Database error: {}", e)))
+            }
+        }
+    }
+
     // Batch Task Operations
     pub async fn batch_update_task_status(
         &self,
@@ -312,25 +438,19 @@ impl TasksHandler {
         let req = request.into_inner();
         let mut db = self.db.lock().await;
 
-        match db.search_tasks(
-            &req.project_id,
-            &req.query,
-            req.limit,
-            req.offset,
-            req.completed_only,
-            req.pending_only,
-        ) {
+        let filters = TaskSearchFilters {
+            completed_only: req.completed_only,
+            pending_only: req.pending_only,
+            min_priority: req.min_priority,
+            assignee: req.assignee,
+            due_before: req.due_before,
+            due_after: req.due_after,
+            overdue_only: req.overdue_only,
+        };
+
+        match db.search_tasks(&req.project_id, &req.query, req.limit, req.offset, &filters) {
             Ok((task_data, total_count)) => {
-                let tasks = task_data
-                    .into_iter()
-                    .map(|(id, description, completed, created_at)| Task {
-                        id,
-                        project_id: req.project_id.clone(),
-                        description,
-                        completed,
-                        created_at,
-                    })
-                    .collect();
+                let tasks = task_data.into_iter().map(convert_task_to_proto).collect();
 
                 let response = SearchTasksResponse { tasks, total_count };
                 Ok(Response::new(response))
@@ -380,6 +500,8 @@ impl TasksHandler {
                     tasks_created_this_month: stats.tasks_created_this_month,
                     tasks_completed_this_month: stats.tasks_completed_this_month,
                     monthly_trends,
+                    overdue_tasks: stats.overdue_tasks,
+                    average_completion_seconds: stats.average_completion_seconds,
                 };
 
                 let response = GetTaskStatisticsResponse {
@@ -1,8 +1,81 @@
+use crate::database::project_audio::ProjectAudioFile as DbProjectAudioFile;
+use crate::database::tags::TagInfo;
+use crate::database::tasks::ProjectTask;
 use crate::database::LiveSetDatabase;
 use crate::error::DatabaseError;
-use super::super::common::{AbletonVersion, KeySignature, Plugin, Project, Sample, TimeSignature, Task, Tag};
+use crate::media::AudioAnalysis as DbAudioAnalysis;
+use super::super::common::{
+    AbletonVersion, AudioAnalysis, KeySignature, MediaFile, Plugin, Project, ProjectAudioFile, Sample,
+    TimeSignature, Task, Tag,
+};
 use crate::live_set::LiveSet;
 
+pub fn convert_task_to_proto(task: ProjectTask) -> Task {
+    Task {
+        id: task.id,
+        project_id: task.project_id,
+        description: task.description,
+        completed: task.completed,
+        created_at: task.created_at,
+        priority: task.priority,
+        due_date: task.due_date,
+        position: task.position,
+        assignee: task.assignee,
+        completed_at: task.completed_at,
+        arrangement_bar: task.arrangement_position.map(|p| p.bar),
+        arrangement_beat: task.arrangement_position.map(|p| p.beat),
+        arrangement_seconds: task.arrangement_seconds,
+        track_name: task.track_name,
+    }
+}
+
+pub fn convert_tag_to_proto(tag: TagInfo) -> Tag {
+    Tag {
+        group: tag.group().map(str::to_string),
+        id: tag.id,
+        name: tag.name,
+        created_at: tag.created_at,
+        color: tag.color,
+        parent_id: tag.parent_id,
+    }
+}
+
+pub fn convert_project_audio_file_to_proto(audio_file: DbProjectAudioFile) -> ProjectAudioFile {
+    let media_file = audio_file.media_file;
+    ProjectAudioFile {
+        media_file_id: media_file.id.clone(),
+        label: audio_file.label,
+        version: audio_file.version,
+        is_primary: audio_file.is_primary,
+        uploaded_at: audio_file.uploaded_at.timestamp(),
+        media_file: Some(MediaFile {
+            id: media_file.id,
+            original_filename: media_file.original_filename,
+            file_extension: media_file.file_extension,
+            media_type: media_file.media_type.as_str().to_string(),
+            file_size_bytes: media_file.file_size_bytes as i64,
+            mime_type: media_file.mime_type,
+            uploaded_at: media_file.uploaded_at.timestamp(),
+            checksum: media_file.checksum,
+        }),
+        analysis: audio_file.analysis.as_ref().map(convert_audio_analysis_to_proto),
+    }
+}
+
+pub fn convert_audio_analysis_to_proto(analysis: &DbAudioAnalysis) -> AudioAnalysis {
+    AudioAnalysis {
+        integrated_lufs: analysis.integrated_lufs,
+        true_peak_dbtp: analysis.true_peak_dbtp,
+        loudness_range_lu: analysis.loudness_range_lu,
+        rms_dbfs: analysis.rms_dbfs,
+        bpm: analysis.bpm,
+        key: analysis.key.as_ref().map(|key| KeySignature {
+            tonic: key.tonic.to_string(),
+            scale: key.scale.to_string(),
+        }),
+    }
+}
+
 pub fn convert_live_set_to_proto(
     live_set: LiveSet,
     db: &mut LiveSetDatabase,
@@ -12,14 +85,31 @@ pub fn convert_live_set_to_proto(
     // Load notes from database
     let notes = db.get_project_notes(&project_id)?.unwrap_or_default();
 
-    // Load audio file ID from database
-    let audio_file_id = db
-        .get_project_audio_file(&project_id)?
-        .map(|media_file| media_file.id);
+    // Load audio files from database, the primary one first
+    let audio_files: Vec<ProjectAudioFile> = db
+        .get_project_audio_files(&project_id)?
+        .into_iter()
+        .map(convert_project_audio_file_to_proto)
+        .collect();
+    let audio_file_id = audio_files
+        .iter()
+        .find(|audio_file| audio_file.is_primary)
+        .map(|audio_file| audio_file.media_file_id.clone());
 
     // Load collection associations from database
     let collection_ids = db.get_collections_for_project(&project_id)?;
 
+    // Load workflow status from database
+    let (status, status_changed_at) = db.get_project_status(&project_id)?.unzip();
+
+    // Load rating, favorite flag and open tracking from database
+    let engagement = db.get_project_engagement(&project_id)?.unwrap_or_default();
+
+    // Load set kind and project folder from database
+    let (set_kind, project_folder) = db
+        .get_project_location(&project_id)?
+        .unwrap_or_else(|| (live_set.kind(), None));
+
     // Load tag data from database
     let tag_data = db.get_project_tag_data(&project_id)?;
 
@@ -27,26 +117,11 @@ pub fn convert_live_set_to_proto(
     let tasks = db
         .get_project_tasks(&project_id)?
         .into_iter()
-        .map(
-            |(task_id, description, completed, created_at)| Task {
-                id: task_id,
-                description,
-                completed,
-                project_id: project_id.clone(), // Add project_id to Task
-                created_at,
-            },
-        )
+        .map(convert_task_to_proto)
         .collect();
 
     // Convert tags with proper IDs and creation timestamps
-    let tags = tag_data
-        .into_iter()
-        .map(|(tag_id, tag_name, created_at)| Tag {
-            id: tag_id,
-            name: tag_name,
-            created_at,
-        })
-        .collect();
+    let tags = tag_data.into_iter().map(convert_tag_to_proto).collect();
 
     Ok(Project {
         id: project_id,
@@ -114,5 +189,14 @@ pub fn convert_live_set_to_proto(
         tasks,
         collection_ids,
         audio_file_id,
+        status,
+        status_changed_at,
+        rating: engagement.rating,
+        is_favorite: engagement.is_favorite,
+        last_opened_at: engagement.last_opened_at,
+        open_count: engagement.open_count,
+        set_kind: set_kind.to_string(),
+        project_folder,
+        audio_files,
     })
 }
@@ -8,6 +8,7 @@ use tonic::{Request, Response, Status};
 use crate::config::CONFIG;
 use crate::database::LiveSetDatabase;
 use crate::media::{MediaConfig, MediaStorageManager};
+use crate::watcher::events::WatcherEventBus;
 
 use super::handlers::*;
 use super::common::*;
@@ -62,7 +63,7 @@ impl StudioProjectManagerServer {
         let scan_status = Arc::new(Mutex::new(ScanStatus::ScanUnknown));
         let scan_progress = Arc::new(Mutex::new(None));
         let watcher = Arc::new(Mutex::new(None));
-        let watcher_events = Arc::new(Mutex::new(None));
+        let watcher_events = Arc::new(WatcherEventBus::default());
         let start_time = Instant::now();
 
         Ok(Self {
@@ -92,7 +93,7 @@ impl StudioProjectManagerServer {
         let scan_status = Arc::new(Mutex::new(ScanStatus::ScanUnknown));
         let scan_progress = Arc::new(Mutex::new(None));
         let watcher = Arc::new(Mutex::new(None));
-        let watcher_events = Arc::new(Mutex::new(None));
+        let watcher_events = Arc::new(WatcherEventBus::default());
         let start_time = Instant::now();
 
         Self {
@@ -204,6 +205,76 @@ impl project_service_server::ProjectService for StudioProjectManagerServer {
         self.projects_handler.batch_delete_projects(request).await
     }
 
+    async fn get_status_pipeline(
+        &self,
+        request: Request<GetStatusPipelineRequest>,
+    ) -> Result<Response<GetStatusPipelineResponse>, Status> {
+        self.projects_handler.get_status_pipeline(request).await
+    }
+
+    async fn update_status_pipeline(
+        &self,
+        request: Request<UpdateStatusPipelineRequest>,
+    ) -> Result<Response<UpdateStatusPipelineResponse>, Status> {
+        self.projects_handler.update_status_pipeline(request).await
+    }
+
+    async fn set_project_status(
+        &self,
+        request: Request<SetProjectStatusRequest>,
+    ) -> Result<Response<SetProjectStatusResponse>, Status> {
+        self.projects_handler.set_project_status(request).await
+    }
+
+    async fn get_project_status_history(
+        &self,
+        request: Request<GetProjectStatusHistoryRequest>,
+    ) -> Result<Response<GetProjectStatusHistoryResponse>, Status> {
+        self.projects_handler.get_project_status_history(request).await
+    }
+
+    async fn set_project_rating(
+        &self,
+        request: Request<SetProjectRatingRequest>,
+    ) -> Result<Response<SetProjectRatingResponse>, Status> {
+        self.projects_handler.set_project_rating(request).await
+    }
+
+    async fn set_project_favorite(
+        &self,
+        request: Request<SetProjectFavoriteRequest>,
+    ) -> Result<Response<SetProjectFavoriteResponse>, Status> {
+        self.projects_handler.set_project_favorite(request).await
+    }
+
+    async fn record_project_opened(
+        &self,
+        request: Request<RecordProjectOpenedRequest>,
+    ) -> Result<Response<RecordProjectOpenedResponse>, Status> {
+        self.projects_handler.record_project_opened(request).await
+    }
+
+    async fn get_forgotten_gems(
+        &self,
+        request: Request<GetForgottenGemsRequest>,
+    ) -> Result<Response<GetForgottenGemsResponse>, Status> {
+        self.projects_handler.get_forgotten_gems(request).await
+    }
+
+    async fn get_project_folders(
+        &self,
+        request: Request<GetProjectFoldersRequest>,
+    ) -> Result<Response<GetProjectFoldersResponse>, Status> {
+        self.projects_handler.get_project_folders(request).await
+    }
+
+    async fn get_project_folder(
+        &self,
+        request: Request<GetProjectFolderRequest>,
+    ) -> Result<Response<GetProjectFolderResponse>, Status> {
+        self.projects_handler.get_project_folder(request).await
+    }
+
     async fn get_project_statistics(
         &self,
         request: Request<GetProjectStatisticsRequest>,
@@ -491,6 +562,13 @@ impl task_service_server::TaskService for StudioProjectManagerServer {
         self.tasks_handler.delete_task(request).await
     }
 
+    async fn get_overdue_tasks(
+        &self,
+        request: Request<GetOverdueTasksRequest>,
+    ) -> Result<Response<GetOverdueTasksResponse>, Status> {
+        self.tasks_handler.get_overdue_tasks(request).await
+    }
+
     async fn batch_update_task_status(
         &self,
         request: Request<BatchUpdateTaskStatusRequest>,
@@ -546,6 +624,27 @@ impl media_service_server::MediaService for StudioProjectManagerServer {
         self.media_handler.download_media(request).await
     }
 
+    async fn start_upload(
+        &self,
+        request: Request<StartUploadRequest>,
+    ) -> Result<Response<StartUploadResponse>, Status> {
+        self.media_handler.start_upload(request).await
+    }
+
+    async fn get_upload_status(
+        &self,
+        request: Request<GetUploadStatusRequest>,
+    ) -> Result<Response<GetUploadStatusResponse>, Status> {
+        self.media_handler.get_upload_status(request).await
+    }
+
+    async fn cancel_upload(
+        &self,
+        request: Request<CancelUploadRequest>,
+    ) -> Result<Response<CancelUploadResponse>, Status> {
+        self.media_handler.cancel_upload(request).await
+    }
+
     async fn delete_media(
         &self,
         request: Request<DeleteMediaRequest>,
@@ -567,6 +666,27 @@ impl media_service_server::MediaService for StudioProjectManagerServer {
         self.media_handler.remove_project_audio_file(request).await
     }
 
+    async fn get_project_audio_files(
+        &self,
+        request: Request<GetProjectAudioFilesRequest>,
+    ) -> Result<Response<GetProjectAudioFilesResponse>, Status> {
+        self.media_handler.get_project_audio_files(request).await
+    }
+
+    async fn update_project_audio_file(
+        &self,
+        request: Request<UpdateProjectAudioFileRequest>,
+    ) -> Result<Response<UpdateProjectAudioFileResponse>, Status> {
+        self.media_handler.update_project_audio_file(request).await
+    }
+
+    async fn compare_project_audio_files(
+        &self,
+        request: Request<CompareProjectAudioFilesRequest>,
+    ) -> Result<Response<CompareProjectAudioFilesResponse>, Status> {
+        self.media_handler.compare_project_audio_files(request).await
+    }
+
     async fn list_media_files(
         &self,
         request: Request<ListMediaFilesRequest>,
@@ -601,6 +721,20 @@ impl media_service_server::MediaService for StudioProjectManagerServer {
     ) -> Result<Response<CleanupOrphanedMediaResponse>, Status> {
         self.media_handler.cleanup_orphaned_media(request).await
     }
+
+    async fn get_waveform(
+        &self,
+        request: Request<GetWaveformRequest>,
+    ) -> Result<Response<GetWaveformResponse>, Status> {
+        self.media_handler.get_waveform(request).await
+    }
+
+    async fn analyze_audio_file(
+        &self,
+        request: Request<AnalyzeAudioFileRequest>,
+    ) -> Result<Response<AnalyzeAudioFileResponse>, Status> {
+        self.media_handler.analyze_audio_file(request).await
+    }
 }
 
 // System Service Implementation
@@ -626,6 +760,20 @@ impl system_service_server::SystemService for StudioProjectManagerServer {
     ) -> Result<Response<ExportStatisticsResponse>, Status> {
         self.system_handler.export_statistics(request).await
     }
+
+    async fn get_year_in_review(
+        &self,
+        request: Request<GetYearInReviewRequest>,
+    ) -> Result<Response<GetYearInReviewResponse>, Status> {
+        self.system_handler.get_year_in_review(request).await
+    }
+
+    async fn get_activity_analytics(
+        &self,
+        request: Request<GetActivityAnalyticsRequest>,
+    ) -> Result<Response<GetActivityAnalyticsResponse>, Status> {
+        self.system_handler.get_activity_analytics(request).await
+    }
 }
 
 // Scanning Service Implementation
@@ -647,6 +795,41 @@ impl scanning_service_server::ScanningService for StudioProjectManagerServer {
         self.system_handler.get_scan_status(request).await
     }
 
+    async fn cancel_scan(
+        &self,
+        request: Request<CancelScanRequest>,
+    ) -> Result<Response<CancelScanResponse>, Status> {
+        self.system_handler.cancel_scan(request).await
+    }
+
+    async fn pause_scan(
+        &self,
+        request: Request<PauseScanRequest>,
+    ) -> Result<Response<PauseScanResponse>, Status> {
+        self.system_handler.pause_scan(request).await
+    }
+
+    async fn resume_scan(
+        &self,
+        request: Request<ResumeScanRequest>,
+    ) -> Result<Response<ResumeScanResponse>, Status> {
+        self.system_handler.resume_scan(request).await
+    }
+
+    async fn get_scan_errors(
+        &self,
+        request: Request<GetScanErrorsRequest>,
+    ) -> Result<Response<GetScanErrorsResponse>, Status> {
+        self.system_handler.get_scan_errors(request).await
+    }
+
+    async fn retry_scan_errors(
+        &self,
+        request: Request<RetryScanErrorsRequest>,
+    ) -> Result<Response<RetryScanErrorsResponse>, Status> {
+        self.system_handler.retry_scan_errors(request).await
+    }
+
     async fn add_single_project(
         &self,
         request: Request<AddSingleProjectRequest>,
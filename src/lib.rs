@@ -128,14 +128,16 @@ pub use utils::decompress_gzip_file;
 // Core processing functions
 use crate::database::batch::BatchInsertManager;
 use crate::error::LiveSetError;
-use crate::live_set::LiveSetPreprocessed;
-use crate::scan::parallel::ParallelParser;
+use crate::scan::incremental::{plan_scan, ScanCounts, ScanOptions};
+use crate::scan::jobs::SCAN_JOBS;
+use crate::scan::quarantine::{record_failure, skip_quarantined};
 use crate::scan::project_scanner::ProjectPathScanner;
-use log::{debug, error, info, trace};
+use crate::scan::roots::ScanRoots;
+use log::{debug, error, info};
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::mpsc::RecvTimeoutError;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Processes all Ableton Live projects found in configured directories.
 ///
@@ -239,23 +241,70 @@ pub fn process_projects() -> Result<(), LiveSetError> {
 /// The callback will receive updates during these phases:
 /// - `"starting"`: Initial setup and validation
 /// - `"discovering"`: Scanning directories for project files
-/// - `"preprocessing"`: Extracting basic metadata for filtering
+/// - `"preprocessing"`: Comparing found sets against the index to skip unchanged ones
 /// - `"parsing"`: Full parsing of project files
 /// - `"inserting"`: Saving results to database
 /// - `"completed"`: Operation finished successfully
 pub fn process_projects_with_progress<F>(
-    mut progress_callback: Option<F>,
+    progress_callback: Option<F>,
 ) -> Result<(), LiveSetError>
 where
     F: FnMut(u32, u32, f32, String, &str) + Send + 'static,
 {
-    debug!("Starting process_projects_with_progress");
+    let progress_callback = progress_callback.map(|mut callback| {
+        move |completed: u32,
+              total: u32,
+              progress: f32,
+              message: String,
+              phase: &str,
+              _counts: &ScanCounts| { callback(completed, total, progress, message, phase) }
+    });
+    process_projects_with_options(&ScanOptions::default(), progress_callback).map(|_| ())
+}
+
+/// Processes all Ableton Live projects with scan options and per-state counts.
+///
+/// By default the scan is incremental: each set found is compared against the
+/// `projects` table by path, size and modification time, and only hashed when those
+/// differ. Only new sets and sets whose content changed are parsed. Changed sets are
+/// updated in place so they keep their tags, notes and other metadata, and projects
+/// under a scanned directory whose file has disappeared are marked deleted. Set
+/// [`ScanOptions::force`] to parse every set found.
+///
+/// Sets are parsed on the shared [`scan::SCAN_JOBS`] workers, so the scan can be
+/// paused, resumed or cancelled from another thread while it runs.
+///
+/// # Arguments
+///
+/// * `options` - How to scan
+/// * `progress_callback` - Optional callback receiving the same updates as
+///   [`process_projects_with_progress`], plus the [`ScanCounts`] so far
+///
+/// # Returns
+///
+/// Returns how many sets were new, updated, skipped, removed or failed to parse.
+///
+/// # Errors
+///
+/// Returns [`LiveSetError`] if the configuration can't be loaded or the database
+/// can't be read or written. Sets that fail to parse are counted, not returned.
+/// Returns [`LiveSetError::Cancelled`] if the scan was cancelled; sets parsed before
+/// the cancel are still saved.
+pub fn process_projects_with_options<F>(
+    options: &ScanOptions,
+    mut progress_callback: Option<F>,
+) -> Result<ScanCounts, LiveSetError>
+where
+    F: FnMut(u32, u32, f32, String, &str, &ScanCounts) + Send + 'static,
+{
+    debug!("Starting process_projects_with_options: {:?}", options);
+    let mut counts = ScanCounts::default();
 
     // Helper macro to call progress callback if provided
     macro_rules! progress {
         ($completed:expr, $total:expr, $progress:expr, $message:expr, $phase:expr) => {
             if let Some(ref mut callback) = progress_callback {
-                callback($completed, $total, $progress, $message, $phase);
+                callback($completed, $total, $progress, $message, $phase, &counts);
             }
         };
     }
@@ -287,93 +336,124 @@ where
 
     let scanner = ProjectPathScanner::new()?;
     let mut found_projects = HashSet::new();
+    let mut scanned_roots = Vec::new();
 
     // Check if configuration is ready for scanning
     if config.needs_setup() {
         info!("Configuration needs setup - no project paths configured");
         progress!(1, 1, 1.0, "Setup required: No project paths configured".to_string(), "completed");
-        return Ok(());
+        return Ok(counts);
     }
 
-    // Scan all configured directories
-    for path in &config.paths {
-        let path = PathBuf::from(path);
+    // Scan all configured directories, each with its own exclusions and limits
+    let roots = ScanRoots::from_config(config)?;
+    for rules in roots.iter() {
+        let path = rules.root().to_path_buf();
         if path.exists() {
             info!("Scanning directory: {}", path.display());
-            let projects = scanner.scan_directory(&path)?;
+            let projects = scanner.scan_root(rules, &path)?;
             debug!("Found {} projects in {}", projects.len(), path.display());
             found_projects.extend(projects);
+            scanned_roots.push(path);
         } else {
             error!("Directory does not exist: {}", path.display());
         }
     }
 
-    if found_projects.is_empty() {
-        info!("No Ableton projects found in configured paths");
-        progress!(1, 1, 1.0, "No projects found".to_string(), "completed");
-        return Ok(());
-    }
-
     let total_found = found_projects.len();
     progress!(
         0,
         total_found as u32,
         0.0,
-        format!("Found {} projects, preprocessing...", total_found),
+        format!("Found {} projects, checking for changes...", total_found),
         "preprocessing"
     );
 
-    // Preprocess and filter projects
-    let preprocessed = preprocess_projects(found_projects)?;
-    let projects_to_parse = filter_unchanged_projects(preprocessed, &db)?;
+    // Sets that keep failing are left alone until they change on disk
+    if !options.force {
+        counts.quarantined = skip_quarantined(&mut found_projects, &db.get_scan_errors(true)?);
+    }
+
+    // Compare against the index so unchanged sets are never decompressed
+    let indexed = db.get_indexed_files()?;
+    let plan = plan_scan(&found_projects, &scanned_roots, &indexed, options.force);
+    counts.skipped = plan.unchanged as u32;
 
+    let touched: Vec<_> = plan
+        .touched
+        .iter()
+        .map(|(path, state)| (path.as_path(), state.size, state.modified_at))
+        .collect();
+    db.record_file_states(&touched)?;
+
+    let projects_to_parse = plan.paths_to_parse();
     if projects_to_parse.is_empty() {
-        info!("No projects need updating");
+        counts.removed = db.mark_removed_projects(&plan.removed)?;
+        let message = if total_found == 0 {
+            info!("No Ableton projects found in configured paths");
+            "No projects found".to_string()
+        } else {
+            info!("No projects need updating");
+            format!("All projects up to date ({})", counts.summary())
+        };
         progress!(
             total_found as u32,
             total_found as u32,
             1.0,
-            "All projects up to date".to_string(),
+            message,
             "completed"
         );
-        return Ok(());
+        return Ok(counts);
     }
 
     let total_projects = projects_to_parse.len();
-    info!("Found {} projects that need parsing", total_projects);
+    info!(
+        "Found {} projects that need parsing ({} new, {} changed, {} unchanged)",
+        total_projects,
+        plan.new.len(),
+        plan.changed.len(),
+        plan.unchanged
+    );
     progress!(
         0,
         total_projects as u32,
         0.0,
-        format!("Parsing {} projects...", total_projects),
+        format!(
+            "Parsing {} projects ({} unchanged skipped)...",
+            total_projects, plan.unchanged
+        ),
         "parsing"
     );
 
-    // Create parallel parser with number of threads based on project count
-    let thread_count = (total_projects / 2).max(1).min(4);
-    debug!("Creating parallel parser with {} threads", thread_count);
-    let parser = ParallelParser::new(thread_count);
-
-    // Submit filtered projects for parsing
-    debug!("Submitting {} projects to parser", total_projects);
-    let receiver = {
-        let receiver = parser.get_results_receiver();
-        parser.submit_paths(projects_to_parse)?;
-        receiver
-    };
-    // Parser is dropped here, which will close the work channel
+    // Queue the sets on the shared scan workers, behind any watcher rescans
+    debug!(
+        "Queueing {} projects on {} scan workers",
+        total_projects,
+        SCAN_JOBS.worker_count()
+    );
+    let job = SCAN_JOBS.start_scan(projects_to_parse);
+    let receiver = job.results();
 
     let mut successful_live_sets = Vec::new();
+    let mut parsed_paths = Vec::new();
 
     // Collect results from parser with progress tracking
     debug!("Starting to collect parser results");
     let mut completed_count = 0;
+    let mut last_heartbeat = Instant::now();
+    let mut was_paused = false;
 
     while completed_count < total_projects {
-        match receiver.recv_timeout(Duration::from_secs(5)) {
+        match receiver.recv_timeout(Duration::from_millis(500)) {
+            // Sets interrupted by a cancel aren't failures
+            Ok(Err((_, LiveSetError::Cancelled))) => {}
             Ok(result) => {
                 completed_count += 1;
                 let progress_value = completed_count as f32 / total_projects as f32;
+                // Sets already being parsed finish after a pause; they mustn't report
+                // the scan as running again
+                was_paused = job.is_paused();
+                let phase = if was_paused { "paused" } else { "parsing" };
 
                 match result {
                     Ok((path, live_set)) => {
@@ -383,6 +463,7 @@ where
                             .unwrap_or("unknown");
 
                         debug!("Successfully parsed: {}", path.display());
+                        parsed_paths.push(path.clone());
 
                         // Send detailed progress update with file name
                         progress!(
@@ -393,7 +474,7 @@ where
                                 "Parsed {} ({}/{})",
                                 filename, completed_count, total_projects
                             ),
-                            "parsing"
+                            phase
                         );
 
                         successful_live_sets.push(live_set);
@@ -405,6 +486,8 @@ where
                             .unwrap_or("unknown");
 
                         error!("Failed to parse {}: {:?}", path.display(), error);
+                        counts.failed += 1;
+                        record_failure(&mut db, &path, &error)?;
 
                         // Send progress update even for failed files
                         progress!(
@@ -415,25 +498,37 @@ where
                                 "✗ Failed to parse {} ({}/{})",
                                 filename, completed_count, total_projects
                             ),
-                            "parsing"
+                            phase
                         );
                     }
                 }
             }
             Err(RecvTimeoutError::Timeout) => {
-                debug!("Timeout waiting for parser results, but continuing...");
-                // Send heartbeat progress update during timeout
+                // A cancelled scan ends when its queued sets are dropped
+                if job.is_cancelled() {
+                    continue;
+                }
+
+                let paused = job.is_paused();
+                if paused == was_paused && last_heartbeat.elapsed() < Duration::from_secs(5) {
+                    continue;
+                }
+                was_paused = paused;
+                last_heartbeat = Instant::now();
+
+                // Send heartbeat progress update while waiting
+                let (message, phase) = if paused {
+                    ("Scan paused", "paused")
+                } else {
+                    ("Parsing in progress...", "parsing")
+                };
                 progress!(
                     completed_count as u32,
                     total_projects as u32,
                     completed_count as f32 / total_projects as f32,
-                    format!(
-                        "Parsing in progress... ({}/{})",
-                        completed_count, total_projects
-                    ),
-                    "parsing"
+                    format!("{} ({}/{})", message, completed_count, total_projects),
+                    phase
                 );
-                continue;
             }
             Err(RecvTimeoutError::Disconnected) => {
                 debug!("Parser channel disconnected, assuming completion");
@@ -442,16 +537,8 @@ where
         }
     }
 
-    if successful_live_sets.is_empty() {
-        progress!(
-            total_projects as u32,
-            total_projects as u32,
-            1.0,
-            "No projects needed updates".to_string(),
-            "completed"
-        );
-        return Ok(());
-    }
+    let cancelled = job.is_cancelled();
+    drop(job);
 
     progress!(
         completed_count as u32,
@@ -461,120 +548,81 @@ where
         "inserting"
     );
 
-    // Batch insert the successfully parsed projects
-    let num_live_sets = successful_live_sets.len();
-    info!("Inserting {} projects into database", num_live_sets);
-    let live_sets = std::sync::Arc::new(successful_live_sets);
-    let mut batch_manager = BatchInsertManager::new(&mut db.conn, live_sets);
-    let stats = batch_manager.execute()?;
+    // Changed sets are updated in place; everything else is new to the index
+    let mut parsed_states = Vec::with_capacity(successful_live_sets.len());
+    let mut new_live_sets = Vec::new();
+    for live_set in successful_live_sets {
+        if let Some(state) = plan.file_states.get(&live_set.file_path) {
+            parsed_states.push((live_set.file_path.clone(), *state));
+        }
+        match plan.changed.get(&live_set.file_path) {
+            Some(project_id) => {
+                db.update_project_from_live_set(project_id, &live_set)?;
+                counts.updated += 1;
+            }
+            None => new_live_sets.push(live_set),
+        }
+    }
 
-    info!(
-        "Batch insert complete: {} projects, {} plugins, {} samples",
-        stats.projects_inserted, stats.plugins_inserted, stats.samples_inserted
-    );
+    // Sets that were moved or renamed keep their existing project and metadata
+    let (new_live_sets, reattached) = db.reconcile_moved_projects(new_live_sets)?;
+    counts.updated += reattached as u32;
+
+    if !new_live_sets.is_empty() {
+        // Batch insert the successfully parsed projects
+        info!("Inserting {} projects into database", new_live_sets.len());
+        let new_projects: Vec<_> = new_live_sets
+            .iter()
+            .map(|live_set| (live_set.id.to_string(), live_set.file_path.clone()))
+            .collect();
+        let live_sets = std::sync::Arc::new(new_live_sets);
+        let mut batch_manager = BatchInsertManager::new(&mut db.conn, live_sets);
+        let stats = batch_manager.execute()?;
+        counts.new = stats.projects_inserted as u32;
+        roots.apply_default_tags(&mut db, &new_projects)?;
+
+        info!(
+            "Batch insert complete: {} projects, {} plugins, {} samples",
+            stats.projects_inserted, stats.plugins_inserted, stats.samples_inserted
+        );
+    }
+
+    let parsed_states: Vec<_> = parsed_states
+        .iter()
+        .map(|(path, state)| (path.as_path(), state.size, state.modified_at))
+        .collect();
+    db.record_file_states(&parsed_states)?;
+    let parsed_paths: Vec<_> = parsed_paths.iter().map(PathBuf::as_path).collect();
+    db.clear_scan_errors(&parsed_paths)?;
+
+    // Sets parsed before a cancel are kept, but removals wait for a full scan
+    if cancelled {
+        info!("Scan cancelled: {}", counts.summary());
+        progress!(
+            completed_count as u32,
+            total_projects as u32,
+            completed_count as f32 / total_projects as f32,
+            format!("Scan cancelled ({})", counts.summary()),
+            "cancelled"
+        );
+        return Err(LiveSetError::Cancelled);
+    }
+
+    // Removals go last so sets that moved within the library have been re-attached
+    counts.removed = db.mark_removed_projects(&plan.removed)?;
 
     progress!(
         total_projects as u32,
         total_projects as u32,
         1.0,
         format!(
-            "Successfully processed {} projects",
-            stats.projects_inserted
+            "Successfully processed {} projects ({} moved): {}",
+            counts.new + counts.updated,
+            reattached,
+            counts.summary()
         ),
         "completed"
     );
-    info!("Successfully processed {} projects", num_live_sets);
-    Ok(())
-}
-
-/// Converts a set of project paths into preprocessed metadata objects.
-///
-/// This function performs lightweight preprocessing on discovered project files,
-/// extracting basic metadata like file modification times and names without
-/// fully parsing the project content. This allows for efficient filtering
-/// of projects that haven't changed since the last scan.
-///
-/// # Arguments
-///
-/// * `paths` - Set of filesystem paths to Ableton Live project files
-///
-/// # Returns
-///
-/// Returns a vector of [`LiveSetPreprocessed`] objects containing basic metadata
-/// for each successfully processed project.
-///
-/// # Errors
-///
-/// Returns [`LiveSetError`] if the preprocessing operation fails critically.
-/// Individual project preprocessing failures are logged but don't stop the overall process.
-fn preprocess_projects(paths: HashSet<PathBuf>) -> Result<Vec<LiveSetPreprocessed>, LiveSetError> {
-    debug!("Preprocessing {} projects", paths.len());
-    let mut preprocessed = Vec::with_capacity(paths.len());
-
-    for path in paths {
-        match LiveSetPreprocessed::new(path.clone()) {
-            Ok(metadata) => {
-                trace!("Successfully preprocessed: {}", metadata.name);
-                preprocessed.push(metadata);
-            }
-            Err(e) => {
-                error!("Failed to preprocess {}: {}", path.display(), e);
-                continue;
-            }
-        }
-    }
-
-    trace!("Successfully preprocessed {} projects", preprocessed.len());
-    Ok(preprocessed)
-}
-
-/// Filters out projects that haven't changed since the last scan.
-///
-/// This function compares the modification time of each preprocessed project
-/// against the last scan time stored in the database. Only projects that are
-/// new or have been modified since the last scan are returned for full parsing.
-///
-/// # Arguments
-///
-/// * `preprocessed` - Vector of preprocessed project metadata
-/// * `db` - Database reference for checking last scan times
-///
-/// # Returns
-///
-/// Returns a vector of [`PathBuf`] objects for projects that need to be parsed.
-///
-/// # Errors
-///
-/// Returns [`LiveSetError`] if database queries fail during the filtering process.
-fn filter_unchanged_projects(
-    preprocessed: Vec<LiveSetPreprocessed>,
-    db: &LiveSetDatabase,
-) -> Result<Vec<PathBuf>, LiveSetError> {
-    let total_count = preprocessed.len();
-    debug!("Filtering {} preprocessed projects", total_count);
-    let mut to_parse = Vec::new();
-
-    for project in preprocessed.into_iter() {
-        match db.get_last_scanned_time(&project.path)? {
-            Some(last_scanned) => {
-                if project.modified_time > last_scanned {
-                    trace!("Project needs update: {}", project.name);
-                    to_parse.push(project.path);
-                } else {
-                    trace!("Project unchanged: {}", project.name);
-                }
-            }
-            None => {
-                debug!("New project found: {}", project.name);
-                to_parse.push(project.path);
-            }
-        }
-    }
-
-    info!(
-        "Found {} projects that need parsing out of {} total",
-        to_parse.len(),
-        total_count
-    );
-    Ok(to_parse)
+    info!("Scan complete: {}, {} moved", counts.summary(), reattached);
+    Ok(counts)
 }
@@ -7,11 +7,12 @@ use uuid::Uuid;
 use crate::ableton_db::AbletonDatabase;
 use crate::config::CONFIG;
 use crate::error::LiveSetError;
-use crate::models::{AbletonVersion, KeySignature, Plugin, Sample, TimeSignature};
-use crate::scan::{ParseOptions, Parser};
+use crate::models::{AbletonVersion, KeySignature, Plugin, Sample, SetKind, TimeSignature};
+use crate::scan::project_folder::classify_set;
+use crate::scan::{ParseLimits, ParseOptions, Parser};
 use crate::utils::metadata::{load_file_hash, load_file_name, load_file_timestamps};
 use crate::utils::plugins::{get_most_recent_db_file, get_most_recent_plugins_db_file};
-use crate::utils::{decompress_gzip_file, validate_ableton_file};
+use crate::utils::{open_gzip_file, validate_ableton_file};
 
 #[derive(Debug)]
 pub struct LiveSetPreprocessed {
@@ -68,19 +69,44 @@ pub struct LiveSet {
 
 impl LiveSet {
     pub fn new(file_path: PathBuf) -> Result<Self, LiveSetError> {
+        Self::new_with_limits(file_path, ParseLimits::default())
+    }
+
+    /// Parses a set, giving up when `limits` are exceeded
+    pub fn new_with_limits(file_path: PathBuf, limits: ParseLimits) -> Result<Self, LiveSetError> {
         let preprocessed = LiveSetPreprocessed::new(file_path)?;
-        Self::from_preprocessed(preprocessed)
+        Self::parse_preprocessed(preprocessed, limits)
     }
 
     pub fn from_preprocessed(preprocessed: LiveSetPreprocessed) -> Result<Self, LiveSetError> {
-        // Scope the xml_data to this block so it's dropped after parsing
+        Self::parse_preprocessed(preprocessed, ParseLimits::default())
+    }
+
+    fn parse_preprocessed(
+        preprocessed: LiveSetPreprocessed,
+        limits: ParseLimits,
+    ) -> Result<Self, LiveSetError> {
+        // Stream the set straight out of the decoder so the decompressed XML is never
+        // held in memory as a whole
         let parse_result = {
-            let xml_data = decompress_gzip_file(&preprocessed.path)?;
-            let parser_options = ParseOptions::default();
-            let mut parser = Parser::new(&xml_data, parser_options)?;
+            let xml_stream = open_gzip_file(&preprocessed.path)?;
+            let mut parser = Parser::streaming(ParseOptions::default());
             parser.set_current_file(&preprocessed.name);
-            parser.parse(&xml_data)?
-            // xml_data is dropped here when the block ends
+            parser.set_limits(limits);
+            parser.parse_reader(xml_stream).map_err(|e| match e {
+                LiveSetError::ParseTimeout(_) | LiveSetError::Cancelled => e,
+                // Keep where the parser got to, for the scan error report
+                e => {
+                    let version = &parser.ableton_version;
+                    LiveSetError::ParseFailed {
+                        line: parser.current_line(),
+                        version: (version.major > 0).then(|| {
+                            format!("{}.{}.{}", version.major, version.minor, version.patch)
+                        }),
+                        source: Box::new(e),
+                    }
+                }
+            })?
         };
 
         let mut live_set = LiveSet {
@@ -110,6 +136,11 @@ impl LiveSet {
         Ok(live_set)
     }
 
+    /// Whether this is a regular set, a template or Live's default set
+    pub fn kind(&self) -> SetKind {
+        classify_set(&self.file_path)
+    }
+
     pub fn calculate_duration(&mut self) -> Result<(), LiveSetError> {
         if let (tempo, Some(furthest_bar)) = (self.tempo, self.furthest_bar) {
             let beats_per_second = tempo / 60.0;
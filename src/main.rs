@@ -146,7 +146,10 @@ async fn run_direct_command(command: &Commands, format: seula::cli::OutputFormat
     use seula::cli::commands::{execute_command, ScanCommand, SearchCommand};
 
     let result: Result<(), Box<dyn std::error::Error>> = match command {
-        Commands::Scan { paths, force } => {
+        Commands::Scan { subcommand: Some(subcommand), .. } => {
+            execute_command(subcommand, format, no_color).await.map_err(|e| e as Box<dyn std::error::Error>)
+        }
+        Commands::Scan { subcommand: None, paths, force } => {
             let scan_cmd = ScanCommand {
                 paths: paths.clone(),
                 force: *force,
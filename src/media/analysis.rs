@@ -0,0 +1,564 @@
+// Loudness and musical estimates of stored audio files
+// Loudness follows ITU-R BS.1770-4 (integrated loudness, true peak) and EBU Tech
+// 3342 (loudness range). Tempo and key are rough estimates meant for sorting and
+// searching, not for beat matching.
+
+use super::waveform::decode_frames;
+use super::MediaError;
+use crate::models::{KeySignature, Scale, Tonic};
+use log::debug;
+use std::f64::consts::PI;
+use std::path::Path;
+
+/// Blocks quieter than this are silence and left out of every measurement
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+
+/// Tempo range the estimate is looked for in
+const MIN_BPM: f64 = 60.0;
+const MAX_BPM: f64 = 200.0;
+
+/// Rate audio is reduced to before looking for the key
+const KEY_SAMPLE_RATE: f64 = 11025.0;
+const KEY_FRAME_LEN: usize = 4096;
+/// MIDI notes the chroma is taken from, C3 to B6
+const KEY_NOTES: std::ops::Range<u8> = 48..96;
+
+/// Krumhansl-Kessler key profiles, starting at the tonic
+const MAJOR_PROFILE: [f64; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+const MINOR_PROFILE: [f64; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+const PITCH_CLASSES: [Tonic; 12] = [
+    Tonic::C,
+    Tonic::CSharp,
+    Tonic::D,
+    Tonic::DSharp,
+    Tonic::E,
+    Tonic::F,
+    Tonic::FSharp,
+    Tonic::G,
+    Tonic::GSharp,
+    Tonic::A,
+    Tonic::ASharp,
+    Tonic::B,
+];
+
+/// Taps of each phase of the true peak interpolation filter
+const TRUE_PEAK_TAPS: usize = 12;
+/// True peak is measured at four times the sample rate
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+
+/// Loudness, level, tempo and key of an audio file. Values are missing when the
+/// audio is silent or too short to measure them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioAnalysis {
+    /// Integrated loudness in LUFS
+    pub integrated_lufs: Option<f64>,
+    /// Highest inter-sample peak in dBTP
+    pub true_peak_dbtp: Option<f64>,
+    /// Spread between quiet and loud passages in LU
+    pub loudness_range_lu: Option<f64>,
+    /// RMS level of all channels in dBFS
+    pub rms_dbfs: Option<f64>,
+    /// Estimated tempo in beats per minute
+    pub bpm: Option<f64>,
+    /// Estimated major or minor key
+    pub key: Option<KeySignature>,
+}
+
+impl AudioAnalysis {
+    /// Decode an audio file in the format of `extension` and measure it
+    pub fn analyze(path: &Path, extension: &str) -> Result<Self, MediaError> {
+        let mut analyzer: Option<Analyzer> = None;
+        decode_frames(path, extension, |sample_rate, frame| {
+            analyzer
+                .get_or_insert_with(|| Analyzer::new(sample_rate, frame.len()))
+                .push_frame(frame);
+        })?;
+
+        let analysis = analyzer.map(Analyzer::finish).unwrap_or_default();
+        debug!("Analyzed {}: {:?}", path.display(), analysis);
+        Ok(analysis)
+    }
+}
+
+/// Measures audio frame by frame while it's decoded
+struct Analyzer {
+    loudness: LoudnessMeter,
+    true_peak: TruePeakMeter,
+    sum_squares: f64,
+    samples: u64,
+    tempo: TempoTracker,
+    key: KeyDetector,
+}
+
+impl Analyzer {
+    fn new(sample_rate: u32, channels: usize) -> Self {
+        let sample_rate = sample_rate.max(1) as f64;
+        Self {
+            loudness: LoudnessMeter::new(sample_rate, channels),
+            true_peak: TruePeakMeter::new(channels),
+            sum_squares: 0.0,
+            samples: 0,
+            tempo: TempoTracker::new(sample_rate),
+            key: KeyDetector::new(sample_rate),
+        }
+    }
+
+    fn push_frame(&mut self, frame: &[f32]) {
+        let mut mono = 0.0;
+        for &sample in frame {
+            let sample = sample as f64;
+            self.sum_squares += sample * sample;
+            mono += sample;
+        }
+        self.samples += frame.len() as u64;
+        mono /= frame.len().max(1) as f64;
+
+        self.loudness.push_frame(frame);
+        self.true_peak.push_frame(frame);
+        self.tempo.push_sample(mono);
+        self.key.push_sample(mono);
+    }
+
+    fn finish(self) -> AudioAnalysis {
+        let rms = if self.samples > 0 {
+            (self.sum_squares / self.samples as f64).sqrt()
+        } else {
+            0.0
+        };
+        AudioAnalysis {
+            integrated_lufs: self.loudness.integrated(),
+            true_peak_dbtp: to_decibels(self.true_peak.peak),
+            loudness_range_lu: self.loudness.range(),
+            rms_dbfs: to_decibels(rms),
+            bpm: self.tempo.estimate(),
+            key: self.key.estimate(),
+        }
+    }
+}
+
+fn to_decibels(amplitude: f64) -> Option<f64> {
+    (amplitude > 0.0).then(|| 20.0 * amplitude.log10())
+}
+
+fn block_loudness(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.max(f64::MIN_POSITIVE).log10()
+}
+
+/// Second order IIR filter
+#[derive(Clone)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+/// The two K-weighting stages of BS.1770 for any sample rate: a high shelf for the
+/// head and a high pass for low frequencies
+fn k_weighting(sample_rate: f64) -> [Biquad; 2] {
+    let f0 = 1681.974450955533;
+    let gain = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (PI * f0 / sample_rate).tan();
+    let vh = 10f64.powf(gain / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        z: [0.0; 2],
+    };
+
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (PI * f0 / sample_rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        z: [0.0; 2],
+    };
+
+    [shelf, high_pass]
+}
+
+/// K-weighted mean square of every 100 ms of audio, from which gating blocks
+/// (400 ms) and short-term windows (3 s) are put together
+struct LoudnessMeter {
+    filters: Vec<[Biquad; 2]>,
+    weights: Vec<f64>,
+    segment_frames: usize,
+    frames_in_segment: usize,
+    energy: f64,
+    segments: Vec<f64>,
+}
+
+impl LoudnessMeter {
+    fn new(sample_rate: f64, channels: usize) -> Self {
+        // Surround channels count more, the LFE of 5.1 audio doesn't count
+        let weights = (0..channels)
+            .map(|channel| match channel {
+                0..=2 => 1.0,
+                3 if channels == 6 => 0.0,
+                _ => 1.41,
+            })
+            .collect();
+        Self {
+            filters: vec![k_weighting(sample_rate); channels],
+            weights,
+            segment_frames: ((sample_rate / 10.0).round() as usize).max(1),
+            frames_in_segment: 0,
+            energy: 0.0,
+            segments: Vec::new(),
+        }
+    }
+
+    fn push_frame(&mut self, frame: &[f32]) {
+        for ((sample, filters), weight) in frame.iter().zip(&mut self.filters).zip(&self.weights) {
+            let weighted = filters
+                .iter_mut()
+                .fold(*sample as f64, |x, filter| filter.process(x));
+            self.energy += weight * weighted * weighted;
+        }
+        self.frames_in_segment += 1;
+        if self.frames_in_segment == self.segment_frames {
+            self.segments.push(self.energy / self.segment_frames as f64);
+            self.energy = 0.0;
+            self.frames_in_segment = 0;
+        }
+    }
+
+    /// Mean squares of overlapping windows of `segments` segments, one per segment
+    fn windows(&self, segments: usize) -> Vec<f64> {
+        self.segments
+            .windows(segments)
+            .map(|window| window.iter().sum::<f64>() / segments as f64)
+            .collect()
+    }
+
+    fn integrated(&self) -> Option<f64> {
+        let blocks = self.windows(4);
+        let gated = gate(&blocks, 10.0);
+        if gated.is_empty() {
+            return None;
+        }
+        Some(block_loudness(mean(&gated)))
+    }
+
+    fn range(&self) -> Option<f64> {
+        let windows = self.windows(30);
+        let mut loudness: Vec<f64> = gate(&windows, 20.0)
+            .into_iter()
+            .map(block_loudness)
+            .collect();
+        if loudness.is_empty() {
+            return None;
+        }
+        loudness.sort_by(f64::total_cmp);
+        let percentile = |p: f64| loudness[((loudness.len() - 1) as f64 * p).round() as usize];
+        Some(percentile(0.95) - percentile(0.10))
+    }
+}
+
+/// Blocks above the absolute gate and less than `relative_lu` below the loudness
+/// of those blocks
+fn gate(blocks: &[f64], relative_lu: f64) -> Vec<f64> {
+    let audible: Vec<f64> = blocks
+        .iter()
+        .copied()
+        .filter(|&block| block_loudness(block) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    if audible.is_empty() {
+        return audible;
+    }
+    let threshold = block_loudness(mean(&audible)) - relative_lu;
+    audible
+        .into_iter()
+        .filter(|&block| block_loudness(block) > threshold)
+        .collect()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len().max(1) as f64
+}
+
+/// Peak of the audio interpolated to four times its sample rate, which catches
+/// peaks between samples that a DAC will reproduce
+struct TruePeakMeter {
+    coefficients: Vec<[f64; TRUE_PEAK_TAPS]>,
+    history: Vec<[f64; TRUE_PEAK_TAPS]>,
+    position: usize,
+    peak: f64,
+}
+
+impl TruePeakMeter {
+    fn new(channels: usize) -> Self {
+        // Hann windowed sinc for the points between the two middle samples
+        let half = (TRUE_PEAK_TAPS / 2) as f64;
+        let coefficients = (1..TRUE_PEAK_OVERSAMPLING)
+            .map(|phase| {
+                let fraction = phase as f64 / TRUE_PEAK_OVERSAMPLING as f64;
+                let mut taps = [0.0; TRUE_PEAK_TAPS];
+                for (tap, coefficient) in taps.iter_mut().enumerate() {
+                    let t = fraction + half - 1.0 - tap as f64;
+                    let sinc = if t == 0.0 {
+                        1.0
+                    } else {
+                        (PI * t).sin() / (PI * t)
+                    };
+                    let window = 0.5 * (1.0 + (PI * t / half).cos());
+                    *coefficient = sinc * window;
+                }
+                taps
+            })
+            .collect();
+        Self {
+            coefficients,
+            history: vec![[0.0; TRUE_PEAK_TAPS]; channels],
+            position: 0,
+            peak: 0.0,
+        }
+    }
+
+    fn push_frame(&mut self, frame: &[f32]) {
+        self.position = (self.position + 1) % TRUE_PEAK_TAPS;
+        for (history, &sample) in self.history.iter_mut().zip(frame) {
+            history[self.position] = sample as f64;
+            self.peak = self.peak.max((sample as f64).abs());
+            for taps in &self.coefficients {
+                // The oldest sample meets the first tap
+                let interpolated: f64 = taps
+                    .iter()
+                    .enumerate()
+                    .map(|(tap, coefficient)| {
+                        coefficient * history[(self.position + 1 + tap) % TRUE_PEAK_TAPS]
+                    })
+                    .sum();
+                self.peak = self.peak.max(interpolated.abs());
+            }
+        }
+    }
+}
+
+/// Tempo from the periodicity of rises in energy, measured every 10 ms
+struct TempoTracker {
+    hop_frames: usize,
+    hop_seconds: f64,
+    frames_in_hop: usize,
+    energy: f64,
+    energies: Vec<f64>,
+}
+
+impl TempoTracker {
+    fn new(sample_rate: f64) -> Self {
+        let hop_frames = ((sample_rate / 100.0) as usize).max(1);
+        Self {
+            hop_frames,
+            hop_seconds: hop_frames as f64 / sample_rate,
+            frames_in_hop: 0,
+            energy: 0.0,
+            energies: Vec::new(),
+        }
+    }
+
+    fn push_sample(&mut self, sample: f64) {
+        self.energy += sample * sample;
+        self.frames_in_hop += 1;
+        if self.frames_in_hop == self.hop_frames {
+            self.energies.push(self.energy);
+            self.energy = 0.0;
+            self.frames_in_hop = 0;
+        }
+    }
+
+    fn estimate(&self) -> Option<f64> {
+        let loudest = self.energies.iter().copied().fold(0.0, f64::max);
+        if loudest <= 0.0 {
+            return None;
+        }
+        // Energy in dB with an 80 dB floor, so noise in quiet passages doesn't read
+        // as onsets
+        let floor = loudest * 1e-8;
+        let levels: Vec<f64> = self
+            .energies
+            .iter()
+            .map(|&energy| 10.0 * energy.max(floor).log10())
+            .collect();
+        let mut onsets: Vec<f64> = levels
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).max(0.0))
+            .collect();
+        let average = mean(&onsets);
+        onsets.iter_mut().for_each(|onset| *onset -= average);
+
+        let min_lag = (60.0 / (MAX_BPM * self.hop_seconds)).ceil() as usize;
+        let max_lag = (60.0 / (MIN_BPM * self.hop_seconds)).floor() as usize;
+        if onsets.len() < max_lag * 4 {
+            return None;
+        }
+
+        let correlation = |lag: usize| {
+            let sum: f64 = onsets.iter().zip(&onsets[lag..]).map(|(a, b)| a * b).sum();
+            sum / (onsets.len() - lag) as f64
+        };
+        let scores: Vec<f64> = (min_lag - 1..=max_lag + 1).map(correlation).collect();
+        // Prefer tempos around 120 BPM so a beat isn't mistaken for half or double
+        let weighted = |index: usize| {
+            let bpm = 60.0 / ((min_lag - 1 + index) as f64 * self.hop_seconds);
+            let octaves = (bpm / 120.0).log2();
+            scores[index] * (-0.5 * octaves * octaves).exp()
+        };
+        let best = (1..scores.len() - 1).max_by(|&a, &b| weighted(a).total_cmp(&weighted(b)))?;
+        if scores[best] <= 0.0 {
+            return None;
+        }
+
+        // Parabolic interpolation between the neighbouring lags
+        let (before, peak, after) = (scores[best - 1], scores[best], scores[best + 1]);
+        let curvature = before - 2.0 * peak + after;
+        let offset = if curvature < 0.0 {
+            (0.5 * (before - after) / curvature).clamp(-0.5, 0.5)
+        } else {
+            0.0
+        };
+        let lag = (min_lag - 1 + best) as f64 + offset;
+        Some(60.0 / (lag * self.hop_seconds))
+    }
+}
+
+/// Key from the strength of each pitch class, matched against the major and minor
+/// key profiles
+struct KeyDetector {
+    decimation: usize,
+    accumulated: f64,
+    accumulated_samples: usize,
+    window: Vec<f64>,
+    frame: Vec<f64>,
+    goertzel: Vec<(usize, f64)>,
+    chroma: [f64; 12],
+}
+
+impl KeyDetector {
+    fn new(sample_rate: f64) -> Self {
+        let decimation = ((sample_rate / KEY_SAMPLE_RATE).round() as usize).max(1);
+        let rate = sample_rate / decimation as f64;
+        let window = (0..KEY_FRAME_LEN)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / KEY_FRAME_LEN as f64).cos())
+            .collect();
+        let goertzel = KEY_NOTES
+            .filter_map(|note| {
+                let frequency = 440.0 * 2f64.powf((note as f64 - 69.0) / 12.0);
+                (frequency < rate / 2.0).then(|| {
+                    (
+                        note as usize % 12,
+                        2.0 * (2.0 * PI * frequency / rate).cos(),
+                    )
+                })
+            })
+            .collect();
+        Self {
+            decimation,
+            accumulated: 0.0,
+            accumulated_samples: 0,
+            window,
+            frame: Vec::with_capacity(KEY_FRAME_LEN),
+            goertzel,
+            chroma: [0.0; 12],
+        }
+    }
+
+    fn push_sample(&mut self, sample: f64) {
+        self.accumulated += sample;
+        self.accumulated_samples += 1;
+        if self.accumulated_samples < self.decimation {
+            return;
+        }
+        self.frame.push(self.accumulated / self.decimation as f64);
+        self.accumulated = 0.0;
+        self.accumulated_samples = 0;
+        if self.frame.len() == KEY_FRAME_LEN {
+            self.add_frame();
+            self.frame.clear();
+        }
+    }
+
+    fn add_frame(&mut self) {
+        for &(pitch_class, coefficient) in &self.goertzel {
+            let (mut s1, mut s2) = (0.0, 0.0);
+            for (sample, window) in self.frame.iter().zip(&self.window) {
+                let s = sample * window + coefficient * s1 - s2;
+                s2 = s1;
+                s1 = s;
+            }
+            let power = s1 * s1 + s2 * s2 - coefficient * s1 * s2;
+            self.chroma[pitch_class] += power.max(0.0).sqrt();
+        }
+    }
+
+    fn estimate(&self) -> Option<KeySignature> {
+        if self.chroma.iter().all(|&strength| strength <= 0.0) {
+            return None;
+        }
+        let mut best: Option<(f64, KeySignature)> = None;
+        for (tonic_index, tonic) in PITCH_CLASSES.iter().enumerate() {
+            for (profile, scale) in [
+                (&MAJOR_PROFILE, Scale::Major),
+                (&MINOR_PROFILE, Scale::Minor),
+            ] {
+                let rotated: Vec<f64> = (0..12)
+                    .map(|pitch_class| profile[(pitch_class + 12 - tonic_index) % 12])
+                    .collect();
+                let score = correlation(&self.chroma, &rotated);
+                if best
+                    .as_ref()
+                    .is_none_or(|(best_score, _)| score > *best_score)
+                {
+                    best = Some((
+                        score,
+                        KeySignature {
+                            tonic: tonic.clone(),
+                            scale,
+                        },
+                    ));
+                }
+            }
+        }
+        best.map(|(_, key)| key)
+    }
+}
+
+/// Pearson correlation of two equally long series
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let mut covariance = 0.0;
+    let mut variance_a = 0.0;
+    let mut variance_b = 0.0;
+    for (x, y) in a.iter().zip(b) {
+        covariance += (x - mean_a) * (y - mean_b);
+        variance_a += (x - mean_a) * (x - mean_a);
+        variance_b += (y - mean_b) * (y - mean_b);
+    }
+    let denominator = (variance_a * variance_b).sqrt();
+    if denominator == 0.0 {
+        0.0
+    } else {
+        covariance / denominator
+    }
+}
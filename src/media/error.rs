@@ -20,6 +20,10 @@ pub enum MediaError {
     InvalidFileId(String),
     DatabaseError(String),
     ConfigurationError(String),
+    DecodeError(String),
+    InvalidVariant(String),
+    InvalidContent(String),
+    InvalidUpload(String),
 }
 
 impl fmt::Display for MediaError {
@@ -61,6 +65,10 @@ impl fmt::Display for MediaError {
             MediaError::InvalidFileId(file_id) => write!(f, "Invalid file ID: {}", file_id),
             MediaError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
             MediaError::ConfigurationError(msg) => write!(f, "Configuration error: {}", msg),
+            MediaError::DecodeError(msg) => write!(f, "Decode error: {}", msg),
+            MediaError::InvalidVariant(size) => write!(f, "Invalid variant size: {}", size),
+            MediaError::InvalidContent(msg) => write!(f, "Invalid file content: {}", msg),
+            MediaError::InvalidUpload(msg) => write!(f, "Invalid upload: {}", msg),
         }
     }
 }
@@ -1,16 +1,37 @@
 use crate::config::Config;
 use chrono::{DateTime, Utc};
-use log::{debug, info};
+use image::DynamicImage;
+use log::{debug, info, warn};
 use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+pub mod analysis;
 pub mod error;
 pub mod storage;
+pub mod thumbnails;
+pub mod uploads;
 pub mod validation;
+pub mod waveform;
 
+pub use analysis::AudioAnalysis;
 pub use error::MediaError;
+pub use thumbnails::{MediaVariant, ThumbnailSize};
+pub use validation::{FileValidator, MediaFormat, MediaInfo};
+pub use waveform::{PeakLevel, Waveform};
+
+/// A validated upload and the bytes that will be stored for it
+#[derive(Debug)]
+pub struct PreparedFile<'a> {
+    pub media_file: MediaFile,
+    pub info: MediaInfo,
+    /// The upright image cover art decoded to, for rendering its thumbnails
+    pub image: Option<DynamicImage>,
+    data: Cow<'a, [u8]>,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum MediaType {
@@ -85,6 +106,8 @@ pub const DEFAULT_MAX_AUDIO_FILE_SIZE_MB: u32 = 50;
 pub struct MediaConfig {
     pub max_cover_art_size_mb: Option<u32>,
     pub max_audio_file_size_mb: Option<u32>,
+    /// Reject uploads that don't parse and decode as the format their extension claims
+    pub strict_validation: bool,
 }
 
 impl From<&Config> for MediaConfig {
@@ -92,6 +115,7 @@ impl From<&Config> for MediaConfig {
         Self {
             max_cover_art_size_mb: config.max_cover_art_size_mb,
             max_audio_file_size_mb: config.max_audio_file_size_mb,
+            strict_validation: config.strict_media_validation,
         }
     }
 }
@@ -101,6 +125,7 @@ impl Default for MediaConfig {
         Self {
             max_cover_art_size_mb: Some(DEFAULT_MAX_COVER_ART_SIZE_MB),
             max_audio_file_size_mb: Some(DEFAULT_MAX_AUDIO_FILE_SIZE_MB),
+            strict_validation: true,
         }
     }
 }
@@ -123,15 +148,25 @@ impl MediaStorageManager {
     }
 
     fn ensure_directories_exist(&self) -> Result<(), MediaError> {
-        let cover_art_dir = self.storage_dir.join("cover_art");
-        let audio_files_dir = self.storage_dir.join("audio_files");
+        let objects_dir = self.storage_dir.join("objects");
+        let waveforms_dir = self.storage_dir.join("waveforms");
+        let thumbnails_dir = self.storage_dir.join("thumbnails");
+        let uploads_dir = self.storage_dir.join("uploads");
+
+        fs::create_dir_all(&objects_dir).map_err(|e| {
+            MediaError::IoError(format!("Failed to create objects directory: {}", e))
+        })?;
 
-        fs::create_dir_all(&cover_art_dir).map_err(|e| {
-            MediaError::IoError(format!("Failed to create cover art directory: {}", e))
+        fs::create_dir_all(&waveforms_dir).map_err(|e| {
+            MediaError::IoError(format!("Failed to create waveforms directory: {}", e))
         })?;
 
-        fs::create_dir_all(&audio_files_dir).map_err(|e| {
-            MediaError::IoError(format!("Failed to create audio files directory: {}", e))
+        fs::create_dir_all(&thumbnails_dir).map_err(|e| {
+            MediaError::IoError(format!("Failed to create thumbnails directory: {}", e))
+        })?;
+
+        fs::create_dir_all(&uploads_dir).map_err(|e| {
+            MediaError::IoError(format!("Failed to create uploads directory: {}", e))
         })?;
 
         debug!(
@@ -147,6 +182,32 @@ impl MediaStorageManager {
         original_filename: &str,
         media_type: MediaType,
     ) -> Result<MediaFile, MediaError> {
+        self.store_file_with_info(file_data, original_filename, media_type)
+            .map(|(media_file, _)| media_file)
+    }
+
+    /// Store a file and describe what was stored: its format, and its duration or
+    /// dimensions
+    pub fn store_file_with_info(
+        &self,
+        file_data: &[u8],
+        original_filename: &str,
+        media_type: MediaType,
+    ) -> Result<(MediaFile, MediaInfo), MediaError> {
+        let prepared = self.prepare_file(file_data, original_filename, media_type)?;
+        self.write_prepared(&prepared)?;
+        Ok((prepared.media_file, prepared.info))
+    }
+
+    /// Validate a file and work out what will be stored, without writing anything.
+    /// Cover art is decoded once, for validating, normalizing and its thumbnails.
+    pub fn prepare_file<'a>(
+        &self,
+        file_data: impl Into<Cow<'a, [u8]>>,
+        original_filename: &str,
+        media_type: MediaType,
+    ) -> Result<PreparedFile<'a>, MediaError> {
+        let file_data = file_data.into();
         debug!(
             "Storing {} file: {}",
             media_type.as_str(),
@@ -161,10 +222,25 @@ impl MediaStorageManager {
             .to_lowercase();
 
         // Validate file
-        self.validate_file(file_data, &file_extension, &media_type)?;
+        let (mut info, image) = self.validate_file(&file_data, &file_extension, &media_type)?;
+
+        // Turn cover art upright and strip its metadata
+        let normalized = match media_type {
+            MediaType::CoverArt => self.normalize_cover_art(image, &file_data, &file_extension),
+            MediaType::AudioFile => None,
+        };
+        let (data, image) = match normalized {
+            Some((normalized, image)) => {
+                // Turning the image may have swapped its sides
+                info.width = Some(image.width());
+                info.height = Some(image.height());
+                (Cow::Owned(normalized), Some(image))
+            }
+            None => (file_data, None),
+        };
 
         // Calculate checksum
-        let checksum = self.calculate_checksum(file_data);
+        let checksum = self.calculate_checksum(&data);
 
         // Determine MIME type
         let mime_type = self.get_mime_type(&file_extension, &media_type)?;
@@ -174,59 +250,319 @@ impl MediaStorageManager {
             original_filename.to_string(),
             file_extension,
             media_type.clone(),
-            file_data.len() as u64,
+            data.len() as u64,
             mime_type,
             checksum,
         );
 
-        // Store physical file
-        let storage_path =
-            self.get_storage_path(&media_file.id, &media_file.file_extension, &media_type)?;
-        fs::write(&storage_path, file_data)
-            .map_err(|e| MediaError::IoError(format!("Failed to write file: {}", e)))?;
+        Ok(PreparedFile {
+            media_file,
+            info,
+            image,
+            data,
+        })
+    }
+
+    /// Store the content of a prepared file once, however many media files share it.
+    /// Callers recording the media file hold the database lock from here until it's
+    /// recorded, or deleting the last other file with the same content could remove
+    /// the content in between.
+    pub fn write_prepared(&self, prepared: &PreparedFile) -> Result<(), MediaError> {
+        let media_file = &prepared.media_file;
+        let storage_path = self
+            .get_content_path(&media_file.checksum)
+            .ok_or_else(|| MediaError::InvalidFileId(media_file.checksum.clone()))?;
+        if storage_path.exists() {
+            info!(
+                "Content of {} is already stored at {}",
+                media_file.original_filename,
+                storage_path.display()
+            );
+        } else {
+            write_content(&storage_path, &prepared.data)?;
+            info!(
+                "Successfully stored media file: {} -> {}",
+                media_file.original_filename,
+                storage_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Where the content of a media file is stored. Files stored before content
+    /// addressing stay under their ID until they're deleted.
+    pub fn get_file_path(&self, media_file: &MediaFile) -> PathBuf {
+        let legacy_path = self.get_legacy_path(media_file);
+        match self.get_content_path(&media_file.checksum) {
+            Some(content_path) if content_path.exists() || !legacy_path.exists() => content_path,
+            _ => legacy_path,
+        }
+    }
+
+    /// Delete what belongs to a single media file: its thumbnails or peaks, and its
+    /// content if it was stored before content addressing. Content shared by
+    /// checksum is deleted with [`MediaStorageManager::delete_content`] once no
+    /// media file uses it.
+    pub fn delete_file(&self, media_file: &MediaFile) -> Result<(), MediaError> {
+        let legacy_path = self.get_legacy_path(media_file);
+        if legacy_path.exists() {
+            fs::remove_file(&legacy_path)
+                .map_err(|e| MediaError::IoError(format!("Failed to delete file: {}", e)))?;
+            info!("Deleted media file: {}", legacy_path.display());
+        }
+
+        match media_file.media_type {
+            MediaType::AudioFile => self.delete_waveform(&media_file.id)?,
+            MediaType::CoverArt => self.delete_thumbnails(&media_file.id)?,
+        }
 
+        Ok(())
+    }
+
+    /// Delete stored content no media file uses anymore
+    pub fn delete_content(&self, checksum: &str) -> Result<(), MediaError> {
+        let content_path = self
+            .get_content_path(checksum)
+            .ok_or_else(|| MediaError::InvalidFileId(checksum.to_string()))?;
+        if content_path.exists() {
+            fs::remove_file(&content_path)
+                .map_err(|e| MediaError::IoError(format!("Failed to delete file: {}", e)))?;
+            info!("Deleted stored content: {}", content_path.display());
+        }
+        // The fan-out directory goes once it's empty
+        if let Some(parent) = content_path.parent() {
+            let _ = fs::remove_dir(parent);
+        }
+        Ok(())
+    }
+
+    /// Format details of a stored file, read from its headers
+    pub fn inspect_file(&self, media_file: &MediaFile) -> Result<MediaInfo, MediaError> {
+        let file_path = self.get_file_path(media_file);
+        if !file_path.exists() {
+            return Err(MediaError::FileNotFound(media_file.id.clone()));
+        }
+        let data = fs::read(&file_path)
+            .map_err(|e| MediaError::IoError(format!("Failed to read file: {}", e)))?;
+        FileValidator::inspect(&data, &media_file.media_type)
+    }
+
+    /// Peaks of a stored audio file, from the cache next to it if they were made from
+    /// the same audio, otherwise decoded and cached again
+    pub fn get_waveform(&self, media_file: &MediaFile) -> Result<Waveform, MediaError> {
+        let cache_path = self.get_waveform_path(&media_file.id);
+        if cache_path.exists() {
+            match Waveform::load(&cache_path) {
+                Ok(waveform) if waveform.checksum == media_file.checksum => return Ok(waveform),
+                Ok(_) => debug!("Audio file {} changed, regenerating peaks", media_file.id),
+                Err(e) => warn!("Unreadable waveform cache for {}: {}", media_file.id, e),
+            }
+        }
+        self.generate_waveform(media_file)
+    }
+
+    /// Decode a stored audio file and cache its peaks
+    pub fn generate_waveform(&self, media_file: &MediaFile) -> Result<Waveform, MediaError> {
+        if media_file.media_type != MediaType::AudioFile {
+            return Err(MediaError::InvalidMediaType(format!(
+                "{} is not an audio file",
+                media_file.id
+            )));
+        }
+
+        let audio_path = self.get_file_path(media_file);
+        if !audio_path.exists() {
+            return Err(MediaError::FileNotFound(media_file.id.clone()));
+        }
+
+        let waveform = Waveform::decode(&audio_path, &media_file.file_extension, &media_file.checksum)?;
+        waveform.save(&self.get_waveform_path(&media_file.id))?;
         info!(
-            "Successfully stored media file: {} -> {}",
-            original_filename,
-            storage_path.display()
+            "Generated waveform for {} ({} zoom levels)",
+            media_file.original_filename,
+            waveform.levels.len()
         );
-        Ok(media_file)
+        Ok(waveform)
     }
 
-    pub fn get_file_path(
-        &self,
-        file_id: &str,
-        file_extension: &str,
-        media_type: &MediaType,
-    ) -> Result<PathBuf, MediaError> {
-        self.get_storage_path(file_id, file_extension, media_type)
+    /// Decode a stored audio file and measure its loudness, tempo and key
+    pub fn analyze_audio(&self, media_file: &MediaFile) -> Result<AudioAnalysis, MediaError> {
+        if media_file.media_type != MediaType::AudioFile {
+            return Err(MediaError::InvalidMediaType(format!(
+                "{} is not an audio file",
+                media_file.id
+            )));
+        }
+
+        let audio_path = self.get_file_path(media_file);
+        if !audio_path.exists() {
+            return Err(MediaError::FileNotFound(media_file.id.clone()));
+        }
+
+        let analysis = AudioAnalysis::analyze(&audio_path, &media_file.file_extension)?;
+        info!("Analyzed audio file {}", media_file.original_filename);
+        Ok(analysis)
+    }
+
+    pub fn delete_waveform(&self, file_id: &str) -> Result<(), MediaError> {
+        let cache_path = self.get_waveform_path(file_id);
+        if cache_path.exists() {
+            fs::remove_file(&cache_path)
+                .map_err(|e| MediaError::IoError(format!("Failed to delete waveform: {}", e)))?;
+            debug!("Deleted waveform: {}", cache_path.display());
+        }
+        Ok(())
     }
 
-    pub fn delete_file(
+    /// Delete cached peaks of audio files that no longer exist
+    pub fn cleanup_waveforms(
         &self,
-        file_id: &str,
-        file_extension: &str,
-        media_type: &MediaType,
-    ) -> Result<(), MediaError> {
-        let file_path = self.get_storage_path(file_id, file_extension, media_type)?;
+        audio_file_ids: &HashSet<String>,
+    ) -> Result<CleanupStats, MediaError> {
+        let mut stats = CleanupStats::new();
+        for entry in fs::read_dir(self.storage_dir.join("waveforms"))? {
+            let path = entry?.path();
+            // Peaks being written sit in `<id>.<uuid>.tmp` until they're complete
+            let Some(file_id) = path
+                .file_name()
+                .and_then(|name| name.to_string_lossy().split('.').next().map(str::to_string))
+            else {
+                continue;
+            };
+            if audio_file_ids.contains(&file_id) {
+                continue;
+            }
+            let size = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+            fs::remove_file(&path)
+                .map_err(|e| MediaError::IoError(format!("Failed to delete waveform: {}", e)))?;
+            stats.add_file(size);
+        }
+        if stats.files_deleted > 0 {
+            info!("Deleted {} stale waveforms", stats.files_deleted);
+        }
+        Ok(stats)
+    }
 
-        if file_path.exists() {
-            fs::remove_file(&file_path)
-                .map_err(|e| MediaError::IoError(format!("Failed to delete file: {}", e)))?;
-            info!("Deleted media file: {}", file_path.display());
+    /// Render every thumbnail size of a stored cover art file
+    pub fn generate_thumbnails(&self, media_file: &MediaFile) -> Result<Vec<MediaVariant>, MediaError> {
+        if media_file.media_type != MediaType::CoverArt {
+            return Err(MediaError::InvalidMediaType(format!(
+                "{} is not cover art",
+                media_file.id
+            )));
         }
 
+        let image_path = self.get_file_path(media_file);
+        let image = thumbnails::decode_upright(&fs::read(&image_path)?)?;
+        self.generate_thumbnails_from_image(media_file, &image)
+    }
+
+    /// Render every thumbnail size of cover art that is already decoded
+    pub fn generate_thumbnails_from_image(
+        &self,
+        media_file: &MediaFile,
+        image: &DynamicImage,
+    ) -> Result<Vec<MediaVariant>, MediaError> {
+        let thumbnail_dir = self.storage_dir.join("thumbnails").join(&media_file.id);
+        fs::create_dir_all(&thumbnail_dir)?;
+        let mut variants = Vec::new();
+        for size in ThumbnailSize::ALL {
+            let thumbnail = thumbnails::render_thumbnail(image, size)?;
+            let path = self.get_variant_path(&media_file.id, size, thumbnail.file_extension);
+            // Swapped in whole, as another request may be rendering the same thumbnails
+            write_content(&path, &thumbnail.data)?;
+            variants.push(MediaVariant {
+                media_file_id: media_file.id.clone(),
+                size,
+                file_extension: thumbnail.file_extension.to_string(),
+                mime_type: thumbnail.mime_type.to_string(),
+                file_size_bytes: thumbnail.data.len() as u64,
+                width: thumbnail.width,
+                height: thumbnail.height,
+                checksum: thumbnail.checksum(),
+            });
+        }
+
+        debug!(
+            "Generated {} thumbnails for {}",
+            variants.len(),
+            media_file.original_filename
+        );
+        Ok(variants)
+    }
+
+    pub fn get_variant_path(&self, file_id: &str, size: ThumbnailSize, file_extension: &str) -> PathBuf {
+        self.storage_dir
+            .join("thumbnails")
+            .join(file_id)
+            .join(format!("{}.{}", size, file_extension))
+    }
+
+    pub fn delete_thumbnails(&self, file_id: &str) -> Result<(), MediaError> {
+        let thumbnail_dir = self.storage_dir.join("thumbnails").join(file_id);
+        if thumbnail_dir.exists() {
+            fs::remove_dir_all(&thumbnail_dir)
+                .map_err(|e| MediaError::IoError(format!("Failed to delete thumbnails: {}", e)))?;
+            debug!("Deleted thumbnails: {}", thumbnail_dir.display());
+        }
         Ok(())
     }
 
+    /// Re-encode decodable cover art upright and without metadata, decoding it unless
+    /// validation already did. Images that can't be decoded are stored as uploaded.
+    fn normalize_cover_art(
+        &self,
+        image: Option<DynamicImage>,
+        file_data: &[u8],
+        file_extension: &str,
+    ) -> Option<(Vec<u8>, DynamicImage)> {
+        let normalized = image
+            .map_or_else(|| thumbnails::decode_upright(file_data), Ok)
+            .and_then(|image| {
+                thumbnails::normalize_image(&image, file_extension).map(|data| (data, image))
+            });
+        match normalized {
+            Ok(normalized) => Some(normalized),
+            Err(e) => {
+                warn!("Storing cover art as uploaded, it couldn't be normalized: {}", e);
+                None
+            }
+        }
+    }
+
+    fn get_waveform_path(&self, file_id: &str) -> PathBuf {
+        self.storage_dir
+            .join("waveforms")
+            .join(format!("{}.peaks", file_id))
+    }
+
     fn validate_file(
         &self,
         file_data: &[u8],
         file_extension: &str,
         media_type: &MediaType,
-    ) -> Result<(), MediaError> {
-        // Check file size
-        let file_size_mb = file_data.len() as f64 / (1024.0 * 1024.0);
+    ) -> Result<(MediaInfo, Option<DynamicImage>), MediaError> {
+        self.check_file_size(file_data.len() as u64, media_type)?;
+
+        // Check file extension
+        let allowed_formats = match media_type {
+            MediaType::CoverArt => ALLOWED_IMAGE_FORMATS,
+            MediaType::AudioFile => ALLOWED_AUDIO_FORMATS,
+        };
+
+        if !allowed_formats.contains(&file_extension) {
+            return Err(MediaError::UnsupportedFormat {
+                format: file_extension.to_string(),
+                allowed_formats: allowed_formats.iter().map(|&s| s.to_string()).collect(),
+            });
+        }
+
+        FileValidator::validate(file_data, file_extension, media_type, self.config.strict_validation)
+    }
+
+    /// Reject files over the configured size limit of their media type
+    pub fn check_file_size(&self, size_bytes: u64, media_type: &MediaType) -> Result<(), MediaError> {
+        let file_size_mb = size_bytes as f64 / (1024.0 * 1024.0);
 
         // Get max size limit (0 means no limit)
         let max_size = match media_type {
@@ -247,22 +583,6 @@ impl MediaStorageManager {
                 max_size_mb: max_size as f64,
             });
         }
-
-        // Check file extension
-        let allowed_formats = match media_type {
-            MediaType::CoverArt => ALLOWED_IMAGE_FORMATS,
-            MediaType::AudioFile => ALLOWED_AUDIO_FORMATS,
-        };
-
-        if !allowed_formats.contains(&file_extension) {
-            return Err(MediaError::UnsupportedFormat {
-                format: file_extension.to_string(),
-                allowed_formats: allowed_formats.iter().map(|&s| s.to_string()).collect(),
-            });
-        }
-
-        // TODO: Add magic number validation for file type verification
-
         Ok(())
     }
 
@@ -306,20 +626,45 @@ impl MediaStorageManager {
         }
     }
 
-    fn get_storage_path(
-        &self,
-        file_id: &str,
-        file_extension: &str,
-        media_type: &MediaType,
-    ) -> Result<PathBuf, MediaError> {
-        let subdirectory = match media_type {
+    /// Content is stored by checksum, fanned out over directories by its first two
+    /// characters. None if the checksum isn't a SHA-256 hash.
+    fn get_content_path(&self, checksum: &str) -> Option<PathBuf> {
+        if checksum.len() != 64 || !checksum.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(
+            self.storage_dir
+                .join("objects")
+                .join(&checksum[..2])
+                .join(checksum),
+        )
+    }
+
+    /// Where files were stored by ID before content addressing
+    fn get_legacy_path(&self, media_file: &MediaFile) -> PathBuf {
+        let subdirectory = match media_file.media_type {
             MediaType::CoverArt => "cover_art",
             MediaType::AudioFile => "audio_files",
         };
 
-        let filename = format!("{}.{}", file_id, file_extension);
-        Ok(self.storage_dir.join(subdirectory).join(filename))
+        let filename = format!("{}.{}", media_file.id, media_file.file_extension);
+        self.storage_dir.join(subdirectory).join(filename)
+    }
+}
+
+/// Write content through a temporary file, so a half-written file is never taken
+/// for stored content
+fn write_content(path: &Path, data: &[u8]) -> Result<(), MediaError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
     }
+    let temp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+    fs::write(&temp_path, data)
+        .and_then(|_| fs::rename(&temp_path, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            MediaError::IoError(format!("Failed to write file: {}", e))
+        })
 }
 
 #[derive(Debug)]
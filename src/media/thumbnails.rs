@@ -0,0 +1,192 @@
+// Cover art normalization and thumbnails
+// Uploaded images are decoded, turned upright according to their EXIF orientation and
+// re-encoded without metadata. Smaller copies are rendered for grids and lists.
+
+use super::MediaError;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::Cursor;
+use std::str::FromStr;
+
+/// Quality of re-encoded JPEG originals
+const NORMALIZED_JPEG_QUALITY: u8 = 92;
+
+/// Quality of JPEG thumbnails
+const THUMBNAIL_JPEG_QUALITY: u8 = 85;
+
+/// Thumbnail sizes rendered for every cover art upload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThumbnailSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl ThumbnailSize {
+    pub const ALL: [ThumbnailSize; 3] = [
+        ThumbnailSize::Small,
+        ThumbnailSize::Medium,
+        ThumbnailSize::Large,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThumbnailSize::Small => "small",
+            ThumbnailSize::Medium => "medium",
+            ThumbnailSize::Large => "large",
+        }
+    }
+
+    /// Longest side of the thumbnail in pixels. Smaller images aren't scaled up.
+    pub fn max_dimension(&self) -> u32 {
+        match self {
+            ThumbnailSize::Small => 256,
+            ThumbnailSize::Medium => 512,
+            ThumbnailSize::Large => 1024,
+        }
+    }
+}
+
+impl FromStr for ThumbnailSize {
+    type Err = MediaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "small" => Ok(ThumbnailSize::Small),
+            "medium" => Ok(ThumbnailSize::Medium),
+            "large" => Ok(ThumbnailSize::Large),
+            _ => Err(MediaError::InvalidVariant(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ThumbnailSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A file derived from a stored media file, such as a thumbnail of cover art
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaVariant {
+    pub media_file_id: String,
+    pub size: ThumbnailSize,
+    pub file_extension: String,
+    pub mime_type: String,
+    pub file_size_bytes: u64,
+    pub width: u32,
+    pub height: u32,
+    pub checksum: String,
+}
+
+/// An encoded image and its dimensions
+#[derive(Debug, Clone)]
+pub struct EncodedImage {
+    pub data: Vec<u8>,
+    pub file_extension: &'static str,
+    pub mime_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl EncodedImage {
+    pub fn checksum(&self) -> String {
+        format!("{:x}", Sha256::digest(&self.data))
+    }
+}
+
+/// Decode an image and turn it upright according to its EXIF orientation
+pub fn decode_upright(data: &[u8]) -> Result<DynamicImage, MediaError> {
+    let (mut image, orientation) = decode_with_orientation(data)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// Decode an image as stored, along with the EXIF orientation it should be shown in
+pub fn decode_with_orientation(data: &[u8]) -> Result<(DynamicImage, Orientation), MediaError> {
+    let reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| MediaError::DecodeError(e.to_string()))?;
+    let mut decoder = reader.into_decoder().map_err(image_error)?;
+    let orientation = decoder.orientation().map_err(image_error)?;
+    let image = DynamicImage::from_decoder(decoder).map_err(image_error)?;
+    Ok((image, orientation))
+}
+
+/// Re-encode an upright image in the format of `file_extension`. Nothing but the
+/// pixels is carried over, so EXIF and other metadata are dropped.
+pub fn normalize_image(image: &DynamicImage, file_extension: &str) -> Result<Vec<u8>, MediaError> {
+    let mut data = Vec::new();
+    match file_extension {
+        "jpg" | "jpeg" => DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(
+            JpegEncoder::new_with_quality(&mut data, NORMALIZED_JPEG_QUALITY),
+        ),
+        "png" => image.write_with_encoder(PngEncoder::new(&mut data)),
+        "webp" => webp_compatible(image).write_with_encoder(WebPEncoder::new_lossless(&mut data)),
+        other => {
+            return Err(MediaError::UnsupportedFormat {
+                format: other.to_string(),
+                allowed_formats: super::ALLOWED_IMAGE_FORMATS
+                    .iter()
+                    .map(|&s| s.to_string())
+                    .collect(),
+            })
+        }
+    }
+    .map_err(image_error)?;
+    Ok(data)
+}
+
+/// Render a thumbnail: JPEG for opaque images, lossless WebP for images with
+/// transparency
+pub fn render_thumbnail(image: &DynamicImage, size: ThumbnailSize) -> Result<EncodedImage, MediaError> {
+    let max = size.max_dimension();
+    let scaled = if image.width() > max || image.height() > max {
+        image.resize(max, max, FilterType::Lanczos3)
+    } else {
+        image.clone()
+    };
+
+    let mut data = Vec::new();
+    let (file_extension, mime_type) = if scaled.color().has_alpha() {
+        webp_compatible(&scaled)
+            .write_with_encoder(WebPEncoder::new_lossless(&mut data))
+            .map_err(image_error)?;
+        ("webp", "image/webp")
+    } else {
+        DynamicImage::ImageRgb8(scaled.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut data, THUMBNAIL_JPEG_QUALITY))
+            .map_err(image_error)?;
+        ("jpg", "image/jpeg")
+    };
+
+    Ok(EncodedImage {
+        data,
+        file_extension,
+        mime_type,
+        width: scaled.width(),
+        height: scaled.height(),
+    })
+}
+
+/// The WebP encoder only takes 8-bit RGB and RGBA
+fn webp_compatible(image: &DynamicImage) -> DynamicImage {
+    if image.color().has_alpha() {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+    }
+}
+
+fn image_error(error: image::ImageError) -> MediaError {
+    match error {
+        image::ImageError::IoError(e) => MediaError::IoError(e.to_string()),
+        e => MediaError::DecodeError(e.to_string()),
+    }
+}
@@ -0,0 +1,137 @@
+// Partial files of resumable uploads. Each upload session writes to
+// uploads/<upload_id>.part until all of its bytes arrived, then the file is
+// validated and stored like any other upload.
+
+use super::{CleanupStats, MediaError, MediaStorageManager, MediaType};
+use log::{debug, info};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Hours an upload session is kept after its last chunk arrived
+pub const UPLOAD_SESSION_TTL_HOURS: i64 = 24;
+
+impl MediaStorageManager {
+    /// Check that a file about to be uploaded is of an allowed format and size
+    pub fn check_upload(
+        &self,
+        filename: &str,
+        total_size_bytes: u64,
+        media_type: &MediaType,
+    ) -> Result<(), MediaError> {
+        let file_extension = Path::new(filename)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        self.get_mime_type(&file_extension, media_type)?;
+        if total_size_bytes == 0 {
+            return Err(MediaError::InvalidUpload("the file is empty".to_string()));
+        }
+        self.check_file_size(total_size_bytes, media_type)
+    }
+
+    /// Number of bytes received so far for an upload
+    pub fn upload_progress(&self, upload_id: &str) -> Result<u64, MediaError> {
+        let path = self.get_upload_path(upload_id)?;
+        match fs::metadata(&path) {
+            Ok(metadata) => Ok(metadata.len()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write a chunk of an upload at `offset` and return the bytes received so far.
+    /// Writing before the end replaces what came after it, so a client can resend
+    /// chunks it isn't sure arrived.
+    pub fn write_upload_chunk(
+        &self,
+        upload_id: &str,
+        offset: u64,
+        chunk: &[u8],
+        total_size_bytes: u64,
+    ) -> Result<u64, MediaError> {
+        let path = self.get_upload_path(upload_id)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| MediaError::IoError(format!("Failed to open upload: {}", e)))?;
+        let received = file.metadata()?.len();
+        if offset > received {
+            return Err(MediaError::InvalidUpload(format!(
+                "offset {} is past the {} bytes received",
+                offset, received
+            )));
+        }
+        let end = offset + chunk.len() as u64;
+        if end > total_size_bytes {
+            return Err(MediaError::InvalidUpload(format!(
+                "{} bytes is more than the announced {}",
+                end, total_size_bytes
+            )));
+        }
+
+        if offset < received {
+            file.set_len(offset)?;
+        }
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(chunk)
+            .map_err(|e| MediaError::IoError(format!("Failed to write upload: {}", e)))?;
+        Ok(end)
+    }
+
+    /// Everything received for an upload
+    pub fn read_upload(&self, upload_id: &str) -> Result<Vec<u8>, MediaError> {
+        let path = self.get_upload_path(upload_id)?;
+        if !path.exists() {
+            return Err(MediaError::FileNotFound(upload_id.to_string()));
+        }
+        Ok(fs::read(&path)?)
+    }
+
+    pub fn delete_upload(&self, upload_id: &str) -> Result<(), MediaError> {
+        let path = self.get_upload_path(upload_id)?;
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| MediaError::IoError(format!("Failed to delete upload: {}", e)))?;
+            debug!("Deleted partial upload: {}", path.display());
+        }
+        Ok(())
+    }
+
+    /// Delete partial files of uploads that no longer have a session
+    pub fn cleanup_uploads(&self, upload_ids: &HashSet<String>) -> Result<CleanupStats, MediaError> {
+        let mut stats = CleanupStats::new();
+        for entry in fs::read_dir(self.storage_dir.join("uploads"))? {
+            let path = entry?.path();
+            let Some(upload_id) = path.file_stem().map(|stem| stem.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+            if upload_ids.contains(&upload_id) {
+                continue;
+            }
+            let size = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+            fs::remove_file(&path)
+                .map_err(|e| MediaError::IoError(format!("Failed to delete upload: {}", e)))?;
+            stats.add_file(size);
+        }
+        if stats.files_deleted > 0 {
+            info!("Deleted {} abandoned uploads", stats.files_deleted);
+        }
+        Ok(stats)
+    }
+
+    fn get_upload_path(&self, upload_id: &str) -> Result<PathBuf, MediaError> {
+        // Upload IDs come from clients, only accept the UUIDs handed out
+        Uuid::parse_str(upload_id).map_err(|_| MediaError::InvalidFileId(upload_id.to_string()))?;
+        Ok(self
+            .storage_dir
+            .join("uploads")
+            .join(format!("{}.part", upload_id)))
+    }
+}
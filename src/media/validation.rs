@@ -1,124 +1,1080 @@
 // File validation utilities
-// This module provides advanced validation beyond basic extension checking
+// Uploads are checked by parsing their structure, not just their extension: the content
+// has to be the format the extension claims, well-formed, and free of anything that
+// could be executed. Strict mode also decodes the file; permissive mode accepts content
+// it can't make sense of with a warning, but never anything executable.
 
-use super::{MediaError, MediaType};
+use super::{thumbnails, MediaError, MediaType};
+use image::DynamicImage;
 use log::{debug, warn};
+use std::fmt;
+use std::io::Cursor;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Absolute upload limit, whatever the configured limits are
+const MAX_FILE_SIZE_BYTES: usize = 100 * 1024 * 1024;
+
+/// Audio packets decoded in strict mode to prove the stream is playable
+const STRICT_DECODE_PACKETS: usize = 32;
+
+/// Bytes searched for the next MPEG audio frame after bytes that aren't one
+const MP3_RESYNC_BYTES: usize = 64 * 1024;
+
+/// A ZIP archive's end-of-central-directory record is this close to the end of the file
+const ZIP_EOCD_SEARCH_BYTES: usize = 22 + u16::MAX as usize;
+
+const PNG_SIGNATURE: &[u8; 8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Markup that makes a file render or run as a page or script
+const SCRIPT_MARKERS: &[&[u8]] = &[b"<script", b"<?php", b"<html", b"<!doctype html"];
+
+/// Leading bytes browsers sniff for markup before deciding what a file is
+const SCRIPT_SNIFF_BYTES: usize = 1445;
+
+/// File formats accepted for upload, identified from their content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaFormat {
+    Wav,
+    Flac,
+    Mp3,
+    M4a,
+    Png,
+    Jpeg,
+    WebP,
+}
+
+impl MediaFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaFormat::Wav => "wav",
+            MediaFormat::Flac => "flac",
+            MediaFormat::Mp3 => "mp3",
+            MediaFormat::M4a => "m4a",
+            MediaFormat::Png => "png",
+            MediaFormat::Jpeg => "jpeg",
+            MediaFormat::WebP => "webp",
+        }
+    }
+
+    pub fn media_type(&self) -> MediaType {
+        match self {
+            MediaFormat::Wav | MediaFormat::Flac | MediaFormat::Mp3 | MediaFormat::M4a => {
+                MediaType::AudioFile
+            }
+            MediaFormat::Png | MediaFormat::Jpeg | MediaFormat::WebP => MediaType::CoverArt,
+        }
+    }
+
+    /// Whether a file with this extension should hold this format
+    pub fn matches_extension(&self, file_extension: &str) -> bool {
+        match self {
+            MediaFormat::Jpeg => file_extension == "jpg" || file_extension == "jpeg",
+            format => file_extension == format.as_str(),
+        }
+    }
+}
+
+impl fmt::Display for MediaFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What validation found out about a file. Audio fields are set for audio, image
+/// fields for images.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaInfo {
+    /// None when permissive validation let through content it couldn't identify
+    pub format: Option<MediaFormat>,
+    pub duration_seconds: Option<f64>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl MediaInfo {
+    fn audio(format: MediaFormat, sample_rate: u32, channels: u16, frames: Option<u64>) -> Self {
+        Self {
+            format: Some(format),
+            duration_seconds: frames.map(|frames| frames as f64 / sample_rate as f64),
+            sample_rate: Some(sample_rate),
+            channels: Some(channels),
+            ..Self::default()
+        }
+    }
+
+    fn image(format: MediaFormat, width: u32, height: u32) -> Result<Self, MediaError> {
+        if width == 0 || height == 0 {
+            return Err(invalid(format!("{} image has no pixels", format)));
+        }
+        Ok(Self {
+            format: Some(format),
+            width: Some(width),
+            height: Some(height),
+            ..Self::default()
+        })
+    }
+}
 
 pub struct FileValidator;
 
 impl FileValidator {
-    /// Validate file using magic numbers (file signatures)
-    pub fn validate_file_signature(
+    /// Check an upload against its extension and media type and describe it. In
+    /// permissive mode, content that fails the format checks is accepted with a
+    /// warning; the security checks apply either way. Images decoded in strict mode
+    /// are handed back upright, so they needn't be decoded again.
+    pub fn validate(
         file_data: &[u8],
+        file_extension: &str,
         expected_type: &MediaType,
-    ) -> Result<(), MediaError> {
-        if file_data.is_empty() {
-            return Err(MediaError::IoError("Empty file data".to_string()));
-        }
+        strict: bool,
+    ) -> Result<(MediaInfo, Option<DynamicImage>), MediaError> {
+        Self::validate_file_security(file_data)?;
 
-        match expected_type {
-            MediaType::CoverArt => Self::validate_image_signature(file_data),
-            MediaType::AudioFile => Self::validate_audio_signature(file_data),
-        }
-    }
+        let result = Self::inspect(file_data, expected_type).and_then(|info| {
+            let format = info.format.expect("inspected files have a format");
+            if !format.matches_extension(file_extension) {
+                return Err(invalid(format!(
+                    "Content is {} but the file extension is .{}",
+                    format, file_extension
+                )));
+            }
+            let image = if strict {
+                Self::decode(file_data, &info)?
+            } else {
+                None
+            };
+            Ok((info, image))
+        });
 
-    fn validate_image_signature(file_data: &[u8]) -> Result<(), MediaError> {
-        if file_data.len() < 8 {
-            return Err(MediaError::IoError(
-                "File too small to contain valid image header".to_string(),
-            ));
+        match result {
+            Ok((info, image)) => {
+                debug!("Validated {} upload: {:?}", file_extension, info);
+                Ok((info, image))
+            }
+            Err(e) if !strict => {
+                warn!("Accepting .{} upload that failed validation: {}", file_extension, e);
+                Ok((Self::inspect(file_data, expected_type).unwrap_or_default(), None))
+            }
+            Err(e) => Err(e),
         }
+    }
 
-        // Check for common image file signatures
+    /// Identify a file from its signature
+    pub fn detect_format(file_data: &[u8]) -> Option<MediaFormat> {
         if file_data.starts_with(&[0xFF, 0xD8, 0xFF]) {
-            // JPEG signature
-            debug!("Detected JPEG file signature");
-            Ok(())
-        } else if file_data.starts_with(&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]) {
-            // PNG signature
-            debug!("Detected PNG file signature");
-            Ok(())
-        } else if file_data.starts_with(b"RIFF")
-            && file_data.len() >= 12
-            && &file_data[8..12] == b"WEBP"
-        {
-            // WebP signature
-            debug!("Detected WebP file signature");
-            Ok(())
+            Some(MediaFormat::Jpeg)
+        } else if file_data.starts_with(PNG_SIGNATURE) {
+            Some(MediaFormat::Png)
+        } else if file_data.starts_with(b"RIFF") && file_data.get(8..12) == Some(b"WEBP") {
+            Some(MediaFormat::WebP)
+        } else if file_data.starts_with(b"RIFF") && file_data.get(8..12) == Some(b"WAVE") {
+            Some(MediaFormat::Wav)
+        } else if file_data.get(4..8) == Some(b"ftyp") {
+            Some(MediaFormat::M4a)
         } else {
-            warn!(
-                "Unknown image file signature: {:02X?}",
-                &file_data[..8.min(file_data.len())]
-            );
-            // For now, we'll be permissive and allow unknown signatures
-            // TODO: Make this stricter based on configuration
-            Ok(())
+            // FLAC and MP3 may both start with an ID3 tag
+            let audio = &file_data[id3_length(file_data).min(file_data.len())..];
+            if audio.starts_with(b"fLaC") {
+                Some(MediaFormat::Flac)
+            } else if is_mpeg_sync(audio) {
+                Some(MediaFormat::Mp3)
+            } else {
+                None
+            }
         }
     }
 
-    fn validate_audio_signature(file_data: &[u8]) -> Result<(), MediaError> {
-        if file_data.len() < 12 {
-            return Err(MediaError::IoError(
-                "File too small to contain valid audio header".to_string(),
-            ));
+    /// Identify a file and parse its structure
+    pub fn inspect(file_data: &[u8], expected_type: &MediaType) -> Result<MediaInfo, MediaError> {
+        let format = Self::detect_format(file_data).ok_or_else(|| {
+            invalid(format!(
+                "Unrecognized file signature: {:02X?}",
+                &file_data[..12.min(file_data.len())]
+            ))
+        })?;
+        if format.media_type() != *expected_type {
+            return Err(invalid(format!(
+                "Content is {}, which is not a valid {}",
+                format,
+                expected_type.as_str()
+            )));
         }
 
-        // Check for common audio file signatures
-        if file_data.starts_with(b"ID3")
-            || file_data.starts_with(&[0xFF, 0xFB])
-            || file_data.starts_with(&[0xFF, 0xFA])
-        {
-            // MP3 signature (ID3 tag or MPEG frame)
-            debug!("Detected MP3 file signature");
-            Ok(())
-        } else if file_data.starts_with(b"RIFF")
-            && file_data.len() >= 12
-            && &file_data[8..12] == b"WAVE"
-        {
-            // WAV signature
-            debug!("Detected WAV file signature");
-            Ok(())
-        } else if file_data.starts_with(b"fLaC") {
-            // FLAC signature
-            debug!("Detected FLAC file signature");
-            Ok(())
-        } else if file_data.len() >= 8 && &file_data[4..8] == b"ftyp" {
-            // MP4/M4A signature (check for ftyp box)
-            debug!("Detected MP4/M4A file signature");
-            Ok(())
-        } else {
-            warn!(
-                "Unknown audio file signature: {:02X?}",
-                &file_data[..12.min(file_data.len())]
-            );
-            // For now, we'll be permissive and allow unknown signatures
-            // TODO: Make this stricter based on configuration
-            Ok(())
+        match format {
+            MediaFormat::Wav => parse_wav(file_data),
+            MediaFormat::Flac => parse_flac(file_data),
+            MediaFormat::Mp3 => parse_mp3(file_data),
+            MediaFormat::M4a => parse_m4a(file_data),
+            MediaFormat::Png => parse_png(file_data),
+            MediaFormat::Jpeg => parse_jpeg(file_data),
+            MediaFormat::WebP => parse_webp(file_data),
+        }
+    }
+
+    /// Decode a parsed file, or the start of it for audio, returning images upright.
+    /// M4A has no decoder here, so its structure has to do.
+    fn decode(file_data: &[u8], info: &MediaInfo) -> Result<Option<DynamicImage>, MediaError> {
+        match info.format {
+            Some(MediaFormat::Png | MediaFormat::Jpeg | MediaFormat::WebP) => {
+                let (mut image, orientation) = thumbnails::decode_with_orientation(file_data)
+                    .map_err(|e| invalid(format!("Image doesn't decode: {}", e)))?;
+                if Some(image.width()) != info.width || Some(image.height()) != info.height {
+                    return Err(invalid(format!(
+                        "Image decodes to {}x{}, not the size in its header",
+                        image.width(),
+                        image.height()
+                    )));
+                }
+                image.apply_orientation(orientation);
+                Ok(Some(image))
+            }
+            Some(format @ (MediaFormat::Wav | MediaFormat::Flac | MediaFormat::Mp3)) => {
+                decode_audio(file_data, format).map(|_| None)
+            }
+            Some(MediaFormat::M4a) | None => Ok(None),
         }
     }
 
-    /// Basic security validation to prevent malicious files
+    /// Reject files that are, or carry, something executable: programs, scripts,
+    /// markup and archives hidden in or appended to media
     pub fn validate_file_security(file_data: &[u8]) -> Result<(), MediaError> {
+        if file_data.is_empty() {
+            return Err(MediaError::IoError("Empty file data".to_string()));
+        }
+
         // Check for suspiciously large files (basic DoS protection)
-        if file_data.len() > 100 * 1024 * 1024 {
-            // 100MB absolute limit
+        if file_data.len() > MAX_FILE_SIZE_BYTES {
             return Err(MediaError::FileTooLarge {
                 actual_size_mb: file_data.len() as f64 / (1024.0 * 1024.0),
-                max_size_mb: 100.0,
+                max_size_mb: (MAX_FILE_SIZE_BYTES / (1024 * 1024)) as f64,
             });
         }
 
-        // TODO: Add more security checks:
-        // - Scan for embedded executables
-        // - Check for malicious metadata
-        // - Validate file structure integrity
+        if let Some(kind) = executable_header(file_data) {
+            return Err(invalid(format!("File is {}", kind)));
+        }
+        if let Some(offset) = find_embedded_executable(file_data) {
+            return Err(invalid(format!("Executable embedded at byte {}", offset)));
+        }
+        if has_zip_directory(file_data) {
+            return Err(invalid("File contains a ZIP archive".to_string()));
+        }
+        if let Some(offset) = find_script_marker(file_data) {
+            return Err(invalid(format!("Script or markup embedded at byte {}", offset)));
+        }
 
         Ok(())
     }
 }
 
-// TODO: Future enhancements:
-// - More comprehensive magic number database
-// - Configurable validation strictness
-// - Integration with virus scanning
-// - Metadata sanitization
+fn invalid(message: String) -> MediaError {
+    MediaError::InvalidContent(message)
+}
+
+fn truncated(format: MediaFormat) -> MediaError {
+    invalid(format!("{} file is truncated", format))
+}
+
+fn bytes<const N: usize>(data: &[u8], offset: usize) -> Option<[u8; N]> {
+    data.get(offset..offset.checked_add(N)?)?.try_into().ok()
+}
+
+fn be_u16(data: &[u8], offset: usize) -> Option<u16> {
+    bytes(data, offset).map(u16::from_be_bytes)
+}
+
+fn be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    bytes(data, offset).map(u32::from_be_bytes)
+}
+
+fn be_u64(data: &[u8], offset: usize) -> Option<u64> {
+    bytes(data, offset).map(u64::from_be_bytes)
+}
+
+fn le_u16(data: &[u8], offset: usize) -> Option<u16> {
+    bytes(data, offset).map(u16::from_le_bytes)
+}
+
+fn le_u32(data: &[u8], offset: usize) -> Option<u32> {
+    bytes(data, offset).map(u32::from_le_bytes)
+}
+
+fn le_u24(data: &[u8], offset: usize) -> Option<u32> {
+    let [a, b, c] = bytes(data, offset)?;
+    Some(u32::from_le_bytes([a, b, c, 0]))
+}
+
+/// A RIFF chunk or MPEG-4 box: its four-character type and its body
+type Chunk<'a> = ([u8; 4], &'a [u8]);
+
+/// Chunks of a RIFF file (WAV, WebP). The RIFF size has to match the
+/// file, give or take a pad byte, so nothing can hide after it.
+fn riff_chunks(data: &[u8], format: MediaFormat) -> Result<Vec<Chunk<'_>>, MediaError> {
+    let riff_size = le_u32(data, 4).ok_or_else(|| truncated(format))? as usize;
+    let end = riff_size.saturating_add(8);
+    if end > data.len() {
+        return Err(truncated(format));
+    }
+    if data.len() > end + 1 {
+        return Err(invalid(format!(
+            "{} bytes of unexpected data after the end of the {} file",
+            data.len() - end,
+            format
+        )));
+    }
+
+    let mut chunks = Vec::new();
+    let mut offset = 12;
+    while offset + 8 <= end {
+        let id = bytes::<4>(data, offset).ok_or_else(|| truncated(format))?;
+        let size = le_u32(data, offset + 4).ok_or_else(|| truncated(format))? as usize;
+        let body = offset + 8;
+        let body_end = body.checked_add(size).filter(|&body_end| body_end <= end);
+        let body_end = body_end.ok_or_else(|| truncated(format))?;
+        chunks.push((id, &data[body..body_end]));
+        offset = body_end + (size & 1);
+    }
+    Ok(chunks)
+}
+
+fn parse_wav(data: &[u8]) -> Result<MediaInfo, MediaError> {
+    let format = MediaFormat::Wav;
+    let mut fmt = None;
+    let mut data_size = None;
+    for (id, body) in riff_chunks(data, format)? {
+        match &id {
+            b"fmt " => {
+                let channels = le_u16(body, 2).ok_or_else(|| truncated(format))?;
+                let sample_rate = le_u32(body, 4).ok_or_else(|| truncated(format))?;
+                let block_align = le_u16(body, 12).ok_or_else(|| truncated(format))?;
+                fmt = Some((channels, sample_rate, block_align));
+            }
+            b"data" => data_size = Some(body.len() as u64),
+            _ => {}
+        }
+    }
+
+    let (channels, sample_rate, block_align) =
+        fmt.ok_or_else(|| invalid("WAV file has no format chunk".to_string()))?;
+    let data_size = data_size.ok_or_else(|| invalid("WAV file has no audio data".to_string()))?;
+    if channels == 0 || sample_rate == 0 || block_align == 0 {
+        return Err(invalid(format!(
+            "WAV format chunk is invalid: {} channels at {} Hz",
+            channels, sample_rate
+        )));
+    }
+    Ok(MediaInfo::audio(
+        format,
+        sample_rate,
+        channels,
+        Some(data_size / block_align as u64),
+    ))
+}
+
+fn parse_flac(data: &[u8]) -> Result<MediaInfo, MediaError> {
+    let format = MediaFormat::Flac;
+    let mut offset = id3_length(data) + 4;
+    let mut stream_info = None;
+    loop {
+        let [header, a, b, c] = bytes::<4>(data, offset).ok_or_else(|| truncated(format))?;
+        let length = u32::from_be_bytes([0, a, b, c]) as usize;
+        let body = offset + 4;
+        if body + length > data.len() {
+            return Err(truncated(format));
+        }
+
+        let block_type = header & 0x7F;
+        if stream_info.is_none() {
+            if block_type != 0 || length < 34 {
+                return Err(invalid("FLAC file doesn't start with STREAMINFO".to_string()));
+            }
+            // 20 bits sample rate, 3 bits channels - 1, 5 bits bits per sample - 1,
+            // 36 bits total samples
+            let packed = be_u64(data, body + 10).ok_or_else(|| truncated(format))?;
+            stream_info = Some((
+                (packed >> 44) as u32,
+                ((packed >> 41) & 0x7) as u16 + 1,
+                packed & 0xF_FFFF_FFFF,
+            ));
+        }
+
+        offset = body + length;
+        if header & 0x80 != 0 {
+            break;
+        }
+    }
+
+    // Audio frames follow the metadata
+    let frame_sync = be_u16(data, offset).is_some_and(|sync| sync & 0xFFFC == 0xFFF8);
+    if offset < data.len() && !frame_sync {
+        return Err(invalid("FLAC metadata isn't followed by audio frames".to_string()));
+    }
+
+    let (sample_rate, channels, total_samples) = stream_info.expect("first block is STREAMINFO");
+    if sample_rate == 0 {
+        return Err(invalid("FLAC stream has no sample rate".to_string()));
+    }
+    Ok(MediaInfo::audio(
+        format,
+        sample_rate,
+        channels,
+        (total_samples > 0).then_some(total_samples),
+    ))
+}
+
+/// Length of an ID3v2 tag at the start of the data, or 0 without one
+fn id3_length(data: &[u8]) -> usize {
+    if !data.starts_with(b"ID3") || data.len() < 10 {
+        return 0;
+    }
+    // Syncsafe size: 7 bits per byte
+    let size = data[6..10]
+        .iter()
+        .fold(0usize, |size, &byte| (size << 7) | (byte & 0x7F) as usize);
+    let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+    10 + size + footer
+}
+
+fn is_mpeg_sync(data: &[u8]) -> bool {
+    MpegFrame::parse(data).is_some()
+}
+
+/// Header of an MPEG audio frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MpegFrame {
+    version: u8,
+    layer: u8,
+    sample_rate: u32,
+    channels: u16,
+    samples: u32,
+    /// None for free-format streams, whose frames run up to the next header
+    length: Option<usize>,
+}
+
+impl MpegFrame {
+    const BITRATES_V1: [[u32; 14]; 3] = [
+        [32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448],
+        [32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384],
+        [32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320],
+    ];
+    const BITRATES_V2: [[u32; 14]; 2] = [
+        [32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256],
+        [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+    ];
+
+    /// Parse the frame header at the start of the data
+    fn parse(data: &[u8]) -> Option<Self> {
+        let [b0, b1, b2, b3] = bytes::<4>(data, 0)?;
+        if b0 != 0xFF || b1 & 0xE0 != 0xE0 {
+            return None;
+        }
+        // 0 = MPEG 2.5, 2 = MPEG 2, 3 = MPEG 1
+        let version = (b1 >> 3) & 0x3;
+        // 1 = layer III, 2 = layer II, 3 = layer I
+        let layer = 4 - ((b1 >> 1) & 0x3);
+        let bitrate_index = (b2 >> 4) as usize;
+        let sample_rate_index = ((b2 >> 2) & 0x3) as usize;
+        if version == 1 || layer == 4 || bitrate_index == 15 || sample_rate_index == 3 {
+            return None;
+        }
+
+        // Index 0 is the free format, where the bitrate isn't given
+        let bitrate = match bitrate_index {
+            0 => None,
+            _ if version == 3 => Some(Self::BITRATES_V1[layer as usize - 1][bitrate_index - 1]),
+            _ => Some(Self::BITRATES_V2[(layer as usize - 1).min(1)][bitrate_index - 1]),
+        }
+        .map(|bitrate| bitrate * 1000);
+        let sample_rate = [44100, 48000, 32000][sample_rate_index]
+            >> match version {
+                3 => 0,
+                2 => 1,
+                _ => 2,
+            };
+        let samples = match (layer, version) {
+            (1, _) => 384,
+            (3, 2) | (3, 0) => 576,
+            _ => 1152,
+        };
+        let padding = ((b2 >> 1) & 0x1) as usize;
+        let length = bitrate.map(|bitrate| {
+            if layer == 1 {
+                (12 * bitrate / sample_rate) as usize * 4 + padding * 4
+            } else {
+                (samples / 8 * bitrate / sample_rate) as usize + padding
+            }
+        });
+
+        Some(Self {
+            version,
+            layer,
+            sample_rate,
+            channels: if b3 >> 6 == 3 { 1 } else { 2 },
+            samples,
+            length,
+        })
+    }
+}
+
+/// Walk every frame, so the duration is right for variable bitrates too. Bytes that
+/// aren't a frame, such as padding or a stray tag, are skipped up to the next header.
+fn parse_mp3(data: &[u8]) -> Result<MediaInfo, MediaError> {
+    let format = MediaFormat::Mp3;
+    let mut offset = id3_length(data);
+    let first = MpegFrame::parse(&data[offset.min(data.len())..])
+        .ok_or_else(|| invalid("MP3 file doesn't start with an MPEG audio frame".to_string()))?;
+
+    let is_tag = |rest: &[u8]| {
+        rest.starts_with(b"TAG") || rest.starts_with(b"APETAGEX") || rest.starts_with(b"LYRICS")
+    };
+    let is_frame = |rest: &[u8]| {
+        MpegFrame::parse(rest).is_some_and(|frame| {
+            frame.version == first.version
+                && frame.layer == first.layer
+                && frame.sample_rate == first.sample_rate
+        })
+    };
+    // The next frame, or tag that may follow the audio, within reach of `from`
+    let resync = |from: usize| {
+        let end = data.len().min(from.saturating_add(MP3_RESYNC_BYTES));
+        (from..end).find(|&i| is_frame(&data[i..]) || is_tag(&data[i..]))
+    };
+
+    let mut frames = 0u64;
+    while offset < data.len() {
+        let rest = &data[offset..];
+        // Tags that may follow the audio
+        if is_tag(rest) {
+            break;
+        }
+        if !is_frame(rest) {
+            match resync(offset + 1) {
+                Some(next) => {
+                    debug!(
+                        "Skipped {} bytes between MPEG audio frames at byte {}",
+                        next - offset,
+                        offset
+                    );
+                    offset = next;
+                    continue;
+                }
+                None => {
+                    warn!(
+                        "No MPEG audio frame within {} bytes of byte {}",
+                        MP3_RESYNC_BYTES, offset
+                    );
+                    break;
+                }
+            }
+        }
+
+        let length = match MpegFrame::parse(rest).and_then(|frame| frame.length) {
+            Some(length) => length,
+            // A free-format frame ends where the next one starts
+            None => resync(offset + 4).unwrap_or(data.len()) - offset,
+        };
+        if offset + length > data.len() {
+            // A cut-off last frame still plays
+            if frames == 0 {
+                return Err(truncated(format));
+            }
+            break;
+        }
+        frames += 1;
+        offset += length;
+    }
+
+    if frames == 0 {
+        return Err(invalid("MP3 file has no MPEG audio frames".to_string()));
+    }
+    Ok(MediaInfo::audio(
+        format,
+        first.sample_rate,
+        first.channels,
+        Some(frames * first.samples as u64),
+    ))
+}
+
+/// Boxes of an MPEG-4 file
+fn mp4_boxes(data: &[u8]) -> Result<Vec<Chunk<'_>>, MediaError> {
+    let format = MediaFormat::M4a;
+    let mut boxes = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let size = be_u32(data, offset).ok_or_else(|| truncated(format))? as usize;
+        let box_type = bytes::<4>(data, offset + 4).ok_or_else(|| truncated(format))?;
+        let (header, size) = match size {
+            0 => (8, data.len() - offset),
+            1 => (16, be_u64(data, offset + 8).ok_or_else(|| truncated(format))? as usize),
+            size => (8, size),
+        };
+        if size < header {
+            return Err(invalid(format!("Invalid MPEG-4 box size at byte {}", offset)));
+        }
+        let end = offset.checked_add(size).filter(|&end| end <= data.len());
+        let end = end.ok_or_else(|| truncated(format))?;
+        boxes.push((box_type, &data[offset + header..end]));
+        offset = end;
+    }
+    Ok(boxes)
+}
+
+fn find_mp4_box<'a>(data: &'a [u8], box_type: &[u8; 4]) -> Result<Option<&'a [u8]>, MediaError> {
+    Ok(mp4_boxes(data)?
+        .into_iter()
+        .find(|(found, _)| found == box_type)
+        .map(|(_, body)| body))
+}
+
+fn parse_m4a(data: &[u8]) -> Result<MediaInfo, MediaError> {
+    let boxes = mp4_boxes(data)?;
+    if boxes.first().map(|(box_type, _)| box_type) != Some(b"ftyp") {
+        return Err(invalid("MPEG-4 file doesn't start with a file type box".to_string()));
+    }
+    let moov = boxes
+        .iter()
+        .find(|(box_type, _)| box_type == b"moov")
+        .map(|(_, body)| *body)
+        .ok_or_else(|| invalid("M4A file has no movie header".to_string()))?;
+
+    // Duration from the movie header, in its own timescale
+    let mvhd = find_mp4_box(moov, b"mvhd")?
+        .ok_or_else(|| invalid("M4A file has no movie header".to_string()))?;
+    let (timescale, duration) = if mvhd.first() == Some(&1) {
+        (be_u32(mvhd, 20), be_u64(mvhd, 24))
+    } else {
+        (be_u32(mvhd, 12), be_u32(mvhd, 16).map(u64::from))
+    };
+    let (timescale, duration) = timescale
+        .zip(duration)
+        .ok_or_else(|| truncated(MediaFormat::M4a))?;
+
+    // Channels and sample rate from the first audio track's sample description
+    for (box_type, trak) in mp4_boxes(moov)? {
+        if &box_type != b"trak" {
+            continue;
+        }
+        let mut body = Some(trak);
+        for path in [b"mdia", b"minf", b"stbl", b"stsd"] {
+            body = match body {
+                Some(body) => find_mp4_box(body, path)?,
+                None => None,
+            };
+        }
+        // Version and flags, entry count, then the first entry
+        let Some(entry) = body.and_then(|stsd| stsd.get(8..)) else {
+            continue;
+        };
+        let Some(entry_type) = bytes::<4>(entry, 4) else {
+            continue;
+        };
+        if &entry_type != b"mp4a" && &entry_type != b"alac" {
+            continue;
+        }
+        let channels = be_u16(entry, 24).ok_or_else(|| truncated(MediaFormat::M4a))?;
+        // 16.16 fixed point
+        let sample_rate = be_u32(entry, 32).ok_or_else(|| truncated(MediaFormat::M4a))? >> 16;
+        return Ok(MediaInfo {
+            format: Some(MediaFormat::M4a),
+            duration_seconds: (timescale > 0).then(|| duration as f64 / timescale as f64),
+            sample_rate: (sample_rate > 0).then_some(sample_rate),
+            channels: Some(channels),
+            ..MediaInfo::default()
+        });
+    }
+    Err(invalid("M4A file has no AAC or ALAC audio track".to_string()))
+}
+
+fn parse_png(data: &[u8]) -> Result<MediaInfo, MediaError> {
+    let format = MediaFormat::Png;
+    let mut offset = PNG_SIGNATURE.len();
+    let mut size = None;
+    loop {
+        let length = be_u32(data, offset).ok_or_else(|| truncated(format))? as usize;
+        let chunk_type = bytes::<4>(data, offset + 4).ok_or_else(|| truncated(format))?;
+        if !chunk_type.iter().all(u8::is_ascii_alphabetic) {
+            return Err(invalid(format!("Invalid PNG chunk at byte {}", offset)));
+        }
+        let body = offset + 8;
+        // Body and CRC
+        let end = body
+            .checked_add(length)
+            .and_then(|end| end.checked_add(4))
+            .filter(|&end| end <= data.len())
+            .ok_or_else(|| truncated(format))?;
+
+        if size.is_none() {
+            if &chunk_type != b"IHDR" {
+                return Err(invalid("PNG file doesn't start with a header chunk".to_string()));
+            }
+            size = be_u32(data, body).zip(be_u32(data, body + 4));
+        }
+        offset = end;
+        if &chunk_type == b"IEND" {
+            break;
+        }
+    }
+
+    if offset < data.len() {
+        return Err(invalid(format!(
+            "{} bytes of unexpected data after the end of the PNG file",
+            data.len() - offset
+        )));
+    }
+    let (width, height) = size.ok_or_else(|| truncated(format))?;
+    MediaInfo::image(format, width, height)
+}
+
+fn parse_jpeg(data: &[u8]) -> Result<MediaInfo, MediaError> {
+    let format = MediaFormat::Jpeg;
+    let end = jpeg_end(data).ok_or_else(|| truncated(format))?;
+    let size = jpeg_size(&data[..end]).ok_or_else(|| invalid("JPEG file has no frame header".to_string()))?;
+
+    // Only more images (multi-picture JPEGs) or padding may follow the first one
+    let trailer = &data[end..];
+    if !trailer.is_empty()
+        && !trailer.starts_with(&[0xFF, 0xD8])
+        && trailer.iter().any(|&byte| byte != 0 && byte != 0xFF)
+    {
+        return Err(invalid(format!(
+            "{} bytes of unexpected data after the end of the JPEG image",
+            trailer.len()
+        )));
+    }
+    MediaInfo::image(format, size.0, size.1)
+}
+
+/// Offset just past the end-of-image marker of the JPEG at the start of the data
+fn jpeg_end(data: &[u8]) -> Option<usize> {
+    let mut offset = 2;
+    loop {
+        if *data.get(offset)? != 0xFF {
+            return None;
+        }
+        // Markers may be padded with fill bytes
+        while *data.get(offset + 1)? == 0xFF {
+            offset += 1;
+        }
+        let marker = *data.get(offset + 1)?;
+        offset += 2;
+        match marker {
+            0xD9 => return Some(offset),
+            0x01 | 0xD0..=0xD7 => continue,
+            _ => {}
+        }
+
+        let length = be_u16(data, offset)? as usize;
+        if length < 2 || offset + length > data.len() {
+            return None;
+        }
+        offset += length;
+
+        if marker == 0xDA {
+            // Entropy-coded data runs until a marker other than a restart or a stuffed byte
+            loop {
+                if *data.get(offset)? == 0xFF {
+                    let next = *data.get(offset + 1)?;
+                    if next != 0x00 && !(0xD0..=0xD7).contains(&next) && next != 0xFF {
+                        break;
+                    }
+                }
+                offset += 1;
+            }
+        }
+    }
+}
+
+/// Width and height from the first frame header
+fn jpeg_size(data: &[u8]) -> Option<(u32, u32)> {
+    let mut offset = 2;
+    while offset + 4 <= data.len() {
+        let marker = data[offset + 1];
+        if data[offset] != 0xFF || marker == 0xDA {
+            return None;
+        }
+        if marker == 0xFF {
+            offset += 1;
+            continue;
+        }
+        let length = be_u16(data, offset + 2)? as usize;
+        if matches!(marker, 0xC0..=0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF) {
+            let height = be_u16(data, offset + 5)?;
+            let width = be_u16(data, offset + 7)?;
+            return Some((width as u32, height as u32));
+        }
+        offset += 2 + length;
+    }
+    None
+}
+
+fn parse_webp(data: &[u8]) -> Result<MediaInfo, MediaError> {
+    let format = MediaFormat::WebP;
+    let chunks = riff_chunks(data, format)?;
+    let (id, body) = chunks.first().ok_or_else(|| truncated(format))?;
+    let (width, height) = match id {
+        b"VP8 " => {
+            if body.get(3..6) != Some(&[0x9D, 0x01, 0x2A]) {
+                return Err(invalid("Invalid lossy WebP frame".to_string()));
+            }
+            let width = le_u16(body, 6).ok_or_else(|| truncated(format))? & 0x3FFF;
+            let height = le_u16(body, 8).ok_or_else(|| truncated(format))? & 0x3FFF;
+            (width as u32, height as u32)
+        }
+        b"VP8L" => {
+            if body.first() != Some(&0x2F) {
+                return Err(invalid("Invalid lossless WebP frame".to_string()));
+            }
+            let packed = le_u32(body, 1).ok_or_else(|| truncated(format))?;
+            ((packed & 0x3FFF) + 1, ((packed >> 14) & 0x3FFF) + 1)
+        }
+        b"VP8X" => {
+            let width = le_u24(body, 4).ok_or_else(|| truncated(format))? + 1;
+            let height = le_u24(body, 7).ok_or_else(|| truncated(format))? + 1;
+            (width, height)
+        }
+        other => {
+            return Err(invalid(format!(
+                "Unknown WebP chunk {}",
+                String::from_utf8_lossy(other)
+            )))
+        }
+    };
+    MediaInfo::image(format, width, height)
+}
+
+/// Decode the first packets of an audio file
+fn decode_audio(file_data: &[u8], format: MediaFormat) -> Result<(), MediaError> {
+    let decode_error = |e: SymphoniaError| invalid(format!("Audio doesn't decode: {}", e));
+
+    let stream = MediaSourceStream::new(Box::new(Cursor::new(file_data.to_vec())), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension(format.as_str());
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(decode_error)?;
+    let mut reader = probed.format;
+
+    let track = reader
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| invalid("No audio track found".to_string()))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(decode_error)?;
+
+    // A bad packet here and there only leaves a gap, as long as something decodes
+    let mut decoded = 0;
+    let mut last_error = None;
+    for _ in 0..STRICT_DECODE_PACKETS {
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(decode_error(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        match decoder.decode(&packet) {
+            Ok(_) => decoded += 1,
+            Err(e @ SymphoniaError::DecodeError(_)) => last_error = Some(e),
+            Err(e) => return Err(decode_error(e)),
+        }
+    }
+
+    match (decoded, last_error) {
+        (0, Some(e)) => Err(decode_error(e)),
+        (0, None) => Err(invalid("Audio file has no audio packets".to_string())),
+        _ => Ok(()),
+    }
+}
+
+/// What kind of program the data starts with, if any
+fn executable_header(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"MZ") {
+        Some("a Windows executable")
+    } else if data.starts_with(b"\x7FELF") {
+        Some("an ELF executable")
+    } else if [
+        [0xFE, 0xED, 0xFA, 0xCE],
+        [0xFE, 0xED, 0xFA, 0xCF],
+        [0xCE, 0xFA, 0xED, 0xFE],
+        [0xCF, 0xFA, 0xED, 0xFE],
+        [0xCA, 0xFE, 0xBA, 0xBE],
+    ]
+    .iter()
+    .any(|magic| data.starts_with(magic))
+    {
+        Some("a Mach-O executable")
+    } else if data.starts_with(b"#!") {
+        Some("a script")
+    } else {
+        None
+    }
+}
+
+/// Offset of a Windows or ELF executable inside the data. The headers are checked
+/// past their magic bytes, so compressed audio and image data doesn't trip this.
+fn find_embedded_executable(data: &[u8]) -> Option<usize> {
+    (0..data.len().saturating_sub(4)).find(|&offset| {
+        let rest = &data[offset..];
+        if rest.starts_with(b"MZ") {
+            // The DOS header points at the PE header
+            le_u32(rest, 0x3C)
+                .map(|pe| pe as usize)
+                .filter(|&pe| (0x40..0x1000).contains(&pe))
+                .is_some_and(|pe| rest.get(pe..pe + 4) == Some(b"PE\0\0"))
+        } else if rest.starts_with(b"\x7FELF") {
+            // 32/64-bit, little/big-endian, version 1
+            matches!(rest.get(4..7), Some([1 | 2, 1 | 2, 1]))
+        } else {
+            false
+        }
+    })
+}
+
+/// Whether the data ends in a ZIP archive's central directory, which is how ZIP, JAR
+/// and Office files get appended to media
+fn has_zip_directory(data: &[u8]) -> bool {
+    let start = data.len().saturating_sub(ZIP_EOCD_SEARCH_BYTES);
+    (start..data.len().saturating_sub(21)).any(|offset| {
+        data[offset..].starts_with(b"PK\x05\x06")
+            && le_u16(data, offset + 20).is_some_and(|comment| offset + 22 + comment as usize == data.len())
+    })
+}
+
+/// Offset of HTML or script markup, which browsers may run if the file is served
+fn find_script_marker(data: &[u8]) -> Option<usize> {
+    let regions = script_regions(data).unwrap_or_else(|| vec![data]);
+    regions.into_iter().find_map(|region| {
+        let start = region.as_ptr() as usize - data.as_ptr() as usize;
+        region
+            .iter()
+            .enumerate()
+            .find_map(|(offset, &byte)| {
+                (byte == b'<'
+                    && SCRIPT_MARKERS.iter().any(|marker| {
+                        region
+                            .get(offset..offset + marker.len())
+                            .is_some_and(|candidate| candidate.eq_ignore_ascii_case(marker))
+                    }))
+                .then_some(offset)
+            })
+            .map(|offset| start + offset)
+    })
+}
+
+/// Parts of a file markup could hide in: the start, which browsers sniff, its text and
+/// metadata, and anything appended. Audio frames and pixel data are left out, as they
+/// spell out a marker by chance now and then. None if the file has to be searched
+/// whole, as its format is unknown or its structure doesn't parse.
+fn script_regions(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut regions = vec![
+        &data[..data.len().min(SCRIPT_SNIFF_BYTES)],
+        &data[..id3_length(data).min(data.len())],
+    ];
+    match FileValidator::detect_format(data)? {
+        MediaFormat::Mp3 => {
+            let tail = &data[data.len().saturating_sub(128)..];
+            if tail.starts_with(b"TAG") {
+                regions.push(tail);
+            }
+        }
+        MediaFormat::Flac => regions.extend(flac_comments(data)?),
+        MediaFormat::Png => regions.extend(png_text(data)?),
+        MediaFormat::Jpeg => regions.extend(jpeg_text(data)?),
+        format @ (MediaFormat::Wav | MediaFormat::WebP) => regions.extend(
+            riff_chunks(data, format)
+                .ok()?
+                .into_iter()
+                .filter(|(id, _)| !matches!(id, b"data" | b"VP8 " | b"VP8L" | b"ALPH" | b"ANMF"))
+                .map(|(_, body)| body),
+        ),
+        MediaFormat::M4a => regions.extend(
+            mp4_boxes(data)
+                .ok()?
+                .into_iter()
+                .filter(|(box_type, _)| box_type != b"mdat")
+                .map(|(_, body)| body),
+        ),
+    }
+    Some(regions)
+}
+
+/// Vorbis comment blocks of a FLAC file
+fn flac_comments(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut comments = Vec::new();
+    let mut offset = id3_length(data) + 4;
+    loop {
+        let [header, a, b, c] = bytes::<4>(data, offset)?;
+        let body = offset + 4;
+        let end = body + u32::from_be_bytes([0, a, b, c]) as usize;
+        if header & 0x7F == 4 {
+            comments.push(data.get(body..end)?);
+        }
+        offset = end;
+        if header & 0x80 != 0 {
+            return Some(comments);
+        }
+    }
+}
+
+/// Text chunks of a PNG file, and anything after its end
+fn png_text(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut text = Vec::new();
+    let mut offset = PNG_SIGNATURE.len();
+    loop {
+        let length = be_u32(data, offset)? as usize;
+        let chunk_type = bytes::<4>(data, offset + 4)?;
+        let body = offset + 8;
+        let chunk = data.get(body..body.checked_add(length)?)?;
+        if matches!(&chunk_type, b"tEXt" | b"zTXt" | b"iTXt") {
+            text.push(chunk);
+        }
+        // Body and CRC
+        offset = body + length + 4;
+        if &chunk_type == b"IEND" {
+            text.push(data.get(offset..)?);
+            return Some(text);
+        }
+    }
+}
+
+/// Application (EXIF, XMP) and comment segments of a JPEG file, and anything after
+/// its end
+fn jpeg_text(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut text = Vec::new();
+    let mut offset = 2;
+    // Segments up to the image data
+    loop {
+        if *data.get(offset)? != 0xFF {
+            return None;
+        }
+        while *data.get(offset + 1)? == 0xFF {
+            offset += 1;
+        }
+        let marker = *data.get(offset + 1)?;
+        offset += 2;
+        match marker {
+            0xD9 | 0xDA => break,
+            0x01 | 0xD0..=0xD7 => continue,
+            _ => {}
+        }
+        let length = be_u16(data, offset)? as usize;
+        let segment = data.get(offset + 2..offset + length)?;
+        if matches!(marker, 0xE0..=0xEF | 0xFE) {
+            text.push(segment);
+        }
+        offset += length;
+    }
+    text.push(&data[jpeg_end(data)?..]);
+    Some(text)
+}
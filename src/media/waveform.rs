@@ -0,0 +1,393 @@
+// Waveform peaks for stored audio files
+// Audio is decoded once into min/max peaks at several resolutions, so clients can
+// draw a waveform at any zoom without downloading the audio itself.
+
+use super::MediaError;
+use log::debug;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use uuid::Uuid;
+
+/// Audio frames per peak at the most detailed zoom level
+pub const FINEST_FRAMES_PER_PEAK: u32 = 256;
+
+/// Coarser levels are added until one has at most this many peaks
+pub const OVERVIEW_PEAKS: usize = 1024;
+
+const CACHE_MAGIC: &[u8; 4] = b"SPK1";
+
+/// Min/max peaks of the audio at one zoom level, each in -1.0..=1.0
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeakLevel {
+    pub frames_per_peak: u32,
+    pub min: Vec<f32>,
+    pub max: Vec<f32>,
+}
+
+impl PeakLevel {
+    pub fn len(&self) -> usize {
+        self.min.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.is_empty()
+    }
+
+    /// The level with half the peaks, each covering two peaks of this one
+    fn halved(&self) -> Self {
+        let min = self
+            .min
+            .chunks(2)
+            .map(|pair| pair.iter().copied().fold(f32::MAX, f32::min))
+            .collect();
+        let max = self
+            .max
+            .chunks(2)
+            .map(|pair| pair.iter().copied().fold(f32::MIN, f32::max))
+            .collect();
+        Self {
+            frames_per_peak: self.frames_per_peak * 2,
+            min,
+            max,
+        }
+    }
+}
+
+/// Peaks of an audio file at every zoom level, from the overview (level 0) to the
+/// most detailed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Waveform {
+    /// Checksum of the audio file the peaks were made from
+    pub checksum: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub total_frames: u64,
+    pub levels: Vec<PeakLevel>,
+}
+
+impl Waveform {
+    pub fn duration_seconds(&self) -> f64 {
+        if self.sample_rate == 0 {
+            return 0.0;
+        }
+        self.total_frames as f64 / self.sample_rate as f64
+    }
+
+    /// Peaks at a zoom level, 0 being the overview
+    pub fn level(&self, zoom_level: usize) -> Option<&PeakLevel> {
+        self.levels.get(zoom_level)
+    }
+
+    /// Decode an audio file and compute its peaks. Channels are merged, so each peak
+    /// covers the loudest sample of any channel.
+    pub fn decode(path: &Path, extension: &str, checksum: &str) -> Result<Self, MediaError> {
+        let mut builder = PeakBuilder::new(FINEST_FRAMES_PER_PEAK);
+        let (sample_rate, channels) =
+            decode_frames(path, extension, |_, frame| builder.push_frame(frame))?;
+
+        let finest = builder.finish();
+        let total_frames = finest.total_frames;
+        let mut levels = vec![finest.level];
+        while let Some(coarser) = levels
+            .last()
+            .filter(|level| level.len() > OVERVIEW_PEAKS)
+            .map(PeakLevel::halved)
+        {
+            levels.push(coarser);
+        }
+        levels.reverse();
+
+        debug!(
+            "Decoded {} frames from {} into {} zoom levels",
+            total_frames,
+            path.display(),
+            levels.len()
+        );
+        Ok(Self {
+            checksum: checksum.to_string(),
+            sample_rate,
+            channels,
+            total_frames,
+            levels,
+        })
+    }
+
+    /// Write the peaks to a cache file. Peaks are stored as 16-bit values.
+    /// Write through a temporary file, so readers never see a partly written cache
+    pub fn save(&self, path: &Path) -> Result<(), MediaError> {
+        let temp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+        let result = self
+            .write_to(&temp_path)
+            .and_then(|_| fs::rename(&temp_path, path).map_err(MediaError::from));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result
+    }
+
+    fn write_to(&self, path: &Path) -> Result<(), MediaError> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(CACHE_MAGIC)?;
+        write_bytes(&mut out, self.checksum.as_bytes())?;
+        out.write_all(&self.sample_rate.to_le_bytes())?;
+        out.write_all(&self.channels.to_le_bytes())?;
+        out.write_all(&self.total_frames.to_le_bytes())?;
+        out.write_all(&(self.levels.len() as u32).to_le_bytes())?;
+        for level in &self.levels {
+            out.write_all(&level.frames_per_peak.to_le_bytes())?;
+            out.write_all(&(level.len() as u32).to_le_bytes())?;
+            for (min, max) in level.min.iter().zip(&level.max) {
+                out.write_all(&quantize(*min).to_le_bytes())?;
+                out.write_all(&quantize(*max).to_le_bytes())?;
+            }
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Read peaks written by [`Waveform::save`]
+    pub fn load(path: &Path) -> Result<Self, MediaError> {
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let mut input = BufReader::new(file);
+        let mut magic = [0u8; 4];
+        input.read_exact(&mut magic)?;
+        if &magic != CACHE_MAGIC {
+            return Err(MediaError::DecodeError(format!(
+                "Not a waveform cache file: {}",
+                path.display()
+            )));
+        }
+
+        let checksum = String::from_utf8(read_bytes(&mut input, file_len)?)
+            .map_err(|e| MediaError::DecodeError(e.to_string()))?;
+        let sample_rate = u32::from_le_bytes(read_array(&mut input)?);
+        let channels = u16::from_le_bytes(read_array(&mut input)?);
+        let total_frames = u64::from_le_bytes(read_array(&mut input)?);
+        // Each level has an 8 byte header and 4 bytes per peak
+        let level_count = u32::from_le_bytes(read_array(&mut input)?);
+        let level_count = bounded_count(level_count, 8, file_len)?;
+
+        let mut levels = Vec::with_capacity(level_count);
+        for _ in 0..level_count {
+            let frames_per_peak = u32::from_le_bytes(read_array(&mut input)?);
+            let peak_count = u32::from_le_bytes(read_array(&mut input)?);
+            let peak_count = bounded_count(peak_count, 4, file_len)?;
+            let mut min = Vec::with_capacity(peak_count);
+            let mut max = Vec::with_capacity(peak_count);
+            for _ in 0..peak_count {
+                min.push(dequantize(i16::from_le_bytes(read_array(&mut input)?)));
+                max.push(dequantize(i16::from_le_bytes(read_array(&mut input)?)));
+            }
+            levels.push(PeakLevel {
+                frames_per_peak,
+                min,
+                max,
+            });
+        }
+
+        Ok(Self {
+            checksum,
+            sample_rate,
+            channels,
+            total_frames,
+            levels,
+        })
+    }
+}
+
+/// Decode the first audio track of a file, passing each interleaved frame with its
+/// sample rate to `on_frame`. Returns the sample rate and channel count. Stored
+/// files have no extension of their own, so the format is hinted by `extension`.
+pub(super) fn decode_frames(
+    path: &Path,
+    extension: &str,
+    mut on_frame: impl FnMut(u32, &[f32]),
+) -> Result<(u32, u16), MediaError> {
+    let file = File::open(path)?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    hint.with_extension(extension);
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(decode_error)?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| MediaError::DecodeError("No audio track found".to_string()))?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut channels = track
+        .codec_params
+        .channels
+        .map_or(0, |channels| channels.count() as u16);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(decode_error)?;
+
+    let mut samples: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(decode_error(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet only leaves a gap
+            Err(SymphoniaError::DecodeError(e)) => {
+                debug!("Skipping undecodable packet in {}: {}", path.display(), e);
+                continue;
+            }
+            Err(e) => return Err(decode_error(e)),
+        };
+
+        let spec = *decoded.spec();
+        sample_rate = spec.rate;
+        channels = spec.channels.count() as u16;
+        let buffer = match samples.as_mut() {
+            Some(buffer) if buffer.capacity() >= decoded.capacity() * spec.channels.count() => {
+                buffer
+            }
+            _ => samples.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+        };
+        buffer.copy_interleaved_ref(decoded);
+        for frame in buffer.samples().chunks(spec.channels.count().max(1)) {
+            on_frame(spec.rate, frame);
+        }
+    }
+
+    Ok((sample_rate, channels))
+}
+
+/// Collects the peaks of the most detailed level while frames are decoded
+struct PeakBuilder {
+    frames_per_peak: u32,
+    frames_in_peak: u32,
+    total_frames: u64,
+    min: f32,
+    max: f32,
+    level: PeakLevel,
+}
+
+struct FinishedPeaks {
+    total_frames: u64,
+    level: PeakLevel,
+}
+
+impl PeakBuilder {
+    fn new(frames_per_peak: u32) -> Self {
+        Self {
+            frames_per_peak,
+            frames_in_peak: 0,
+            total_frames: 0,
+            min: 0.0,
+            max: 0.0,
+            level: PeakLevel {
+                frames_per_peak,
+                min: Vec::new(),
+                max: Vec::new(),
+            },
+        }
+    }
+
+    fn push_frame(&mut self, frame: &[f32]) {
+        for &sample in frame {
+            self.min = self.min.min(sample);
+            self.max = self.max.max(sample);
+        }
+        self.frames_in_peak += 1;
+        self.total_frames += 1;
+        if self.frames_in_peak == self.frames_per_peak {
+            self.close_peak();
+        }
+    }
+
+    fn close_peak(&mut self) {
+        self.level.min.push(self.min.clamp(-1.0, 1.0));
+        self.level.max.push(self.max.clamp(-1.0, 1.0));
+        self.frames_in_peak = 0;
+        self.min = 0.0;
+        self.max = 0.0;
+    }
+
+    fn finish(mut self) -> FinishedPeaks {
+        if self.frames_in_peak > 0 {
+            self.close_peak();
+        }
+        FinishedPeaks {
+            total_frames: self.total_frames,
+            level: self.level,
+        }
+    }
+}
+
+fn decode_error(error: SymphoniaError) -> MediaError {
+    match error {
+        SymphoniaError::IoError(e) => MediaError::IoError(e.to_string()),
+        SymphoniaError::Unsupported(what) => {
+            MediaError::DecodeError(format!("Unsupported audio: {}", what))
+        }
+        e => MediaError::DecodeError(e.to_string()),
+    }
+}
+
+fn quantize(peak: f32) -> i16 {
+    (peak.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16
+}
+
+fn dequantize(value: i16) -> f32 {
+    (value as f32 / i16::MAX as f32).max(-1.0)
+}
+
+fn write_bytes(out: &mut impl Write, bytes: &[u8]) -> Result<(), MediaError> {
+    out.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    out.write_all(bytes)?;
+    Ok(())
+}
+
+fn read_bytes(input: &mut impl Read, file_len: u64) -> Result<Vec<u8>, MediaError> {
+    let len = bounded_count(u32::from_le_bytes(read_array(input)?), 1, file_len)?;
+    let mut bytes = vec![0u8; len];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// A count read from a cache file, checked against what a file of `file_len` bytes
+/// can hold so a corrupt count can't allocate more than that
+fn bounded_count(count: u32, item_bytes: u64, file_len: u64) -> Result<usize, MediaError> {
+    if count as u64 * item_bytes > file_len {
+        return Err(MediaError::DecodeError(
+            "Waveform cache file is truncated or corrupt".to_string(),
+        ));
+    }
+    Ok(count as usize)
+}
+
+fn read_array<const N: usize>(input: &mut impl Read) -> Result<[u8; N], MediaError> {
+    let mut bytes = [0u8; N];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
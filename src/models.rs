@@ -12,6 +12,7 @@
 //! - [`KeySignature`]: Musical key information combining tonic and scale
 //! - [`TimeSignature`]: Musical time signature with validation
 //! - [`PluginFormat`]: Enumeration of supported plugin formats (VST2/VST3)
+//! - [`SetKind`]: Whether a set is a project, a template or Live's default set
 //!
 //! ## Musical Types
 //!
@@ -958,6 +959,18 @@ impl TimeSignature {
         self.denominator & (self.denominator - 1) == 0
     }
 
+    /// Convert a 1-based bar/beat position to seconds, assuming a constant tempo.
+    /// Beats are counted in the signature's note value (eighths in 6/8), while tempo
+    /// is in quarter notes per minute as in Live.
+    pub fn position_to_seconds(&self, bar: i32, beat: f64, tempo: f64) -> Option<f64> {
+        if tempo <= 0.0 || !self.is_valid() {
+            return None;
+        }
+        let beats = (bar - 1) as f64 * self.numerator as f64 + (beat - 1.0);
+        let quarter_notes = beats * 4.0 / self.denominator as f64;
+        Some(quarter_notes * 60.0 / tempo)
+    }
+
     pub fn from_encoded(encoded_value: i32) -> Result<Self, TimeSignatureError> {
         if encoded_value < 0 || encoded_value > 494 {
             return Err(TimeSignatureError::InvalidEncodedValue(encoded_value));
@@ -1012,6 +1025,51 @@ impl fmt::Display for AbletonVersion {
     }
 }
 
+/// What a Live Set is used for, worked out from where it lives.
+///
+/// Templates and the default set are indexed like any other set but kept out of
+/// library statistics, since they aren't songs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SetKind {
+    /// A regular set
+    #[default]
+    Project,
+    /// A set in a `Templates` folder, which Live offers as a starting point
+    Template,
+    /// `DefaultLiveSet.als`, which Live opens for every new set
+    Default,
+}
+
+impl SetKind {
+    /// Name stored in the database and used by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SetKind::Project => "project",
+            SetKind::Template => "template",
+            SetKind::Default => "default",
+        }
+    }
+}
+
+impl FromStr for SetKind {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "project" => Ok(SetKind::Project),
+            "template" => Ok(SetKind::Template),
+            "default" => Ok(SetKind::Default),
+            _ => Err(format!("Invalid set kind: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for SetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl Default for Id {
     fn default() -> Self {
         Id(0)
@@ -1037,4 +1095,20 @@ pub struct CollectionStatistics {
     pub most_common_key: Option<String>,
     /// Most common time signature across all projects
     pub most_common_time_signature: Option<String>,
+    /// Project count per workflow status, in pipeline order
+    pub status_breakdown: Vec<(String, i32)>,
+    /// Number of projects that have no workflow status yet
+    pub projects_without_status: i32,
+    /// Number of projects whose primary audio file has been measured
+    pub analyzed_project_count: i32,
+    /// Average integrated loudness of the projects' audio in LUFS
+    pub average_lufs: Option<f64>,
+    /// Integrated loudness of the quietest project's audio in LUFS
+    pub min_lufs: Option<f64>,
+    /// Integrated loudness of the loudest project's audio in LUFS
+    pub max_lufs: Option<f64>,
+    /// Highest true peak of any project's audio in dBTP
+    pub max_true_peak_dbtp: Option<f64>,
+    /// Average loudness range of the projects' audio in LU
+    pub average_loudness_range_lu: Option<f64>,
 }
@@ -0,0 +1,172 @@
+use log::{debug, trace, warn};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::database::scan_state::IndexedFile;
+use crate::utils::metadata::load_file_hash;
+
+/// Options for a directory scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Parse every set found, even ones whose file hasn't changed or that are
+    /// quarantined after failing to parse
+    pub force: bool,
+}
+
+/// How many sets a scan found in each state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanCounts {
+    pub new: u32,
+    pub updated: u32,
+    pub skipped: u32,
+    pub removed: u32,
+    pub failed: u32,
+    /// Sets skipped because they keep failing to parse and haven't changed
+    pub quarantined: u32,
+}
+
+impl ScanCounts {
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} new, {} updated, {} unchanged, {} removed",
+            self.new, self.updated, self.skipped, self.removed
+        );
+        if self.failed > 0 {
+            summary.push_str(&format!(", {} failed", self.failed));
+        }
+        if self.quarantined > 0 {
+            summary.push_str(&format!(", {} quarantined", self.quarantined));
+        }
+        summary
+    }
+}
+
+/// Size and modification time of a set's file as found on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileState {
+    pub size: u64,
+    pub modified_at: i64,
+}
+
+impl FileState {
+    pub fn read(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified_at = metadata
+            .modified()
+            .ok()
+            .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp())?;
+        Some(Self {
+            size: metadata.len(),
+            modified_at,
+        })
+    }
+}
+
+/// What an incremental scan has to do to bring the database in line with the disk
+#[derive(Debug, Default)]
+pub struct ScanPlan {
+    /// Sets with no project at their path
+    pub new: Vec<PathBuf>,
+    /// Sets whose content changed, with the project they belong to
+    pub changed: HashMap<PathBuf, String>,
+    /// Sets that don't need parsing
+    pub unchanged: usize,
+    /// File states of the sets to parse, recorded once they are stored
+    pub file_states: HashMap<PathBuf, FileState>,
+    /// Unchanged sets whose size or modification time moved without the content changing
+    pub touched: Vec<(PathBuf, FileState)>,
+    /// Projects under a scanned root whose file is gone, keyed by project ID
+    pub removed: Vec<(String, PathBuf)>,
+}
+
+impl ScanPlan {
+    /// Every set that has to be parsed
+    pub fn paths_to_parse(&self) -> Vec<PathBuf> {
+        self.new
+            .iter()
+            .chain(self.changed.keys())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Compare the sets found on disk against what is indexed. Path, size and modification
+/// time are checked first; the file is only hashed when they differ, and only parsed when
+/// the hash differs too. With `force` every set found is parsed.
+pub fn plan_scan(
+    found: &HashSet<PathBuf>,
+    roots: &[PathBuf],
+    indexed: &HashMap<String, IndexedFile>,
+    force: bool,
+) -> ScanPlan {
+    let mut plan = ScanPlan::default();
+
+    for path in found {
+        let state = match FileState::read(path) {
+            Some(state) => state,
+            None => {
+                warn!("Could not read metadata for {}", path.display());
+                continue;
+            }
+        };
+
+        let existing = match indexed.get(path.to_string_lossy().as_ref()) {
+            Some(existing) => existing,
+            None => {
+                trace!("New set: {}", path.display());
+                plan.new.push(path.clone());
+                plan.file_states.insert(path.clone(), state);
+                continue;
+            }
+        };
+
+        if force {
+            plan.changed
+                .insert(path.clone(), existing.project_id.clone());
+            plan.file_states.insert(path.clone(), state);
+            continue;
+        }
+
+        if existing.file_size == Some(state.size) && existing.modified_at == state.modified_at {
+            trace!("Unchanged set: {}", path.display());
+            plan.unchanged += 1;
+            continue;
+        }
+
+        match load_file_hash(path) {
+            Ok(hash) if hash == existing.hash => {
+                trace!("Set touched but content unchanged: {}", path.display());
+                plan.unchanged += 1;
+                plan.touched.push((path.clone(), state));
+            }
+            Ok(_) => {
+                trace!("Changed set: {}", path.display());
+                plan.changed
+                    .insert(path.clone(), existing.project_id.clone());
+                plan.file_states.insert(path.clone(), state);
+            }
+            Err(e) => warn!("Failed to hash {}: {}", path.display(), e),
+        }
+    }
+
+    for (path, file) in indexed {
+        let path = PathBuf::from(path);
+        if found.contains(&path) || path.exists() {
+            continue;
+        }
+        if roots.iter().any(|root| path.starts_with(root)) {
+            trace!("Removed set: {}", path.display());
+            plan.removed.push((file.project_id.clone(), path));
+        }
+    }
+
+    debug!(
+        "Scan plan: {} new, {} changed, {} unchanged, {} removed",
+        plan.new.len(),
+        plan.changed.len(),
+        plan.unchanged,
+        plan.removed.len()
+    );
+    plan
+}
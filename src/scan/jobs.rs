@@ -0,0 +1,350 @@
+use log::{debug, trace, warn};
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::parser::ParseLimits;
+use crate::config::{CONFIG, DEFAULT_SCAN_FILE_TIMEOUT_SECS, DEFAULT_SCAN_WORKERS};
+use crate::error::LiveSetError;
+use crate::live_set::LiveSet;
+
+/// Outcome of parsing one set, with the path it came from
+pub type ParseOutcome = Result<(PathBuf, LiveSet), (PathBuf, LiveSetError)>;
+
+/// Process-wide scan job manager, sized from `scan_workers` and `scan_file_timeout_secs`
+pub static SCAN_JOBS: Lazy<ScanJobManager> = Lazy::new(|| match CONFIG.as_ref() {
+    Ok(config) => ScanJobManager::new(config.scan_workers(), config.scan_file_timeout()),
+    Err(_) => ScanJobManager::new(
+        DEFAULT_SCAN_WORKERS,
+        Some(Duration::from_secs(DEFAULT_SCAN_FILE_TIMEOUT_SECS)),
+    ),
+});
+
+/// Which queue a set waits in. Workers always take priority work before bulk work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanLane {
+    /// Single sets someone is waiting on, such as watcher rescans
+    Priority,
+    /// Sets queued by a directory scan
+    Bulk,
+}
+
+/// Cancel and pause switches shared by every set queued for one scan
+#[derive(Debug, Default)]
+pub struct ScanControl {
+    cancelled: Arc<AtomicBool>,
+    paused: AtomicBool,
+}
+
+impl ScanControl {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+}
+
+struct Task {
+    path: PathBuf,
+    control: Arc<ScanControl>,
+    results: Sender<ParseOutcome>,
+}
+
+#[derive(Default)]
+struct Queues {
+    priority: VecDeque<Task>,
+    bulk: VecDeque<Task>,
+    closed: bool,
+}
+
+impl Queues {
+    /// Priority work first, then bulk work from scans that aren't paused
+    fn next(&mut self) -> Option<Task> {
+        if let Some(task) = self.priority.pop_front() {
+            return Some(task);
+        }
+        let mut index = 0;
+        while let Some(task) = self.bulk.get(index) {
+            if task.control.is_cancelled() {
+                // Dropped unparsed, which disconnects the scan's results once all are gone
+                self.bulk.remove(index);
+            } else if task.control.is_paused() {
+                index += 1;
+            } else {
+                return self.bulk.remove(index);
+            }
+        }
+        None
+    }
+}
+
+struct Shared {
+    queues: Mutex<Queues>,
+    ready: Condvar,
+    file_timeout: Option<Duration>,
+}
+
+/// Fixed set of parser threads fed from a priority lane and a bulk lane
+pub struct ScanPool {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ScanPool {
+    /// Start `worker_count` parser threads. Each set may take up to `file_timeout` to
+    /// parse before it's abandoned and reported as [`LiveSetError::ParseTimeout`].
+    pub fn new(worker_count: usize, file_timeout: Option<Duration>) -> Self {
+        let shared = Arc::new(Shared {
+            queues: Mutex::new(Queues::default()),
+            ready: Condvar::new(),
+            file_timeout,
+        });
+
+        let workers = (0..worker_count.max(1))
+            .map(|id| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || run_worker(id, &shared))
+            })
+            .collect();
+
+        debug!(
+            "Started scan pool with {} workers, file timeout {:?}",
+            worker_count.max(1),
+            file_timeout
+        );
+        Self { shared, workers }
+    }
+
+    pub fn worker_count(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Queue a set; its outcome is sent to `results` once parsed
+    pub fn submit(
+        &self,
+        path: PathBuf,
+        lane: ScanLane,
+        control: &Arc<ScanControl>,
+        results: &Sender<ParseOutcome>,
+    ) {
+        trace!("Queueing {} in the {:?} lane", path.display(), lane);
+        let task = Task {
+            path,
+            control: Arc::clone(control),
+            results: results.clone(),
+        };
+        let mut queues = self.shared.queues.lock().unwrap();
+        match lane {
+            ScanLane::Priority => queues.priority.push_back(task),
+            ScanLane::Bulk => queues.bulk.push_back(task),
+        }
+        drop(queues);
+        self.shared.ready.notify_one();
+    }
+
+    /// Wake workers after a scan was paused, resumed or cancelled
+    fn notify(&self) {
+        // Take the lock so a worker can't miss the wakeup between checking and waiting
+        let _queues = self.shared.queues.lock().unwrap();
+        self.shared.ready.notify_all();
+    }
+}
+
+impl Drop for ScanPool {
+    fn drop(&mut self) {
+        trace!("Scan pool being dropped, signaling workers to stop");
+        self.shared.queues.lock().unwrap().closed = true;
+        self.shared.ready.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+        debug!("All scan workers completed");
+    }
+}
+
+fn run_worker(id: usize, shared: &Shared) {
+    trace!("Scan worker {} started", id);
+    loop {
+        let task = {
+            let mut queues = shared.queues.lock().unwrap();
+            loop {
+                if let Some(task) = queues.next() {
+                    break task;
+                }
+                // Work left behind by a paused scan is dropped with the pool
+                if queues.closed {
+                    trace!("Scan worker {} exiting", id);
+                    return;
+                }
+                queues = shared.ready.wait(queues).unwrap();
+            }
+        };
+
+        trace!("Worker {} processing file: {}", id, task.path.display());
+        let limits = ParseLimits {
+            timeout: shared.file_timeout,
+            cancelled: Some(Arc::clone(&task.control.cancelled)),
+        };
+        let result = match LiveSet::new_with_limits(task.path.clone(), limits) {
+            Ok(live_set) => Ok((task.path, live_set)),
+            Err(e) => {
+                if let LiveSetError::ParseTimeout(_) = e {
+                    warn!("Gave up on {}: {}", task.path.display(), e);
+                }
+                Err((task.path, e))
+            }
+        };
+        let _ = task.results.send(result);
+    }
+}
+
+/// Runs directory scans and single-set rescans on one shared [`ScanPool`], and lets
+/// running scans be paused, resumed and cancelled
+pub struct ScanJobManager {
+    pool: ScanPool,
+    scans: Mutex<Vec<Arc<ScanControl>>>,
+}
+
+impl ScanJobManager {
+    pub fn new(worker_count: usize, file_timeout: Option<Duration>) -> Self {
+        Self {
+            pool: ScanPool::new(worker_count, file_timeout),
+            scans: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn worker_count(&self) -> usize {
+        self.pool.worker_count()
+    }
+
+    /// Queue a directory scan in the bulk lane. The job's results channel disconnects
+    /// once every set has been parsed, or dropped after a cancel.
+    pub fn start_scan(&self, paths: Vec<PathBuf>) -> ScanJob<'_> {
+        let control = Arc::new(ScanControl::default());
+        let (results_tx, results_rx) = channel();
+
+        debug!("Queueing scan of {} sets", paths.len());
+        self.scans.lock().unwrap().push(Arc::clone(&control));
+        for path in paths {
+            self.pool.submit(path, ScanLane::Bulk, &control, &results_tx);
+        }
+
+        ScanJob {
+            manager: self,
+            control,
+            results: results_rx,
+        }
+    }
+
+    /// Parse one set ahead of any queued bulk work and wait for it
+    pub fn parse_now(&self, path: PathBuf) -> Result<LiveSet, LiveSetError> {
+        let control = Arc::new(ScanControl::default());
+        let (results_tx, results_rx) = channel();
+        self.pool
+            .submit(path.clone(), ScanLane::Priority, &control, &results_tx);
+        drop(results_tx);
+
+        match results_rx.recv() {
+            Ok(result) => result.map(|(_, live_set)| live_set).map_err(|(_, e)| e),
+            Err(_) => Err(LiveSetError::CreateLiveSetError(format!(
+                "Scan worker stopped before parsing {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Stop handing out sets from running scans. Sets already being parsed finish.
+    /// Returns how many scans were paused.
+    pub fn pause(&self) -> usize {
+        let count = self.set_flag(|control| &control.paused, true);
+        if count > 0 {
+            debug!("Paused {} scans", count);
+        }
+        count
+    }
+
+    /// Continue paused scans. Returns how many scans were resumed.
+    pub fn resume(&self) -> usize {
+        let count = self.set_flag(|control| &control.paused, false);
+        if count > 0 {
+            debug!("Resumed {} scans", count);
+            self.pool.notify();
+        }
+        count
+    }
+
+    /// Cancel running scans. Queued sets are dropped and sets being parsed stop at
+    /// their next read. Returns how many scans were cancelled.
+    pub fn cancel(&self) -> usize {
+        let count = self.set_flag(|control| control.cancelled.as_ref(), true);
+        if count > 0 {
+            debug!("Cancelled {} scans", count);
+            self.pool.notify();
+        }
+        count
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        !self.scans.lock().unwrap().is_empty()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.scans
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|control| control.is_paused())
+    }
+
+    /// Set a flag on every running scan, returning how many changed
+    fn set_flag(&self, flag: impl Fn(&ScanControl) -> &AtomicBool, value: bool) -> usize {
+        self.scans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|control| !control.is_cancelled())
+            .filter(|control| flag(control).swap(value, Ordering::Relaxed) != value)
+            .count()
+    }
+}
+
+/// A directory scan queued on a [`ScanJobManager`]
+pub struct ScanJob<'a> {
+    manager: &'a ScanJobManager,
+    control: Arc<ScanControl>,
+    results: Receiver<ParseOutcome>,
+}
+
+impl ScanJob<'_> {
+    pub fn results(&self) -> &Receiver<ParseOutcome> {
+        &self.results
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.control.is_cancelled()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.control.is_paused()
+    }
+}
+
+impl Drop for ScanJob<'_> {
+    fn drop(&mut self) {
+        // Anything still queued is no longer wanted
+        self.control.cancelled.store(true, Ordering::Relaxed);
+        self.manager
+            .scans
+            .lock()
+            .unwrap()
+            .retain(|control| !Arc::ptr_eq(control, &self.control));
+        self.manager.pool.notify();
+    }
+}
@@ -1,7 +1,15 @@
+pub mod incremental;
+pub mod jobs;
 pub mod parallel;
 pub mod parser;
+pub mod project_folder;
 pub mod project_scanner;
+pub mod quarantine;
+pub mod roots;
 
 // Re-export all public items from scanner
+pub use incremental::{ScanCounts, ScanOptions};
+pub use jobs::{ScanJobManager, SCAN_JOBS};
+pub use roots::{RootRules, ScanRoots};
 pub use parallel::*;
 pub use parser::*;
@@ -49,9 +49,11 @@ use log::{debug, trace, warn};
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use std::collections::{HashMap, HashSet};
-use std::io::BufRead;
+use std::io::{self, BufRead, Read};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use crate::ableton_db::AbletonDatabase;
@@ -61,7 +63,7 @@ use crate::models::{
     AbletonVersion, KeySignature, Plugin, PluginInfo, Sample, Scale, TimeSignature, Tonic,
 };
 use crate::utils::plugins::{get_most_recent_db_file, get_most_recent_plugins_db_file};
-use crate::utils::plugins::LineTrackingBuffer;
+use crate::utils::plugins::{LineNumber, LineTrackingBuffer};
 use crate::utils::{EventExt, StringResultExt};
 #[allow(unused_imports)]
 use crate::{trace_fn, warn_fn};
@@ -253,6 +255,64 @@ impl Default for ParseOptions {
     }
 }
 
+/// Limits that stop a parse part way through.
+///
+/// Both are checked every time the parser pulls more XML from its source, so a set
+/// that never finishes parsing can't hold on to a scan worker.
+#[derive(Debug, Clone, Default)]
+pub struct ParseLimits {
+    /// Give up once this much time has passed since the parse started
+    pub timeout: Option<Duration>,
+    /// Give up as soon as this is set, e.g. when the scan is cancelled
+    pub cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl ParseLimits {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+}
+
+/// Source wrapper that fails reads once the parse's limits are exceeded
+struct LimitedSource<R> {
+    inner: R,
+    deadline: Option<Instant>,
+    limits: ParseLimits,
+}
+
+impl<R> LimitedSource<R> {
+    fn check(&self) -> io::Result<()> {
+        if self.limits.is_cancelled() {
+            // Not `Interrupted`, which readers retry
+            return Err(io::Error::other("parse cancelled"));
+        }
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "parse timed out"));
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for LimitedSource<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.check()?;
+        self.inner.read(buf)
+    }
+}
+
+impl<R: BufRead> BufRead for LimitedSource<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.check()?;
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
 /// Complete parsing results containing all extracted project metadata.
 ///
 /// This struct holds all the data extracted from an Ableton Live project file.
@@ -364,9 +424,11 @@ pub struct Parser {
     /// Configuration options controlling what data to extract
     pub options: ParseOptions,
     /// Line tracking for error reporting and debugging
-    line_tracker: LineTrackingBuffer,
+    line_tracker: LineNumber,
     /// Current file being parsed (for debugging purposes)
     current_file: Option<String>,
+    /// Timeout and cancellation checked while streaming
+    limits: ParseLimits,
 
     // Sample parsing state
     /// Collected sample file paths discovered during parsing
@@ -452,28 +514,39 @@ impl Parser {
     /// The parser automatically adjusts options based on detected version:
     /// - **< v11**: Disables key signature parsing (not supported)
     /// - **>= v11**: Full feature support including key signatures
-    pub fn new(xml_data: &[u8], mut options: ParseOptions) -> Result<Self, LiveSetError> {
+    pub fn new(xml_data: &[u8], options: ParseOptions) -> Result<Self, LiveSetError> {
         // First, detect and validate the version
         let version = Self::detect_version(xml_data)?;
 
-        // Disable features not supported in older versions
-        if version.major < 11 {
-            options.parse_key = false; // Key detection only available in v11+
-                                       // warn_fn!(
-                                       //     "parser",
-                                       //     "Key detection not supported in version {}",
-                                       //     version
-                                       // );
-                                       // Add other version-specific feature flags here
-        }
+        let mut parser = Self::streaming(options);
+        parser.apply_version(version);
+        Ok(parser)
+    }
 
-        Ok(Self {
+    /// Creates a parser for a set that will be streamed through [`Parser::parse_reader`].
+    ///
+    /// Unlike [`Parser::new`] this doesn't need the XML up front: the version is read
+    /// from the root `<Ableton>` element as the document streams past, and options not
+    /// supported by that version are switched off before anything else is parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use seula::scan::parser::{Parser, ParseOptions};
+    /// use seula::utils::open_gzip_file;
+    ///
+    /// let mut parser = Parser::streaming(ParseOptions::default());
+    /// let result = parser.parse_reader(open_gzip_file(path)?)?;
+    /// ```
+    pub fn streaming(options: ParseOptions) -> Self {
+        Self {
             state: ParserState::Root,
             depth: 0,
-            ableton_version: version,
+            ableton_version: AbletonVersion::default(),
             options,
-            line_tracker: LineTrackingBuffer::new(xml_data.to_vec()),
+            line_tracker: LineNumber::default(),
             current_file: None,
+            limits: ParseLimits::default(),
 
             // Initialize sample parsing state
             sample_paths: HashSet::new(),
@@ -498,7 +571,16 @@ impl Parser {
             key_frequencies: HashMap::new(),
             current_scale_info: None,
             current_clip_in_key: false,
-        })
+        }
+    }
+
+    /// Records the detected version and disables features it doesn't support
+    fn apply_version(&mut self, version: AbletonVersion) {
+        if version.major < 11 {
+            self.options.parse_key = false; // Key detection only available in v11+
+                                            // Add other version-specific feature flags here
+        }
+        self.ableton_version = version;
     }
 
     /// Sets the current file name for debugging purposes.
@@ -520,6 +602,16 @@ impl Parser {
         self.current_file = Some(file_name.to_string());
     }
 
+    /// Line of the document the parser has reached
+    pub fn current_line(&self) -> usize {
+        self.line_tracker.get()
+    }
+
+    /// Sets the timeout and cancellation flag checked by [`Parser::parse_reader`].
+    pub fn set_limits(&mut self, limits: ParseLimits) {
+        self.limits = limits;
+    }
+
     /// Detects the Ableton Live version from XML header information.
     ///
     /// This method performs a fast scan of the XML header to extract version information
@@ -571,58 +663,10 @@ impl Parser {
 
         loop {
             match reader.read_event_into(&mut buf) {
-                Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e)) => {
-                    if e.name().as_ref() == b"Ableton" {
-                        // Get MinorVersion attribute which contains the actual version info
-                        let version_str = e
-                            .try_get_attribute("MinorVersion")?
-                            .ok_or(LiveSetError::MissingVersion)?
-                            .unescape_value()?;
-
-                        // Parse version components from MinorVersion (format: "12.0_12049")
-                        let parts: Vec<&str> = version_str.split('_').collect();
-                        if parts.len() != 2 {
-                            return Err(LiveSetError::InvalidVersion(version_str.to_string()));
-                        }
-
-                        let version_parts: Vec<&str> = parts[0].split('.').collect();
-                        if version_parts.len() != 2 {
-                            return Err(LiveSetError::InvalidVersion(version_str.to_string()));
-                        }
-
-                        // Parse major, minor, and patch versions
-                        let major: u32 = version_parts[0]
-                            .parse()
-                            .map_err(|_| LiveSetError::InvalidVersion(version_str.to_string()))?;
-                        let minor: u32 = version_parts[1]
-                            .parse()
-                            .map_err(|_| LiveSetError::InvalidVersion(version_str.to_string()))?;
-                        let patch: u32 = parts[1]
-                            .parse()
-                            .map_err(|_| LiveSetError::InvalidVersion(version_str.to_string()))?;
-
-                        // Get beta status from SchemaChangeCount
-                        let beta = e
-                            .try_get_attribute("SchemaChangeCount")
-                            .ok()
-                            .flatten()
-                            .map(|attr| attr.unescape_value())
-                            .transpose()?
-                            .map(|v| v == "beta")
-                            .unwrap_or(false);
-
-                        // Validate major version
-                        if major < 9 || major > 12 {
-                            return Err(LiveSetError::UnsupportedVersion(major));
-                        }
-
-                        return Ok(AbletonVersion {
-                            major,
-                            minor,
-                            patch,
-                            beta,
-                        });
-                    }
+                Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e))
+                    if e.name().as_ref() == b"Ableton" =>
+                {
+                    return Self::version_from_element(e);
                 }
                 Ok(Event::Eof) => break,
                 Err(e) => return Err(LiveSetError::from(e)),
@@ -633,6 +677,61 @@ impl Parser {
         Err(LiveSetError::MissingVersion)
     }
 
+    /// Reads the version from the attributes of the root `<Ableton>` element.
+    fn version_from_element(
+        e: &quick_xml::events::BytesStart,
+    ) -> Result<AbletonVersion, LiveSetError> {
+        // Get MinorVersion attribute which contains the actual version info
+        let version_str = e
+            .try_get_attribute("MinorVersion")?
+            .ok_or(LiveSetError::MissingVersion)?
+            .unescape_value()?;
+
+        // Parse version components from MinorVersion (format: "12.0_12049")
+        let parts: Vec<&str> = version_str.split('_').collect();
+        if parts.len() != 2 {
+            return Err(LiveSetError::InvalidVersion(version_str.to_string()));
+        }
+
+        let version_parts: Vec<&str> = parts[0].split('.').collect();
+        if version_parts.len() != 2 {
+            return Err(LiveSetError::InvalidVersion(version_str.to_string()));
+        }
+
+        // Parse major, minor, and patch versions
+        let major: u32 = version_parts[0]
+            .parse()
+            .map_err(|_| LiveSetError::InvalidVersion(version_str.to_string()))?;
+        let minor: u32 = version_parts[1]
+            .parse()
+            .map_err(|_| LiveSetError::InvalidVersion(version_str.to_string()))?;
+        let patch: u32 = parts[1]
+            .parse()
+            .map_err(|_| LiveSetError::InvalidVersion(version_str.to_string()))?;
+
+        // Get beta status from SchemaChangeCount
+        let beta = e
+            .try_get_attribute("SchemaChangeCount")
+            .ok()
+            .flatten()
+            .map(|attr| attr.unescape_value())
+            .transpose()?
+            .map(|v| v == "beta")
+            .unwrap_or(false);
+
+        // Validate major version
+        if !(9..=12).contains(&major) {
+            return Err(LiveSetError::UnsupportedVersion(major));
+        }
+
+        Ok(AbletonVersion {
+            major,
+            minor,
+            patch,
+            beta,
+        })
+    }
+
     /// Parses the complete XML data and extracts all configured project metadata.
     ///
     /// This is the main parsing method that processes the entire XML structure using
@@ -690,24 +789,70 @@ impl Parser {
     /// - Processing time is roughly linear with XML file size
     /// - Configure [`ParseOptions`] to skip unnecessary data extraction
     pub fn parse(&mut self, xml_data: &[u8]) -> Result<ParseResult, LiveSetError> {
-        let mut reader = Reader::from_reader(xml_data);
+        self.parse_reader(xml_data)
+    }
+
+    /// Parses a set streamed from any buffered reader in a single pass.
+    ///
+    /// This is what [`Parser::parse`] does over an in-memory slice, but the document
+    /// never has to be held in memory: only the current XML event is buffered, so
+    /// memory use stays bounded however large the decompressed set is. The version is
+    /// read from the root `<Ableton>` element when it streams past.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Decompressed XML, typically from [`crate::utils::open_gzip_file`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LiveSetError`] if the root element carries no valid version, the XML
+    /// is malformed, decompression fails part way through, or the parsed properties
+    /// are invalid. Returns [`LiveSetError::ParseTimeout`] or [`LiveSetError::Cancelled`]
+    /// when the parser's [`ParseLimits`] stop it.
+    pub fn parse_reader<R: BufRead>(&mut self, source: R) -> Result<ParseResult, LiveSetError> {
+        let source = LimitedSource {
+            inner: source,
+            deadline: self.limits.timeout.map(|timeout| Instant::now() + timeout),
+            limits: self.limits.clone(),
+        };
+        let deadline = source.deadline;
+
+        self.parse_events(source).map_err(|e| {
+            if self.limits.is_cancelled() {
+                LiveSetError::Cancelled
+            } else if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                LiveSetError::ParseTimeout(self.limits.timeout.unwrap_or_default().as_secs())
+            } else {
+                e
+            }
+        })
+    }
+
+    fn parse_events<R: BufRead>(&mut self, source: R) -> Result<ParseResult, LiveSetError> {
+        self.line_tracker = LineNumber::default();
+        let mut reader = Reader::from_reader(LineTrackingBuffer::new(
+            source,
+            self.line_tracker.clone(),
+        ));
         reader.config_mut().trim_text(true);
         let mut buf = Vec::new();
         let mut byte_pos; // Will be set in the loop
         let result = ParseResult::default();
 
-        // Skip the version tag since we've already processed it
-        let mut skip_first = true;
+        // The root element carries the version and is not handled like other tags
+        let mut found_root = false;
 
         #[allow(unused_variables)]
         loop {
             byte_pos = reader.buffer_position();
-            let line = self.line_tracker.get_line_number(byte_pos);
+            let line = self.line_tracker.get();
 
             match reader.read_event_into(&mut buf) {
                 Ok(Event::Start(ref event)) => {
-                    if skip_first && event.name().as_ref() == b"Ableton" {
-                        skip_first = false;
+                    if !found_root && event.name().as_ref() == b"Ableton" {
+                        self.apply_version(Self::version_from_element(event)?);
+                        found_root = true;
+                        buf.clear();
                         continue;
                     }
                     self.depth += 1;
@@ -715,8 +860,10 @@ impl Parser {
                 }
 
                 Ok(Event::Empty(ref event)) => {
-                    if skip_first && event.name().as_ref() == b"Ableton" {
-                        skip_first = false;
+                    if !found_root && event.name().as_ref() == b"Ableton" {
+                        self.apply_version(Self::version_from_element(event)?);
+                        found_root = true;
+                        buf.clear();
                         continue;
                     }
                     self.handle_start_event(event, &mut reader, &mut byte_pos)?;
@@ -739,6 +886,10 @@ impl Parser {
             buf.clear();
         }
 
+        if !found_root {
+            return Err(LiveSetError::MissingVersion);
+        }
+
         // Convert collected data into final result
         self.finalize_result(result)
     }
@@ -1080,7 +1231,7 @@ impl Parser {
         byte_pos: &mut u64,
     ) -> Result<(), LiveSetError> {
         let name = event.name().to_string_result()?;
-        let line = self.line_tracker.get_line_number(*byte_pos);
+        let line = self.line_tracker.get();
 
         trace_fn!(
             "handle_start_event",
@@ -1194,7 +1345,7 @@ impl Parser {
 
                 loop {
                     *byte_pos = reader.buffer_position();
-                    let line = self.line_tracker.get_line_number(*byte_pos);
+                    let line = self.line_tracker.get();
 
                     match reader.read_event_into(&mut buf) {
                         Ok(Event::Empty(ref event)) => {
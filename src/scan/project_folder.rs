@@ -0,0 +1,58 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::models::SetKind;
+
+/// Folder Live keeps at the top of every project folder
+pub const PROJECT_INFO_DIR: &str = "Ableton Project Info";
+/// The set Live opens for every new set
+pub const DEFAULT_SET_NAME: &str = "DefaultLiveSet.als";
+/// Folder Live keeps templates in, inside the User Library
+pub const TEMPLATES_DIR: &str = "Templates";
+
+/// Tell templates and the default set apart from regular sets by their path
+pub fn classify_set(path: &Path) -> SetKind {
+    let is_default = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(DEFAULT_SET_NAME));
+    if is_default {
+        return SetKind::Default;
+    }
+
+    let in_templates = path.parent().is_some_and(|parent| {
+        parent.components().any(|component| match component {
+            Component::Normal(name) => name.to_string_lossy().eq_ignore_ascii_case(TEMPLATES_DIR),
+            _ => false,
+        })
+    });
+    if in_templates {
+        SetKind::Template
+    } else {
+        SetKind::Project
+    }
+}
+
+/// The Live project folder a set belongs to: the closest folder above it that holds
+/// an `Ableton Project Info` folder. Sets saved outside a project folder have none.
+pub fn find_project_folder(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.join(PROJECT_INFO_DIR).is_dir())
+        .map(Path::to_path_buf)
+}
+
+/// Where a set sits in the library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetLocation {
+    pub kind: SetKind,
+    pub project_folder: Option<PathBuf>,
+}
+
+impl SetLocation {
+    /// Classify the set and look for its project folder on disk
+    pub fn detect(path: &Path) -> Self {
+        Self {
+            kind: classify_set(path),
+            project_folder: find_project_folder(path),
+        }
+    }
+}
@@ -3,49 +3,29 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use regex::Regex;
-use walkdir::WalkDir;
-
-use crate::error::{LiveSetError, PatternError};
+use super::roots::RootRules;
+use crate::error::LiveSetError;
 
 /// Scanner for finding Ableton Live project files in directories
-pub struct ProjectPathScanner {
-    /// Regex pattern for identifying backup files
-    backup_pattern: Regex,
-}
+pub struct ProjectPathScanner;
 
 impl ProjectPathScanner {
     pub fn new() -> Result<Self, LiveSetError> {
-        // This pattern matches Ableton's backup format: [YYYY-MM-DD HHMMSS]
-        let backup_pattern =
-            Regex::new(r"\[\d{4}-\d{2}-\d{2}\s\d{6}]").map_err(PatternError::InvalidRegex)?;
-
-        Ok(Self { backup_pattern })
+        Ok(Self)
     }
 
-    /// Scan a directory for Ableton Live project files
+    /// Scan a directory for Ableton Live project files, skipping Live's backups
     pub fn scan_directory(&self, dir: &Path) -> Result<Vec<PathBuf>, LiveSetError> {
-        let mut project_paths = HashSet::new();
-
-        for entry in WalkDir::new(dir)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-
-            // Check if it's an .als file
-            if let Some(ext) = path.extension() {
-                if ext == "als" {
-                    let path_str = path.to_string_lossy();
+        self.scan_root(&RootRules::unrestricted(dir), dir)
+    }
 
-                    // Skip if it's a backup file
-                    if !self.backup_pattern.is_match(&path_str) {
-                        project_paths.insert(path.to_path_buf());
-                    }
-                }
-            }
-        }
+    /// Scan `dir`, the root of `rules` or a folder inside it, for the project files
+    /// the root's settings include
+    pub fn scan_root(&self, rules: &RootRules, dir: &Path) -> Result<Vec<PathBuf>, LiveSetError> {
+        let project_paths: HashSet<PathBuf> = rules
+            .walk(dir)
+            .map(|entry| entry.path().to_path_buf())
+            .collect();
 
         Ok(project_paths.into_iter().collect())
     }
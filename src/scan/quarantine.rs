@@ -0,0 +1,110 @@
+use log::{debug, info, trace, warn};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+use super::incremental::FileState;
+use super::jobs::SCAN_JOBS;
+use crate::database::scan_errors::ScanErrorRecord;
+use crate::database::LiveSetDatabase;
+use crate::error::LiveSetError;
+
+/// What retrying failed sets did
+#[derive(Debug, Clone, Default)]
+pub struct RetryCounts {
+    /// Sets that parsed and are now indexed
+    pub fixed: u32,
+    /// Sets that failed again
+    pub failed: u32,
+    /// Sets whose file no longer exists, so their errors were dropped
+    pub missing: u32,
+}
+
+/// Take quarantined sets that haven't changed since they last failed out of a scan.
+/// Returns how many were skipped.
+pub fn skip_quarantined(found: &mut HashSet<PathBuf>, quarantined: &[ScanErrorRecord]) -> u32 {
+    let mut skipped = 0;
+    for record in quarantined.iter().filter(|record| record.is_quarantined()) {
+        let path = PathBuf::from(&record.path);
+        let unchanged = FileState::read(&path)
+            .is_some_and(|state| record.matches_file(state.size, state.modified_at));
+        if unchanged && found.remove(&path) {
+            trace!("Skipping quarantined set: {}", path.display());
+            skipped += 1;
+        }
+    }
+    if skipped > 0 {
+        debug!("Skipping {} quarantined sets", skipped);
+    }
+    skipped
+}
+
+/// Record a failed parse along with the state of the file that failed
+pub fn record_failure(
+    db: &mut LiveSetDatabase,
+    path: &Path,
+    error: &LiveSetError,
+) -> Result<u32, LiveSetError> {
+    let state = FileState::read(path).map(|state| (state.size, state.modified_at));
+    Ok(db.record_scan_error(path, error, state)?)
+}
+
+/// Parse failed sets again, whether or not they're quarantined. With no paths given
+/// every recorded failure is retried. Sets that parse are indexed and their errors
+/// cleared; sets that fail again have the failure recorded. The database is only
+/// locked to record results, never while parsing, so this blocks and must not run on
+/// the async runtime.
+pub fn retry_failed_sets(
+    db: &Mutex<LiveSetDatabase>,
+    paths: &[PathBuf],
+) -> Result<RetryCounts, LiveSetError> {
+    let targets: Vec<PathBuf> = if paths.is_empty() {
+        db.blocking_lock()
+            .get_scan_errors(false)?
+            .into_iter()
+            .map(|record| PathBuf::from(record.path))
+            .collect()
+    } else {
+        paths.to_vec()
+    };
+
+    let mut counts = RetryCounts::default();
+    let (existing, missing): (Vec<_>, Vec<_>) = targets.into_iter().partition(|p| p.exists());
+    if !missing.is_empty() {
+        let missing: Vec<&Path> = missing.iter().map(PathBuf::as_path).collect();
+        counts.missing = db.blocking_lock().clear_scan_errors(&missing)? as u32;
+    }
+
+    info!("Retrying {} failed sets", existing.len());
+    let job = SCAN_JOBS.start_scan(existing);
+    for outcome in job.results().iter() {
+        let mut db = db.blocking_lock();
+        match outcome {
+            Ok((path, live_set)) => {
+                let path_str = path.to_string_lossy().to_string();
+                match db.get_project_id_by_path(&path_str)? {
+                    Some((project_id, _)) => {
+                        db.update_project_from_live_set(&project_id, &live_set)?;
+                    }
+                    None => db.insert_project(&live_set)?,
+                }
+                if let Some(state) = FileState::read(&path) {
+                    db.record_file_states(&[(path.as_path(), state.size, state.modified_at)])?;
+                }
+                db.clear_scan_errors(&[path.as_path()])?;
+                counts.fixed += 1;
+            }
+            Err((path, error)) => {
+                warn!("Failed to parse {} again: {}", path.display(), error);
+                record_failure(&mut db, &path, &error)?;
+                counts.failed += 1;
+            }
+        }
+    }
+
+    info!(
+        "Retried failed sets: {} fixed, {} still failing, {} missing",
+        counts.fixed, counts.failed, counts.missing
+    );
+    Ok(counts)
+}
@@ -0,0 +1,309 @@
+use log::{debug, trace};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use walkdir::{DirEntry, WalkDir};
+
+use crate::config::{Config, RootSettings};
+use crate::database::LiveSetDatabase;
+use crate::error::{DatabaseError, LiveSetError, PatternError};
+
+/// Live's backup copies: `Song [YYYY-MM-DD HHMMSS].als`
+static BACKUP_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[\d{4}-\d{2}-\d{2}\s\d{6}]").expect("valid backup pattern"));
+
+/// True if the path is one of Live's timestamped backup copies of a set
+pub fn is_backup(path: &Path) -> bool {
+    BACKUP_PATTERN.is_match(&path.to_string_lossy())
+}
+
+fn is_set_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "als")
+}
+
+/// Turn an exclude glob into a regex over `/`-separated paths relative to the root.
+/// Matching is case-insensitive, and a pattern that matches a folder matches
+/// everything inside it. Patterns without a `/` match at any depth.
+fn glob_to_regex(glob: &str) -> Result<Regex, PatternError> {
+    let glob = glob.trim().replace('\\', "/");
+    let glob = glob.trim_matches('/');
+
+    let mut pattern = String::from("(?i)^");
+    if !glob.contains('/') {
+        pattern.push_str("(?:.*/)?");
+    }
+
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    pattern.push_str("(?:/.*)?$");
+
+    Ok(Regex::new(&pattern)?)
+}
+
+/// The scan settings of one project root, ready to match paths against
+#[derive(Debug, Clone)]
+pub struct RootRules {
+    root: PathBuf,
+    excludes: Vec<Regex>,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+    include_backups: bool,
+    default_tag: Option<String>,
+}
+
+impl RootRules {
+    pub fn new(settings: &RootSettings) -> Result<Self, LiveSetError> {
+        let excludes = settings
+            .exclude
+            .iter()
+            .map(|glob| glob_to_regex(glob))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            root: PathBuf::from(&settings.path),
+            excludes,
+            max_depth: settings.max_depth,
+            follow_symlinks: settings.follow_symlinks,
+            include_backups: settings.include_backups,
+            default_tag: settings
+                .default_tag
+                .as_deref()
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string),
+        })
+    }
+
+    /// Rules that find every set under `root` except backups
+    pub fn unrestricted(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            excludes: Vec::new(),
+            max_depth: None,
+            follow_symlinks: true,
+            include_backups: false,
+            default_tag: None,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn default_tag(&self) -> Option<&str> {
+        self.default_tag.as_deref()
+    }
+
+    /// True if the path is the root or somewhere below it
+    pub fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.root)
+    }
+
+    /// The folder and file names leading from the root to the path
+    fn relative(&self, path: &Path) -> Option<Vec<String>> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        Some(
+            relative
+                .components()
+                .filter_map(|component| match component {
+                    Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    fn is_excluded(&self, relative: &str, is_dir: bool) -> bool {
+        self.excludes.iter().any(|exclude| {
+            exclude.is_match(relative) || (is_dir && exclude.is_match(&format!("{}/", relative)))
+        })
+    }
+
+    /// True if a symlink sits between the root and the path, or is the path itself
+    fn through_symlink(&self, path: &Path) -> bool {
+        path.ancestors()
+            .take_while(|ancestor| *ancestor != self.root)
+            .any(|ancestor| {
+                ancestor
+                    .symlink_metadata()
+                    .is_ok_and(|metadata| metadata.file_type().is_symlink())
+            })
+    }
+
+    /// True if the path is a set these rules index. The file doesn't need to exist,
+    /// except to check for symlinks.
+    pub fn allows(&self, path: &Path) -> bool {
+        if !is_set_file(path) {
+            return false;
+        }
+        let Some(relative) = self.relative(path) else {
+            return false;
+        };
+        if self.max_depth.is_some_and(|max| relative.len() > max) {
+            return false;
+        }
+        if !self.include_backups && is_backup(path) {
+            return false;
+        }
+        if self.is_excluded(&relative.join("/"), false) {
+            return false;
+        }
+        self.follow_symlinks || !self.through_symlink(path)
+    }
+
+    /// Walk the sets these rules index under `start`, which is the root or a folder
+    /// inside it. Excluded folders aren't descended into.
+    pub fn walk<'a>(&'a self, start: &Path) -> impl Iterator<Item = DirEntry> + 'a {
+        self.walk_entries(start).filter(move |entry| self.indexes(entry))
+    }
+
+    /// Every entry visited while walking under `start`, before filtering for sets
+    pub fn walk_entries<'a>(&'a self, start: &Path) -> impl Iterator<Item = DirEntry> + 'a {
+        let start_depth = self.relative(start).map_or(0, |relative| relative.len());
+        let mut walker = WalkDir::new(start).follow_links(self.follow_symlinks);
+        if let Some(max_depth) = self.max_depth {
+            walker = walker.max_depth(max_depth.saturating_sub(start_depth));
+        }
+
+        walker
+            .into_iter()
+            .filter_entry(move |entry| {
+                if entry.depth() == 0 || !entry.file_type().is_dir() {
+                    return true;
+                }
+                let excluded = self
+                    .relative(entry.path())
+                    .is_some_and(|relative| self.is_excluded(&relative.join("/"), true));
+                if excluded {
+                    trace!("Skipping excluded folder: {}", entry.path().display());
+                }
+                !excluded
+            })
+            .filter_map(|e| e.ok())
+    }
+
+    /// Whether a walked entry is a set these rules index
+    pub fn indexes(&self, entry: &DirEntry) -> bool {
+        if !self.follow_symlinks && entry.path_is_symlink() {
+            return false;
+        }
+        let path = entry.path();
+        is_set_file(path)
+            && (self.include_backups || !is_backup(path))
+            && self
+                .relative(path)
+                .is_none_or(|relative| !self.is_excluded(&relative.join("/"), false))
+    }
+}
+
+/// Scan rules for every configured project root
+#[derive(Debug, Clone, Default)]
+pub struct ScanRoots {
+    roots: Vec<RootRules>,
+}
+
+impl ScanRoots {
+    pub fn new(roots: Vec<RootRules>) -> Self {
+        Self { roots }
+    }
+
+    /// Rules for each entry of `paths`, with the settings configured for it
+    pub fn from_config(config: &Config) -> Result<Self, LiveSetError> {
+        let roots = config
+            .paths
+            .iter()
+            .map(|path| RootRules::new(&config.root_settings(path)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { roots })
+    }
+
+    /// Add rules for a root, replacing any it already had
+    pub fn insert(&mut self, rules: RootRules) {
+        self.remove(rules.root());
+        self.roots.push(rules);
+    }
+
+    pub fn remove(&mut self, root: &Path) {
+        self.roots.retain(|rules| rules.root() != root);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RootRules> {
+        self.roots.iter()
+    }
+
+    /// The rules of the innermost root the path is under
+    pub fn root_for(&self, path: &Path) -> Option<&RootRules> {
+        self.roots
+            .iter()
+            .filter(|rules| rules.contains(path))
+            .max_by_key(|rules| rules.root().components().count())
+    }
+
+    /// The rules to scan a folder with: those of the root it's in, or no restrictions
+    /// if it isn't under any root
+    pub fn rules_for_dir(&self, dir: &Path) -> RootRules {
+        self.root_for(dir)
+            .cloned()
+            .unwrap_or_else(|| RootRules::unrestricted(dir))
+    }
+
+    /// True if the path is a set that should be indexed. Sets outside every root are
+    /// only held to the defaults.
+    pub fn allows(&self, path: &Path) -> bool {
+        match self.root_for(path) {
+            Some(rules) => rules.allows(path),
+            None => is_set_file(path) && !is_backup(path),
+        }
+    }
+
+    pub fn default_tag(&self, path: &Path) -> Option<&str> {
+        self.root_for(path).and_then(RootRules::default_tag)
+    }
+
+    /// Tag newly indexed projects with the default tag of their root, creating the
+    /// tags as needed. Returns how many projects were tagged.
+    pub fn apply_default_tags(
+        &self,
+        db: &mut LiveSetDatabase,
+        projects: &[(String, PathBuf)],
+    ) -> Result<usize, DatabaseError> {
+        let mut tag_ids: HashMap<&str, String> = HashMap::new();
+        let mut tagged = 0;
+        for (project_id, path) in projects {
+            let Some(tag) = self.default_tag(path) else {
+                continue;
+            };
+            let tag_id = match tag_ids.get(tag) {
+                Some(tag_id) => tag_id.clone(),
+                None => {
+                    let tag_id = db.get_or_add_tag(tag)?;
+                    tag_ids.insert(tag, tag_id.clone());
+                    tag_id
+                }
+            };
+            db.tag_project(project_id, &tag_id)?;
+            tagged += 1;
+        }
+
+        if tagged > 0 {
+            debug!("Applied default tags to {} projects", tagged);
+        }
+        Ok(tagged)
+    }
+}
@@ -189,6 +189,46 @@ pub fn format_file_size(size: u64) -> String {
     formatted
 }
 
+/// Buffer size for streaming decompressed set data into the parser
+const GZIP_STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Opens a gzip file for streaming decompression.
+///
+/// Unlike [`decompress_gzip_file`] nothing is decompressed up front: data is inflated as
+/// it is read, so memory use doesn't grow with the size of the file. Decompression
+/// errors surface from the returned reader.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io::Read;
+/// use std::path::Path;
+/// use seula::utils::open_gzip_file;
+///
+/// let mut reader = open_gzip_file(Path::new("path/to/project.als")).expect("Failed to open file");
+/// let mut header = [0u8; 64];
+/// reader.read_exact(&mut header).expect("Failed to read file");
+/// ```
+pub fn open_gzip_file(file_path: &Path) -> Result<impl BufRead, FileError> {
+    trace!("Opening {:?} for streaming gzip decompression", file_path);
+
+    let file = File::open(file_path).map_err(|error| {
+        error!(
+            "Failed to open file for gzip decompression: {:?}",
+            file_path
+        );
+        FileError::GzipDecompressionError {
+            path: file_path.to_path_buf(),
+            source: error,
+        }
+    })?;
+
+    Ok(BufReader::with_capacity(
+        GZIP_STREAM_BUFFER_SIZE,
+        GzDecoder::new(BufReader::new(file)),
+    ))
+}
+
 /// Decompresses a gzip file and returns its contents as a byte vector.
 ///
 /// # Examples
@@ -1,5 +1,7 @@
 use std::fs;
+use std::io::{self, BufRead, Read};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use crate::{
@@ -9,39 +11,62 @@ use crate::{
 
 // LINE TRACKER FOR DEBUGGING
 
-#[derive(Clone)]
-pub(crate) struct LineTrackingBuffer {
-    data: Arc<Vec<u8>>,
-    current_line: usize,
-    current_position: usize,
+/// Current line of a document being streamed through a [`LineTrackingBuffer`].
+/// Clones share the same counter.
+#[derive(Clone, Debug)]
+pub(crate) struct LineNumber(Arc<AtomicUsize>);
+
+impl Default for LineNumber {
+    fn default() -> Self {
+        Self(Arc::new(AtomicUsize::new(1)))
+    }
 }
 
-impl LineTrackingBuffer {
-    pub(crate) fn new(data: Vec<u8>) -> Self {
-        Self {
-            data: Arc::new(data),
-            current_line: 1,
-            current_position: 0,
+impl LineNumber {
+    pub(crate) fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn advance(&self, bytes: &[u8]) {
+        let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+        if newlines > 0 {
+            self.0.fetch_add(newlines, Ordering::Relaxed);
         }
     }
+}
 
-    pub(crate) fn get_line_number(&mut self, byte_position: u64) -> usize {
-        let byte_position_usize =
-            usize::try_from(byte_position).unwrap_or_else(|_| self.data.len()); // Clamp to max usize or data length
+/// Wraps a reader and counts the lines in everything consumed from it, so line numbers
+/// are available without keeping the document in memory
+pub(crate) struct LineTrackingBuffer<R> {
+    inner: R,
+    line: LineNumber,
+}
 
-        while self.current_position < byte_position_usize && self.current_position < self.data.len()
-        {
-            if self.data[self.current_position] == b'\n' {
-                self.current_line += 1;
-            }
-            self.current_position += 1;
-        }
-        self.current_line
+impl<R> LineTrackingBuffer<R> {
+    pub(crate) fn new(inner: R, line: LineNumber) -> Self {
+        Self { inner, line }
     }
+}
+
+impl<R: Read> Read for LineTrackingBuffer<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.line.advance(&buf[..read]);
+        Ok(read)
+    }
+}
 
-    #[allow(dead_code)]
-    pub(crate) fn update_position(&mut self, byte_position: u64) {
-        self.get_line_number(byte_position);
+impl<R: BufRead> BufRead for LineTrackingBuffer<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The bytes being consumed are still buffered, so this doesn't read
+        if let Ok(buffered) = self.inner.fill_buf() {
+            self.line.advance(&buffered[..amt.min(buffered.len())]);
+        }
+        self.inner.consume(amt);
     }
 }
 
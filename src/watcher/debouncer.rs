@@ -0,0 +1,124 @@
+use log::debug;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::file_watcher::FileEvent;
+
+/// How long a path has to be quiet before its changes are applied. Live saves by
+/// writing a temp file and renaming it over the set, which arrives as a burst of events.
+pub const DEFAULT_DEBOUNCE_WINDOW: Duration = Duration::from_millis(1500);
+
+/// Net effect of a burst of filesystem events on one project file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatcherChange {
+    /// The set at this path is new or its content changed
+    Upsert(PathBuf),
+    /// The set at this path is gone
+    Removed(PathBuf),
+    /// A set moved from one path to another
+    Moved { from: PathBuf, to: PathBuf },
+}
+
+impl WatcherChange {
+    pub fn path(&self) -> &Path {
+        match self {
+            WatcherChange::Upsert(path) | WatcherChange::Removed(path) => path,
+            WatcherChange::Moved { to, .. } => to,
+        }
+    }
+}
+
+/// Coalesces raw [`FileEvent`]s per path until the path has been quiet for the window
+pub struct EventDebouncer {
+    window: Duration,
+    pending: HashMap<PathBuf, (WatcherChange, Instant)>,
+}
+
+impl EventDebouncer {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn push(&mut self, event: FileEvent, now: Instant) {
+        debug!("Debouncing event: {:?}", event);
+        match event {
+            FileEvent::Created(path) | FileEvent::Modified(path) => {
+                // A create after a delete is a rewrite of the same file
+                let change = match self.pending.remove(&path) {
+                    Some((moved @ WatcherChange::Moved { .. }, _)) => moved,
+                    _ => WatcherChange::Upsert(path.clone()),
+                };
+                self.pending.insert(path, (change, now));
+            }
+            FileEvent::Deleted(path) => {
+                // A set deleted right after a move is gone from where it was indexed
+                let change = match self.pending.remove(&path) {
+                    Some((WatcherChange::Moved { from, .. }, _)) => WatcherChange::Removed(from),
+                    _ => WatcherChange::Removed(path.clone()),
+                };
+                self.pending.insert(path, (change, now));
+            }
+            FileEvent::Renamed { from, to } => {
+                let is_set = from.extension().is_some_and(|ext| ext == "als");
+                let change = if !is_set {
+                    // Temp file renamed over the set: a save
+                    WatcherChange::Upsert(to.clone())
+                } else {
+                    match self.pending.remove(&from) {
+                        // Moved twice before settling: keep the original source
+                        Some((WatcherChange::Moved { from: origin, .. }, _)) => {
+                            WatcherChange::Moved {
+                                from: origin,
+                                to: to.clone(),
+                            }
+                        }
+                        // Only existed briefly at `from`: it's new at `to`
+                        Some((WatcherChange::Upsert(_), _)) => WatcherChange::Upsert(to.clone()),
+                        _ => WatcherChange::Moved {
+                            from,
+                            to: to.clone(),
+                        },
+                    }
+                };
+                self.pending.insert(to, (change, now));
+            }
+        }
+    }
+
+    /// Take the changes of all paths that have been quiet for the window
+    pub fn drain_ready(&mut self, now: Instant) -> Vec<WatcherChange> {
+        let ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, (_, last_event))| now.duration_since(*last_event) >= self.window)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let mut changes: Vec<WatcherChange> = ready
+            .into_iter()
+            .filter_map(|path| self.pending.remove(&path).map(|(change, _)| change))
+            .collect();
+        // Apply moves first so a set moved and then rewritten is found at its new path
+        changes.sort_by_key(|change| !matches!(change, WatcherChange::Moved { .. }));
+        changes
+    }
+
+    /// Take every pending change regardless of the window, e.g. when shutting down
+    pub fn drain_all(&mut self) -> Vec<WatcherChange> {
+        let mut changes: Vec<WatcherChange> = self
+            .pending
+            .drain()
+            .map(|(_, (change, _))| change)
+            .collect();
+        changes.sort_by_key(|change| !matches!(change, WatcherChange::Moved { .. }));
+        changes
+    }
+}
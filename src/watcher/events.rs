@@ -0,0 +1,216 @@
+use log::debug;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+use super::debouncer::WatcherChange;
+use super::file_watcher::FileEvent;
+use super::indexer::IndexOutcome;
+
+/// Number of recent events kept for clients resuming from a sequence number
+pub const DEFAULT_EVENT_BUFFER_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatcherEventKind {
+    /// Raw filesystem events, published as they arrive
+    Created,
+    Modified,
+    Deleted,
+    Renamed,
+    /// Indexing results, published once a burst of events has settled
+    ProjectAdded,
+    ProjectUpdated,
+    ProjectMoved,
+    ProjectRemoved,
+    ProjectFailed,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatcherEvent {
+    /// Assigned by the bus when published, starting at 1
+    pub sequence: u64,
+    pub kind: WatcherEventKind,
+    pub path: PathBuf,
+    pub new_path: Option<PathBuf>,
+    pub project_id: Option<String>,
+    /// What changed, or why indexing failed
+    pub summary: Option<String>,
+    pub timestamp: i64,
+}
+
+impl WatcherEvent {
+    fn new(kind: WatcherEventKind, path: PathBuf) -> Self {
+        Self {
+            sequence: 0,
+            kind,
+            path,
+            new_path: None,
+            project_id: None,
+            summary: None,
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    pub fn from_file_event(event: &FileEvent) -> Self {
+        match event {
+            FileEvent::Created(path) => Self::new(WatcherEventKind::Created, path.clone()),
+            FileEvent::Modified(path) => Self::new(WatcherEventKind::Modified, path.clone()),
+            FileEvent::Deleted(path) => Self::new(WatcherEventKind::Deleted, path.clone()),
+            FileEvent::Renamed { from, to } => Self {
+                new_path: Some(to.clone()),
+                ..Self::new(WatcherEventKind::Renamed, from.clone())
+            },
+        }
+    }
+
+    /// The event for an indexing result, or None when the database didn't change
+    pub fn from_outcome(change: &WatcherChange, outcome: &IndexOutcome) -> Option<Self> {
+        let path = change.path().to_path_buf();
+        let event = match outcome {
+            IndexOutcome::Inserted { project_id } => Self {
+                project_id: Some(project_id.clone()),
+                ..Self::new(WatcherEventKind::ProjectAdded, path)
+            },
+            IndexOutcome::Updated {
+                project_id,
+                summary,
+            } => Self {
+                project_id: Some(project_id.clone()),
+                summary: Some(summary.clone()),
+                ..Self::new(WatcherEventKind::ProjectUpdated, path)
+            },
+            IndexOutcome::Moved {
+                project_id,
+                from,
+                to,
+            } => Self {
+                new_path: Some(PathBuf::from(to)),
+                project_id: Some(project_id.clone()),
+                ..Self::new(WatcherEventKind::ProjectMoved, PathBuf::from(from))
+            },
+            IndexOutcome::Removed { project_id } => Self {
+                project_id: Some(project_id.clone()),
+                ..Self::new(WatcherEventKind::ProjectRemoved, path)
+            },
+            IndexOutcome::Failed { path, error } => Self {
+                summary: Some(error.clone()),
+                ..Self::new(WatcherEventKind::ProjectFailed, PathBuf::from(path))
+            },
+            IndexOutcome::Unchanged { .. } | IndexOutcome::Ignored => return None,
+        };
+        Some(event)
+    }
+}
+
+/// Events to catch a subscriber up, followed by live events from `receiver`
+pub struct Subscription {
+    pub replay: Vec<WatcherEvent>,
+    /// Events after the requested sequence have already been dropped from the buffer
+    pub missed: bool,
+    /// Sequence the subscriber is caught up to before the replay. A requested sequence
+    /// ahead of the bus is clamped to its latest one, so live events still get through.
+    pub resume_after: u64,
+    pub receiver: broadcast::Receiver<WatcherEvent>,
+}
+
+struct BusState {
+    next_sequence: u64,
+    buffer: VecDeque<WatcherEvent>,
+}
+
+/// Fans watcher events out to any number of subscribers and keeps the most recent
+/// ones so a reconnecting client can pick up where it left off
+pub struct WatcherEventBus {
+    capacity: usize,
+    state: Mutex<BusState>,
+    sender: broadcast::Sender<WatcherEvent>,
+}
+
+impl WatcherEventBus {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            capacity,
+            state: Mutex::new(BusState {
+                next_sequence: 1,
+                buffer: VecDeque::with_capacity(capacity),
+            }),
+            sender,
+        }
+    }
+
+    /// Sequence number of the most recent event, 0 if nothing was published yet
+    pub fn latest_sequence(&self) -> u64 {
+        self.state.lock().unwrap().next_sequence - 1
+    }
+
+    pub fn publish(&self, mut event: WatcherEvent) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        event.sequence = state.next_sequence;
+        state.next_sequence += 1;
+
+        if state.buffer.len() == self.capacity {
+            state.buffer.pop_front();
+        }
+        state.buffer.push_back(event.clone());
+
+        debug!(
+            "Publishing watcher event {}: {:?}",
+            event.sequence, event.kind
+        );
+        // Sent while holding the lock so subscribers never see events out of order
+        let _ = self.sender.send(event.clone());
+        event.sequence
+    }
+
+    /// Buffered events after `since`, and whether some were already dropped. A
+    /// sequence ahead of the bus (e.g. from before a restart) replays everything.
+    pub fn events_since(&self, since: u64) -> (Vec<WatcherEvent>, bool) {
+        let state = self.state.lock().unwrap();
+        Self::collect_since(&state, since)
+    }
+
+    fn collect_since(state: &BusState, since: u64) -> (Vec<WatcherEvent>, bool) {
+        let latest = state.next_sequence - 1;
+        let oldest = state
+            .buffer
+            .front()
+            .map_or(state.next_sequence, |e| e.sequence);
+
+        if since > latest {
+            return (state.buffer.iter().cloned().collect(), true);
+        }
+        let events = state
+            .buffer
+            .iter()
+            .filter(|e| e.sequence > since)
+            .cloned()
+            .collect();
+        (events, since + 1 < oldest)
+    }
+
+    /// Subscribe to live events, optionally replaying buffered events after `since`
+    pub fn subscribe(&self, since: Option<u64>) -> Subscription {
+        let state = self.state.lock().unwrap();
+        let receiver = self.sender.subscribe();
+        let latest = state.next_sequence - 1;
+        let (replay, missed) = match since {
+            Some(since) => Self::collect_since(&state, since),
+            None => (Vec::new(), false),
+        };
+        Subscription {
+            replay,
+            missed,
+            resume_after: since.map_or(latest, |since| since.min(latest)),
+            receiver,
+        }
+    }
+}
+
+impl Default for WatcherEventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_BUFFER_SIZE)
+    }
+}
@@ -1,27 +1,33 @@
 #![allow(dead_code)]
 use crate::database::LiveSetDatabase;
+use crate::scan::roots::{RootRules, ScanRoots};
 use log::{debug, info, warn};
 use notify::{
     self,
     event::{CreateKind, ModifyKind, RemoveKind, RenameMode},
     Event, EventKind, RecursiveMode, Watcher,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::path::{Path, PathBuf};
 use std::sync::{mpsc, Arc};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tokio::sync::Mutex;
-use walkdir::WalkDir;
+
+use super::poller::PollingWatcher;
 
 pub struct FileWatcher {
     watcher: notify::RecommendedWatcher,
     watch_paths: HashSet<PathBuf>,
+    /// Scan settings of each watched path
+    roots: ScanRoots,
+    /// Watched paths that are polled instead of relying on notifications
+    pollers: HashMap<PathBuf, PollingWatcher>,
     event_tx: mpsc::Sender<FileEvent>,
     db: Arc<Mutex<LiveSetDatabase>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum FileEvent {
     Created(PathBuf),
     Modified(PathBuf),
@@ -54,6 +60,8 @@ impl FileWatcher {
             Self {
                 watcher,
                 watch_paths: HashSet::new(),
+                roots: ScanRoots::default(),
+                pollers: HashMap::new(),
                 event_tx: tx,
                 db,
             },
@@ -63,9 +71,19 @@ impl FileWatcher {
 
     /// Add a new directory to watch
     pub fn add_watch_path(&mut self, path: PathBuf) -> notify::Result<()> {
+        self.add_watch_root(RootRules::unrestricted(&path))
+    }
+
+    /// Add a new directory to watch, with its scan settings
+    pub fn add_watch_root(&mut self, rules: RootRules) -> notify::Result<()> {
+        let path = rules.root().to_path_buf();
         debug!("Adding watch path: {:?}", path);
         if self.watch_paths.insert(path.clone()) {
-            self.watcher.watch(&path, RecursiveMode::Recursive)?;
+            if let Err(e) = self.watcher.watch(&path, RecursiveMode::Recursive) {
+                self.watch_paths.remove(&path);
+                return Err(e);
+            }
+            self.roots.insert(rules);
             info!("Successfully added watch path: {:?}", path);
         } else {
             debug!("Path already being watched: {:?}", path);
@@ -73,11 +91,40 @@ impl FileWatcher {
         Ok(())
     }
 
+    /// Watch a directory by polling it on an interval, for network shares and sync
+    /// folders where filesystem notifications are missed
+    pub fn add_poll_path(&mut self, path: PathBuf, interval: Duration) {
+        self.add_poll_root(RootRules::unrestricted(&path), interval)
+    }
+
+    /// Poll a directory on an interval, with its scan settings
+    pub fn add_poll_root(&mut self, rules: RootRules, interval: Duration) {
+        let path = rules.root().to_path_buf();
+        debug!("Adding poll path: {:?}", path);
+        if self.watch_paths.insert(path.clone()) {
+            let poller = PollingWatcher::spawn(rules.clone(), interval, self.event_tx.clone());
+            self.pollers.insert(path.clone(), poller);
+            self.roots.insert(rules);
+            info!("Successfully added poll path: {:?}", path);
+        } else {
+            debug!("Path already being watched: {:?}", path);
+        }
+    }
+
+    /// Check if a watched path is polled rather than notified
+    pub fn is_polling(&self, path: &Path) -> bool {
+        self.pollers.contains_key(path)
+    }
+
     /// Remove a watched directory
     pub fn remove_watch_path(&mut self, path: &Path) -> notify::Result<()> {
         debug!("Removing watch path: {:?}", path);
         if self.watch_paths.remove(path) {
-            self.watcher.unwatch(path)?;
+            self.roots.remove(path);
+            // Dropping a poller stops it
+            if self.pollers.remove(path).is_none() {
+                self.watcher.unwatch(path)?;
+            }
             info!("Successfully removed watch path: {:?}", path);
         } else {
             debug!("Path was not being watched: {:?}", path);
@@ -97,6 +144,11 @@ impl FileWatcher {
         &self.watch_paths
     }
 
+    /// Get the scan settings of the watched paths
+    pub fn scan_roots(&self) -> &ScanRoots {
+        &self.roots
+    }
+
     /// Check for changes that occurred while the application was not running
     pub async fn check_offline_changes(&self) -> Result<(), Box<dyn Error>> {
         debug!("Checking for offline changes");
@@ -131,15 +183,12 @@ impl FileWatcher {
         debug!("Starting scan for new files");
         let mut found_paths = HashSet::new();
 
-        // First collect all .als files
-        for watch_path in &self.watch_paths {
-            debug!("Scanning directory: {:?}", watch_path);
-            for entry in WalkDir::new(watch_path).into_iter().filter_map(|e| e.ok()) {
-                let path = entry.path();
-                if path.extension().map_or(false, |ext| ext == "als") {
-                    debug!("Found .als file: {:?}", path);
-                    found_paths.insert(path.to_path_buf());
-                }
+        // First collect all sets the watched paths' settings include
+        for rules in self.roots.iter() {
+            debug!("Scanning directory: {:?}", rules.root());
+            for entry in rules.walk(rules.root()) {
+                debug!("Found .als file: {:?}", entry.path());
+                found_paths.insert(entry.path().to_path_buf());
             }
         }
 
@@ -224,7 +273,9 @@ fn handle_fs_event(event: &Event, tx: &mpsc::Sender<FileEvent>) -> notify::Resul
 impl Drop for FileWatcher {
     fn drop(&mut self) {
         debug!("Dropping FileWatcher, cleaning up watch paths");
-        for path in self.watch_paths.iter() {
+        // Polled paths were never registered with the native watcher, their pollers stop
+        // when dropped
+        for path in self.watch_paths.iter().filter(|path| !self.pollers.contains_key(*path)) {
             if let Err(e) = self.watcher.unwatch(path) {
                 warn!("Failed to unwatch path during cleanup: {:?} ({})", path, e);
             }
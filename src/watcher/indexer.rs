@@ -0,0 +1,345 @@
+use log::{debug, info, warn};
+use std::path::Path;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use super::debouncer::{EventDebouncer, WatcherChange};
+use super::events::{WatcherEvent, WatcherEventBus};
+use super::file_watcher::FileEvent;
+use crate::database::LiveSetDatabase;
+use crate::error::{DatabaseError, LiveSetError};
+use crate::live_set::LiveSet;
+use crate::scan::incremental::FileState;
+use crate::scan::roots::ScanRoots;
+use crate::scan::SCAN_JOBS;
+use crate::utils::metadata::load_file_hash;
+
+/// What applying a [`WatcherChange`] did to the database
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexOutcome {
+    Inserted {
+        project_id: String,
+    },
+    Updated {
+        project_id: String,
+        summary: String,
+    },
+    Unchanged {
+        project_id: String,
+    },
+    Moved {
+        project_id: String,
+        from: String,
+        to: String,
+    },
+    Removed {
+        project_id: String,
+    },
+    /// The set could not be parsed; the database was left as it was
+    Failed {
+        path: String,
+        error: String,
+    },
+    /// Nothing in the database refers to this path
+    Ignored,
+}
+
+/// The set a change points at, hashed and parsed. Reading it is the slow part of
+/// applying a change and needs no database access.
+#[derive(Debug)]
+pub struct ChangedSet {
+    pub hash: String,
+    pub parsed: Result<LiveSet, LiveSetError>,
+}
+
+/// Read the set a settled change points at, or `None` if it no longer exists
+pub fn read_change(change: &WatcherChange) -> Result<Option<ChangedSet>, DatabaseError> {
+    let path = change.path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let hash = load_file_hash(&path.to_path_buf())?;
+    // Jumps ahead of any bulk scan queued on the shared workers
+    let parsed = SCAN_JOBS.parse_now(path.to_path_buf());
+    Ok(Some(ChangedSet { hash, parsed }))
+}
+
+/// Bring the database in line with a settled change. The filesystem is checked again
+/// because the change may be stale by the time it settles.
+pub fn apply_change(
+    db: &mut LiveSetDatabase,
+    change: &WatcherChange,
+) -> Result<IndexOutcome, DatabaseError> {
+    let set = read_change(change)?;
+    apply_read_change(db, change, set.as_ref())
+}
+
+/// Apply a change whose set was read with [`read_change`]
+pub fn apply_read_change(
+    db: &mut LiveSetDatabase,
+    change: &WatcherChange,
+    set: Option<&ChangedSet>,
+) -> Result<IndexOutcome, DatabaseError> {
+    debug!("Applying watcher change: {:?}", change);
+    match (change, set) {
+        (WatcherChange::Upsert(path) | WatcherChange::Removed(path), None) => remove(db, path),
+        (WatcherChange::Upsert(path) | WatcherChange::Removed(path), Some(set)) => {
+            upsert(db, path, set)
+        }
+        (WatcherChange::Moved { from, .. }, None) => remove(db, from),
+        (WatcherChange::Moved { from, to }, Some(set)) => move_project(db, from, to, set),
+    }
+}
+
+fn upsert(
+    db: &mut LiveSetDatabase,
+    path: &Path,
+    set: &ChangedSet,
+) -> Result<IndexOutcome, DatabaseError> {
+    let path_str = path.to_string_lossy().to_string();
+
+    if let Some((project_id, is_active)) = db.get_project_id_by_path(&path_str)? {
+        if !is_active {
+            db.reactivate_project(&parse_uuid(&project_id)?, path)?;
+        }
+        // Live only writes a set while it's open
+        db.record_project_opened(&project_id, None)?;
+        return rescan(db, &project_id, set);
+    }
+
+    // A set that was moved, or deleted and showing up again, keeps its identity. Identical
+    // content is recognised by its hash.
+    if let Some(project_id) = db.find_moved_project_by_hash(path, &set.hash)? {
+        let from = project_path(db, &project_id)?;
+        db.update_project_path(&project_id, path)?;
+        info!("Project {} found again at {}", project_id, path.display());
+        return Ok(IndexOutcome::Moved {
+            project_id,
+            from,
+            to: path_str,
+        });
+    }
+
+    match &set.parsed {
+        Ok(live_set) => {
+            if let Some(project_id) = db.find_moved_project_by_fingerprint(live_set)? {
+                let from = project_path(db, &project_id)?;
+                db.reattach_moved_project(&project_id, live_set)?;
+                return Ok(IndexOutcome::Moved {
+                    project_id,
+                    from,
+                    to: path_str,
+                });
+            }
+
+            let project_id = live_set.id.to_string();
+            db.insert_project(live_set)?;
+            db.clear_scan_errors(&[path])?;
+            info!("Indexed new project {} ({})", live_set.name, project_id);
+            Ok(IndexOutcome::Inserted { project_id })
+        }
+        Err(e) => {
+            warn!("Failed to parse {}: {}", path.display(), e);
+            let state = FileState::read(path).map(|state| (state.size, state.modified_at));
+            db.record_scan_error(path, e, state)?;
+            Ok(IndexOutcome::Failed {
+                path: path_str,
+                error: e.to_string(),
+            })
+        }
+    }
+}
+
+fn project_path(db: &mut LiveSetDatabase, project_id: &str) -> Result<String, DatabaseError> {
+    Ok(db
+        .get_project_by_id_any_status(project_id)?
+        .map(|p| p.file_path.to_string_lossy().to_string())
+        .unwrap_or_default())
+}
+
+fn rescan(
+    db: &mut LiveSetDatabase,
+    project_id: &str,
+    set: &ChangedSet,
+) -> Result<IndexOutcome, DatabaseError> {
+    let existing = db
+        .get_project_by_id_any_status(project_id)?
+        .ok_or_else(|| DatabaseError::NotFound(format!("Project {} not found", project_id)))?;
+    if existing.file_hash == set.hash {
+        return Ok(IndexOutcome::Unchanged {
+            project_id: project_id.to_string(),
+        });
+    }
+
+    let result = match &set.parsed {
+        Ok(live_set) => db.update_project_from_live_set(project_id, live_set)?,
+        Err(e) => {
+            let error = format!("Parse error: {}", e);
+            warn!("Failed to rescan project {}: {}", project_id, error);
+            let state = FileState::read(&existing.file_path)
+                .map(|state| (state.size, state.modified_at));
+            db.record_scan_error(&existing.file_path, e, state)?;
+            let path = existing.file_path.to_string_lossy().to_string();
+            return Ok(IndexOutcome::Failed { path, error });
+        }
+    };
+    if !result.success {
+        let error = result.error_message.unwrap_or(result.scan_summary);
+        warn!("Failed to rescan project {}: {}", project_id, error);
+        let path = project_path(db, project_id)?;
+        return Ok(IndexOutcome::Failed { path, error });
+    }
+    db.clear_scan_errors(&[existing.file_path.as_path()])?;
+
+    let project_id = project_id.to_string();
+    if result.was_updated {
+        info!("Rescanned project {}: {}", project_id, result.scan_summary);
+        Ok(IndexOutcome::Updated {
+            project_id,
+            summary: result.scan_summary,
+        })
+    } else {
+        Ok(IndexOutcome::Unchanged { project_id })
+    }
+}
+
+fn move_project(
+    db: &mut LiveSetDatabase,
+    from: &Path,
+    to: &Path,
+    set: &ChangedSet,
+) -> Result<IndexOutcome, DatabaseError> {
+    let from_str = from.to_string_lossy().to_string();
+    let to_str = to.to_string_lossy().to_string();
+
+    let project_id = match db.get_project_id_by_path(&from_str)? {
+        Some((project_id, _)) => project_id,
+        None => return upsert(db, to, set),
+    };
+
+    // Another project is already indexed at the destination; it now holds this set
+    if let Some((existing_id, _)) = db.get_project_id_by_path(&to_str)? {
+        if existing_id != project_id {
+            db.mark_project_deleted(&parse_uuid(&project_id)?)?;
+            return upsert(db, to, set);
+        }
+    }
+
+    db.update_project_path(&project_id, to)?;
+    info!(
+        "Project {} moved from {} to {}",
+        project_id, from_str, to_str
+    );
+
+    // The content may have changed on the way
+    if let IndexOutcome::Failed { path, error } = rescan(db, &project_id, set)? {
+        return Ok(IndexOutcome::Failed { path, error });
+    }
+
+    Ok(IndexOutcome::Moved {
+        project_id,
+        from: from_str,
+        to: to_str,
+    })
+}
+
+fn remove(db: &mut LiveSetDatabase, path: &Path) -> Result<IndexOutcome, DatabaseError> {
+    match db.get_project_id_by_path(&path.to_string_lossy())? {
+        Some((project_id, true)) => {
+            db.mark_project_deleted(&parse_uuid(&project_id)?)?;
+            info!("Project {} removed from {}", project_id, path.display());
+            Ok(IndexOutcome::Removed { project_id })
+        }
+        _ => Ok(IndexOutcome::Ignored),
+    }
+}
+
+fn parse_uuid(project_id: &str) -> Result<Uuid, DatabaseError> {
+    Uuid::parse_str(project_id)
+        .map_err(|e| DatabaseError::InvalidOperation(format!("Invalid project ID: {}", e)))
+}
+
+/// Hold a raw event to the scan settings of the watched paths. Sets the settings
+/// leave out are ignored, and one renamed into an excluded location counts as
+/// deleted. Deletions always go through, so an excluded set that was indexed can
+/// still be removed.
+pub fn filter_event(roots: &ScanRoots, event: FileEvent) -> Option<FileEvent> {
+    match event {
+        FileEvent::Created(ref path) | FileEvent::Modified(ref path) => {
+            roots.allows(path).then_some(event)
+        }
+        FileEvent::Renamed { from, to } if !roots.allows(&to) => Some(FileEvent::Deleted(from)),
+        event => Some(event),
+    }
+}
+
+/// Debounce watcher events and index the settled changes on a background thread.
+/// Raw events and indexing results are published on `bus`. The thread ends once the
+/// watcher is dropped and the remaining changes have been applied. Events are held
+/// to `roots`, and new projects get the default tag of their root.
+pub fn spawn_indexer(
+    db: Arc<Mutex<LiveSetDatabase>>,
+    events: mpsc::Receiver<FileEvent>,
+    bus: Arc<WatcherEventBus>,
+    window: Duration,
+    roots: ScanRoots,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        debug!("Watcher indexer started");
+        let mut debouncer = EventDebouncer::new(window);
+        let tick = (window / 4).max(Duration::from_millis(50));
+
+        let apply = |changes: Vec<WatcherChange>| {
+            if changes.is_empty() {
+                return;
+            }
+            for change in changes {
+                // Parsing can take a while, so the database is only locked to write
+                let set = match read_change(&change) {
+                    Ok(set) => set,
+                    Err(e) => {
+                        warn!("Failed to read watcher change {:?}: {}", change, e);
+                        continue;
+                    }
+                };
+                let mut db = db.blocking_lock();
+                match apply_read_change(&mut db, &change, set.as_ref()) {
+                    Ok(outcome) => {
+                        debug!("Watcher change {:?} -> {:?}", change, outcome);
+                        if let IndexOutcome::Inserted { project_id } = &outcome {
+                            let project = [(project_id.clone(), change.path().to_path_buf())];
+                            if let Err(e) = roots.apply_default_tags(&mut db, &project) {
+                                warn!("Failed to apply default tag to {}: {}", project_id, e);
+                            }
+                        }
+                        if let Some(event) = WatcherEvent::from_outcome(&change, &outcome) {
+                            bus.publish(event);
+                        }
+                    }
+                    Err(e) => warn!("Failed to apply watcher change {:?}: {}", change, e),
+                }
+            }
+        };
+
+        loop {
+            match events.recv_timeout(tick) {
+                Ok(event) => {
+                    let Some(event) = filter_event(&roots, event) else {
+                        continue;
+                    };
+                    bus.publish(WatcherEvent::from_file_event(&event));
+                    debouncer.push(event, Instant::now());
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+            apply(debouncer.drain_ready(Instant::now()));
+        }
+
+        apply(debouncer.drain_all());
+        debug!("Watcher indexer stopped");
+    })
+}
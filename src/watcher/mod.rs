@@ -1,3 +1,7 @@
 #![allow(unused)]
+pub mod debouncer;
+pub mod events;
 pub mod file_watcher;
+pub mod indexer;
+pub mod poller;
 pub use file_watcher::FileWatcher;
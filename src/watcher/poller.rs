@@ -0,0 +1,174 @@
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use super::file_watcher::FileEvent;
+use crate::scan::roots::RootRules;
+
+/// Directory entries visited in one go before pausing, so a large tree on a network share is
+/// walked gradually instead of in one burst
+pub const POLL_BATCH_SIZE: usize = 500;
+const POLL_BATCH_PAUSE: Duration = Duration::from_millis(25);
+/// How often a sleeping poller checks whether it was stopped
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    pub modified: SystemTime,
+    pub len: u64,
+}
+
+pub type PollSnapshot = HashMap<PathBuf, FileStamp>;
+
+/// Stamps of every set the root's settings include. Returns None if stopped part way
+/// through.
+fn snapshot(rules: &RootRules, stop: &AtomicBool) -> Option<PollSnapshot> {
+    let mut files = PollSnapshot::new();
+    let mut visit_count = 0;
+
+    // Every visited entry counts towards the batch, not just the sets, so a tree full of
+    // samples and other files is paced too
+    for entry in rules.walk_entries(rules.root()) {
+        visit_count += 1;
+        if visit_count % POLL_BATCH_SIZE == 0 {
+            if stop.load(Ordering::Relaxed) {
+                return None;
+            }
+            thread::sleep(POLL_BATCH_PAUSE);
+        }
+        if !rules.indexes(&entry) {
+            continue;
+        }
+
+        let path = entry.path();
+
+        match entry.metadata() {
+            Ok(metadata) => {
+                files.insert(
+                    path.to_path_buf(),
+                    FileStamp {
+                        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                        len: metadata.len(),
+                    },
+                );
+            }
+            Err(e) => debug!("Failed to stat {}: {}", path.display(), e),
+        }
+    }
+    Some(files)
+}
+
+/// Events that turn `old` into `new`. A set that disappeared while one with an
+/// identical stamp appeared is reported as a rename.
+pub fn diff_snapshots(old: &PollSnapshot, new: &PollSnapshot) -> Vec<FileEvent> {
+    let mut events = Vec::new();
+    let mut created: Vec<&PathBuf> = Vec::new();
+    let mut deleted: Vec<&PathBuf> = Vec::new();
+
+    for (path, stamp) in new {
+        match old.get(path) {
+            None => created.push(path),
+            Some(old_stamp) if old_stamp != stamp => events.push(FileEvent::Modified(path.clone())),
+            Some(_) => {}
+        }
+    }
+    for path in old.keys() {
+        if !new.contains_key(path) {
+            deleted.push(path);
+        }
+    }
+
+    for from in deleted {
+        let stamp = old[from];
+        let candidates: Vec<usize> = created
+            .iter()
+            .enumerate()
+            .filter(|(_, to)| new[**to] == stamp)
+            .map(|(i, _)| i)
+            .collect();
+
+        if let [index] = candidates[..] {
+            let to = created.remove(index);
+            events.push(FileEvent::Renamed {
+                from: from.clone(),
+                to: to.clone(),
+            });
+        } else {
+            events.push(FileEvent::Deleted(from.clone()));
+        }
+    }
+    events.extend(
+        created
+            .into_iter()
+            .map(|path| FileEvent::Created(path.clone())),
+    );
+    events
+}
+
+/// Watches a directory by comparing mtimes and sizes on an interval, for network
+/// shares and sync folders that don't deliver filesystem notifications. Stops when
+/// dropped.
+pub struct PollingWatcher {
+    stop: Arc<AtomicBool>,
+}
+
+impl PollingWatcher {
+    pub fn spawn(rules: RootRules, interval: Duration, tx: mpsc::Sender<FileEvent>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+
+        thread::spawn(move || {
+            let root = rules.root();
+            info!("Polling {} every {}s", root.display(), interval.as_secs());
+            // The first pass is the baseline; changes made while offline are picked up
+            // by the watcher's own offline check
+            let mut previous = match snapshot(&rules, &thread_stop) {
+                Some(files) => files,
+                None => return,
+            };
+            debug!(
+                "Polling baseline for {}: {} sets",
+                root.display(),
+                previous.len()
+            );
+
+            loop {
+                let mut waited = Duration::ZERO;
+                while waited < interval {
+                    if thread_stop.load(Ordering::Relaxed) {
+                        debug!("Stopped polling {}", root.display());
+                        return;
+                    }
+                    let step = STOP_CHECK_INTERVAL.min(interval - waited);
+                    thread::sleep(step);
+                    waited += step;
+                }
+
+                let current = match snapshot(&rules, &thread_stop) {
+                    Some(files) => files,
+                    None => return,
+                };
+                for event in diff_snapshots(&previous, &current) {
+                    debug!("Polled change: {:?}", event);
+                    if tx.send(event).is_err() {
+                        warn!("Watcher gone, stopped polling {}", root.display());
+                        return;
+                    }
+                }
+                previous = current;
+            }
+        });
+
+        Self { stop }
+    }
+}
+
+impl Drop for PollingWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
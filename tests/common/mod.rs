@@ -44,6 +44,8 @@ pub struct LiveSetBuilder {
     pub version: AbletonVersion,
     pub created_time: Option<DateTime<Local>>,
     pub modified_time: Option<DateTime<Local>>,
+    pub path: Option<PathBuf>,
+    pub file_hash: Option<String>,
 }
 
 impl LiveSetBuilder {
@@ -58,6 +60,8 @@ impl LiveSetBuilder {
             version: AbletonVersion::default(),
             created_time: None,
             modified_time: None,
+            path: None,
+            file_hash: None,
         }
     }
 
@@ -71,6 +75,16 @@ impl LiveSetBuilder {
         self
     }
 
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn with_file_hash(mut self, hash: &str) -> Self {
+        self.file_hash = Some(hash.to_string());
+        self
+    }
+
     pub fn with_plugin(mut self, name: &str) -> Self {
         self.plugins.insert(Plugin {
             id: Uuid::new_v4(),
@@ -152,6 +166,29 @@ impl LiveSetBuilder {
         self
     }
 
+    /// Build a complete LiveSet, applying the path, hash and times when they were set
+    pub fn build_live_set(self, name: &str) -> LiveSet {
+        let path = self.path.clone();
+        let file_hash = self.file_hash.clone();
+        let created_time = self.created_time;
+        let modified_time = self.modified_time;
+
+        let mut live_set = create_test_live_set_from_parse(name, self.build());
+        if let Some(path) = path {
+            live_set.file_path = path;
+        }
+        if let Some(file_hash) = file_hash {
+            live_set.file_hash = file_hash;
+        }
+        if let Some(created_time) = created_time {
+            live_set.created_time = created_time;
+        }
+        if let Some(modified_time) = modified_time {
+            live_set.modified_time = modified_time;
+        }
+        live_set
+    }
+
     pub fn build(self) -> ParseResult {
         ParseResult {
             plugins: self.plugins,
@@ -1,6 +1,8 @@
 mod common;
 
-use seula::config::CONFIG;
+use seula::config::{
+    Config, RootSettings, CONFIG, DEFAULT_POLL_INTERVAL_SECS, DEFAULT_SCAN_WORKERS,
+};
 use seula::error::ConfigError;
 
 use crate::common::setup;
@@ -129,6 +131,115 @@ fn test_config_clone() {
     }
 }
 
+#[test]
+fn test_config_poll_settings() {
+    setup("error");
+    let base = r#"
+        paths = ["C:/Music", "N:/Shared Projects"]
+        live_database_dir = "C:/Live Database"
+        media_storage_dir = "C:/media"
+    "#;
+
+    // Polling is opt-in per path
+    let config: Config = toml::from_str(base).unwrap();
+    assert!(config.poll_paths.is_empty());
+    assert_eq!(config.poll_interval_secs, DEFAULT_POLL_INTERVAL_SECS);
+    assert!(!config.is_poll_path("C:/Music"));
+
+    let polled = format!(
+        r#"{}
+        poll_paths = ["N:/Shared Projects"]
+        poll_interval_secs = 0
+        "#,
+        base
+    );
+    let config: Config = toml::from_str(&polled).unwrap();
+    assert!(config.is_poll_path("N:/Shared Projects"));
+    assert!(!config.is_poll_path("C:/Music"));
+    // Never busy-loops
+    assert_eq!(config.poll_interval(), std::time::Duration::from_secs(1));
+}
+
+#[test]
+fn test_config_scan_settings() {
+    setup("error");
+    let base = r#"
+        paths = ["C:/Music"]
+        live_database_dir = "C:/Live Database"
+        media_storage_dir = "C:/media"
+    "#;
+
+    let config: Config = toml::from_str(base).unwrap();
+    assert_eq!(config.scan_workers(), DEFAULT_SCAN_WORKERS);
+    assert!(config.scan_file_timeout().is_some());
+
+    let unlimited = format!(
+        r#"{}
+        scan_workers = 0
+        scan_file_timeout_secs = 0
+        "#,
+        base
+    );
+    let config: Config = toml::from_str(&unlimited).unwrap();
+    // Always at least one worker
+    assert_eq!(config.scan_workers(), 1);
+    assert_eq!(config.scan_file_timeout(), None);
+}
+
+#[test]
+fn test_config_media_validation() {
+    setup("error");
+    let base = r#"
+        paths = ["C:/Music"]
+        live_database_dir = "C:/Live Database"
+        media_storage_dir = "C:/media"
+    "#;
+
+    let config: Config = toml::from_str(base).unwrap();
+    assert!(config.strict_media_validation);
+    assert!(seula::media::MediaConfig::from(&config).strict_validation);
+
+    let permissive = format!("{}\n        strict_media_validation = false\n", base);
+    let config: Config = toml::from_str(&permissive).unwrap();
+    assert!(!seula::media::MediaConfig::from(&config).strict_validation);
+}
+
+#[test]
+fn test_config_root_settings() {
+    setup("error");
+    let config: Config = toml::from_str(
+        r#"
+        paths = ["C:/Music", "D:/Archive"]
+        live_database_dir = "C:/Live Database"
+        media_storage_dir = "C:/media"
+
+        [[roots]]
+        path = "D:/Archive"
+        exclude = ["**/Old Stuff/**", "**/_Freeze/**"]
+        max_depth = 3
+        follow_symlinks = false
+        default_tag = "archive"
+        "#,
+    )
+    .unwrap();
+
+    let archive = config.root_settings("D:/Archive");
+    assert_eq!(archive.exclude.len(), 2);
+    assert_eq!(archive.max_depth, Some(3));
+    assert!(!archive.follow_symlinks);
+    assert!(!archive.include_backups);
+    assert_eq!(archive.default_tag.as_deref(), Some("archive"));
+
+    // Paths without settings scan everything, as before
+    let music = config.root_settings("C:/Music");
+    assert_eq!(music, RootSettings::new("C:/Music"));
+    assert!(music.follow_symlinks);
+
+    // Settings survive being saved
+    let saved: Config = toml::from_str(&config.to_toml_string().unwrap()).unwrap();
+    assert_eq!(saved.roots, config.roots);
+}
+
 #[test]
 fn test_config_debug() {
     setup("error");
@@ -0,0 +1,116 @@
+//! Activity log tests
+
+use chrono::{DateTime, Days, Local, TimeZone};
+use seula::database::activity::{group_sessions, WorkSession};
+
+use super::*;
+use crate::common::{setup, LiveSetBuilder};
+
+fn at(days_ago: u64, hour: u32, minute: u32) -> DateTime<Local> {
+    let date = Local::now()
+        .date_naive()
+        .checked_sub_days(Days::new(days_ago))
+        .unwrap();
+    Local
+        .from_local_datetime(&date.and_hms_opt(hour, minute, 0).unwrap())
+        .earliest()
+        .unwrap()
+}
+
+fn project(name: &str, created: DateTime<Local>, modified: DateTime<Local>) -> LiveSet {
+    LiveSetBuilder::new()
+        .with_path(format!("/music/{}.als", name))
+        .with_created_time(created)
+        .with_modified_time(modified)
+        .build_live_set(&format!("{}.als", name))
+}
+
+#[test]
+fn test_group_sessions() {
+    let sessions = group_sessions(&[0, 600, 1800, 9000, 9100], 1800);
+    assert_eq!(
+        sessions,
+        vec![
+            WorkSession {
+                start: 0,
+                end: 1800,
+                saves: 3
+            },
+            WorkSession {
+                start: 9000,
+                end: 9100,
+                saves: 2
+            },
+        ]
+    );
+    // Lead-in time before the first save is counted
+    assert_eq!(sessions[1].seconds(), 100 + 15 * 60);
+    assert!(group_sessions(&[], 1800).is_empty());
+}
+
+#[test]
+fn test_activity_analytics() {
+    setup("error");
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+    let long_running = project("Long Running", at(1, 10, 0), at(1, 10, 20));
+    let long_running_id = long_running.id.to_string();
+    db.insert_project(&long_running).unwrap();
+    db.insert_project(&project("Sketch", at(0, 14, 10), at(0, 14, 10)))
+        .unwrap();
+
+    // A newer modification time is a new save, an unchanged one is not
+    db.conn
+        .execute(
+            "UPDATE projects SET modified_at = ? WHERE id = ?",
+            rusqlite::params![at(0, 14, 0).timestamp(), long_running_id],
+        )
+        .unwrap();
+    db.conn
+        .execute(
+            "UPDATE projects SET name = 'Renamed.als' WHERE id = ?",
+            [&long_running_id],
+        )
+        .unwrap();
+
+    let analytics = db.get_activity_analytics(7, 30).unwrap();
+    assert_eq!(analytics.days.len(), 7);
+    assert_eq!(analytics.days[6].date, Local::now().date_naive());
+    // Creating a project isn't a save, only its latest modification is
+    assert_eq!(analytics.total_saves(), 3);
+    assert_eq!((analytics.days[5].saves, analytics.days[6].saves), (1, 2));
+    assert_eq!(analytics.days[5].sessions, 1);
+    assert_eq!(analytics.days[5].seconds, 15 * 60);
+    assert_eq!(analytics.days[6].seconds, (10 + 15) * 60);
+    assert_eq!(analytics.sessions.len(), 2);
+    assert_eq!(analytics.total_seconds(), (15 + 25) * 60);
+
+    let projects: Vec<(&str, i32)> = analytics
+        .projects
+        .iter()
+        .map(|project| (project.name.as_str(), project.saves))
+        .collect();
+    assert_eq!(projects, vec![("Sketch.als", 1), ("Renamed.als", 2)]);
+    let long_running = &analytics.projects[1];
+    assert_eq!(long_running.first_save_at, at(1, 10, 20).timestamp());
+    assert_eq!(long_running.last_save_at, at(0, 14, 0).timestamp());
+    assert_eq!(
+        analytics.median_project_seconds(),
+        Some(long_running.seconds() / 2)
+    );
+
+    // Saves before the period are left out
+    let today = db.get_activity_analytics(1, 30).unwrap();
+    assert_eq!(today.total_saves(), 2);
+    assert_eq!(today.projects.len(), 2);
+    assert_eq!(today.projects[1].saves, 2);
+
+    // Saves of deleted projects are left out
+    db.conn
+        .execute("DELETE FROM projects WHERE id = ?", [&long_running_id])
+        .unwrap();
+    assert!(db
+        .get_project_saves(0)
+        .unwrap()
+        .iter()
+        .all(|(id, _)| *id != long_running_id));
+}
@@ -318,13 +318,13 @@ fn test_notes_and_tasks() {
     assert_eq!(project_tasks.len(), 3);
     assert!(project_tasks
         .iter()
-        .any(|(_, desc, _, _)| desc == "Fix the bass mix"));
+        .any(|task| task.description == "Fix the bass mix"));
     assert!(project_tasks
         .iter()
-        .any(|(_, desc, _, _)| desc == "Add more reverb"));
+        .any(|task| task.description == "Add more reverb"));
     assert!(project_tasks
         .iter()
-        .any(|(_, desc, _, _)| desc == "Export final version"));
+        .any(|task| task.description == "Export final version"));
 
     // Test completing a task
     db.complete_task(&task1_id, true)
@@ -334,9 +334,37 @@ fn test_notes_and_tasks() {
         .expect("Failed to get project tasks after completion");
     let completed_task = project_tasks
         .iter()
-        .find(|(id, _, _, _)| id == &task1_id)
+        .find(|task| task.id == task1_id)
         .expect("Couldn't find completed task");
-    assert!(completed_task.2); // Check completion status
+    assert!(completed_task.completed);
+    assert!(completed_task.completed_at.is_some());
+
+    // Completing a finished task again keeps its completion time
+    db.conn
+        .execute(
+            "UPDATE project_tasks SET completed_at = 1000 WHERE id = ?",
+            [&task1_id],
+        )
+        .unwrap();
+    db.complete_task(&task1_id, true)
+        .expect("Failed to complete task again");
+    db.batch_update_task_status(&[task1_id.clone()], true)
+        .expect("Failed to batch complete task");
+    let completed_at = |db: &mut LiveSetDatabase| {
+        db.get_project_tasks(&project_id)
+            .unwrap()
+            .into_iter()
+            .find(|task| task.id == task1_id)
+            .unwrap()
+            .completed_at
+    };
+    assert_eq!(completed_at(&mut db), Some(1000));
+    db.complete_task(&task1_id, false)
+        .expect("Failed to reopen task");
+    assert_eq!(completed_at(&mut db), None);
+    db.complete_task(&task1_id, true)
+        .expect("Failed to complete task");
+    assert!(completed_at(&mut db).is_some_and(|at| at > 1000));
 
     // Test removing a task
     db.remove_task(&task2_id).expect("Failed to remove task");
@@ -344,7 +372,7 @@ fn test_notes_and_tasks() {
         .get_project_tasks(&project_id)
         .expect("Failed to get project tasks after removal");
     assert_eq!(project_tasks.len(), 2);
-    assert!(!project_tasks.iter().any(|(id, _, _, _)| id == &task2_id));
+    assert!(!project_tasks.iter().any(|task| task.id == task2_id));
 
     // Test getting collection tasks
     let collection_tasks = db
@@ -355,10 +383,10 @@ fn test_notes_and_tasks() {
     // Verify collection tasks contain project name and correct completion status
     let completed_collection_task = collection_tasks
         .iter()
-        .find(|(id, _, desc, _, _)| desc == "Fix the bass mix")
+        .find(|(_, task)| task.description == "Fix the bass mix")
         .expect("Couldn't find completed task in collection");
-    assert!(completed_collection_task.3); // Check completion status
-    assert_eq!(completed_collection_task.1, "test_project.als"); // Check project name
+    assert!(completed_collection_task.1.completed); // Check completion status
+    assert_eq!(completed_collection_task.0, "test_project.als"); // Check project name
 
     // Create a second project with tasks
     let project2 =
@@ -381,8 +409,8 @@ fn test_notes_and_tasks() {
     assert_eq!(collection_tasks.len(), 3);
 
     // Tasks should be ordered by project position in collection
-    assert_eq!(collection_tasks[0].1, "test_project.als");
-    assert_eq!(collection_tasks[2].1, "Second Project.als");
+    assert_eq!(collection_tasks[0].0, "test_project.als");
+    assert_eq!(collection_tasks[2].0, "Second Project.als");
 }
 
 #[test]
@@ -389,3 +389,155 @@ fn test_media_file_foreign_key_constraints() {
     let result = db.update_project_audio_file("non-existent-project", Some("non-existent-media"));
     assert!(result.is_err());
 }
+
+#[test]
+fn test_project_audio_versions() {
+    setup("error");
+    let mut db =
+        LiveSetDatabase::new(PathBuf::from(":memory:")).expect("Failed to create database");
+    let test_project = create_test_live_set();
+    let project_id = test_project.id.to_string();
+    db.insert_project(&test_project).unwrap();
+
+    let first = create_test_media_file(MediaType::AudioFile, "mix1.wav", 2048, "audio/wav");
+    let second = create_test_media_file(MediaType::AudioFile, "mix2.wav", 2048, "audio/wav");
+    let master = create_test_media_file(MediaType::AudioFile, "master.wav", 2048, "audio/wav");
+    let cover = create_test_media_file(MediaType::CoverArt, "cover.jpg", 1024, "image/jpeg");
+    for media_file in [&first, &second, &master, &cover] {
+        db.insert_media_file(media_file).unwrap();
+    }
+
+    // Versions count up per label and the first audio file becomes primary
+    let attached = db
+        .attach_project_audio_file(&project_id, &first.id, Some("Mixdown"), None, false)
+        .unwrap();
+    assert_eq!((attached.label.as_str(), attached.version), ("Mixdown", 1));
+    assert!(attached.is_primary);
+    let attached = db
+        .attach_project_audio_file(&project_id, &second.id, Some("Mixdown"), None, false)
+        .unwrap();
+    assert_eq!(attached.version, 2);
+    assert!(!attached.is_primary);
+    let attached = db
+        .attach_project_audio_file(&project_id, &master.id, Some("Master"), None, true)
+        .unwrap();
+    assert_eq!(attached.version, 1);
+    assert!(attached.is_primary);
+
+    let audio_files = db.get_project_audio_files(&project_id).unwrap();
+    assert_eq!(audio_files.len(), 3);
+    assert_eq!(audio_files[0].media_file.id, master.id);
+    assert_eq!(audio_files.iter().filter(|audio| audio.is_primary).count(), 1);
+    assert_eq!(db.get_project_audio_file(&project_id).unwrap().unwrap().id, master.id);
+
+    // Only audio can be attached
+    let result = db.attach_project_audio_file(&project_id, &cover.id, None, None, false);
+    assert!(result.is_err());
+
+    // Relabeling starts the new label's count
+    let relabeled = db
+        .update_project_audio_file_details(&project_id, &second.id, Some("Master"), None)
+        .unwrap();
+    assert_eq!((relabeled.label.as_str(), relabeled.version), ("Master", 2));
+    let renumbered = db
+        .update_project_audio_file_details(&project_id, &second.id, None, Some(5))
+        .unwrap();
+    assert_eq!((renumbered.label.as_str(), renumbered.version), ("Master", 5));
+
+    db.set_primary_project_audio_file(&project_id, &first.id).unwrap();
+    assert_eq!(db.get_project_audio_file(&project_id).unwrap().unwrap().id, first.id);
+
+    // Detaching the primary one hands over to the latest of the others
+    db.detach_project_audio_file(&project_id, &first.id).unwrap();
+    let audio_files = db.get_project_audio_files(&project_id).unwrap();
+    assert_eq!(audio_files.len(), 2);
+    assert_eq!(audio_files[0].media_file.id, second.id);
+    assert!(audio_files[0].is_primary);
+    assert!(db.detach_project_audio_file(&project_id, &first.id).is_err());
+
+    // Detached audio files are orphaned, attached ones aren't
+    let orphaned: Vec<String> = db
+        .get_orphaned_media_files(None, None)
+        .unwrap()
+        .into_iter()
+        .map(|media_file| media_file.id)
+        .collect();
+    assert!(orphaned.contains(&first.id));
+    assert!(!orphaned.contains(&second.id));
+    assert!(!orphaned.contains(&master.id));
+
+    // Deleting the primary audio file takes it off the project and hands over too
+    db.delete_media_file(&second.id).unwrap();
+    let audio_files = db.get_project_audio_files(&project_id).unwrap();
+    assert_eq!(audio_files.len(), 1);
+    assert_eq!(audio_files[0].media_file.id, master.id);
+    assert!(audio_files[0].is_primary);
+}
+
+#[test]
+fn test_legacy_project_audio_file_is_migrated() {
+    setup("error");
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("legacy.db");
+    let test_project = create_test_live_set();
+    let project_id = test_project.id.to_string();
+    let audio_file = create_test_media_file(MediaType::AudioFile, "demo.mp3", 2048, "audio/mpeg");
+
+    {
+        let mut db = LiveSetDatabase::new(db_path.clone()).unwrap();
+        db.insert_project(&test_project).unwrap();
+        db.insert_media_file(&audio_file).unwrap();
+        // Databases from before audio versions kept a single audio file per project
+        db.conn
+            .execute(
+                "ALTER TABLE projects ADD COLUMN audio_file_id TEXT REFERENCES media_files(id)",
+                [],
+            )
+            .unwrap();
+        db.conn
+            .execute(
+                "UPDATE projects SET audio_file_id = ? WHERE id = ?",
+                [&audio_file.id, &project_id],
+            )
+            .unwrap();
+    }
+
+    let db = LiveSetDatabase::new(db_path).unwrap();
+    let audio_files = db.get_project_audio_files(&project_id).unwrap();
+    assert_eq!(audio_files.len(), 1);
+    assert_eq!(audio_files[0].media_file.id, audio_file.id);
+    assert_eq!(audio_files[0].label, "Bounce");
+    assert_eq!(audio_files[0].version, 1);
+    assert!(audio_files[0].is_primary);
+}
+
+#[test]
+fn test_media_files_sharing_content_are_counted() {
+    setup("error");
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+    let first = create_test_media_file(MediaType::AudioFile, "mix.wav", 4096, "audio/wav");
+    let second = MediaFile {
+        id: Uuid::new_v4().to_string(),
+        original_filename: "mix (copy).wav".to_string(),
+        ..first.clone()
+    };
+    let other = MediaFile {
+        checksum: "other-checksum".to_string(),
+        ..create_test_media_file(MediaType::CoverArt, "cover.png", 512, "image/png")
+    };
+    for media_file in [&first, &second, &other] {
+        db.insert_media_file(media_file).unwrap();
+    }
+
+    assert_eq!(db.get_media_blob_references(&first.checksum).unwrap(), 2);
+    assert_eq!(db.get_media_blob_statistics().unwrap(), (2, 4096 + 512));
+    let (total_files, total_size, ..) = db.get_media_statistics().unwrap();
+    assert_eq!((total_files, total_size), (3, 4096 * 2 + 512));
+
+    // The content stays in use until its last media file is deleted
+    assert!(!db.delete_media_file(&first.id).unwrap());
+    assert_eq!(db.get_media_blob_references(&first.checksum).unwrap(), 1);
+    assert!(db.delete_media_file(&second.id).unwrap());
+    assert_eq!(db.get_media_blob_references(&first.checksum).unwrap(), 0);
+    assert_eq!(db.get_media_blob_statistics().unwrap(), (1, 512));
+}
@@ -2,12 +2,16 @@
 //!
 //! This module contains all database-related tests
 
+pub mod activity;
 pub mod batch;
 pub mod collections;
 pub mod core;
 pub mod media;
+pub mod project_folders;
+pub mod reconcile;
 pub mod search;
 pub mod tags;
+pub mod year_in_review;
 
 // Common imports for database tests
 use seula::database::LiveSetDatabase;
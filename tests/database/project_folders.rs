@@ -0,0 +1,125 @@
+//! Set kind and project folder tests
+
+use chrono::{Duration, Local};
+use std::fs;
+
+use seula::models::SetKind;
+
+use super::*;
+use crate::common::{setup, LiveSetBuilder};
+
+#[test]
+fn test_sets_are_grouped_by_project_folder() {
+    setup("error");
+    let temp_dir = tempfile::tempdir().unwrap();
+    let folder = temp_dir.path().join("Song Project");
+    fs::create_dir_all(folder.join("Ableton Project Info")).unwrap();
+
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    let first = LiveSetBuilder::new()
+        .with_path(folder.join("Song.als"))
+        .with_modified_time(Local::now() - Duration::days(2))
+        .build_live_set("Idea.als");
+    let second = LiveSetBuilder::new()
+        .with_path(folder.join("Song v2.als"))
+        .build_live_set("Idea.als");
+    let loose = LiveSetBuilder::new()
+        .with_path(temp_dir.path().join("Sketch.als"))
+        .build_live_set("Idea.als");
+    db.insert_project(&first).unwrap();
+    db.insert_project(&second).unwrap();
+    db.insert_project(&loose).unwrap();
+
+    let folders = db.get_project_folders().unwrap();
+    assert_eq!(folders.len(), 1);
+    assert_eq!(folders[0].path, folder.to_string_lossy());
+    assert_eq!(folders[0].name, "Song Project");
+    assert_eq!(
+        folders[0].project_ids,
+        vec![second.id.to_string(), first.id.to_string()]
+    );
+    assert_eq!(folders[0].latest_project_id(), Some(second.id.to_string().as_str()));
+
+    let found = db
+        .get_project_folder(&folder.to_string_lossy())
+        .unwrap()
+        .unwrap();
+    assert_eq!(found, folders[0]);
+    assert!(db.get_project_folder("/nowhere").unwrap().is_none());
+
+    assert_eq!(
+        db.get_project_location(&loose.id.to_string()).unwrap(),
+        Some((SetKind::Project, None))
+    );
+
+    // Deleted sets drop out of their folder
+    db.mark_project_deleted(&second.id).unwrap();
+    let folder_after = db.get_project_folder(&folder.to_string_lossy()).unwrap().unwrap();
+    assert_eq!(folder_after.project_ids, vec![first.id.to_string()]);
+}
+
+#[test]
+fn test_templates_are_left_out_of_statistics() {
+    setup("error");
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    let project = LiveSetBuilder::new()
+        .with_path("/music/Song.als")
+        .build_live_set("Idea.als");
+    let template = LiveSetBuilder::new()
+        .with_path("/music/User Library/Templates/Four on the Floor.als")
+        .build_live_set("Idea.als");
+    let default = LiveSetBuilder::new()
+        .with_path("/music/User Library/DefaultLiveSet.als")
+        .build_live_set("Idea.als");
+    db.insert_project(&project).unwrap();
+    db.insert_project(&template).unwrap();
+    db.insert_project(&default).unwrap();
+
+    let (total_projects, ..) = db.get_basic_counts().unwrap();
+    assert_eq!(total_projects, 1);
+
+    assert_eq!(
+        db.get_project_location(&template.id.to_string()).unwrap(),
+        Some((SetKind::Template, None))
+    );
+    assert_eq!(
+        db.get_project_location(&default.id.to_string()).unwrap(),
+        Some((SetKind::Default, None))
+    );
+
+    let (templates, count) = db
+        .get_projects_with_filters(
+            None, None, None, None, None, None, None, None, None, None, None, None, None, None,
+            None, None, None, None, None, None,
+            Some(SetKind::Template),
+        )
+        .unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(templates[0].id, template.id);
+}
+
+#[test]
+fn test_moving_a_set_reclassifies_it() {
+    setup("error");
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    let live_set = LiveSetBuilder::new()
+        .with_path("/music/Song.als")
+        .build_live_set("Idea.als");
+    db.insert_project(&live_set).unwrap();
+    let project_id = live_set.id.to_string();
+
+    db.update_project_path(
+        &project_id,
+        std::path::Path::new("/music/User Library/Templates/Song.als"),
+    )
+    .unwrap();
+    assert_eq!(
+        db.get_project_location(&project_id).unwrap(),
+        Some((SetKind::Template, None))
+    );
+
+    assert_eq!(db.refresh_set_locations().unwrap(), 1);
+}
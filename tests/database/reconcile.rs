@@ -0,0 +1,131 @@
+//! Moved project reconciliation tests
+
+use std::fs::File;
+use uuid::Uuid;
+
+use seula::database::reconcile::MoveMatch;
+
+use super::*;
+use crate::common::{setup, LiveSetBuilder};
+
+fn live_set_at(path: &str, hash: &str) -> LiveSet {
+    LiveSetBuilder::new()
+        .with_plugin("Serum")
+        .with_sample("kick.wav")
+        .with_tempo(124.0)
+        .with_path(path)
+        .with_file_hash(hash)
+        .build_live_set("Idea.als")
+}
+
+#[test]
+fn test_find_moved_project_by_hash() {
+    setup("error");
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    let original = live_set_at("C:/music/old/Idea.als", "hash-1");
+    db.insert_project(&original).unwrap();
+    db.mark_project_deleted(&original.id).unwrap();
+
+    let found = live_set_at("C:/music/new/Idea.als", "hash-1");
+    assert_eq!(
+        db.find_moved_project(&found).unwrap(),
+        Some((original.id.to_string(), MoveMatch::Hash))
+    );
+
+    // A project whose file is still in place hasn't moved, even with the same content
+    let temp_dir = tempfile::tempdir().unwrap();
+    let copy_path = temp_dir.path().join("Copy.als");
+    File::create(&copy_path).unwrap();
+    let mut copy = live_set_at(&copy_path.to_string_lossy(), "hash-2");
+    copy.id = Uuid::new_v4();
+    db.insert_project(&copy).unwrap();
+
+    let duplicate = live_set_at("C:/music/new/Copy of Idea.als", "hash-2");
+    assert!(db
+        .find_moved_project_by_hash(&duplicate.file_path, &duplicate.file_hash)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_find_moved_project_by_fingerprint() {
+    setup("error");
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    // Still active, but its file is gone: a full scan hasn't noticed the move yet
+    let original = live_set_at("C:/music/old/Idea.als", "hash-1");
+    db.insert_project(&original).unwrap();
+
+    // Saved again after moving, so the hash differs but the content is the same
+    let mut found = live_set_at("C:/music/new/Idea.als", "hash-2");
+    found.id = Uuid::new_v4();
+    assert_eq!(
+        db.find_moved_project(&found).unwrap(),
+        Some((original.id.to_string(), MoveMatch::Fingerprint))
+    );
+
+    // Different content doesn't match
+    let mut changed = live_set_at("C:/music/new/Idea.als", "hash-3");
+    changed.tempo = 90.0;
+    assert!(db.find_moved_project(&changed).unwrap().is_none());
+
+    // Two equally good candidates are left alone
+    let loop_at = |path: &str, hash: &str| {
+        let mut live_set = live_set_at(path, hash);
+        live_set.id = Uuid::new_v4();
+        live_set.name = "Loop.als".to_string();
+        live_set.plugins.clear();
+        live_set.samples.clear();
+        live_set
+    };
+    db.insert_project(&loop_at("C:/music/a/Loop.als", "hash-4"))
+        .unwrap();
+    db.insert_project(&loop_at("C:/music/b/Loop.als", "hash-5"))
+        .unwrap();
+    let found_loop = loop_at("C:/music/new/Loop.als", "hash-6");
+    assert!(db.find_moved_project(&found_loop).unwrap().is_none());
+}
+
+#[test]
+fn test_reconcile_moved_projects_keeps_metadata() {
+    setup("error");
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    let original = live_set_at("C:/music/old/Idea.als", "hash-1");
+    let project_id = original.id.to_string();
+    db.insert_project(&original).unwrap();
+    db.set_project_notes(&project_id, "keep me").unwrap();
+    db.set_project_rating(&project_id, Some(5)).unwrap();
+    let tag_id = db.add_tag("keeper").unwrap();
+    db.tag_project(&project_id, &tag_id).unwrap();
+    db.mark_project_deleted(&original.id).unwrap();
+
+    let mut moved = live_set_at("C:/music/new/Idea.als", "hash-1");
+    moved.id = Uuid::new_v4();
+    let mut brand_new = live_set_at("C:/music/new/Other.als", "hash-9");
+    brand_new.id = Uuid::new_v4();
+    brand_new.name = "Other.als".to_string();
+    brand_new.tempo = 170.0;
+
+    let (remaining, reattached) = db.reconcile_moved_projects(vec![moved, brand_new]).unwrap();
+    assert_eq!(reattached, 1);
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].name, "Other.als");
+
+    let project = db.get_project_by_id(&project_id).unwrap().unwrap();
+    assert!(project.is_active);
+    assert_eq!(project.file_path, PathBuf::from("C:/music/new/Idea.als"));
+    assert_eq!(
+        db.get_project_notes(&project_id).unwrap().as_deref(),
+        Some("keep me")
+    );
+    assert_eq!(
+        db.get_project_engagement(&project_id)
+            .unwrap()
+            .unwrap()
+            .rating,
+        Some(5)
+    );
+    assert!(db.get_project_tags(&project_id).unwrap().contains("keeper"));
+}
@@ -0,0 +1,94 @@
+//! Year in review tests
+
+use chrono::{Local, NaiveDate, TimeZone};
+use seula::database::BatchInsertManager;
+use seula::models::{KeySignature, Scale, Tonic};
+use std::sync::Arc;
+
+use super::*;
+use crate::common::{setup, LiveSetBuilder};
+
+fn project(
+    name: &str,
+    created: (i32, u32, u32, u32),
+    modified: (i32, u32, u32, u32),
+    plugins: &[&str],
+    tempo: f64,
+    duration_seconds: i64,
+) -> LiveSet {
+    let at = |(year, month, day, hour): (i32, u32, u32, u32)| {
+        Local.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    };
+    let builder = plugins
+        .iter()
+        .fold(LiveSetBuilder::new(), |builder, plugin| builder.with_plugin(plugin))
+        .with_path(format!("/music/{}.als", name))
+        .with_created_time(at(created))
+        .with_modified_time(at(modified))
+        .with_tempo(tempo)
+        .with_key_signature(KeySignature {
+            tonic: Tonic::C,
+            scale: Scale::Major,
+        });
+    let mut live_set = builder.build_live_set(&format!("{}.als", name));
+    live_set.estimated_duration = Some(chrono::Duration::seconds(duration_seconds));
+    live_set
+}
+
+#[test]
+fn test_year_in_review() {
+    setup("error");
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+    let anthem = project("Anthem", (2024, 3, 4, 10), (2024, 3, 5, 22), &["Serum"], 128.0, 300);
+    let carried_over = project(
+        "Carried Over",
+        (2023, 11, 1, 12),
+        (2024, 3, 6, 22),
+        &["Serum", "Pro-Q 3"],
+        124.0,
+        120,
+    );
+    let summer = project("Summer", (2024, 7, 1, 9), (2024, 7, 1, 9), &["Vital"], 126.0, 60);
+    let untouched = project("Untouched", (2022, 5, 1, 12), (2022, 5, 2, 12), &["Serum"], 90.0, 900);
+    // Inserted like a scan does, so projects share plugin rows
+    let anthem_id = anthem.id.to_string();
+    let live_sets = Arc::new(vec![anthem, carried_over, summer, untouched]);
+    BatchInsertManager::new(&mut db.conn, live_sets).execute().unwrap();
+
+    let review = db.get_year_in_review(2024).unwrap();
+    assert_eq!(review.projects_worked_on, 3);
+    assert_eq!(review.projects_started, 2);
+
+    let top_plugins: Vec<(&str, i32)> = review
+        .top_plugins
+        .iter()
+        .map(|(name, _, count)| (name.as_str(), *count))
+        .collect();
+    assert_eq!(top_plugins, vec![("Serum", 2), ("Pro-Q 3", 1), ("Vital", 1)]);
+    // Serum and Pro-Q 3 were already used the year before
+    let new_plugins: Vec<&str> = review.new_plugins.iter().map(|(name, ..)| name.as_str()).collect();
+    assert_eq!(new_plugins, vec!["Vital"]);
+
+    assert_eq!(review.busiest_month(), Some(3));
+    assert_eq!(review.busiest_hour(), Some(22));
+    // 2024-03-05 was a Tuesday, 2024-03-06 a Wednesday and 2024-07-01 a Monday
+    assert_eq!(review.saves_per_weekday, [1, 1, 1, 0, 0, 0, 0]);
+    assert_eq!(review.busiest_weekday(), Some(1));
+
+    assert_eq!(review.longest_project_id, Some(anthem_id));
+    assert_eq!(review.favorite_key, Some(("C Major".to_string(), 3)));
+    assert_eq!(review.favorite_tempo_range, Some((120.0, 3)));
+    assert_eq!(review.average_tempo, Some(126.0));
+
+    // Started on the 4th and saved on the 5th and 6th of March, then once in July
+    assert_eq!(review.active_days, 4);
+    let streak = review.longest_streak.unwrap();
+    assert_eq!(streak.start, NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+    assert_eq!(streak.days, 3);
+
+    let quiet = db.get_year_in_review(2021).unwrap();
+    assert_eq!(quiet.projects_worked_on, 0);
+    assert!(quiet.top_plugins.is_empty());
+    assert_eq!(quiet.busiest_month(), None);
+    assert!(quiet.longest_streak.is_none());
+}
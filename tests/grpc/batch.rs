@@ -179,12 +179,14 @@ async fn test_batch_tag_projects() {
     // Create test tags
     let tag1_req = CreateTagRequest {
         name: "Test Tag 1".to_string(),
+        ..Default::default()
     };
     let tag1_resp = server.create_tag(Request::new(tag1_req)).await.unwrap();
     let tag1_id = tag1_resp.into_inner().tag.unwrap().id;
 
     let tag2_req = CreateTagRequest {
         name: "Test Tag 2".to_string(),
+        ..Default::default()
     };
     let tag2_resp = server.create_tag(Request::new(tag2_req)).await.unwrap();
     let tag2_id = tag2_resp.into_inner().tag.unwrap().id;
@@ -238,12 +240,14 @@ async fn test_batch_untag_projects() {
     // Create test tags
     let tag1_req = CreateTagRequest {
         name: "Test Tag 1".to_string(),
+        ..Default::default()
     };
     let tag1_resp = server.create_tag(Request::new(tag1_req)).await.unwrap();
     let tag1_id = tag1_resp.into_inner().tag.unwrap().id;
 
     let tag2_req = CreateTagRequest {
         name: "Test Tag 2".to_string(),
+        ..Default::default()
     };
     let tag2_resp = server.create_tag(Request::new(tag2_req)).await.unwrap();
     let tag2_id = tag2_resp.into_inner().tag.unwrap().id;
@@ -493,6 +497,7 @@ async fn test_batch_update_task_status() {
     let task1_req = CreateTaskRequest {
         project_id: project_id.clone(),
         description: "First task".to_string(),
+        ..Default::default()
     };
 
     let task1_resp = server.create_task(Request::new(task1_req)).await.unwrap();
@@ -501,6 +506,7 @@ async fn test_batch_update_task_status() {
     let task2_req = CreateTaskRequest {
         project_id: project_id.clone(),
         description: "Second task".to_string(),
+        ..Default::default()
     };
     let task2_resp = server.create_task(Request::new(task2_req)).await.unwrap();
     let task2_id = task2_resp.into_inner().task.unwrap().id;
@@ -508,6 +514,7 @@ async fn test_batch_update_task_status() {
     let task3_req = CreateTaskRequest {
         project_id: project_id.clone(),
         description: "Third task".to_string(),
+        ..Default::default()
     };
     let task3_resp = server.create_task(Request::new(task3_req)).await.unwrap();
     let task3_id = task3_resp.into_inner().task.unwrap().id;
@@ -515,6 +522,7 @@ async fn test_batch_update_task_status() {
     // Verify initial statuses
     let get_tasks_req = GetProjectTasksRequest {
         project_id: project_id.clone(),
+        ..Default::default()
     };
     let get_tasks_resp = server
         .get_project_tasks(Request::new(get_tasks_req))
@@ -542,6 +550,7 @@ async fn test_batch_update_task_status() {
     // Verify all tasks are now completed
     let get_tasks_req = GetProjectTasksRequest {
         project_id: project_id.clone(),
+        ..Default::default()
     };
     let get_tasks_resp = server
         .get_project_tasks(Request::new(get_tasks_req))
@@ -568,6 +577,7 @@ async fn test_batch_delete_tasks() {
     let task1_req = CreateTaskRequest {
         project_id: project_id.clone(),
         description: "First task".to_string(),
+        ..Default::default()
     };
     let task1_resp = server.create_task(Request::new(task1_req)).await.unwrap();
     let task1_id = task1_resp.into_inner().task.unwrap().id;
@@ -575,6 +585,7 @@ async fn test_batch_delete_tasks() {
     let task2_req = CreateTaskRequest {
         project_id: project_id.clone(),
         description: "Second task".to_string(),
+        ..Default::default()
     };
     let task2_resp = server.create_task(Request::new(task2_req)).await.unwrap();
     let task2_id = task2_resp.into_inner().task.unwrap().id;
@@ -582,6 +593,7 @@ async fn test_batch_delete_tasks() {
     let task3_req = CreateTaskRequest {
         project_id: project_id.clone(),
         description: "Third task".to_string(),
+        ..Default::default()
     };
     let task3_resp = server.create_task(Request::new(task3_req)).await.unwrap();
     let task3_id = task3_resp.into_inner().task.unwrap().id;
@@ -589,6 +601,7 @@ async fn test_batch_delete_tasks() {
     // Verify initial task count
     let get_tasks_req = GetProjectTasksRequest {
         project_id: project_id.clone(),
+        ..Default::default()
     };
     let get_tasks_resp = server
         .get_project_tasks(Request::new(get_tasks_req))
@@ -615,6 +628,7 @@ async fn test_batch_delete_tasks() {
     // Verify only one task remains
     let get_tasks_req = GetProjectTasksRequest {
         project_id: project_id.clone(),
+        ..Default::default()
     };
     let get_tasks_resp = server
         .get_project_tasks(Request::new(get_tasks_req))
@@ -645,6 +659,7 @@ async fn test_batch_operations_with_mixed_success_failure() {
     // Create a valid tag
     let tag_req = CreateTagRequest {
         name: "Test Tag".to_string(),
+        ..Default::default()
     };
     let tag_resp = server.create_tag(Request::new(tag_req)).await.unwrap();
     let tag_id = tag_resp.into_inner().tag.unwrap().id;
@@ -694,12 +709,14 @@ async fn test_batch_operations_workflow() {
     // Create tags
     let tag1_req = CreateTagRequest {
         name: "Workflow Tag 1".to_string(),
+        ..Default::default()
     };
     let tag1_resp = server.create_tag(Request::new(tag1_req)).await.unwrap();
     let tag1_id = tag1_resp.into_inner().tag.unwrap().id;
 
     let tag2_req = CreateTagRequest {
         name: "Workflow Tag 2".to_string(),
+        ..Default::default()
     };
     let tag2_resp = server.create_tag(Request::new(tag2_req)).await.unwrap();
     let tag2_id = tag2_resp.into_inner().tag.unwrap().id;
@@ -738,6 +755,7 @@ async fn test_batch_operations_workflow() {
         let task_req = CreateTaskRequest {
             project_id: project_id.clone(),
             description: format!("Task for project {}", project_id),
+            ..Default::default()
         };
         let task_resp = server.create_task(Request::new(task_req)).await.unwrap();
         let task_id = task_resp.into_inner().task.unwrap().id;
@@ -826,6 +844,7 @@ async fn test_batch_operations_workflow() {
     for project_id in &project_ids {
         let get_tasks_req = GetProjectTasksRequest {
             project_id: project_id.clone(),
+            ..Default::default()
         };
         let get_tasks_resp = server
             .get_project_tasks(Request::new(get_tasks_req))
@@ -1187,6 +1187,9 @@ async fn create_test_project(server: &StudioProjectManagerServer, _: &str) -> se
             modified_after: None,
             modified_before: None,
             has_audio_file: None,
+            min_rating: None,
+            is_favorite: None,
+            set_kind: None,
         }))
         .await
         .unwrap();
@@ -9,9 +9,16 @@
 use crate::common::setup;
 
 use super::*;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
 use seula::grpc::media::media_service_server::MediaService;
-use seula::media::{MediaFile, MediaType};
+use seula::database::search::SearchQuery;
+use seula::grpc::projects::project_service_server::ProjectService;
+use image::{DynamicImage, ImageEncoder, RgbImage};
+use seula::media::{
+    FileValidator, MediaConfig, MediaError, MediaFile, MediaFormat, MediaStorageManager, MediaType,
+    ThumbnailSize,
+};
 use tokio_stream::StreamExt;
 
 #[tokio::test]
@@ -164,6 +171,8 @@ async fn test_set_project_audio_file_success() {
     let request = Request::new(SetProjectAudioFileRequest {
         project_id: project_id.clone(),
         media_file_id: audio_file.id.clone(),
+        label: None,
+        version: None,
     });
 
     let response = server.set_project_audio_file(request).await.unwrap();
@@ -211,6 +220,7 @@ async fn test_remove_project_audio_file_success() {
     // Remove project audio file
     let request = Request::new(RemoveProjectAudioFileRequest {
         project_id: project_id.clone(),
+        media_file_id: None,
     });
 
     let response = server.remove_project_audio_file(request).await.unwrap();
@@ -291,13 +301,14 @@ async fn test_download_media_streaming() {
     let server = create_test_server().await;
 
     // Create a test file using the MediaStorageManager
-    let test_data = b"test file content for streaming";
-    let filename = "test.jpg";
+    let test_data = png(64, 64);
+    let filename = "test.png";
 
     let media_file = server
         .media_storage()
-        .store_file(test_data, filename, MediaType::CoverArt)
+        .store_file(&test_data, filename, MediaType::CoverArt)
         .unwrap();
+    let stored_data = fs::read(server.media_storage().get_file_path(&media_file)).unwrap();
 
     // Insert the media file into the database
     {
@@ -308,6 +319,10 @@ async fn test_download_media_streaming() {
     // Download the media file
     let request = Request::new(DownloadMediaRequest {
         media_file_id: media_file.id.clone(),
+        size: None,
+        offset: None,
+        length: None,
+        chunk_size: None,
     });
 
     let mut response_stream = server.download_media(request).await.unwrap().into_inner();
@@ -342,8 +357,8 @@ async fn test_download_media_streaming() {
         }
     }
 
-    // Verify the downloaded content matches the original
-    assert_eq!(all_chunks, test_data);
+    // Verify the downloaded content matches the stored file
+    assert_eq!(all_chunks, stored_data);
 }
 
 #[tokio::test]
@@ -353,9 +368,1071 @@ async fn test_download_media_not_found() {
 
     let request = Request::new(DownloadMediaRequest {
         media_file_id: "nonexistent-media-id".to_string(),
+        size: None,
+        offset: None,
+        length: None,
+        chunk_size: None,
     });
 
     let result = server.download_media(request).await;
     assert!(result.is_err());
     assert_eq!(result.unwrap_err().code(), Code::NotFound);
 }
+
+/// A 16-bit mono WAV file of a sine wave at half scale
+fn sine_wav(frames: u32, sample_rate: u32) -> Vec<u8> {
+    pcm_wav(frames, sample_rate, 1, |i, _| {
+        let phase = i as f32 / sample_rate as f32 * 440.0 * std::f32::consts::TAU;
+        phase.sin() * 0.5
+    })
+}
+
+/// A 16-bit WAV file with samples from `sample(frame, channel)`, each in -1.0..=1.0
+fn pcm_wav(frames: u32, sample_rate: u32, channels: u16, sample: impl Fn(u32, u16) -> f32) -> Vec<u8> {
+    let block_align = channels as u32 * 2;
+    let data_len = frames * block_align;
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * block_align).to_le_bytes());
+    wav.extend_from_slice(&(block_align as u16).to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for i in 0..frames {
+        for channel in 0..channels {
+            let value = (sample(i, channel) * i16::MAX as f32) as i16;
+            wav.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    wav
+}
+
+#[tokio::test]
+async fn test_get_waveform_for_project_audio_file() {
+    setup("error");
+    let server = create_test_server().await;
+    let project_id = create_test_project_in_db(server.db()).await;
+
+    let media_file = server
+        .media_storage()
+        .store_file(&sine_wav(22050 * 20, 22050), "bounce.wav", MediaType::AudioFile)
+        .unwrap();
+    {
+        let mut db = server.db().lock().await;
+        db.insert_media_file(&media_file).unwrap();
+        db.update_project_audio_file(&project_id, Some(&media_file.id))
+            .unwrap();
+    }
+
+    let overview = server
+        .get_waveform(Request::new(GetWaveformRequest {
+            media_file_id: None,
+            project_id: Some(project_id.clone()),
+            zoom_level: None,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(overview.media_file_id, media_file.id);
+    assert_eq!(overview.zoom_level, 0);
+    assert_eq!(overview.sample_rate, 22050);
+    assert_eq!(overview.channels, 1);
+    assert!((overview.duration_seconds - 20.0).abs() < 0.01);
+    assert_eq!(overview.zoom_levels, 2);
+    assert!(overview.min_peaks.len() <= 1024);
+    assert_eq!(overview.min_peaks.len(), overview.max_peaks.len());
+    // The last peak only covers the tail end of the audio
+    let full_peaks = overview.max_peaks.len() - 1;
+    assert!(overview.max_peaks[..full_peaks].iter().all(|peak| (peak - 0.5).abs() < 0.01));
+    assert!(overview.min_peaks[..full_peaks].iter().all(|peak| (peak + 0.5).abs() < 0.01));
+
+    // The most detailed level has a peak for every 256 frames
+    let detailed = server
+        .get_waveform(Request::new(GetWaveformRequest {
+            media_file_id: Some(media_file.id.clone()),
+            project_id: None,
+            zoom_level: Some(overview.zoom_levels - 1),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(detailed.frames_per_peak, 256);
+    assert_eq!(detailed.max_peaks.len(), (22050 * 20usize).div_ceil(256));
+
+    let out_of_range = server
+        .get_waveform(Request::new(GetWaveformRequest {
+            media_file_id: Some(media_file.id.clone()),
+            project_id: None,
+            zoom_level: Some(overview.zoom_levels),
+        }))
+        .await;
+    assert_eq!(out_of_range.unwrap_err().code(), Code::InvalidArgument);
+
+    // Uploads have to decode, so the file breaks after it's stored
+    let undecodable = server
+        .media_storage()
+        .store_file(&sine_wav(22050, 22050), "broken.wav", MediaType::AudioFile)
+        .unwrap();
+    fs::write(
+        server.media_storage().get_file_path(&undecodable),
+        b"not really audio at all",
+    )
+    .unwrap();
+    server.db().lock().await.insert_media_file(&undecodable).unwrap();
+    let result = server
+        .get_waveform(Request::new(GetWaveformRequest {
+            media_file_id: Some(undecodable.id.clone()),
+            project_id: None,
+            zoom_level: None,
+        }))
+        .await;
+    assert_eq!(result.unwrap_err().code(), Code::FailedPrecondition);
+}
+
+#[tokio::test]
+async fn test_waveform_cache_follows_audio_file() {
+    setup("error");
+    let storage_dir = tempfile::tempdir().unwrap();
+    let storage =
+        MediaStorageManager::new(storage_dir.path().to_path_buf(), MediaConfig::default()).unwrap();
+    let mut media_file = storage
+        .store_file(&sine_wav(44100, 44100), "bounce.wav", MediaType::AudioFile)
+        .unwrap();
+    let cache_path = storage_dir
+        .path()
+        .join("waveforms")
+        .join(format!("{}.peaks", media_file.id));
+
+    let waveform = storage.get_waveform(&media_file).unwrap();
+    assert!(cache_path.exists());
+    assert_eq!(waveform.checksum, media_file.checksum);
+    // Cached peaks come back as they were stored, give or take 16-bit rounding
+    let cached = storage.get_waveform(&media_file).unwrap();
+    assert_eq!(cached.levels.len(), waveform.levels.len());
+    assert!(cached.levels[0]
+        .max
+        .iter()
+        .zip(&waveform.levels[0].max)
+        .all(|(a, b)| (a - b).abs() < 0.001));
+
+    // A corrupt cache is decoded again instead of trusting its lengths
+    let mut corrupt = b"SPK1".to_vec();
+    corrupt.extend_from_slice(&u32::MAX.to_le_bytes());
+    fs::write(&cache_path, corrupt).unwrap();
+    let recovered = storage.get_waveform(&media_file).unwrap();
+    assert_eq!(recovered.levels.len(), waveform.levels.len());
+    let cache_files: Vec<_> = fs::read_dir(storage_dir.path().join("waveforms"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(cache_files, vec![cache_path.clone()]);
+
+    // New audio under the same ID replaces the cached peaks
+    let replaced = storage
+        .store_file(&sine_wav(44100 * 2, 44100), "bounce.wav", MediaType::AudioFile)
+        .unwrap();
+    media_file.checksum = replaced.checksum.clone();
+    let regenerated = storage.get_waveform(&media_file).unwrap();
+    assert_eq!(regenerated.checksum, replaced.checksum);
+    assert_eq!(regenerated.total_frames, 44100 * 2);
+
+    // Peaks without an audio file are cleaned up, and go with their audio file
+    fs::write(storage_dir.path().join("waveforms").join("gone.peaks"), b"stale").unwrap();
+    let known: HashSet<String> = [media_file.id.clone()].into_iter().collect();
+    let stats = storage.cleanup_waveforms(&known).unwrap();
+    assert_eq!(stats.files_deleted, 1);
+    assert!(cache_path.exists());
+
+    storage.delete_file(&media_file).unwrap();
+    assert!(!cache_path.exists());
+}
+
+/// A JPEG whose EXIF data says it has to be turned 90 degrees clockwise
+fn rotated_jpeg(width: u32, height: u32) -> Vec<u8> {
+    // Little-endian TIFF header with one IFD entry: Orientation (0x0112) = 6
+    let mut exif = b"II*\0".to_vec();
+    exif.extend_from_slice(&8u32.to_le_bytes());
+    exif.extend_from_slice(&1u16.to_le_bytes());
+    exif.extend_from_slice(&0x0112u16.to_le_bytes());
+    exif.extend_from_slice(&3u16.to_le_bytes());
+    exif.extend_from_slice(&1u32.to_le_bytes());
+    exif.extend_from_slice(&6u32.to_le_bytes());
+    exif.extend_from_slice(&0u32.to_le_bytes());
+
+    let image = RgbImage::from_fn(width, height, |x, _| image::Rgb([(x % 256) as u8, 64, 128]));
+    let mut jpeg = Vec::new();
+    let mut encoder = image::codecs::jpeg::JpegEncoder::new(&mut jpeg);
+    encoder.set_exif_metadata(exif).unwrap();
+    encoder
+        .write_image(image.as_raw(), width, height, image::ExtendedColorType::Rgb8)
+        .unwrap();
+    jpeg
+}
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut png = Vec::new();
+    DynamicImage::ImageRgb8(RgbImage::new(width, height))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+    png
+}
+
+#[tokio::test]
+async fn test_cover_art_is_stored_upright_with_thumbnails() {
+    setup("error");
+    let storage_dir = tempfile::tempdir().unwrap();
+    let storage =
+        MediaStorageManager::new(storage_dir.path().to_path_buf(), MediaConfig::default()).unwrap();
+
+    let upload = rotated_jpeg(300, 200);
+    assert!(upload.windows(4).any(|window| window == b"Exif"));
+    let media_file = storage
+        .store_file(&upload, "cover.jpg", MediaType::CoverArt)
+        .unwrap();
+
+    // The stored image is turned upright and its metadata is gone
+    let stored = fs::read(storage.get_file_path(&media_file)).unwrap();
+    assert!(!stored.windows(4).any(|window| window == b"Exif"));
+    assert_eq!(media_file.file_size_bytes, stored.len() as u64);
+    let upright = image::load_from_memory(&stored).unwrap();
+    assert_eq!((upright.width(), upright.height()), (200, 300));
+
+    let variants = storage.generate_thumbnails(&media_file).unwrap();
+    assert_eq!(variants.len(), ThumbnailSize::ALL.len());
+    let small = variants
+        .iter()
+        .find(|variant| variant.size == ThumbnailSize::Small)
+        .unwrap();
+    assert_eq!(small.height, 256);
+    assert!(small.width < 256);
+    assert_eq!(small.mime_type, "image/jpeg");
+    // Images smaller than a thumbnail size aren't scaled up
+    let large = variants
+        .iter()
+        .find(|variant| variant.size == ThumbnailSize::Large)
+        .unwrap();
+    assert_eq!((large.width, large.height), (200, 300));
+
+    let small_path = storage.get_variant_path(&media_file.id, small.size, &small.file_extension);
+    let thumbnail = image::load_from_memory(&fs::read(&small_path).unwrap()).unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (small.width, small.height));
+
+    // Thumbnails go with their cover art
+    storage.delete_file(&media_file).unwrap();
+    assert!(!small_path.exists());
+}
+
+#[tokio::test]
+async fn test_download_cover_art_thumbnail() {
+    setup("error");
+    let server = create_test_server().await;
+
+    // Stored without thumbnails, like files uploaded before they existed
+    let media_file = server
+        .media_storage()
+        .store_file(&png(600, 400), "cover.png", MediaType::CoverArt)
+        .unwrap();
+    server.db().lock().await.insert_media_file(&media_file).unwrap();
+
+    let mut response_stream = server
+        .download_media(Request::new(DownloadMediaRequest {
+            media_file_id: media_file.id.clone(),
+            size: Some("small".to_string()),
+            offset: None,
+            length: None,
+            chunk_size: None,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut metadata = None;
+    let mut data = Vec::new();
+    while let Some(response) = response_stream.next().await {
+        match response.unwrap().data {
+            Some(download_media_response::Data::Metadata(m)) => metadata = Some(m),
+            Some(download_media_response::Data::Chunk(chunk)) => data.extend(chunk),
+            None => {}
+        }
+    }
+    let metadata = metadata.unwrap();
+    assert_eq!(metadata.id, media_file.id);
+    assert_eq!(metadata.mime_type, "image/jpeg");
+    assert_eq!(metadata.file_size_bytes, data.len() as i64);
+    let thumbnail = image::load_from_memory(&data).unwrap();
+    assert_eq!(thumbnail.width(), 256);
+
+    // Every size was generated and recorded on the way
+    let variants = server.db().lock().await.get_media_variants(&media_file.id).unwrap();
+    assert_eq!(variants.len(), 3);
+    let variant_bytes: u64 = variants.iter().map(|variant| variant.file_size_bytes).sum();
+
+    let stats = server
+        .get_media_statistics(Request::new(GetMediaStatisticsRequest {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(stats.total_files, 1);
+    assert_eq!(stats.derived_files_count, 3);
+    assert_eq!(stats.derived_files_size_bytes, variant_bytes as i64);
+    assert_eq!(
+        stats.total_size_bytes,
+        (media_file.file_size_bytes + variant_bytes) as i64
+    );
+
+    let invalid = server
+        .download_media(Request::new(DownloadMediaRequest {
+            media_file_id: media_file.id.clone(),
+            size: Some("huge".to_string()),
+            offset: None,
+            length: None,
+            chunk_size: None,
+        }))
+        .await;
+    assert_eq!(invalid.unwrap_err().code(), Code::InvalidArgument);
+
+    // The cover art isn't used by any collection, so cleanup frees its thumbnails too
+    let dry_run = server
+        .cleanup_orphaned_media(Request::new(CleanupOrphanedMediaRequest { dry_run: true }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        dry_run.bytes_freed,
+        (media_file.file_size_bytes + variant_bytes) as i64
+    );
+    let cleanup = server
+        .cleanup_orphaned_media(Request::new(CleanupOrphanedMediaRequest { dry_run: false }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(cleanup.deleted_file_ids, vec![media_file.id.clone()]);
+    assert!(server
+        .db()
+        .lock()
+        .await
+        .get_media_variants(&media_file.id)
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_identical_uploads_share_stored_content() {
+    setup("error");
+    let server = create_test_server().await;
+    let upload = png(32, 32);
+    let first = server
+        .media_storage()
+        .store_file(&upload, "cover.png", MediaType::CoverArt)
+        .unwrap();
+    let second = server
+        .media_storage()
+        .store_file(&upload, "same-cover.png", MediaType::CoverArt)
+        .unwrap();
+    assert_ne!(first.id, second.id);
+    assert_eq!(first.checksum, second.checksum);
+    let content_path = server.media_storage().get_file_path(&first);
+    assert_eq!(server.media_storage().get_file_path(&second), content_path);
+    {
+        let mut db = server.db().lock().await;
+        db.insert_media_file(&first).unwrap();
+        db.insert_media_file(&second).unwrap();
+    }
+
+    let stats = server
+        .get_media_statistics(Request::new(GetMediaStatisticsRequest {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(stats.total_files, 2);
+    assert_eq!(stats.stored_files_count, 1);
+    assert_eq!(stats.logical_size_bytes, 2 * first.file_size_bytes as i64);
+    assert_eq!(stats.physical_size_bytes, first.file_size_bytes as i64);
+
+    // Both are orphaned, so cleanup would free the shared content once
+    let dry_run = server
+        .cleanup_orphaned_media(Request::new(CleanupOrphanedMediaRequest { dry_run: true }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(dry_run.bytes_freed, first.file_size_bytes as i64);
+
+    // Deleting one keeps the content the other still uses
+    let response = server
+        .delete_media(Request::new(DeleteMediaRequest {
+            media_file_id: first.id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success);
+    assert!(content_path.exists());
+    let remaining = fs::read(server.media_storage().get_file_path(&second)).unwrap();
+    assert_eq!(remaining.len() as u64, second.file_size_bytes);
+
+    let cleanup = server
+        .cleanup_orphaned_media(Request::new(CleanupOrphanedMediaRequest { dry_run: false }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(cleanup.deleted_file_ids, vec![second.id.clone()]);
+    assert_eq!(cleanup.bytes_freed, second.file_size_bytes as i64);
+    assert!(!content_path.exists());
+
+    // Content is only checked for when it's written, so an upload prepared while
+    // its content was still stored writes it again once it has been deleted
+    let data = png(64, 64);
+    let storage = server.media_storage();
+    let stored = storage.store_file(&data, "cover.png", MediaType::CoverArt).unwrap();
+    let prepared = storage.prepare_file(&data, "cover.png", MediaType::CoverArt).unwrap();
+    storage.delete_content(&stored.checksum).unwrap();
+    storage.write_prepared(&prepared).unwrap();
+    assert!(storage.get_file_path(&prepared.media_file).exists());
+}
+
+fn test_storage(strict_validation: bool) -> (tempfile::TempDir, MediaStorageManager) {
+    let storage_dir = tempfile::tempdir().unwrap();
+    let config = MediaConfig {
+        strict_validation,
+        ..MediaConfig::default()
+    };
+    let storage = MediaStorageManager::new(storage_dir.path().to_path_buf(), config).unwrap();
+    (storage_dir, storage)
+}
+
+/// An MP3 of silent MPEG-1 layer III frames at 128 kbps, 44.1 kHz
+fn mp3_frames(frames: usize) -> Vec<u8> {
+    let mut frame = vec![0u8; 417];
+    frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+    let mut mp3 = b"ID3\x04\0\0\0\0\0\x0A".to_vec();
+    mp3.extend_from_slice(&[0; 10]);
+    for _ in 0..frames {
+        mp3.extend_from_slice(&frame);
+    }
+    mp3
+}
+
+fn mp4_box(box_type: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut mp4 = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+    mp4.extend_from_slice(box_type);
+    mp4.extend_from_slice(body);
+    mp4
+}
+
+#[tokio::test]
+async fn test_inspect_reports_audio_details() {
+    setup("error");
+
+    let info = FileValidator::inspect(&sine_wav(44100 * 3, 44100), &MediaType::AudioFile).unwrap();
+    assert_eq!(info.format, Some(MediaFormat::Wav));
+    assert_eq!(info.sample_rate, Some(44100));
+    assert_eq!(info.channels, Some(1));
+    assert!((info.duration_seconds.unwrap() - 3.0).abs() < 1e-9);
+
+    let info = FileValidator::inspect(&mp3_frames(100), &MediaType::AudioFile).unwrap();
+    assert_eq!(info.format, Some(MediaFormat::Mp3));
+    assert_eq!(info.channels, Some(2));
+    assert!((info.duration_seconds.unwrap() - 100.0 * 1152.0 / 44100.0).abs() < 1e-9);
+
+    // Bytes between frames are skipped over
+    let mut padded = mp3_frames(50);
+    padded.extend_from_slice(b"junk between frames");
+    padded.extend_from_slice(&mp3_frames(50)[20..]);
+    let info = FileValidator::inspect(&padded, &MediaType::AudioFile).unwrap();
+    assert!((info.duration_seconds.unwrap() - 100.0 * 1152.0 / 44100.0).abs() < 1e-9);
+
+    // Free-format frames have no bitrate, each runs up to the next header
+    let mut free_format = Vec::new();
+    for _ in 0..10 {
+        free_format.extend_from_slice(&[0xFF, 0xFB, 0x00, 0x64]);
+        free_format.extend_from_slice(&[0; 600]);
+    }
+    let info = FileValidator::inspect(&free_format, &MediaType::AudioFile).unwrap();
+    assert!((info.duration_seconds.unwrap() - 10.0 * 1152.0 / 44100.0).abs() < 1e-9);
+
+    // STREAMINFO: 48 kHz stereo, 16 bits, 96000 samples, followed by a frame
+    let mut flac = b"fLaC".to_vec();
+    flac.extend_from_slice(&[0x80, 0, 0, 34]);
+    flac.extend_from_slice(&[0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+    let packed: u64 = (48000 << 44) | (1 << 41) | (15 << 36) | 96000;
+    flac.extend_from_slice(&packed.to_be_bytes());
+    flac.extend_from_slice(&[0; 16]);
+    flac.extend_from_slice(&[0xFF, 0xF8, 0x69, 0x08]);
+    let info = FileValidator::inspect(&flac, &MediaType::AudioFile).unwrap();
+    assert_eq!(info.format, Some(MediaFormat::Flac));
+    assert_eq!((info.sample_rate, info.channels), (Some(48000), Some(2)));
+    assert!((info.duration_seconds.unwrap() - 2.0).abs() < 1e-9);
+
+    // 3.5 seconds of stereo AAC at 44.1 kHz
+    let mut mvhd = vec![0u8; 12];
+    mvhd.extend_from_slice(&1000u32.to_be_bytes());
+    mvhd.extend_from_slice(&3500u32.to_be_bytes());
+    mvhd.extend_from_slice(&[0; 80]);
+    // Box header, reserved, data reference index, version, revision and vendor
+    let mut mp4a = vec![0u8; 24];
+    mp4a.extend_from_slice(&2u16.to_be_bytes());
+    mp4a.extend_from_slice(&16u16.to_be_bytes());
+    mp4a.extend_from_slice(&[0; 4]);
+    mp4a.extend_from_slice(&(44100u32 << 16).to_be_bytes());
+    let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+    stsd.extend(mp4_box(b"mp4a", &mp4a[8..]));
+    let stbl = mp4_box(b"stbl", &mp4_box(b"stsd", &stsd));
+    let trak = mp4_box(b"trak", &mp4_box(b"mdia", &mp4_box(b"minf", &stbl)));
+    let mut moov = mp4_box(b"mvhd", &mvhd);
+    moov.extend(trak);
+    let mut m4a = mp4_box(b"ftyp", b"M4A \0\0\0\0M4A isom");
+    m4a.extend(mp4_box(b"moov", &moov));
+    m4a.extend(mp4_box(b"mdat", &[0; 64]));
+    let info = FileValidator::inspect(&m4a, &MediaType::AudioFile).unwrap();
+    assert_eq!(info.format, Some(MediaFormat::M4a));
+    assert_eq!((info.sample_rate, info.channels), (Some(44100), Some(2)));
+    assert!((info.duration_seconds.unwrap() - 3.5).abs() < 1e-9);
+
+    // A cut-off WAV
+    let wav = sine_wav(44100, 44100);
+    assert!(FileValidator::inspect(&wav[..wav.len() - 1000], &MediaType::AudioFile).is_err());
+}
+
+#[tokio::test]
+async fn test_strict_validation() {
+    setup("error");
+    let (_storage_dir, storage) = test_storage(true);
+
+    let (_, info) = storage
+        .store_file_with_info(&sine_wav(44100, 44100), "bounce.wav", MediaType::AudioFile)
+        .unwrap();
+    assert_eq!(info.format, Some(MediaFormat::Wav));
+    assert!((info.duration_seconds.unwrap() - 1.0).abs() < 1e-9);
+
+    // Dimensions are those of the stored, upright image
+    let (_, info) = storage
+        .store_file_with_info(&rotated_jpeg(300, 200), "cover.jpeg", MediaType::CoverArt)
+        .unwrap();
+    assert_eq!(info.format, Some(MediaFormat::Jpeg));
+    assert_eq!((info.width, info.height), (Some(200), Some(300)));
+
+    // The image decoded while validating is kept for the thumbnails
+    let prepared = storage
+        .prepare_file(rotated_jpeg(300, 200), "cover.jpeg", MediaType::CoverArt)
+        .unwrap();
+    let image = prepared.image.as_ref().unwrap();
+    assert_eq!((image.width(), image.height()), (200, 300));
+    let variants = storage
+        .generate_thumbnails_from_image(&prepared.media_file, image)
+        .unwrap();
+    assert_eq!(variants.len(), ThumbnailSize::ALL.len());
+
+    // Frames that parse but don't decode
+    let noise: Vec<u8> = mp3_frames(40)
+        .into_iter()
+        .map(|byte| if byte == 0 { 0xFF } else { byte })
+        .collect();
+    let rejected = [
+        (png(32, 32), "cover.jpg", MediaType::CoverArt),
+        (sine_wav(44100, 44100), "cover.png", MediaType::CoverArt),
+        (png(32, 32), "bounce.wav", MediaType::AudioFile),
+        (b"not really audio at all".to_vec(), "broken.mp3", MediaType::AudioFile),
+        (noise, "noise.mp3", MediaType::AudioFile),
+    ];
+    for (data, filename, media_type) in rejected {
+        match storage.store_file(&data, filename, media_type) {
+            Err(MediaError::InvalidContent(_)) => {}
+            other => panic!("{} wasn't rejected: {:?}", filename, other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_permissive_validation() {
+    setup("error");
+    let (_storage_dir, storage) = test_storage(false);
+
+    let (_, info) = storage
+        .store_file_with_info(b"not really audio at all", "broken.mp3", MediaType::AudioFile)
+        .unwrap();
+    assert_eq!(info.format, None);
+    let (_, info) = storage
+        .store_file_with_info(&png(32, 16), "cover.jpg", MediaType::CoverArt)
+        .unwrap();
+    assert_eq!(info.format, Some(MediaFormat::Png));
+    assert_eq!(info.width, Some(32));
+
+    // Executable content is rejected in any mode
+    let mut with_zip = png(32, 32);
+    with_zip.extend_from_slice(b"PK\x05\x06");
+    with_zip.extend_from_slice(&[0; 18]);
+    // Markup in the comment of an INFO list
+    let script = b"<SCRIPT>alert('hi')</SCRIPT>";
+    let mut info_list = b"INFOICMT".to_vec();
+    info_list.extend_from_slice(&(script.len() as u32).to_le_bytes());
+    info_list.extend_from_slice(script);
+    let mut with_script = sine_wav(4410, 44100);
+    with_script.extend_from_slice(b"LIST");
+    with_script.extend_from_slice(&(info_list.len() as u32).to_le_bytes());
+    with_script.extend_from_slice(&info_list);
+    let riff_size = (with_script.len() - 8) as u32;
+    with_script[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    let mut with_exe = sine_wav(4410, 44100);
+    let mut exe = vec![0u8; 0x84];
+    exe[..2].copy_from_slice(b"MZ");
+    exe[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+    exe[0x80..].copy_from_slice(b"PE\0\0");
+    with_exe.splice(100..100 + exe.len(), exe);
+    let elf = [b"\x7FELF\x02\x01\x01".as_slice(), &[0; 64]].concat();
+
+    let rejected = [
+        (with_zip, "cover.png", MediaType::CoverArt),
+        (with_script, "bounce.wav", MediaType::AudioFile),
+        (with_exe, "bounce.wav", MediaType::AudioFile),
+        (elf, "bounce.mp3", MediaType::AudioFile),
+    ];
+    for (data, filename, media_type) in rejected {
+        match storage.store_file(&data, filename, media_type) {
+            Err(MediaError::InvalidContent(_)) => {}
+            other => panic!("{} wasn't rejected: {:?}", filename, other),
+        }
+    }
+
+    // Samples that happen to spell out markup are audio, not a page
+    let mut markup_samples = sine_wav(4410, 44100);
+    let middle = markup_samples.len() / 2;
+    markup_samples.splice(middle..middle + 5, b"<html".iter().copied());
+    assert!(storage
+        .store_file(&markup_samples, "bounce.wav", MediaType::AudioFile)
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_project_audio_versions_and_comparison() {
+    setup("error");
+    let server = create_test_server().await;
+    let project_id = create_test_project_in_db(server.db()).await;
+
+    let mut ids = Vec::new();
+    for (seconds, filename) in [(4, "mix-v1.wav"), (3, "mix-v2.wav"), (5, "master.wav")] {
+        let media_file = server
+            .media_storage()
+            .store_file(&sine_wav(8000 * seconds, 8000), filename, MediaType::AudioFile)
+            .unwrap();
+        server.db().lock().await.insert_media_file(&media_file).unwrap();
+        ids.push(media_file.id);
+    }
+
+    // Setting an audio file adds a version and makes it primary
+    for (id, label) in [(&ids[0], "Mixdown"), (&ids[1], "Mixdown"), (&ids[2], " Master ")] {
+        let response = server
+            .set_project_audio_file(Request::new(SetProjectAudioFileRequest {
+                project_id: project_id.clone(),
+                media_file_id: id.clone(),
+                label: Some(label.to_string()),
+                version: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.success);
+    }
+
+    let project = server
+        .get_project(Request::new(GetProjectRequest {
+            project_id: project_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .project
+        .unwrap();
+    assert_eq!(project.audio_files.len(), 3);
+    assert_eq!(project.audio_file_id.as_ref(), Some(&ids[2]));
+    let primary = &project.audio_files[0];
+    assert_eq!((primary.label.as_str(), primary.version), ("Master", 1));
+    assert!(primary.is_primary);
+    assert_eq!(primary.media_file.as_ref().unwrap().original_filename, "master.wav");
+    let second_mix = project
+        .audio_files
+        .iter()
+        .find(|audio| audio.media_file_id == ids[1])
+        .unwrap();
+    assert_eq!((second_mix.label.as_str(), second_mix.version), ("Mixdown", 2));
+
+    // Another version can take over as primary
+    let updated = server
+        .update_project_audio_file(Request::new(UpdateProjectAudioFileRequest {
+            project_id: project_id.clone(),
+            media_file_id: ids[1].clone(),
+            label: None,
+            version: None,
+            is_primary: Some(true),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .audio_file
+        .unwrap();
+    assert!(updated.is_primary);
+    let audio_files = server
+        .get_project_audio_files(Request::new(GetProjectAudioFilesRequest {
+            project_id: project_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .audio_files;
+    assert_eq!(audio_files[0].media_file_id, ids[1]);
+    assert_eq!(audio_files.iter().filter(|audio| audio.is_primary).count(), 1);
+
+    let unset_primary = server
+        .update_project_audio_file(Request::new(UpdateProjectAudioFileRequest {
+            project_id: project_id.clone(),
+            media_file_id: ids[1].clone(),
+            label: None,
+            version: None,
+            is_primary: Some(false),
+        }))
+        .await;
+    assert_eq!(unset_primary.unwrap_err().code(), Code::InvalidArgument);
+
+    // A/B comparison of two versions
+    let comparison = server
+        .compare_project_audio_files(Request::new(CompareProjectAudioFilesRequest {
+            project_id: project_id.clone(),
+            a_media_file_id: ids[0].clone(),
+            b_media_file_id: ids[2].clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(comparison.a.unwrap().media_file_id, ids[0]);
+    assert_eq!(comparison.b.unwrap().label, "Master");
+    let a_info = comparison.a_format_info.unwrap();
+    assert_eq!(a_info.format, "wav");
+    assert_eq!(a_info.sample_rate, Some(8000));
+    assert!((comparison.duration_difference_seconds.unwrap() - 1.0).abs() < 0.01);
+
+    let same = server
+        .compare_project_audio_files(Request::new(CompareProjectAudioFilesRequest {
+            project_id: project_id.clone(),
+            a_media_file_id: ids[0].clone(),
+            b_media_file_id: ids[0].clone(),
+        }))
+        .await;
+    assert_eq!(same.unwrap_err().code(), Code::InvalidArgument);
+
+    // Removing one version keeps the others
+    let response = server
+        .remove_project_audio_file(Request::new(RemoveProjectAudioFileRequest {
+            project_id: project_id.clone(),
+            media_file_id: Some(ids[0].clone()),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success);
+    let not_attached = server
+        .compare_project_audio_files(Request::new(CompareProjectAudioFilesRequest {
+            project_id: project_id.clone(),
+            a_media_file_id: ids[0].clone(),
+            b_media_file_id: ids[2].clone(),
+        }))
+        .await;
+    assert_eq!(not_attached.unwrap_err().code(), Code::NotFound);
+    let audio_files = server
+        .get_project_audio_files(Request::new(GetProjectAudioFilesRequest {
+            project_id: project_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .audio_files;
+    assert_eq!(audio_files.len(), 2);
+}
+
+#[tokio::test]
+async fn test_download_media_range() {
+    setup("error");
+    let server = create_test_server().await;
+    let media_file = server
+        .media_storage()
+        .store_file(&sine_wav(100_000, 8000), "long-mix.wav", MediaType::AudioFile)
+        .unwrap();
+    server.db().lock().await.insert_media_file(&media_file).unwrap();
+    let stored = fs::read(server.media_storage().get_file_path(&media_file)).unwrap();
+
+    let download = |offset, length, chunk_size| {
+        server.download_media(Request::new(DownloadMediaRequest {
+            media_file_id: media_file.id.clone(),
+            size: None,
+            offset,
+            length,
+            chunk_size,
+        }))
+    };
+
+    // A range comes in chunks that say where they belong
+    let mut stream = download(Some(1000), Some(5000), Some(2048)).await.unwrap().into_inner();
+    let metadata = stream.next().await.unwrap().unwrap();
+    assert_eq!(metadata.offset, 1000);
+    assert!(matches!(metadata.data, Some(download_media_response::Data::Metadata(_))));
+    let mut offsets = Vec::new();
+    let mut received = Vec::new();
+    while let Some(response) = stream.next().await {
+        let response = response.unwrap();
+        offsets.push(response.offset);
+        match response.data {
+            Some(download_media_response::Data::Chunk(chunk)) => received.extend(chunk),
+            _ => panic!("Response should be chunk data"),
+        }
+    }
+    assert_eq!(offsets, vec![1000, 3048, 5096]);
+    assert_eq!(received, stored[1000..6000]);
+
+    // Far more chunks than the stream buffers, and a length past the end
+    let mut stream = download(Some(150_000), Some(1_000_000), Some(1024))
+        .await
+        .unwrap()
+        .into_inner();
+    stream.next().await.unwrap().unwrap();
+    let mut received = Vec::new();
+    while let Some(response) = stream.next().await {
+        if let Some(download_media_response::Data::Chunk(chunk)) = response.unwrap().data {
+            received.extend(chunk);
+        }
+    }
+    assert_eq!(received, stored[150_000..]);
+
+    let past_end = download(Some(stored.len() as u64 + 1), None, None).await;
+    assert_eq!(past_end.unwrap_err().code(), Code::OutOfRange);
+    let tiny_chunks = download(None, None, Some(16)).await;
+    assert_eq!(tiny_chunks.unwrap_err().code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_resumable_upload_sessions() {
+    setup("error");
+    // Cleanup in other tests sweeps the shared media directory
+    let (_storage_dir, storage) = test_storage(true);
+    let server = seula::grpc::StudioProjectManagerServer::new_for_test(
+        LiveSetDatabase::new(std::path::PathBuf::from(":memory:")).unwrap(),
+        storage,
+    );
+    let audio = sine_wav(20_000, 8000);
+    let total = audio.len() as u64;
+
+    let start = |media_type: &str, filename: &str, total_size_bytes| {
+        server.start_upload(Request::new(StartUploadRequest {
+            media_type: media_type.to_string(),
+            filename: filename.to_string(),
+            total_size_bytes,
+        }))
+    };
+    let upload_id = start("audio_file", "mix.wav", total)
+        .await
+        .unwrap()
+        .into_inner()
+        .upload_id;
+    for (media_type, filename, size) in [
+        ("audio_file", "mix.exe", total),
+        ("audio_file", "mix.wav", 60 * 1024 * 1024),
+        ("audio_file", "mix.wav", 0),
+        ("stems", "mix.wav", total),
+    ] {
+        let result = start(media_type, filename, size).await;
+        assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+    }
+
+    let status = |upload_id: String| {
+        server.get_upload_status(Request::new(GetUploadStatusRequest { upload_id }))
+    };
+    let storage = server.media_storage();
+    assert_eq!(storage.write_upload_chunk(&upload_id, 0, &audio[..1000], total).unwrap(), 1000);
+    let progress = status(upload_id.clone()).await.unwrap().into_inner();
+    assert_eq!(progress.bytes_received, 1000);
+    assert_eq!(progress.total_size_bytes, total);
+    assert_eq!(progress.filename, "mix.wav");
+    assert_eq!(progress.media_type, "audio_file");
+
+    // Chunks can't leave a gap or go past the announced size, but can be resent
+    assert!(storage.write_upload_chunk(&upload_id, 2000, &audio[2000..3000], total).is_err());
+    assert_eq!(storage.write_upload_chunk(&upload_id, 1000, &audio[1000..], total).unwrap(), total);
+    assert!(storage
+        .write_upload_chunk(&upload_id, total, b"extra", total)
+        .is_err());
+    assert_eq!(storage.write_upload_chunk(&upload_id, 500, &audio[500..], total).unwrap(), total);
+    assert_eq!(storage.read_upload(&upload_id).unwrap(), audio);
+    assert!(storage.write_upload_chunk("../escape", 0, b"data", total).is_err());
+
+    server
+        .cancel_upload(Request::new(CancelUploadRequest {
+            upload_id: upload_id.clone(),
+        }))
+        .await
+        .unwrap();
+    assert_eq!(status(upload_id.clone()).await.unwrap_err().code(), Code::NotFound);
+    assert_eq!(storage.upload_progress(&upload_id).unwrap(), 0);
+
+    // Partial files of sessions that are gone are swept up
+    let abandoned = start("cover_art", "cover.png", 4096)
+        .await
+        .unwrap()
+        .into_inner()
+        .upload_id;
+    storage.write_upload_chunk(&abandoned, 0, &[0u8; 1024], 4096).unwrap();
+    let expired = server
+        .db()
+        .lock()
+        .await
+        .expire_upload_sessions(chrono::Utc::now() + chrono::Duration::minutes(1))
+        .unwrap();
+    assert_eq!(expired, vec![abandoned.clone()]);
+    let cleanup = server
+        .cleanup_orphaned_media(Request::new(CleanupOrphanedMediaRequest { dry_run: false }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(cleanup.uploads_cleaned, 1);
+    assert_eq!(cleanup.bytes_freed, 1024);
+    assert_eq!(storage.upload_progress(&abandoned).unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_loudness_analysis_of_project_audio() {
+    setup("error");
+    let (_storage_dir, storage) = test_storage(true);
+    let server = seula::grpc::StudioProjectManagerServer::new_for_test(
+        LiveSetDatabase::new(std::path::PathBuf::from(":memory:")).unwrap(),
+        storage,
+    );
+    let project_id = create_test_project_in_db(server.db()).await;
+
+    // A stereo 997 Hz tone peaking at -20 dBFS reads -20 LUFS, its RMS 3 dB lower
+    let sample_rate = 48000;
+    let tone = pcm_wav(sample_rate * 10, sample_rate, 2, |i, _| {
+        let phase = i as f32 / sample_rate as f32 * 997.0 * std::f32::consts::TAU;
+        phase.sin() * 0.1
+    });
+    let media_file = server
+        .media_storage()
+        .store_file(&tone, "master.wav", MediaType::AudioFile)
+        .unwrap();
+    {
+        let mut db = server.db().lock().await;
+        db.insert_media_file(&media_file).unwrap();
+        db.attach_project_audio_file(&project_id, &media_file.id, Some("Master"), None, true)
+            .unwrap();
+    }
+
+    let response = server
+        .analyze_audio_file(Request::new(AnalyzeAudioFileRequest {
+            media_file_id: None,
+            project_id: Some(project_id.clone()),
+            reanalyze: false,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.media_file_id, media_file.id);
+    let analysis = response.analysis.unwrap();
+    assert!((analysis.integrated_lufs.unwrap() + 20.0).abs() < 0.1);
+    assert!((analysis.true_peak_dbtp.unwrap() + 20.0).abs() < 0.1);
+    assert!((analysis.rms_dbfs.unwrap() + 23.01).abs() < 0.1);
+    assert!(analysis.loudness_range_lu.unwrap() < 0.1);
+
+    // The analysis is stored with the project's audio
+    let project = server
+        .get_project(Request::new(GetProjectRequest {
+            project_id: project_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .project
+        .unwrap();
+    assert_eq!(project.audio_files[0].analysis.as_ref(), Some(&analysis));
+
+    {
+        let mut db = server.db().lock().await;
+        let search = |db: &mut LiveSetDatabase, query: &str| {
+            db.search_fts(&SearchQuery::parse(query)).unwrap().len()
+        };
+        assert_eq!(search(&mut db, "lufs:<-15"), 1);
+        assert_eq!(search(&mut db, "lufs:>=-15"), 0);
+        assert_eq!(search(&mut db, "lufs:-20"), 1);
+        assert_eq!(search(&mut db, "peak:>-1"), 0);
+        assert_eq!(search(&mut db, "lra:loud"), 0);
+
+        let collection_id = db.create_collection("Album", None, None).unwrap();
+        db.add_project_to_collection(&collection_id, &project_id).unwrap();
+        let stats = db.get_collection_detailed_statistics(&collection_id).unwrap();
+        assert_eq!(stats.analyzed_project_count, 1);
+        assert!((stats.average_lufs.unwrap() + 20.0).abs() < 0.1);
+        assert_eq!(stats.min_lufs, stats.max_lufs);
+        assert_eq!(stats.max_true_peak_dbtp, analysis.true_peak_dbtp);
+    }
+
+    // A track that gets 10 dB louder halfway through has a loudness range of 10 LU
+    let dynamic = pcm_wav(sample_rate * 20, sample_rate, 2, |i, _| {
+        let phase = i as f32 / sample_rate as f32 * 997.0 * std::f32::consts::TAU;
+        let amplitude = if i < sample_rate * 10 { 0.1 / 10f32.sqrt() } else { 0.1 };
+        phase.sin() * amplitude
+    });
+    let media_file = server
+        .media_storage()
+        .store_file(&dynamic, "dynamic.wav", MediaType::AudioFile)
+        .unwrap();
+    server.db().lock().await.insert_media_file(&media_file).unwrap();
+    let analysis = server
+        .analyze_audio_file(Request::new(AnalyzeAudioFileRequest {
+            media_file_id: Some(media_file.id),
+            project_id: None,
+            reanalyze: true,
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .analysis
+        .unwrap();
+    assert!((analysis.loudness_range_lu.unwrap() - 10.0).abs() < 0.5);
+}
+
+#[tokio::test]
+async fn test_tempo_and_key_estimates() {
+    setup("error");
+    let (_storage_dir, storage) = test_storage(true);
+
+    // A C major chord struck on every beat at 120 BPM
+    let sample_rate = 22050;
+    let beat = sample_rate / 2;
+    let chord = [261.63, 329.63, 392.0];
+    let audio = pcm_wav(sample_rate * 12, sample_rate, 1, |i, _| {
+        let t = i as f32 / sample_rate as f32;
+        let since_beat = (i % beat) as f32 / sample_rate as f32;
+        let envelope = (-since_beat * 12.0).exp();
+        let tone: f32 = chord
+            .iter()
+            .map(|frequency| (t * frequency * std::f32::consts::TAU).sin())
+            .sum();
+        tone * envelope * 0.25
+    });
+    let media_file = storage
+        .store_file(&audio, "chords.wav", MediaType::AudioFile)
+        .unwrap();
+
+    let analysis = storage.analyze_audio(&media_file).unwrap();
+    let bpm = analysis.bpm.unwrap();
+    assert!((bpm - 120.0).abs() < 1.0, "estimated {} BPM", bpm);
+    assert_eq!(analysis.key.unwrap().to_string(), "C Major");
+
+    let silence = pcm_wav(sample_rate * 5, sample_rate, 1, |_, _| 0.0);
+    let media_file = storage
+        .store_file(&silence, "silence.wav", MediaType::AudioFile)
+        .unwrap();
+    let analysis = storage.analyze_audio(&media_file).unwrap();
+    assert_eq!(analysis.integrated_lufs, None);
+    assert_eq!(analysis.bpm, None);
+    assert_eq!(analysis.key, None);
+}
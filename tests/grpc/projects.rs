@@ -675,6 +675,9 @@ async fn test_get_projects_with_enhanced_filtering() {
         modified_after: None,
         modified_before: None,
         has_audio_file: None,
+        min_rating: None,
+        is_favorite: None,
+        set_kind: None,
     };
 
     let response = server
@@ -827,3 +830,372 @@ async fn test_rescan_project_invalid_id() {
     assert_eq!(status.code(), tonic::Code::Internal);
     assert!(status.message().contains("Project not found"));
 }
+
+#[tokio::test]
+async fn test_project_workflow_status() {
+    use seula::grpc::search::search_service_server::SearchService;
+
+    setup("error");
+
+    let server = create_test_server().await;
+    let db = server.db();
+    let project_id = create_test_project_in_db(db).await;
+
+    // Default pipeline is seeded on startup
+    let pipeline = server
+        .get_status_pipeline(Request::new(GetStatusPipelineRequest {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        pipeline.statuses,
+        vec!["idea", "arrangement", "mixing", "mastering", "released"]
+    );
+
+    // New projects have no status
+    let project = server
+        .get_project(Request::new(GetProjectRequest {
+            project_id: project_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .project
+        .unwrap();
+    assert!(project.status.is_none());
+
+    for status in ["idea", "mixing"] {
+        let project = server
+            .set_project_status(Request::new(SetProjectStatusRequest {
+                project_id: project_id.clone(),
+                status: Some(status.to_string()),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .project
+            .unwrap();
+        assert_eq!(project.status.as_deref(), Some(status));
+        assert!(project.status_changed_at.is_some());
+    }
+
+    // Unknown stages are rejected
+    let result = server
+        .set_project_status(Request::new(SetProjectStatusRequest {
+            project_id: project_id.clone(),
+            status: Some("forgotten".to_string()),
+        }))
+        .await;
+    assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+
+    let history = server
+        .get_project_status_history(Request::new(GetProjectStatusHistoryRequest {
+            project_id: project_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .transitions;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].from_status, None);
+    assert_eq!(history[0].to_status.as_deref(), Some("idea"));
+    assert_eq!(history[1].from_status.as_deref(), Some("idea"));
+    assert_eq!(history[1].to_status.as_deref(), Some("mixing"));
+
+    // status: search operator
+    for (query, expected) in [("status:mixing", true), ("status:idea", false)] {
+        let response = server
+            .search(Request::new(SearchRequest {
+                query: query.to_string(),
+                limit: None,
+                offset: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            response.projects.iter().any(|p| p.id == project_id),
+            expected,
+            "unexpected result for {}",
+            query
+        );
+    }
+
+    // Time spent per stage
+    let stats = server
+        .get_project_statistics(Request::new(GetProjectStatisticsRequest::default()))
+        .await
+        .unwrap()
+        .into_inner();
+    let stage = |name: &str| {
+        stats
+            .status_stages
+            .iter()
+            .find(|s| s.status == name)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(stats.status_stages.len(), 5);
+    assert_eq!(stage("idea").times_entered, 1);
+    assert_eq!(stage("idea").current_projects, 0);
+    assert_eq!(stage("mixing").times_entered, 1);
+    assert_eq!(stage("mixing").current_projects, 1);
+    assert_eq!(stage("released").times_entered, 0);
+
+    // Collection status breakdown
+    let collection_id = server
+        .create_collection(Request::new(CreateCollectionRequest {
+            name: "Album".to_string(),
+            description: None,
+            notes: None,
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .collection
+        .unwrap()
+        .id;
+    let other_project_id = create_test_project_in_db(db).await;
+    for id in [&project_id, &other_project_id] {
+        server
+            .add_project_to_collection(Request::new(AddProjectToCollectionRequest {
+                collection_id: collection_id.clone(),
+                project_id: id.clone(),
+                position: None,
+            }))
+            .await
+            .unwrap();
+    }
+    let collection_stats = server
+        .get_collection_statistics(Request::new(GetCollectionStatisticsRequest {
+            collection_id: collection_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    let breakdown: Vec<(&str, i32)> = collection_stats
+        .status_breakdown
+        .iter()
+        .map(|s| (s.status.as_str(), s.count))
+        .collect();
+    assert_eq!(
+        breakdown,
+        vec![
+            ("idea", 0),
+            ("arrangement", 0),
+            ("mixing", 1),
+            ("mastering", 0),
+            ("released", 0)
+        ]
+    );
+    assert_eq!(collection_stats.projects_without_status, 1);
+
+    // Stages still in use cannot be removed from the pipeline
+    let result = server
+        .update_status_pipeline(Request::new(UpdateStatusPipelineRequest {
+            statuses: vec!["idea".to_string(), "released".to_string()],
+        }))
+        .await;
+    assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+
+    let pipeline = server
+        .update_status_pipeline(Request::new(UpdateStatusPipelineRequest {
+            statuses: vec![
+                "Demo".to_string(),
+                "mixing".to_string(),
+                "released".to_string(),
+            ],
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(pipeline.statuses, vec!["demo", "mixing", "released"]);
+}
+
+#[tokio::test]
+async fn test_project_ratings_favorites_and_opens() {
+    use seula::grpc::search::search_service_server::SearchService;
+
+    setup("error");
+
+    let server = create_test_server().await;
+    let db = server.db();
+    let gem_id = create_test_project_in_db(db).await;
+    let sketch_id = create_test_project_in_db(db).await;
+
+    let project = server
+        .set_project_rating(Request::new(SetProjectRatingRequest {
+            project_id: gem_id.clone(),
+            rating: Some(5),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .project
+        .unwrap();
+    assert_eq!(project.rating, Some(5));
+    assert!(!project.is_favorite);
+    assert_eq!(project.open_count, 0);
+
+    server
+        .set_project_rating(Request::new(SetProjectRatingRequest {
+            project_id: sketch_id.clone(),
+            rating: Some(2),
+        }))
+        .await
+        .unwrap();
+
+    let result = server
+        .set_project_rating(Request::new(SetProjectRatingRequest {
+            project_id: gem_id.clone(),
+            rating: Some(6),
+        }))
+        .await;
+    assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+
+    let project = server
+        .set_project_favorite(Request::new(SetProjectFavoriteRequest {
+            project_id: gem_id.clone(),
+            is_favorite: true,
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .project
+        .unwrap();
+    assert!(project.is_favorite);
+
+    // Opens within the same session are only counted once
+    let opened_at = chrono::Utc::now().timestamp() - 3600;
+    for offset in [0, 60, 3600] {
+        server
+            .record_project_opened(Request::new(RecordProjectOpenedRequest {
+                project_id: sketch_id.clone(),
+                opened_at: Some(opened_at + offset),
+            }))
+            .await
+            .unwrap();
+    }
+    let project = server
+        .get_project(Request::new(GetProjectRequest {
+            project_id: sketch_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .project
+        .unwrap();
+    assert_eq!(project.open_count, 2);
+    assert_eq!(project.last_opened_at, Some(opened_at + 3600));
+
+    let result = server
+        .record_project_opened(Request::new(RecordProjectOpenedRequest {
+            project_id: Uuid::new_v4().to_string(),
+            opened_at: None,
+        }))
+        .await;
+    assert_eq!(result.unwrap_err().code(), Code::NotFound);
+
+    // Filtering and sorting
+    let response = server
+        .get_projects(Request::new(GetProjectsRequest {
+            sort_by: Some("rating".to_string()),
+            sort_desc: Some(true),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    let ids: Vec<_> = response.projects.iter().map(|p| p.id.clone()).collect();
+    assert_eq!(ids, vec![gem_id.clone(), sketch_id.clone()]);
+
+    let response = server
+        .get_projects(Request::new(GetProjectsRequest {
+            min_rating: Some(3),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.total_count, 1);
+    assert_eq!(response.projects[0].id, gem_id);
+
+    let response = server
+        .get_projects(Request::new(GetProjectsRequest {
+            is_favorite: Some(false),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.total_count, 1);
+    assert_eq!(response.projects[0].id, sketch_id);
+
+    // Search operators
+    for (query, expected) in [
+        ("rating:5", vec![&gem_id]),
+        ("rating:2+", vec![&gem_id, &sketch_id]),
+        ("fav:true", vec![&gem_id]),
+        ("opened:never", vec![&gem_id]),
+        ("opened:7d", vec![&sketch_id]),
+        ("opened:9999999999999999m", vec![]),
+        ("rating:4+ fav:no", vec![]),
+    ] {
+        let response = server
+            .search(Request::new(SearchRequest {
+                query: query.to_string(),
+                limit: None,
+                offset: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let mut ids: Vec<_> = response.projects.iter().map(|p| &p.id).collect();
+        ids.sort();
+        let mut expected = expected;
+        expected.sort();
+        assert_eq!(ids, expected, "unexpected results for {}", query);
+    }
+
+    // Forgotten gems: nothing qualifies until the gem hasn't been touched for months
+    let gems = server
+        .get_forgotten_gems(Request::new(GetForgottenGemsRequest::default()))
+        .await
+        .unwrap()
+        .into_inner()
+        .projects;
+    assert!(gems.is_empty());
+
+    let a_year_ago = chrono::Utc::now().timestamp() - 365 * 86400;
+    for id in [&gem_id, &sketch_id] {
+        db.lock()
+            .await
+            .conn
+            .execute(
+                "UPDATE projects SET modified_at = ?, last_opened_at = NULL WHERE id = ?",
+                rusqlite::params![a_year_ago, id],
+            )
+            .unwrap();
+    }
+    let gems = server
+        .get_forgotten_gems(Request::new(GetForgottenGemsRequest::default()))
+        .await
+        .unwrap()
+        .into_inner()
+        .projects;
+    assert_eq!(gems.len(), 1);
+    assert_eq!(gems[0].id, gem_id);
+
+    let gems = server
+        .get_forgotten_gems(Request::new(GetForgottenGemsRequest {
+            min_rating: Some(2),
+            months: Some(6),
+            limit: None,
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .projects;
+    assert_eq!(gems.len(), 2);
+    assert_eq!(gems[0].id, gem_id);
+}
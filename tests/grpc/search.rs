@@ -530,6 +530,7 @@ async fn test_search_tag_operator() {
     // Create a tag and apply it to the project
     let tag_req = CreateTagRequest {
         name: "Electronic".to_string(),
+        ..Default::default()
     };
     let tag_resp = server.create_tag(Request::new(tag_req)).await.unwrap();
     let tag = tag_resp.into_inner().tag.unwrap();
@@ -561,6 +562,70 @@ async fn test_search_tag_operator() {
     }
 }
 
+#[tokio::test]
+async fn test_search_tag_operator_includes_child_tags() {
+    setup("error");
+
+    let server = create_test_server().await;
+    let db = server.db();
+
+    let project_id = create_test_project_in_db(db).await;
+
+    let tag_req = CreateTagRequest {
+        name: "genre/techno".to_string(),
+        ..Default::default()
+    };
+    let tag = server.create_tag(Request::new(tag_req)).await.unwrap().into_inner().tag.unwrap();
+    server
+        .tag_project(Request::new(TagProjectRequest {
+            project_id: project_id.clone(),
+            tag_id: tag.id.clone(),
+        }))
+        .await
+        .unwrap();
+
+    for query in ["tag:genre", "tag:genre/techno"] {
+        let request = SearchRequest {
+            query: query.to_string(),
+            limit: None,
+            offset: None,
+        };
+        let response = server.search(Request::new(request)).await.unwrap().into_inner();
+        assert!(
+            response.projects.iter().any(|p| p.id == project_id),
+            "{} should match a project tagged genre/techno",
+            query
+        );
+    }
+
+    // A tag only matches its own subtree, not other tags sharing a word
+    let request = SearchRequest {
+        query: "tag:techno".to_string(),
+        limit: None,
+        offset: None,
+    };
+    let response = server.search(Request::new(request)).await.unwrap().into_inner();
+    assert!(!response.projects.iter().any(|p| p.id == project_id));
+
+    // Renaming the parent is reflected in search
+    let parent_id = tag.parent_id.unwrap();
+    server
+        .update_tag(Request::new(UpdateTagRequest {
+            tag_id: parent_id,
+            name: Some("style".to_string()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    let request = SearchRequest {
+        query: "tag:style".to_string(),
+        limit: None,
+        offset: None,
+    };
+    let response = server.search(Request::new(request)).await.unwrap().into_inner();
+    assert!(response.projects.iter().any(|p| p.id == project_id));
+}
+
 #[tokio::test]
 async fn test_search_path_operator() {
     setup("error");
@@ -16,8 +16,11 @@ pub async fn create_test_server() -> StudioProjectManagerServer {
     // Create a test media config (use defaults)
     let media_config = MediaConfig::default();
 
-    // Create temporary directory for test media storage
-    let temp_dir = std::env::temp_dir().join("seula_test_media");
+    // Create a temporary directory for test media storage. Each server gets its own,
+    // since stored files are shared by every media file with the same content.
+    let temp_dir = std::env::temp_dir()
+        .join("seula_test_media")
+        .join(Uuid::new_v4().to_string());
     let media_storage = MediaStorageManager::new(temp_dir, media_config)
         .expect("Failed to create test media storage");
 
@@ -165,6 +168,7 @@ mod tests {
             progress: 0.5,
             message: "Test progress".to_string(),
             status: ScanStatus::ScanParsing as i32,
+            ..Default::default()
         };
 
         // Set the progress using the system handler
@@ -1,5 +1,7 @@
 //! Statistics-related gRPC tests
 
+use chrono::Datelike;
+use seula::grpc::common::ExportFormat;
 use seula::grpc::system::system_service_server::SystemService;
 
 use super::*;
@@ -163,3 +165,125 @@ async fn test_individual_statistics_functions() {
         }
     }
 }
+
+#[tokio::test]
+async fn test_export_statistics_formats() {
+    setup("error");
+    let server = create_test_server().await;
+    create_test_project_in_db(server.db()).await;
+
+    let export = |format: ExportFormat| {
+        server.export_statistics(Request::new(ExportStatisticsRequest {
+            format: format as i32,
+            filters: None,
+        }))
+    };
+
+    let json = export(ExportFormat::ExportJson).await.unwrap().into_inner();
+    assert!(json.filename.ends_with(".json"));
+    let value: serde_json::Value = serde_json::from_slice(&json.data).unwrap();
+    assert_eq!(value["total_projects"], 1);
+    assert_eq!(value["top_plugins"][0]["name"], "Serum");
+
+    let markdown = export(ExportFormat::ExportMarkdown).await.unwrap().into_inner();
+    assert!(markdown.filename.ends_with(".md"));
+    let markdown = String::from_utf8(markdown.data).unwrap();
+    assert!(markdown.starts_with("# Statistics Report"));
+    assert!(markdown.contains("| Projects | 1 |"));
+    assert!(markdown.contains("| 1 | Serum |"));
+
+    // The page stands alone, with its charts drawn inline
+    let html = export(ExportFormat::ExportHtml).await.unwrap().into_inner();
+    assert!(html.filename.ends_with(".html"));
+    let html = String::from_utf8(html.data).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<svg"));
+    assert!(html.contains(">Serum</text>"));
+    assert!(!html.contains("<script") && !html.contains("src=\"http"));
+
+    let csv = export(ExportFormat::ExportCsv).await.unwrap().into_inner();
+    assert!(csv.filename.ends_with(".csv"));
+    assert!(String::from_utf8(csv.data).unwrap().contains("Total Projects,1"));
+}
+
+#[tokio::test]
+async fn test_get_year_in_review() {
+    setup("error");
+    let server = create_test_server().await;
+    let project_id = create_test_project_in_db(server.db()).await;
+    // Reaching the last stage of the pipeline finishes a project
+    server
+        .db()
+        .lock()
+        .await
+        .set_project_status(&project_id, Some("released"))
+        .unwrap();
+
+    let review = server
+        .get_year_in_review(Request::new(GetYearInReviewRequest { year: None }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(review.year, chrono::Local::now().year());
+    assert_eq!(review.projects_started, 1);
+    assert_eq!(review.projects_finished, 1);
+    assert_eq!(review.top_plugins[0].name, "Serum");
+    assert_eq!(review.new_plugins[0].name, "Serum");
+    assert_eq!(review.saves_per_month.len(), 12);
+    assert_eq!(review.saves_per_month.iter().sum::<i32>(), 1);
+    assert_eq!(review.busiest_month, Some(chrono::Local::now().month() as i32));
+    assert_eq!(review.favorite_key.as_deref(), Some("C Major"));
+    assert_eq!(review.favorite_tempo_min, Some(140.0));
+    assert_eq!(review.active_days, 1);
+    assert_eq!(review.longest_streak_days, 1);
+
+    let empty = server
+        .get_year_in_review(Request::new(GetYearInReviewRequest { year: Some(1999) }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(empty.projects_worked_on, 0);
+    assert!(empty.longest_project.is_none());
+}
+
+#[tokio::test]
+async fn test_get_activity_analytics() {
+    setup("error");
+    let server = create_test_server().await;
+    let project_id = create_test_project_in_db(server.db()).await;
+
+    let activity = server
+        .get_activity_analytics(Request::new(GetActivityAnalyticsRequest {
+            days: Some(7),
+            session_gap_minutes: None,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(activity.days.len(), 7);
+    // Only the latest save is logged when a project is indexed
+    assert_eq!(activity.total_saves, 1);
+    assert_eq!(activity.days[6].saves, activity.total_saves);
+    assert_eq!(activity.total_sessions, 1);
+    assert!(activity.average_session_minutes >= 15.0);
+    assert_eq!(activity.projects.len(), 1);
+    assert_eq!(activity.projects[0].project_id, project_id);
+    assert!(activity.median_project_days.unwrap() < 0.01);
+
+    for request in [
+        GetActivityAnalyticsRequest {
+            days: Some(0),
+            session_gap_minutes: None,
+        },
+        GetActivityAnalyticsRequest {
+            days: None,
+            session_gap_minutes: Some(0),
+        },
+    ] {
+        let status = server
+            .get_activity_analytics(Request::new(request))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+}
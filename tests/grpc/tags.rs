@@ -11,7 +11,7 @@ async fn test_get_tags_empty() {
 
     let server = create_test_server().await;
 
-    let request = GetTagsRequest {};
+    let request = GetTagsRequest::default();
     let response = server.get_tags(Request::new(request)).await.unwrap();
     let tags = response.into_inner().tags;
 
@@ -26,6 +26,7 @@ async fn test_create_tag() {
 
     let request = CreateTagRequest {
         name: "Electronic".to_string(),
+        ..Default::default()
     };
     let response = server.create_tag(Request::new(request)).await.unwrap();
     let tag = response.into_inner().tag.unwrap();
@@ -48,24 +49,27 @@ async fn test_get_tags_with_data() {
     // Create multiple tags
     let tag1_req = CreateTagRequest {
         name: "Rock".to_string(),
+        ..Default::default()
     };
     let tag1_resp = server.create_tag(Request::new(tag1_req)).await.unwrap();
     // let tag1 = tag1_resp.into_inner().tag.unwrap();
 
     let tag2_req = CreateTagRequest {
         name: "Electronic".to_string(),
+        ..Default::default()
     };
     let tag2_resp = server.create_tag(Request::new(tag2_req)).await.unwrap();
     // let tag2 = tag2_resp.into_inner().tag.unwrap();
 
     let tag3_req = CreateTagRequest {
         name: "Ambient".to_string(),
+        ..Default::default()
     };
     let tag3_resp = server.create_tag(Request::new(tag3_req)).await.unwrap();
     // let tag3 = tag3_resp.into_inner().tag.unwrap();
 
     // Get all tags
-    let request = GetTagsRequest {};
+    let request = GetTagsRequest::default();
     let response = server.get_tags(Request::new(request)).await.unwrap();
     let tags = response.into_inner().tags;
 
@@ -96,6 +100,7 @@ async fn test_tag_project() {
     // Create a tag
     let tag_req = CreateTagRequest {
         name: "Work In Progress".to_string(),
+        ..Default::default()
     };
     let tag_resp = server.create_tag(Request::new(tag_req)).await.unwrap();
     let tag = tag_resp.into_inner().tag.unwrap();
@@ -133,12 +138,14 @@ async fn test_untag_project() {
     // Create multiple tags
     let tag1_req = CreateTagRequest {
         name: "Tag 1".to_string(),
+        ..Default::default()
     };
     let tag1_resp = server.create_tag(Request::new(tag1_req)).await.unwrap();
     let tag1 = tag1_resp.into_inner().tag.unwrap();
 
     let tag2_req = CreateTagRequest {
         name: "Tag 2".to_string(),
+        ..Default::default()
     };
     let tag2_resp = server.create_tag(Request::new(tag2_req)).await.unwrap();
     let tag2 = tag2_resp.into_inner().tag.unwrap();
@@ -203,6 +210,7 @@ async fn test_tag_project_nonexistent_project() {
     // Create a tag
     let tag_req = CreateTagRequest {
         name: "Test Tag".to_string(),
+        ..Default::default()
     };
     let tag_resp = server.create_tag(Request::new(tag_req)).await.unwrap();
     let tag = tag_resp.into_inner().tag.unwrap();
@@ -249,6 +257,7 @@ async fn test_create_duplicate_tag() {
     // Create a tag
     let tag_req = CreateTagRequest {
         name: "Duplicate Tag".to_string(),
+        ..Default::default()
     };
     let tag_resp = server.create_tag(Request::new(tag_req)).await.unwrap();
     let tag = tag_resp.into_inner().tag.unwrap();
@@ -256,6 +265,7 @@ async fn test_create_duplicate_tag() {
     // Try to create another tag with the same name
     let duplicate_req = CreateTagRequest {
         name: "Duplicate Tag".to_string(),
+        ..Default::default()
     };
     let result = server.create_tag(Request::new(duplicate_req)).await;
 
@@ -276,6 +286,7 @@ async fn test_tag_project_idempotent() {
     // Create a tag
     let tag_req = CreateTagRequest {
         name: "Idempotent Tag".to_string(),
+        ..Default::default()
     };
     let tag_resp = server.create_tag(Request::new(tag_req)).await.unwrap();
     let tag = tag_resp.into_inner().tag.unwrap();
@@ -321,6 +332,7 @@ async fn test_update_tag() {
     // Create a tag
     let create_req = CreateTagRequest {
         name: "Original Tag".to_string(),
+        ..Default::default()
     };
     let create_resp = server.create_tag(Request::new(create_req)).await.unwrap();
     let tag = create_resp.into_inner().tag.unwrap();
@@ -328,7 +340,8 @@ async fn test_update_tag() {
     // Update the tag
     let update_req = UpdateTagRequest {
         tag_id: tag.id.clone(),
-        name: "Updated Tag".to_string(),
+        name: Some("Updated Tag".to_string()),
+        ..Default::default()
     };
     let update_resp = server.update_tag(Request::new(update_req)).await.unwrap();
     let updated_tag = update_resp.into_inner().tag.unwrap();
@@ -337,7 +350,7 @@ async fn test_update_tag() {
     assert_eq!(updated_tag.id, tag.id);
 
     // Verify the tag was updated by getting all tags
-    let get_req = GetTagsRequest {};
+    let get_req = GetTagsRequest::default();
     let get_resp = server.get_tags(Request::new(get_req)).await.unwrap();
     let tags = get_resp.into_inner().tags;
 
@@ -355,7 +368,8 @@ async fn test_update_tag_nonexistent() {
     // Try to update a non-existent tag
     let update_req = UpdateTagRequest {
         tag_id: "non-existent-tag-id".to_string(),
-        name: "New Name".to_string(),
+        name: Some("New Name".to_string()),
+        ..Default::default()
     };
     let update_resp = server.update_tag(Request::new(update_req)).await;
 
@@ -372,6 +386,7 @@ async fn test_update_tag_empty_name() {
     // Create a tag
     let create_req = CreateTagRequest {
         name: "Test Tag".to_string(),
+        ..Default::default()
     };
     let create_resp = server.create_tag(Request::new(create_req)).await.unwrap();
     let tag = create_resp.into_inner().tag.unwrap();
@@ -379,7 +394,8 @@ async fn test_update_tag_empty_name() {
     // Update with empty name (should be allowed)
     let update_req = UpdateTagRequest {
         tag_id: tag.id.clone(),
-        name: "".to_string(),
+        name: Some("".to_string()),
+        ..Default::default()
     };
     let update_resp = server.update_tag(Request::new(update_req)).await.unwrap();
     let updated_tag = update_resp.into_inner().tag.unwrap();
@@ -388,12 +404,24 @@ async fn test_update_tag_empty_name() {
     assert_eq!(updated_tag.id, tag.id);
 
     // Verify the tag was updated to empty name
-    let get_req = GetTagsRequest {};
+    let get_req = GetTagsRequest::default();
     let get_resp = server.get_tags(Request::new(get_req)).await.unwrap();
     let tags = get_resp.into_inner().tags;
 
     assert_eq!(tags.len(), 1);
     assert_eq!(tags[0].name, "");
+
+    // Without a name only the given fields change
+    let update_req = UpdateTagRequest {
+        tag_id: tag.id.clone(),
+        color: Some("#ff8800".to_string()),
+        ..Default::default()
+    };
+    let update_resp = server.update_tag(Request::new(update_req)).await.unwrap();
+    let updated_tag = update_resp.into_inner().tag.unwrap();
+
+    assert_eq!(updated_tag.name, "");
+    assert_eq!(updated_tag.color.as_deref(), Some("#ff8800"));
 }
 
 #[tokio::test]
@@ -405,18 +433,20 @@ async fn test_delete_tag() {
     // Create multiple tags
     let tag1_req = CreateTagRequest {
         name: "Tag 1".to_string(),
+        ..Default::default()
     };
     let tag1_resp = server.create_tag(Request::new(tag1_req)).await.unwrap();
     let tag1 = tag1_resp.into_inner().tag.unwrap();
 
     let tag2_req = CreateTagRequest {
         name: "Tag 2".to_string(),
+        ..Default::default()
     };
     let tag2_resp = server.create_tag(Request::new(tag2_req)).await.unwrap();
     let tag2 = tag2_resp.into_inner().tag.unwrap();
 
     // Verify both tags exist
-    let get_req = GetTagsRequest {};
+    let get_req = GetTagsRequest::default();
     let get_resp = server.get_tags(Request::new(get_req)).await.unwrap();
     let tags = get_resp.into_inner().tags;
     assert_eq!(tags.len(), 2);
@@ -431,7 +461,7 @@ async fn test_delete_tag() {
     assert!(result.success);
 
     // Verify only one tag remains
-    let get_req = GetTagsRequest {};
+    let get_req = GetTagsRequest::default();
     let get_resp = server.get_tags(Request::new(get_req)).await.unwrap();
     let tags = get_resp.into_inner().tags;
 
@@ -470,6 +500,7 @@ async fn test_delete_tag_with_project_associations() {
     // Create a tag
     let tag_req = CreateTagRequest {
         name: "Associated Tag".to_string(),
+        ..Default::default()
     };
     let tag_resp = server.create_tag(Request::new(tag_req)).await.unwrap();
     let tag = tag_resp.into_inner().tag.unwrap();
@@ -501,7 +532,7 @@ async fn test_delete_tag_with_project_associations() {
     assert!(result.success);
 
     // Verify the tag is deleted
-    let get_req = GetTagsRequest {};
+    let get_req = GetTagsRequest::default();
     let get_resp = server.get_tags(Request::new(get_req)).await.unwrap();
     let tags = get_resp.into_inner().tags;
     assert_eq!(tags.len(), 0);
@@ -527,6 +558,7 @@ async fn test_update_tag_with_project_associations() {
     // Create a tag
     let tag_req = CreateTagRequest {
         name: "Original Tag".to_string(),
+        ..Default::default()
     };
     let tag_resp = server.create_tag(Request::new(tag_req)).await.unwrap();
     let tag = tag_resp.into_inner().tag.unwrap();
@@ -544,7 +576,8 @@ async fn test_update_tag_with_project_associations() {
     // Update the tag name
     let update_req = UpdateTagRequest {
         tag_id: tag.id.clone(),
-        name: "Updated Tag".to_string(),
+        name: Some("Updated Tag".to_string()),
+        ..Default::default()
     };
     let update_resp = server.update_tag(Request::new(update_req)).await.unwrap();
     let updated_tag = update_resp.into_inner().tag.unwrap();
@@ -561,3 +594,173 @@ async fn test_update_tag_with_project_associations() {
         assert!(!project_tags.contains("Original Tag"));
     }
 }
+
+#[tokio::test]
+async fn test_hierarchical_tags() {
+    setup("error");
+
+    let server = create_test_server().await;
+    let db = server.db();
+
+    let techno_project = create_test_project_in_db(db).await;
+    let house_project = create_test_project_in_db(db).await;
+
+    // Creating a nested tag creates its missing parents
+    let techno = server
+        .create_tag(Request::new(CreateTagRequest {
+            name: "genre/techno".to_string(),
+            color: Some("#ff8800".to_string()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .tag
+        .unwrap();
+    assert_eq!(techno.color.as_deref(), Some("#ff8800"));
+    assert_eq!(techno.group.as_deref(), Some("genre"));
+
+    let tags = server
+        .get_tags(Request::new(GetTagsRequest::default()))
+        .await
+        .unwrap()
+        .into_inner()
+        .tags;
+    assert_eq!(tags.len(), 2);
+    let genre = tags.iter().find(|t| t.name == "genre").unwrap().clone();
+    assert_eq!(techno.parent_id.as_deref(), Some(genre.id.as_str()));
+    assert_eq!(genre.parent_id, None);
+    assert_eq!(genre.group, None);
+
+    // Children can also be created under an existing parent by ID
+    let house = server
+        .create_tag(Request::new(CreateTagRequest {
+            name: "house".to_string(),
+            parent_id: Some(genre.id.clone()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .tag
+        .unwrap();
+    assert_eq!(house.name, "genre/house");
+    assert_eq!(house.parent_id.as_deref(), Some(genre.id.as_str()));
+
+    for (project_id, tag_id) in [(&techno_project, &techno.id), (&house_project, &house.id)] {
+        server
+            .tag_project(Request::new(TagProjectRequest {
+                project_id: project_id.clone(),
+                tag_id: tag_id.clone(),
+            }))
+            .await
+            .unwrap();
+    }
+
+    // Querying the parent includes projects tagged with its children
+    let by_parent = server
+        .get_projects_by_tag(Request::new(GetProjectsByTagRequest {
+            tag_id: genre.id.clone(),
+            limit: None,
+            offset: None,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(by_parent.total_count, 2);
+
+    let by_child = server
+        .get_projects_by_tag(Request::new(GetProjectsByTagRequest {
+            tag_id: techno.id.clone(),
+            limit: None,
+            offset: None,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(by_child.total_count, 1);
+
+    let usage = server
+        .get_all_tags_with_usage(Request::new(GetAllTagsWithUsageRequest {
+            group: Some("genre".to_string()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(usage.total_count, 3);
+    let genre_usage = usage.tags.iter().find(|t| t.tag_id == genre.id).unwrap();
+    assert_eq!(genre_usage.project_count, 0);
+    assert_eq!(genre_usage.total_project_count, 2);
+
+    // Renaming the parent renames the whole subtree
+    server
+        .update_tag(Request::new(UpdateTagRequest {
+            tag_id: genre.id.clone(),
+            name: Some("style".to_string()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    let techno = server
+        .get_tag(Request::new(GetTagRequest { tag_id: techno.id.clone() }))
+        .await
+        .unwrap()
+        .into_inner()
+        .tag
+        .unwrap();
+    assert_eq!(techno.name, "style/techno");
+
+    // Moving a tag to the top level keeps its leaf name
+    let moved = server
+        .update_tag(Request::new(UpdateTagRequest {
+            tag_id: techno.id.clone(),
+            clear_parent: true,
+            color: Some("#0af".to_string()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .tag
+        .unwrap();
+    assert_eq!(moved.name, "techno");
+    assert_eq!(moved.parent_id, None);
+    assert_eq!(moved.color.as_deref(), Some("#0af"));
+
+    // A tag cannot be moved underneath one of its own children
+    let cycle = server
+        .update_tag(Request::new(UpdateTagRequest {
+            tag_id: genre.id.clone(),
+            name: Some("style".to_string()),
+            parent_id: Some(house.id.clone()),
+            ..Default::default()
+        }))
+        .await;
+    assert_eq!(cycle.unwrap_err().code(), Code::InvalidArgument);
+
+    let invalid_color = server
+        .create_tag(Request::new(CreateTagRequest {
+            name: "status/mixing".to_string(),
+            color: Some("orange".to_string()),
+            ..Default::default()
+        }))
+        .await;
+    assert_eq!(invalid_color.unwrap_err().code(), Code::InvalidArgument);
+
+    // Deleting a parent removes its children
+    server
+        .delete_tag(Request::new(DeleteTagRequest { tag_id: genre.id.clone() }))
+        .await
+        .unwrap();
+    let remaining: Vec<String> = server
+        .get_tags(Request::new(GetTagsRequest::default()))
+        .await
+        .unwrap()
+        .into_inner()
+        .tags
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    assert_eq!(remaining, vec!["techno".to_string()]);
+}
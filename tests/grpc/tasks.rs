@@ -16,6 +16,7 @@ async fn test_search_tasks() {
     let task1_req = Request::new(CreateTaskRequest {
         project_id: project_id.clone(),
         description: "Finish the intro".to_string(),
+        ..Default::default()
     });
     let task1_response = server.create_task(task1_req).await.unwrap();
     let _task1 = task1_response.into_inner().task.unwrap();
@@ -23,6 +24,7 @@ async fn test_search_tasks() {
     let task2_req = Request::new(CreateTaskRequest {
         project_id: project_id.clone(),
         description: "Mix the vocals".to_string(),
+        ..Default::default()
     });
     let task2_response = server.create_task(task2_req).await.unwrap();
     let task2 = task2_response.into_inner().task.unwrap();
@@ -30,6 +32,7 @@ async fn test_search_tasks() {
     let task3_req = Request::new(CreateTaskRequest {
         project_id: project_id.clone(),
         description: "Master the track".to_string(),
+        ..Default::default()
     });
     let task3_response = server.create_task(task3_req).await.unwrap();
     let _task3 = task3_response.into_inner().task.unwrap();
@@ -39,6 +42,7 @@ async fn test_search_tasks() {
         task_id: task2.id.clone(),
         description: None,
         completed: Some(true),
+        ..Default::default()
     });
     server.update_task(complete_req).await.unwrap();
 
@@ -50,6 +54,7 @@ async fn test_search_tasks() {
         offset: None,
         completed_only: None,
         pending_only: None,
+        ..Default::default()
     });
     let search_response = server.search_tasks(search_req).await.unwrap();
     let search_result = search_response.into_inner();
@@ -66,6 +71,7 @@ async fn test_search_tasks() {
         offset: None,
         completed_only: Some(true),
         pending_only: None,
+        ..Default::default()
     });
     let completed_response = server.search_tasks(completed_search_req).await.unwrap();
     let completed_result = completed_response.into_inner();
@@ -83,6 +89,7 @@ async fn test_search_tasks() {
         offset: None,
         completed_only: None,
         pending_only: Some(true),
+        ..Default::default()
     });
     let pending_response = server.search_tasks(pending_search_req).await.unwrap();
     let pending_result = pending_response.into_inner();
@@ -103,12 +110,14 @@ async fn test_get_task_statistics() {
     let task1_req = Request::new(CreateTaskRequest {
         project_id: project_id.clone(),
         description: "Task 1".to_string(),
+        ..Default::default()
     });
     server.create_task(task1_req).await.unwrap();
 
     let task2_req = Request::new(CreateTaskRequest {
         project_id: project_id.clone(),
         description: "Task 2".to_string(),
+        ..Default::default()
     });
     let task2_response = server.create_task(task2_req).await.unwrap();
     let task2 = task2_response.into_inner().task.unwrap();
@@ -116,6 +125,7 @@ async fn test_get_task_statistics() {
     let task3_req = Request::new(CreateTaskRequest {
         project_id: project_id.clone(),
         description: "Task 3".to_string(),
+        ..Default::default()
     });
     let task3_response = server.create_task(task3_req).await.unwrap();
     let task3 = task3_response.into_inner().task.unwrap();
@@ -125,6 +135,7 @@ async fn test_get_task_statistics() {
         task_id: task2.id.clone(),
         description: None,
         completed: Some(true),
+        ..Default::default()
     });
     server.update_task(complete_req1).await.unwrap();
 
@@ -132,6 +143,7 @@ async fn test_get_task_statistics() {
         task_id: task3.id.clone(),
         description: None,
         completed: Some(true),
+        ..Default::default()
     });
     server.update_task(complete_req2).await.unwrap();
 
@@ -172,6 +184,7 @@ async fn test_search_tasks_pagination() {
         let task_req = Request::new(CreateTaskRequest {
             project_id: project_id.clone(),
             description: format!("Task {}", i),
+            ..Default::default()
         });
         server.create_task(task_req).await.unwrap();
     }
@@ -184,6 +197,7 @@ async fn test_search_tasks_pagination() {
         offset: Some(0),
         completed_only: None,
         pending_only: None,
+        ..Default::default()
     });
     let search_response = server.search_tasks(search_req).await.unwrap();
     let search_result = search_response.into_inner();
@@ -199,6 +213,7 @@ async fn test_search_tasks_pagination() {
         offset: Some(3),
         completed_only: None,
         pending_only: None,
+        ..Default::default()
     });
     let search_response2 = server.search_tasks(search_req2).await.unwrap();
     let search_result2 = search_response2.into_inner();
@@ -206,3 +221,405 @@ async fn test_search_tasks_pagination() {
     assert_eq!(search_result2.tasks.len(), 2); // Remaining 2 tasks
     assert_eq!(search_result2.total_count, 5);
 }
+
+#[tokio::test]
+async fn test_create_task_with_details() {
+    let (server, _db) = setup_test_server().await;
+
+    let project_id = create_test_project(&server, "Test Project", "/path/to/project.als").await;
+
+    let due_date = chrono::Utc::now().timestamp() + 86400;
+    let create_req = Request::new(CreateTaskRequest {
+        project_id: project_id.clone(),
+        description: "Print stems for mastering".to_string(),
+        priority: Some(5),
+        due_date: Some(due_date),
+        assignee: Some("Sam".to_string()),
+        ..Default::default()
+    });
+    let task = server.create_task(create_req).await.unwrap().into_inner().task.unwrap();
+
+    assert_eq!(task.priority, 5);
+    assert_eq!(task.due_date, Some(due_date));
+    assert_eq!(task.assignee.as_deref(), Some("Sam"));
+    assert_eq!(task.position, 0);
+    assert_eq!(task.completed_at, None);
+
+    // Default priority when none is given
+    let default_req = Request::new(CreateTaskRequest {
+        project_id: project_id.clone(),
+        description: "Check phase on the kick".to_string(),
+        ..Default::default()
+    });
+    let default_task = server.create_task(default_req).await.unwrap().into_inner().task.unwrap();
+    assert_eq!(default_task.priority, 3);
+    assert_eq!(default_task.position, 1);
+
+    // Out of range priority is rejected
+    let invalid_req = Request::new(CreateTaskRequest {
+        project_id: project_id.clone(),
+        description: "Invalid".to_string(),
+        priority: Some(9),
+        ..Default::default()
+    });
+    let result = server.create_task(invalid_req).await;
+    assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+
+    // Completing records a timestamp, reopening clears it
+    let complete_req = Request::new(UpdateTaskRequest {
+        task_id: task.id.clone(),
+        completed: Some(true),
+        clear_due_date: true,
+        clear_assignee: true,
+        ..Default::default()
+    });
+    let updated = server.update_task(complete_req).await.unwrap().into_inner().task.unwrap();
+    assert!(updated.completed);
+    assert!(updated.completed_at.is_some());
+    assert_eq!(updated.due_date, None);
+    assert_eq!(updated.assignee, None);
+
+    let reopen_req = Request::new(UpdateTaskRequest {
+        task_id: task.id.clone(),
+        completed: Some(false),
+        ..Default::default()
+    });
+    let reopened = server.update_task(reopen_req).await.unwrap().into_inner().task.unwrap();
+    assert!(!reopened.completed);
+    assert_eq!(reopened.completed_at, None);
+
+    // A rejected update writes none of its fields
+    let invalid_update = Request::new(UpdateTaskRequest {
+        task_id: task.id.clone(),
+        description: Some("Print stems twice".to_string()),
+        completed: Some(true),
+        priority: Some(0),
+        ..Default::default()
+    });
+    let result = server.update_task(invalid_update).await;
+    assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+
+    let unchanged = server
+        .get_project_tasks(Request::new(GetProjectTasksRequest {
+            project_id: project_id.clone(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .tasks
+        .into_iter()
+        .find(|t| t.id == task.id)
+        .unwrap();
+    assert_eq!(unchanged.description, "Print stems for mastering");
+    assert!(!unchanged.completed);
+}
+
+#[tokio::test]
+async fn test_reorder_tasks() {
+    let (server, _db) = setup_test_server().await;
+
+    let project_id = create_test_project(&server, "Test Project", "/path/to/project.als").await;
+
+    let mut task_ids = Vec::new();
+    for description in ["Intro", "Drop", "Breakdown", "Outro"] {
+        let req = Request::new(CreateTaskRequest {
+            project_id: project_id.clone(),
+            description: description.to_string(),
+            ..Default::default()
+        });
+        task_ids.push(server.create_task(req).await.unwrap().into_inner().task.unwrap().id);
+    }
+
+    // Move "Outro" to the top
+    let move_req = Request::new(UpdateTaskRequest {
+        task_id: task_ids[3].clone(),
+        position: Some(0),
+        ..Default::default()
+    });
+    server.update_task(move_req).await.unwrap();
+
+    let tasks = server
+        .get_project_tasks(Request::new(GetProjectTasksRequest {
+            project_id: project_id.clone(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .tasks;
+    let order: Vec<&str> = tasks.iter().map(|t| t.description.as_str()).collect();
+    assert_eq!(order, vec!["Outro", "Intro", "Drop", "Breakdown"]);
+    assert_eq!(
+        tasks.iter().map(|t| t.position).collect::<Vec<_>>(),
+        vec![0, 1, 2, 3]
+    );
+
+    // Deleting a task closes the gap
+    server
+        .delete_task(Request::new(DeleteTaskRequest {
+            task_id: task_ids[0].clone(),
+        }))
+        .await
+        .unwrap();
+
+    let tasks = server
+        .get_project_tasks(Request::new(GetProjectTasksRequest {
+            project_id: project_id.clone(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .tasks;
+    let order: Vec<&str> = tasks.iter().map(|t| t.description.as_str()).collect();
+    assert_eq!(order, vec!["Outro", "Drop", "Breakdown"]);
+    assert_eq!(
+        tasks.iter().map(|t| t.position).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+}
+
+#[tokio::test]
+async fn test_overdue_tasks_and_filters() {
+    let (server, _db) = setup_test_server().await;
+
+    let project_a = create_test_project(&server, "Project A", "/path/to/a.als").await;
+    let project_b = create_test_project(&server, "Project B", "/path/to/b.als").await;
+
+    let now = chrono::Utc::now().timestamp();
+
+    let overdue_req = Request::new(CreateTaskRequest {
+        project_id: project_a.clone(),
+        description: "Send mix to label".to_string(),
+        priority: Some(4),
+        due_date: Some(now - 3600),
+        assignee: Some("Alex".to_string()),
+        ..Default::default()
+    });
+    server.create_task(overdue_req).await.unwrap();
+
+    let future_req = Request::new(CreateTaskRequest {
+        project_id: project_a.clone(),
+        description: "Rebounce master".to_string(),
+        priority: Some(2),
+        due_date: Some(now + 86400),
+        ..Default::default()
+    });
+    server.create_task(future_req).await.unwrap();
+
+    let other_overdue_req = Request::new(CreateTaskRequest {
+        project_id: project_b.clone(),
+        description: "Fix clipping".to_string(),
+        due_date: Some(now - 7200),
+        ..Default::default()
+    });
+    let other_overdue = server.create_task(other_overdue_req).await.unwrap().into_inner().task.unwrap();
+
+    // Completed tasks are never overdue
+    let done_req = Request::new(CreateTaskRequest {
+        project_id: project_b.clone(),
+        description: "Old task".to_string(),
+        due_date: Some(now - 86400),
+        ..Default::default()
+    });
+    let done = server.create_task(done_req).await.unwrap().into_inner().task.unwrap();
+    server
+        .update_task(Request::new(UpdateTaskRequest {
+            task_id: done.id.clone(),
+            completed: Some(true),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+
+    let overdue = server
+        .get_overdue_tasks(Request::new(GetOverdueTasksRequest {}))
+        .await
+        .unwrap()
+        .into_inner()
+        .tasks;
+    assert_eq!(overdue.len(), 2);
+    // Oldest due date first
+    assert_eq!(overdue[0].id, other_overdue.id);
+    assert_eq!(overdue[1].description, "Send mix to label");
+
+    // Search filters
+    let search = |filters: SearchTasksRequest| {
+        let server = &server;
+        async move { server.search_tasks(Request::new(filters)).await.unwrap().into_inner() }
+    };
+
+    let high_priority = search(SearchTasksRequest {
+        project_id: project_a.clone(),
+        min_priority: Some(4),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(high_priority.total_count, 1);
+    assert_eq!(high_priority.tasks[0].description, "Send mix to label");
+
+    let by_assignee = search(SearchTasksRequest {
+        project_id: project_a.clone(),
+        assignee: Some("alex".to_string()),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(by_assignee.total_count, 1);
+
+    let overdue_only = search(SearchTasksRequest {
+        project_id: project_a.clone(),
+        overdue_only: Some(true),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(overdue_only.total_count, 1);
+
+    let due_later = search(SearchTasksRequest {
+        project_id: project_a.clone(),
+        due_after: Some(now),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(due_later.total_count, 1);
+    assert_eq!(due_later.tasks[0].description, "Rebounce master");
+
+    let stats = server
+        .get_task_statistics(Request::new(GetTaskStatisticsRequest { project_id: None }))
+        .await
+        .unwrap()
+        .into_inner()
+        .statistics
+        .unwrap();
+    assert_eq!(stats.overdue_tasks, 2);
+    assert!(stats.average_completion_seconds.is_some());
+}
+
+#[tokio::test]
+async fn test_arrangement_anchored_tasks() {
+    let (server, _db) = setup_test_server().await;
+
+    // Test projects are 120 BPM in 4/4, so one bar is two seconds
+    let project_id = create_test_project(&server, "Test Project", "/path/to/project.als").await;
+
+    let drop_task = server
+        .create_task(Request::new(CreateTaskRequest {
+            project_id: project_id.clone(),
+            description: "Fix the clap at the drop".to_string(),
+            arrangement_bar: Some(33),
+            arrangement_beat: Some(3.0),
+            track_name: Some("Drums".to_string()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .task
+        .unwrap();
+
+    assert_eq!(drop_task.arrangement_bar, Some(33));
+    assert_eq!(drop_task.arrangement_beat, Some(3.0));
+    assert_eq!(drop_task.arrangement_seconds, Some(65.0));
+    assert_eq!(drop_task.track_name.as_deref(), Some("Drums"));
+
+    let intro_task = server
+        .create_task(Request::new(CreateTaskRequest {
+            project_id: project_id.clone(),
+            description: "Automate the intro filter".to_string(),
+            arrangement_bar: Some(1),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .task
+        .unwrap();
+    assert_eq!(intro_task.arrangement_beat, Some(1.0));
+    assert_eq!(intro_task.arrangement_seconds, Some(0.0));
+
+    let general_task = server
+        .create_task(Request::new(CreateTaskRequest {
+            project_id: project_id.clone(),
+            description: "Rename the project".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .task
+        .unwrap();
+    assert_eq!(general_task.arrangement_bar, None);
+    assert_eq!(general_task.arrangement_seconds, None);
+
+    let invalid = server
+        .create_task(Request::new(CreateTaskRequest {
+            project_id: project_id.clone(),
+            description: "Nowhere".to_string(),
+            arrangement_bar: Some(0),
+            ..Default::default()
+        }))
+        .await;
+    assert_eq!(invalid.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+    // A 4/4 bar has no fifth beat
+    let past_bar = server
+        .create_task(Request::new(CreateTaskRequest {
+            project_id: project_id.clone(),
+            description: "Off the grid".to_string(),
+            arrangement_bar: Some(2),
+            arrangement_beat: Some(5.0),
+            ..Default::default()
+        }))
+        .await;
+    assert_eq!(past_bar.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+    let beat_without_bar = server
+        .create_task(Request::new(CreateTaskRequest {
+            project_id: project_id.clone(),
+            description: "Somewhere".to_string(),
+            arrangement_beat: Some(2.0),
+            ..Default::default()
+        }))
+        .await;
+    assert_eq!(beat_without_bar.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+    for (bar, beat) in [(Some(33), Some(7.0)), (None, Some(2.0))] {
+        let rejected = server
+            .update_task(Request::new(UpdateTaskRequest {
+                task_id: drop_task.id.clone(),
+                arrangement_bar: bar,
+                arrangement_beat: beat,
+                ..Default::default()
+            }))
+            .await;
+        assert_eq!(rejected.unwrap_err().code(), tonic::Code::InvalidArgument);
+    }
+
+    let tasks = server
+        .get_project_tasks(Request::new(GetProjectTasksRequest {
+            project_id: project_id.clone(),
+            sort_order: TaskSortOrder::TaskSortArrangement as i32,
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .tasks;
+    let order: Vec<_> = tasks.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(order, vec![intro_task.id.as_str(), drop_task.id.as_str(), general_task.id.as_str()]);
+
+    let cleared = server
+        .update_task(Request::new(UpdateTaskRequest {
+            task_id: drop_task.id.clone(),
+            clear_arrangement_position: true,
+            clear_track_name: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .task
+        .unwrap();
+    assert_eq!(cleared.arrangement_bar, None);
+    assert_eq!(cleared.arrangement_seconds, None);
+    assert_eq!(cleared.track_name, None);
+}
@@ -0,0 +1,159 @@
+//! Incremental scan planning tests
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use seula::database::LiveSetDatabase;
+use seula::scan::incremental::{plan_scan, FileState};
+use seula::utils::metadata::load_file_hash;
+use tempfile::TempDir;
+
+use crate::common::{create_test_live_set_from_parse, setup, LiveSetBuilder};
+
+fn write_set(dir: &Path, name: &str, content: &[u8]) -> PathBuf {
+    let path = dir.join(name);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn set_mtime(path: &Path, time: SystemTime) {
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(time)
+        .unwrap();
+}
+
+/// Index a set as it currently is on disk
+fn index_set(db: &mut LiveSetDatabase, path: &Path) -> String {
+    let mut live_set = create_test_live_set_from_parse(
+        &path.file_name().unwrap().to_string_lossy(),
+        LiveSetBuilder::new().build(),
+    );
+    live_set.file_path = path.to_path_buf();
+    live_set.file_hash = load_file_hash(&path.to_path_buf()).unwrap();
+    db.insert_project(&live_set).unwrap();
+
+    let state = FileState::read(path).unwrap();
+    db.record_file_states(&[(path, state.size, state.modified_at)])
+        .unwrap();
+    live_set.id.to_string()
+}
+
+#[test]
+fn test_plan_scan_classifies_sets() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().to_path_buf();
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+    let an_hour_ago = SystemTime::now() - Duration::from_secs(3600);
+
+    let unchanged = write_set(&root, "Unchanged.als", b"same");
+    set_mtime(&unchanged, an_hour_ago);
+    index_set(&mut db, &unchanged);
+
+    let touched = write_set(&root, "Touched.als", b"touched");
+    set_mtime(&touched, an_hour_ago);
+    index_set(&mut db, &touched);
+    set_mtime(&touched, SystemTime::now());
+
+    let edited = write_set(&root, "Edited.als", b"before");
+    set_mtime(&edited, an_hour_ago);
+    let edited_id = index_set(&mut db, &edited);
+    fs::write(&edited, b"after edit").unwrap();
+
+    let deleted = write_set(&root, "Deleted.als", b"gone");
+    let deleted_id = index_set(&mut db, &deleted);
+    fs::remove_file(&deleted).unwrap();
+
+    let new = write_set(&root, "New.als", b"new");
+
+    let found: HashSet<PathBuf> = [&unchanged, &touched, &edited, &new]
+        .into_iter()
+        .cloned()
+        .collect();
+    let roots = vec![root];
+    let indexed = db.get_indexed_files().unwrap();
+    let plan = plan_scan(&found, &roots, &indexed, false);
+
+    assert_eq!(plan.new, vec![new.clone()]);
+    assert_eq!(plan.changed.get(&edited), Some(&edited_id));
+    assert_eq!(plan.changed.len(), 1);
+    assert_eq!(plan.unchanged, 2);
+    assert_eq!(plan.touched.len(), 1);
+    assert_eq!(plan.touched[0].0, touched);
+    assert_eq!(plan.removed, vec![(deleted_id, deleted.clone())]);
+
+    let mut to_parse = plan.paths_to_parse();
+    to_parse.sort();
+    assert_eq!(to_parse, vec![edited.clone(), new.clone()]);
+
+    // Forcing parses everything found, but still reports removals
+    let forced = plan_scan(&found, &roots, &indexed, true);
+    assert_eq!(forced.paths_to_parse().len(), 4);
+    assert_eq!(forced.unchanged, 0);
+    assert_eq!(forced.removed.len(), 1);
+
+    // Missing sets outside the scanned roots are left alone
+    let elsewhere = plan_scan(&found, &[PathBuf::from("/somewhere/else")], &indexed, false);
+    assert!(elsewhere.removed.is_empty());
+}
+
+#[test]
+fn test_recorded_file_state_skips_rehash() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().to_path_buf();
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    let touched = write_set(&root, "Touched.als", b"content");
+    set_mtime(&touched, SystemTime::now() - Duration::from_secs(3600));
+    index_set(&mut db, &touched);
+    set_mtime(&touched, SystemTime::now());
+
+    let found: HashSet<PathBuf> = [touched.clone()].into_iter().collect();
+    let roots = vec![root];
+    let plan = plan_scan(&found, &roots, &db.get_indexed_files().unwrap(), false);
+    assert_eq!(plan.touched.len(), 1);
+
+    let states: Vec<_> = plan
+        .touched
+        .iter()
+        .map(|(path, state)| (path.as_path(), state.size, state.modified_at))
+        .collect();
+    db.record_file_states(&states).unwrap();
+
+    // With the new state recorded the set matches on size and time alone
+    let plan = plan_scan(&found, &roots, &db.get_indexed_files().unwrap(), false);
+    assert_eq!(plan.unchanged, 1);
+    assert!(plan.touched.is_empty());
+    assert!(plan.paths_to_parse().is_empty());
+}
+
+#[test]
+fn test_mark_removed_projects() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    let gone = write_set(temp_dir.path(), "Gone.als", b"gone");
+    let gone_id = index_set(&mut db, &gone);
+    let moved = write_set(temp_dir.path(), "Moved.als", b"moved");
+    let moved_id = index_set(&mut db, &moved);
+
+    // A project re-attached elsewhere during the scan no longer sits at the old path
+    let new_path = temp_dir.path().join("Sub").join("Moved.als");
+    db.update_project_path(&moved_id, &new_path).unwrap();
+
+    let marked = db
+        .mark_removed_projects(&[(gone_id.clone(), gone), (moved_id.clone(), moved)])
+        .unwrap();
+    assert_eq!(marked, 1);
+
+    let indexed = db.get_indexed_files().unwrap();
+    assert!(indexed.values().all(|file| file.project_id != gone_id));
+    assert!(indexed.values().any(|file| file.project_id == moved_id));
+}
@@ -0,0 +1,109 @@
+//! Scan job manager tests
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use seula::error::LiveSetError;
+use seula::scan::{ParseLimits, ParseOptions, Parser, ScanJobManager};
+use tempfile::TempDir;
+
+use crate::common::setup;
+
+/// Files that fail to parse straight away, which is all the queueing tests need
+fn write_sets(dir: &Path, count: usize) -> Vec<PathBuf> {
+    (0..count)
+        .map(|i| {
+            let path = dir.join(format!("Set {}.als", i));
+            fs::write(&path, b"not a live set").unwrap();
+            path
+        })
+        .collect()
+}
+
+#[test]
+fn test_priority_parse_runs_while_bulk_scan_paused() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let manager = ScanJobManager::new(1, None);
+
+    let job = manager.start_scan(write_sets(temp_dir.path(), 3));
+    assert_eq!(manager.pause(), 1);
+    assert!(job.is_paused());
+    // Pausing twice doesn't count the scan again
+    assert_eq!(manager.pause(), 0);
+
+    // A single rescan isn't held up behind the paused scan
+    let single = temp_dir.path().join("Watched.als");
+    fs::write(&single, b"not a live set").unwrap();
+    assert!(manager.parse_now(single).is_err());
+
+    // Sets picked up before the pause finish, then nothing more comes through
+    thread::sleep(Duration::from_millis(100));
+    let before_pause = job.results().try_iter().count();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(job.results().try_iter().count(), 0);
+
+    assert_eq!(manager.resume(), 1);
+    let mut received = before_pause;
+    while job.results().recv_timeout(Duration::from_secs(5)).is_ok() {
+        received += 1;
+    }
+    assert_eq!(received, 3);
+
+    drop(job);
+    assert!(!manager.is_scanning());
+}
+
+#[test]
+fn test_cancel_drops_queued_sets() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let manager = ScanJobManager::new(1, None);
+
+    let job = manager.start_scan(write_sets(temp_dir.path(), 5));
+    manager.pause();
+    // Let a set picked up before the pause finish
+    thread::sleep(Duration::from_millis(100));
+    job.results().try_iter().for_each(drop);
+
+    assert_eq!(manager.cancel(), 1);
+    assert!(job.is_cancelled());
+
+    // The results disconnect once the queued sets are dropped
+    assert_eq!(job.results().iter().count(), 0);
+
+    // Nothing left to cancel
+    drop(job);
+    assert_eq!(manager.cancel(), 0);
+    assert!(!manager.is_scanning());
+}
+
+#[test]
+fn test_parse_limits_stop_parser() {
+    setup("error");
+    let xml = br#"<Ableton MajorVersion="5" MinorVersion="11.2_11215"><LiveSet /></Ableton>"#;
+
+    let mut parser = Parser::streaming(ParseOptions::default());
+    parser.set_limits(ParseLimits {
+        timeout: Some(Duration::ZERO),
+        cancelled: None,
+    });
+    assert!(matches!(
+        parser.parse_reader(&xml[..]),
+        Err(LiveSetError::ParseTimeout(0))
+    ));
+
+    let mut parser = Parser::streaming(ParseOptions::default());
+    parser.set_limits(ParseLimits {
+        timeout: None,
+        cancelled: Some(Arc::new(AtomicBool::new(true))),
+    });
+    assert!(matches!(
+        parser.parse_reader(&xml[..]),
+        Err(LiveSetError::Cancelled)
+    ));
+}
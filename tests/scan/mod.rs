@@ -2,9 +2,13 @@
 //!
 //! This module contains all tests related to file scanning and project parsing
 
+pub mod incremental;
+pub mod jobs;
 pub mod parallel;
 pub mod parser;
+pub mod project_folder;
 pub mod project_scanner;
+pub mod quarantine;
 
 // Common imports for scanning tests
 // use seula::scan::*;
@@ -8,6 +8,7 @@ pub mod edge_cases;
 pub mod macos_sample_paths;
 pub mod plugins;
 pub mod samples;
+pub mod streaming;
 pub mod unicode_encoding;
 
 // Common imports for parser tests
@@ -0,0 +1,101 @@
+//! Streaming parser tests
+
+use std::io::Write;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use seula::utils::open_gzip_file;
+use tempfile::TempDir;
+
+use super::*;
+use crate::common::setup;
+
+fn live_set_xml(version: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<Ableton MajorVersion="5" MinorVersion="{}" SchemaChangeCount="3">
+<LiveSet>
+    <Tempo>
+        <LomId Value="0" />
+        <Manual Value="128.0" />
+    </Tempo>
+    <CurrentEnd Value="64.0" />
+</LiveSet>
+</Ableton>"#,
+        version
+    )
+}
+
+#[test]
+fn test_parse_reader_streams_gzip_file() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("Streamed.als");
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(live_set_xml("11.2_11215").as_bytes())
+        .unwrap();
+    std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+    let mut parser = Parser::streaming(ParseOptions::default());
+    // The result depends on the plugin database; the parsed state doesn't
+    let _ = parser.parse_reader(open_gzip_file(&path).unwrap());
+
+    assert_eq!(parser.ableton_version.major, 11);
+    assert_eq!(parser.ableton_version.minor, 2);
+    assert_eq!(parser.ableton_version.patch, 11215);
+    assert_eq!(parser.current_tempo, 128.0);
+    assert_eq!(parser.current_end_times, vec![64.0]);
+    assert!(parser.options.parse_key);
+}
+
+#[test]
+fn test_parse_reader_applies_version_options() {
+    setup("error");
+    let xml = live_set_xml("10.1_10123");
+
+    let mut parser = Parser::streaming(ParseOptions::default());
+    let _ = parser.parse_reader(xml.as_bytes());
+
+    assert_eq!(parser.ableton_version.major, 10);
+    assert!(
+        !parser.options.parse_key,
+        "Key parsing should be disabled for Live 10 sets"
+    );
+}
+
+#[test]
+fn test_parse_reader_requires_version() {
+    setup("error");
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Ableton MajorVersion="5" SchemaChangeCount="7">
+    <LiveSet>
+    </LiveSet>
+</Ableton>"#;
+
+    let mut parser = Parser::streaming(ParseOptions::default());
+    let result = parser.parse_reader(xml.as_bytes());
+    assert!(matches!(result, Err(LiveSetError::MissingVersion)));
+
+    let mut parser = Parser::streaming(ParseOptions::default());
+    let result = parser.parse_reader("<LiveSet></LiveSet>".as_bytes());
+    assert!(matches!(result, Err(LiveSetError::MissingVersion)));
+}
+
+#[test]
+fn test_parse_reader_rejects_truncated_gzip() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("Truncated.als");
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(live_set_xml("12.0_12049").repeat(50).as_bytes())
+        .unwrap();
+    let compressed = encoder.finish().unwrap();
+    std::fs::write(&path, &compressed[..compressed.len() / 2]).unwrap();
+
+    let mut parser = Parser::streaming(ParseOptions::default());
+    assert!(parser.parse_reader(open_gzip_file(&path).unwrap()).is_err());
+}
@@ -0,0 +1,49 @@
+//! Template, default set and project folder detection tests
+
+use std::fs;
+use std::path::Path;
+
+use seula::models::SetKind;
+use seula::scan::project_folder::{classify_set, find_project_folder, SetLocation};
+
+use crate::common::setup;
+
+#[test]
+fn test_classify_set() {
+    setup("error");
+    assert_eq!(classify_set(Path::new("/music/Song Project/Song.als")), SetKind::Project);
+    assert_eq!(
+        classify_set(Path::new("/music/User Library/Templates/Techno.als")),
+        SetKind::Template
+    );
+    assert_eq!(
+        classify_set(Path::new("/music/User Library/templates/Drums/Kit.als")),
+        SetKind::Template
+    );
+    assert_eq!(
+        classify_set(Path::new("/Preferences/Live 12.1/DefaultLiveSet.als")),
+        SetKind::Default
+    );
+    // Only folders count, not a set that happens to be called Templates
+    assert_eq!(classify_set(Path::new("/music/Templates.als")), SetKind::Project);
+}
+
+#[test]
+fn test_find_project_folder() {
+    setup("error");
+    let temp_dir = tempfile::tempdir().unwrap();
+    let folder = temp_dir.path().join("Song Project");
+    fs::create_dir_all(folder.join("Ableton Project Info")).unwrap();
+    fs::create_dir_all(folder.join("Versions")).unwrap();
+
+    assert_eq!(find_project_folder(&folder.join("Song.als")), Some(folder.clone()));
+    assert_eq!(
+        find_project_folder(&folder.join("Versions").join("Song v1.als")),
+        Some(folder.clone())
+    );
+    assert_eq!(find_project_folder(&temp_dir.path().join("Loose.als")), None);
+
+    let location = SetLocation::detect(&folder.join("Song.als"));
+    assert_eq!(location.kind, SetKind::Project);
+    assert_eq!(location.project_folder, Some(folder));
+}
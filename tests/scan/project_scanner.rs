@@ -2,10 +2,13 @@
 
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
+use seula::config::RootSettings;
+use seula::database::LiveSetDatabase;
 use seula::scan::project_scanner::ProjectPathScanner;
+use seula::scan::{RootRules, ScanRoots};
 use tempfile::TempDir;
 
-use crate::common::setup;
+use crate::common::{generate_mock_live_set, setup};
 
 fn create_test_file(dir: &Path, name: &str) -> PathBuf {
     let path = dir.join(name);
@@ -94,3 +97,136 @@ fn test_multiple_directory_scanning() {
         .iter()
         .any(|p| p.file_name().unwrap() == "project2.als"));
 }
+
+fn file_names(paths: &[PathBuf]) -> Vec<String> {
+    let mut names: Vec<String> = paths
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_root_settings_exclude_depth_and_backups() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    let old = root.join("Old Stuff").join("Demo Project");
+    let freeze = root.join("Song Project").join("_Freeze");
+    let deep = root.join("Archive").join("2019").join("Deep Project");
+    for dir in [&old, &freeze, &deep] {
+        fs::create_dir_all(dir).unwrap();
+    }
+    create_test_file(root, "Top.als");
+    create_test_file(&root.join("Song Project"), "Song.als");
+    create_test_file(&root.join("Song Project"), "Song [2023-10-15 123456].als");
+    create_test_file(&freeze, "Frozen.als");
+    create_test_file(&old, "Demo.als");
+    create_test_file(&deep, "Deep.als");
+
+    let mut settings = RootSettings::new(root.to_string_lossy());
+    settings.exclude = vec!["**/old stuff/**".to_string(), "_Freeze".to_string()];
+    let rules = RootRules::new(&settings).unwrap();
+    let scanner = ProjectPathScanner::new().unwrap();
+    assert_eq!(
+        file_names(&scanner.scan_root(&rules, root).unwrap()),
+        ["Deep.als", "Song.als", "Top.als"]
+    );
+
+    // Watcher events are held to the same rules
+    assert!(rules.allows(&root.join("Song Project").join("Song.als")));
+    assert!(!rules.allows(&old.join("Demo.als")));
+    assert!(!rules.allows(&freeze.join("Frozen.als")));
+    assert!(!rules.allows(&root.join("Song Project").join("Song [2023-10-15 123456].als")));
+
+    settings.max_depth = Some(2);
+    settings.include_backups = true;
+    let rules = RootRules::new(&settings).unwrap();
+    assert_eq!(
+        file_names(&scanner.scan_root(&rules, root).unwrap()),
+        ["Song [2023-10-15 123456].als", "Song.als", "Top.als"]
+    );
+    assert!(!rules.allows(&deep.join("Deep.als")));
+
+    // Scanning a folder inside the root counts depth from the root
+    assert!(scanner
+        .scan_root(&rules, &root.join("Archive"))
+        .unwrap()
+        .is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_root_settings_symlinks() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("Projects");
+    let elsewhere = temp_dir.path().join("Elsewhere");
+    fs::create_dir_all(&root).unwrap();
+    fs::create_dir_all(&elsewhere).unwrap();
+    create_test_file(&root, "Local.als");
+    create_test_file(&elsewhere, "Linked.als");
+    std::os::unix::fs::symlink(&elsewhere, root.join("Linked")).unwrap();
+
+    let scanner = ProjectPathScanner::new().unwrap();
+    let mut settings = RootSettings::new(root.to_string_lossy());
+    let rules = RootRules::new(&settings).unwrap();
+    assert_eq!(
+        file_names(&scanner.scan_root(&rules, &root).unwrap()),
+        ["Linked.als", "Local.als"]
+    );
+
+    settings.follow_symlinks = false;
+    let rules = RootRules::new(&settings).unwrap();
+    assert_eq!(
+        file_names(&scanner.scan_root(&rules, &root).unwrap()),
+        ["Local.als"]
+    );
+    assert!(!rules.allows(&root.join("Linked").join("Linked.als")));
+}
+
+#[test]
+fn test_default_tag_applied_under_root() {
+    setup("error");
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+    let tagged_root = PathBuf::from("/music/Label");
+    let untagged_root = PathBuf::from("/music/Sketches");
+
+    let mut settings = RootSettings::new(tagged_root.to_string_lossy());
+    settings.default_tag = Some(" label/releases ".to_string());
+    let roots = ScanRoots::new(vec![
+        RootRules::new(&settings).unwrap(),
+        RootRules::unrestricted(&untagged_root),
+    ]);
+    assert_eq!(
+        roots.default_tag(&tagged_root.join("Single").join("Single.als")),
+        Some("label/releases")
+    );
+    assert_eq!(roots.default_tag(&untagged_root.join("Idea.als")), None);
+
+    let projects: Vec<(String, PathBuf)> = [
+        tagged_root.join("One.als"),
+        tagged_root.join("Two.als"),
+        untagged_root.join("Idea.als"),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, path)| {
+        let mut live_set = generate_mock_live_set(i);
+        live_set.file_path = path.clone();
+        db.insert_project(&live_set).unwrap();
+        (live_set.id.to_string(), path)
+    })
+    .collect();
+    assert_eq!(roots.apply_default_tags(&mut db, &projects).unwrap(), 2);
+    // The tag is reused rather than added again
+    assert_eq!(roots.apply_default_tags(&mut db, &projects[..1]).unwrap(), 1);
+
+    let tags = db.list_tags().unwrap();
+    let releases: Vec<_> = tags.iter().filter(|t| t.name == "label/releases").collect();
+    assert_eq!(releases.len(), 1);
+    assert_eq!(db.get_project_tag_ids(&projects[0].0).unwrap(), [releases[0].id.clone()]);
+    assert!(db.get_project_tag_ids(&projects[2].0).unwrap().is_empty());
+}
@@ -0,0 +1,119 @@
+//! Scan error recording and quarantine tests
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use seula::database::LiveSetDatabase;
+use seula::error::LiveSetError;
+use seula::scan::incremental::FileState;
+use seula::scan::quarantine::{retry_failed_sets, skip_quarantined};
+use seula::LiveSet;
+use tempfile::TempDir;
+use tokio::sync::Mutex;
+
+use crate::common::setup;
+
+fn write_gzip(path: &Path, xml: &str) {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(xml.as_bytes()).unwrap();
+    fs::write(path, encoder.finish().unwrap()).unwrap();
+}
+
+fn record(db: &mut LiveSetDatabase, path: &Path) -> u32 {
+    let state = FileState::read(path).map(|state| (state.size, state.modified_at));
+    db.record_scan_error(path, &LiveSetError::MissingVersion, state)
+        .unwrap()
+}
+
+#[test]
+fn test_parse_failure_keeps_line_and_version() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("Broken.als");
+    write_gzip(
+        &path,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <Ableton MajorVersion=\"5\" MinorVersion=\"11.2_11215\">\n\
+         <LiveSet>\n\
+         <Tempo></Wrong>\n",
+    );
+
+    let error = LiveSet::new(path).unwrap_err();
+    assert_eq!(error.kind(), "xml_syntax");
+    assert_eq!(error.line(), Some(4));
+    assert_eq!(error.live_version(), Some("11.2.11215"));
+}
+
+#[test]
+fn test_repeated_failures_quarantine_until_changed() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    let path = temp_dir.path().join("Corrupt.als");
+    fs::write(&path, b"corrupt").unwrap();
+
+    assert_eq!(record(&mut db, &path), 1);
+    let errors = db.get_scan_errors(false).unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error_kind, "missing_version");
+    assert!(!errors[0].is_quarantined());
+    assert!(db.get_scan_errors(true).unwrap().is_empty());
+
+    // A second failure of the same file quarantines it
+    assert_eq!(record(&mut db, &path), 2);
+    let quarantined = db.get_scan_errors(true).unwrap();
+    assert_eq!(quarantined.len(), 1);
+
+    let other = temp_dir.path().join("Fine.als");
+    fs::write(&other, b"fine").unwrap();
+    let mut found: HashSet<PathBuf> = [path.clone(), other.clone()].into_iter().collect();
+    assert_eq!(skip_quarantined(&mut found, &quarantined), 1);
+    assert_eq!(found, [other.clone()].into_iter().collect());
+
+    // Once the file changes it's scanned again, and a new failure starts over
+    fs::write(&path, b"corrupt, but differently").unwrap();
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(60))
+        .unwrap();
+    let mut found: HashSet<PathBuf> = [path.clone()].into_iter().collect();
+    assert_eq!(skip_quarantined(&mut found, &quarantined), 0);
+    assert_eq!(record(&mut db, &path), 1);
+
+    assert_eq!(db.clear_scan_errors(&[path.as_path()]).unwrap(), 1);
+    assert!(db.get_scan_errors(false).unwrap().is_empty());
+}
+
+#[test]
+fn test_retry_failed_sets() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    let corrupt = temp_dir.path().join("Corrupt.als");
+    fs::write(&corrupt, b"corrupt").unwrap();
+    let gone = temp_dir.path().join("Gone.als");
+    fs::write(&gone, b"corrupt").unwrap();
+    record(&mut db, &corrupt);
+    record(&mut db, &gone);
+    fs::remove_file(&gone).unwrap();
+
+    // Takes the lock itself, only to record results
+    let db = Mutex::new(db);
+    let counts = retry_failed_sets(&db, &[]).unwrap();
+    assert_eq!((counts.fixed, counts.failed, counts.missing), (0, 1, 1));
+
+    let errors = db.blocking_lock().get_scan_errors(false).unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(PathBuf::from(&errors[0].path), corrupt);
+    // Failing again counts towards quarantine
+    assert!(errors[0].is_quarantined());
+}
@@ -0,0 +1,136 @@
+//! Watcher event bus tests
+
+use seula::watcher::debouncer::WatcherChange;
+use seula::watcher::events::{WatcherEvent, WatcherEventBus, WatcherEventKind};
+use seula::watcher::file_watcher::FileEvent;
+use seula::watcher::indexer::IndexOutcome;
+use std::path::PathBuf;
+
+use crate::common::setup;
+
+fn modified(name: &str) -> WatcherEvent {
+    WatcherEvent::from_file_event(&FileEvent::Modified(PathBuf::from(name)))
+}
+
+#[tokio::test]
+async fn test_event_bus_fans_out_to_all_subscribers() {
+    setup("error");
+    let bus = WatcherEventBus::new(16);
+    assert_eq!(bus.latest_sequence(), 0);
+
+    let mut first = bus.subscribe(None);
+    let mut second = bus.subscribe(None);
+    assert!(first.replay.is_empty());
+
+    assert_eq!(bus.publish(modified("/music/a.als")), 1);
+    assert_eq!(bus.publish(modified("/music/b.als")), 2);
+
+    for subscription in [&mut first, &mut second] {
+        let a = subscription.receiver.recv().await.unwrap();
+        let b = subscription.receiver.recv().await.unwrap();
+        assert_eq!((a.sequence, b.sequence), (1, 2));
+        assert_eq!(b.path, PathBuf::from("/music/b.als"));
+    }
+}
+
+#[tokio::test]
+async fn test_event_bus_replays_from_sequence() {
+    setup("error");
+    let bus = WatcherEventBus::new(3);
+    for i in 0..5 {
+        bus.publish(modified(&format!("/music/{}.als", i)));
+    }
+    assert_eq!(bus.latest_sequence(), 5);
+
+    // Still buffered: only what came after
+    let subscription = bus.subscribe(Some(3));
+    let sequences: Vec<u64> = subscription.replay.iter().map(|e| e.sequence).collect();
+    assert_eq!(sequences, vec![4, 5]);
+    assert!(!subscription.missed);
+
+    // Partly dropped from the buffer
+    let subscription = bus.subscribe(Some(1));
+    let sequences: Vec<u64> = subscription.replay.iter().map(|e| e.sequence).collect();
+    assert_eq!(sequences, vec![3, 4, 5]);
+    assert!(subscription.missed);
+
+    // Up to date
+    let (events, missed) = bus.events_since(5);
+    assert!(events.is_empty());
+    assert!(!missed);
+
+    // From before a restart: everything buffered, flagged as missed
+    let (events, missed) = bus.events_since(42);
+    assert_eq!(events.len(), 3);
+    assert!(missed);
+}
+
+#[tokio::test]
+async fn test_event_bus_resumes_from_sequence_ahead_of_bus() {
+    setup("error");
+    // A fresh bus, e.g. after a restart, with a client that saw sequence 42 before
+    let bus = WatcherEventBus::new(16);
+    let mut subscription = bus.subscribe(Some(42));
+    assert!(subscription.replay.is_empty());
+    assert!(subscription.missed);
+    assert_eq!(subscription.resume_after, 0);
+
+    // New events count as unseen even though their sequence is below 42
+    assert_eq!(bus.publish(modified("/music/a.als")), 1);
+    let event = subscription.receiver.recv().await.unwrap();
+    assert!(event.sequence > subscription.resume_after);
+
+    let subscription = bus.subscribe(Some(42));
+    assert_eq!(subscription.resume_after, 1);
+    assert_eq!(subscription.replay.len(), 1);
+
+    // Caught up, or subscribing without a sequence, nothing is missed
+    let subscription = bus.subscribe(Some(1));
+    assert!(!subscription.missed);
+    assert_eq!(subscription.resume_after, 1);
+    assert_eq!(bus.subscribe(None).resume_after, 1);
+}
+
+#[test]
+fn test_event_from_index_outcome() {
+    setup("error");
+    let change = WatcherChange::Upsert(PathBuf::from("/music/song.als"));
+
+    let updated = WatcherEvent::from_outcome(
+        &change,
+        &IndexOutcome::Updated {
+            project_id: "project-1".to_string(),
+            summary: "Project updated: tempo".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(updated.kind, WatcherEventKind::ProjectUpdated);
+    assert_eq!(updated.path, PathBuf::from("/music/song.als"));
+    assert_eq!(updated.project_id.as_deref(), Some("project-1"));
+    assert_eq!(updated.summary.as_deref(), Some("Project updated: tempo"));
+
+    let moved = WatcherEvent::from_outcome(
+        &WatcherChange::Moved {
+            from: PathBuf::from("/music/old.als"),
+            to: PathBuf::from("/music/new.als"),
+        },
+        &IndexOutcome::Moved {
+            project_id: "project-1".to_string(),
+            from: "/music/old.als".to_string(),
+            to: "/music/new.als".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(moved.kind, WatcherEventKind::ProjectMoved);
+    assert_eq!(moved.path, PathBuf::from("/music/old.als"));
+    assert_eq!(moved.new_path, Some(PathBuf::from("/music/new.als")));
+
+    assert!(WatcherEvent::from_outcome(&change, &IndexOutcome::Ignored).is_none());
+    assert!(WatcherEvent::from_outcome(
+        &change,
+        &IndexOutcome::Unchanged {
+            project_id: "project-1".to_string()
+        }
+    )
+    .is_none());
+}
@@ -0,0 +1,269 @@
+//! Watcher debouncing and automatic indexing tests
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use seula::config::RootSettings;
+use seula::database::LiveSetDatabase;
+use seula::scan::{RootRules, ScanRoots};
+use seula::watcher::debouncer::{EventDebouncer, WatcherChange};
+use seula::watcher::file_watcher::FileEvent;
+use seula::watcher::indexer::{apply_change, filter_event, IndexOutcome};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+use crate::common::setup;
+
+const WINDOW: Duration = Duration::from_millis(100);
+
+/// Write a minimal gzipped set the parser accepts
+fn write_live_set(path: &Path, tempo: f64) {
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<Ableton MajorVersion="5" MinorVersion="11.0_433" SchemaChangeCount="3" Creator="Ableton Live 11.0">
+    <LiveSet>
+        <MasterTrack>
+            <DeviceChain>
+                <Mixer>
+                    <Tempo>
+                        <Manual Value="{}" />
+                    </Tempo>
+                    <TimeSignature>
+                        <AutomationTarget>
+                            <EnumEvent Time="-63072000" Value="201" />
+                        </AutomationTarget>
+                    </TimeSignature>
+                </Mixer>
+            </DeviceChain>
+        </MasterTrack>
+    </LiveSet>
+</Ableton>"#,
+        tempo
+    );
+    let mut encoder = GzEncoder::new(File::create(path).unwrap(), Compression::default());
+    encoder.write_all(xml.as_bytes()).unwrap();
+    encoder.finish().unwrap();
+}
+
+fn project_id(outcome: &IndexOutcome) -> String {
+    match outcome {
+        IndexOutcome::Inserted { project_id }
+        | IndexOutcome::Updated { project_id, .. }
+        | IndexOutcome::Unchanged { project_id }
+        | IndexOutcome::Moved { project_id, .. }
+        | IndexOutcome::Removed { project_id } => project_id.clone(),
+        other => panic!("Expected an indexed project, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_debouncer_coalesces_save_burst() {
+    setup("error");
+    let mut debouncer = EventDebouncer::new(WINDOW);
+    let start = Instant::now();
+    let set = PathBuf::from("/music/song.als");
+
+    // Live writes a temp file and renames it over the set
+    debouncer.push(
+        FileEvent::Created(PathBuf::from("/music/song.als.tmp")),
+        start,
+    );
+    debouncer.push(
+        FileEvent::Renamed {
+            from: PathBuf::from("/music/song.als.tmp"),
+            to: set.clone(),
+        },
+        start,
+    );
+    debouncer.push(FileEvent::Modified(set.clone()), start);
+
+    assert!(debouncer.drain_ready(start + WINDOW / 2).is_empty());
+    let changes: Vec<WatcherChange> = debouncer
+        .drain_ready(start + WINDOW)
+        .into_iter()
+        .filter(|change| change.path() == set)
+        .collect();
+    assert_eq!(changes, vec![WatcherChange::Upsert(set)]);
+}
+
+#[test]
+fn test_debouncer_move_chains_and_rewrites() {
+    setup("error");
+    let mut debouncer = EventDebouncer::new(WINDOW);
+    let start = Instant::now();
+    let a = PathBuf::from("/music/a.als");
+    let b = PathBuf::from("/music/b.als");
+    let c = PathBuf::from("/music/c.als");
+    let d = PathBuf::from("/music/d.als");
+
+    // Moved twice before settling keeps the original source
+    debouncer.push(
+        FileEvent::Renamed {
+            from: a.clone(),
+            to: b.clone(),
+        },
+        start,
+    );
+    debouncer.push(
+        FileEvent::Renamed {
+            from: b.clone(),
+            to: c.clone(),
+        },
+        start,
+    );
+    // Deleted and written again is just an update
+    debouncer.push(FileEvent::Deleted(d.clone()), start);
+    debouncer.push(FileEvent::Created(d.clone()), start);
+
+    let changes = debouncer.drain_all();
+    assert!(debouncer.is_empty());
+    assert_eq!(changes.len(), 2);
+    assert_eq!(
+        changes[0],
+        WatcherChange::Moved {
+            from: a.clone(),
+            to: c.clone()
+        }
+    );
+    assert_eq!(changes[1], WatcherChange::Upsert(d));
+
+    // A move followed by a delete removes the set where it was indexed
+    debouncer.push(
+        FileEvent::Renamed {
+            from: a.clone(),
+            to: b.clone(),
+        },
+        start,
+    );
+    debouncer.push(FileEvent::Deleted(b), start);
+    assert_eq!(debouncer.drain_all(), vec![WatcherChange::Removed(a)]);
+}
+
+#[test]
+fn test_apply_change_indexes_moves_and_removes() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let mut db = LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap();
+
+    let original = temp_dir.path().join("Idea.als");
+    write_live_set(&original, 120.0);
+
+    let inserted = apply_change(&mut db, &WatcherChange::Upsert(original.clone())).unwrap();
+    assert!(
+        matches!(inserted, IndexOutcome::Inserted { .. }),
+        "{:?}",
+        inserted
+    );
+    let project_id = project_id(&inserted);
+
+    let tag_id = db.add_tag("keeper").unwrap();
+    db.tag_project(&project_id, &tag_id).unwrap();
+    db.set_project_notes(&project_id, "needs a bridge").unwrap();
+
+    // A save that fails to parse is recorded against the indexed set
+    fs::write(&original, b"not a live set").unwrap();
+    let failed = apply_change(&mut db, &WatcherChange::Upsert(original.clone())).unwrap();
+    assert!(matches!(failed, IndexOutcome::Failed { .. }), "{:?}", failed);
+    let errors = db.get_scan_errors(false).unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, original.to_string_lossy());
+
+    // Saving again updates the same project in place and clears the failure
+    write_live_set(&original, 128.0);
+    let updated = apply_change(&mut db, &WatcherChange::Upsert(original.clone())).unwrap();
+    assert_eq!(self::project_id(&updated), project_id);
+    let project = db.get_project_by_id(&project_id).unwrap().unwrap();
+    assert_eq!(project.tempo, 128.0);
+    assert!(db.get_scan_errors(false).unwrap().is_empty());
+
+    // Moving keeps the identity, tags and notes
+    let moved_dir = temp_dir.path().join("Finished");
+    fs::create_dir(&moved_dir).unwrap();
+    let moved = moved_dir.join("Idea.als");
+    fs::rename(&original, &moved).unwrap();
+
+    let outcome = apply_change(
+        &mut db,
+        &WatcherChange::Moved {
+            from: original.clone(),
+            to: moved.clone(),
+        },
+    )
+    .unwrap();
+    assert!(matches!(outcome, IndexOutcome::Moved { .. }));
+    assert_eq!(self::project_id(&outcome), project_id);
+
+    let project = db.get_project_by_id(&project_id).unwrap().unwrap();
+    assert_eq!(project.file_path, moved);
+    assert!(db.get_project_tags(&project_id).unwrap().contains("keeper"));
+    assert_eq!(
+        db.get_project_notes(&project_id).unwrap().as_deref(),
+        Some("needs a bridge")
+    );
+
+    // Deleting marks the project deleted without dropping it
+    fs::remove_file(&moved).unwrap();
+    let removed = apply_change(&mut db, &WatcherChange::Removed(moved.clone())).unwrap();
+    assert_eq!(
+        removed,
+        IndexOutcome::Removed {
+            project_id: project_id.clone()
+        }
+    );
+    assert!(db.get_project_by_id(&project_id).unwrap().is_none());
+
+    // Showing up again elsewhere restores the same project
+    let restored = temp_dir.path().join("Idea again.als");
+    write_live_set(&restored, 128.0);
+    let outcome = apply_change(&mut db, &WatcherChange::Upsert(restored.clone())).unwrap();
+    assert_eq!(self::project_id(&outcome), project_id);
+    assert!(db.get_project_by_id(&project_id).unwrap().is_some());
+
+    // Paths nothing refers to are ignored
+    let unknown = temp_dir.path().join("Unknown.als");
+    assert_eq!(
+        apply_change(&mut db, &WatcherChange::Removed(unknown)).unwrap(),
+        IndexOutcome::Ignored
+    );
+}
+
+#[test]
+fn test_filter_event_follows_root_settings() {
+    setup("error");
+    let root = PathBuf::from("/music/Projects");
+    let mut settings = RootSettings::new(root.to_string_lossy());
+    settings.exclude = vec!["**/Old Stuff/**".to_string()];
+    let roots = ScanRoots::new(vec![RootRules::new(&settings).unwrap()]);
+
+    let kept = root.join("Song Project").join("Song.als");
+    let excluded = root.join("Old Stuff").join("Demo.als");
+    let backup = root
+        .join("Song Project")
+        .join("Backup")
+        .join("Song [2023-10-15 123456].als");
+
+    assert!(matches!(
+        filter_event(&roots, FileEvent::Created(kept.clone())),
+        Some(FileEvent::Created(_))
+    ));
+    assert!(filter_event(&roots, FileEvent::Modified(excluded.clone())).is_none());
+    assert!(filter_event(&roots, FileEvent::Created(backup)).is_none());
+
+    // Moving a set into an excluded folder takes it out of the index
+    match filter_event(
+        &roots,
+        FileEvent::Renamed {
+            from: kept.clone(),
+            to: excluded.clone(),
+        },
+    ) {
+        Some(FileEvent::Deleted(path)) => assert_eq!(path, kept),
+        other => panic!("expected a deletion, got {:?}", other),
+    }
+    assert!(matches!(
+        filter_event(&roots, FileEvent::Deleted(excluded)),
+        Some(FileEvent::Deleted(_))
+    ));
+}
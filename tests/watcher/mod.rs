@@ -1 +1,4 @@
 pub mod file_events;
+pub mod indexing;
+pub mod events;
+pub mod polling;
@@ -0,0 +1,107 @@
+//! Polling watcher backend tests
+
+use seula::database::LiveSetDatabase;
+use seula::watcher::file_watcher::{FileEvent, FileWatcher};
+use seula::watcher::poller::{diff_snapshots, FileStamp, PollSnapshot};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tempfile::TempDir;
+use tokio::sync::Mutex;
+
+use crate::common::setup;
+
+fn stamp(secs: u64, len: u64) -> FileStamp {
+    FileStamp {
+        modified: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+        len,
+    }
+}
+
+fn snapshot(files: &[(&str, FileStamp)]) -> PollSnapshot {
+    files
+        .iter()
+        .map(|(path, stamp)| (PathBuf::from(path), *stamp))
+        .collect()
+}
+
+#[test]
+fn test_diff_snapshots() {
+    setup("error");
+    let old = snapshot(&[
+        ("/music/kept.als", stamp(100, 10)),
+        ("/music/saved.als", stamp(100, 10)),
+        ("/music/gone.als", stamp(100, 10)),
+        ("/music/moved.als", stamp(200, 20)),
+    ]);
+    let new = snapshot(&[
+        ("/music/kept.als", stamp(100, 10)),
+        ("/music/saved.als", stamp(150, 12)),
+        ("/music/archive/moved.als", stamp(200, 20)),
+        ("/music/fresh.als", stamp(300, 30)),
+    ]);
+
+    let mut events = diff_snapshots(&old, &new);
+    events.sort_by_key(|e| format!("{:?}", e));
+    let expected = vec![
+        FileEvent::Created(PathBuf::from("/music/fresh.als")),
+        FileEvent::Deleted(PathBuf::from("/music/gone.als")),
+        FileEvent::Modified(PathBuf::from("/music/saved.als")),
+        FileEvent::Renamed {
+            from: PathBuf::from("/music/moved.als"),
+            to: PathBuf::from("/music/archive/moved.als"),
+        },
+    ];
+    assert_eq!(format!("{:?}", events), format!("{:?}", expected));
+
+    // Two identical candidates can't be told apart, so no rename is guessed
+    let old = snapshot(&[("/music/a.als", stamp(100, 10))]);
+    let new = snapshot(&[
+        ("/music/b.als", stamp(100, 10)),
+        ("/music/c.als", stamp(100, 10)),
+    ]);
+    let events = diff_snapshots(&old, &new);
+    assert_eq!(events.len(), 3);
+    assert!(!events
+        .iter()
+        .any(|e| matches!(e, FileEvent::Renamed { .. })));
+}
+
+#[tokio::test]
+async fn test_poll_path_reports_changes() {
+    setup("error");
+    let temp_dir = TempDir::new().unwrap();
+    let db = Arc::new(Mutex::new(
+        LiveSetDatabase::new(PathBuf::from(":memory:")).unwrap(),
+    ));
+    let (mut watcher, rx) = FileWatcher::new(db).unwrap();
+    watcher.add_poll_path(temp_dir.path().to_path_buf(), Duration::from_secs(1));
+    assert!(watcher.is_watching(temp_dir.path()));
+    assert!(watcher.is_polling(temp_dir.path()));
+
+    // Let the baseline pass finish before changing anything
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let set = temp_dir.path().join("Polled.als");
+    fs::write(&set, b"set").unwrap();
+    fs::write(temp_dir.path().join("notes.txt"), b"ignored").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut created = false;
+    while Instant::now() < deadline && !created {
+        if let Ok(event) = rx.recv_timeout(Duration::from_millis(100)) {
+            match event {
+                FileEvent::Created(path) => {
+                    assert_eq!(path, set);
+                    created = true;
+                }
+                other => panic!("Unexpected event: {:?}", other),
+            }
+        }
+    }
+    assert!(created, "Polling didn't report the new set");
+
+    // Removing the path stops polling
+    watcher.remove_watch_path(temp_dir.path()).unwrap();
+    assert!(!watcher.is_polling(temp_dir.path()));
+}